@@ -1,20 +1,23 @@
-use bevy_app::App;
+use bevy_app::{App, Startup, Update};
 use bevy_doryen::doryen::{AppOptions, TextAlign};
-use bevy_doryen::{DoryenPlugin, DoryenPluginSettings, Input, RenderSystemExtensions, RootConsole};
+use bevy_doryen::{
+    DoryenPlugin, DoryenPluginSettings, Input, Key, RenderSystemExtensions, RootConsole,
+};
 use bevy_ecs::bundle::Bundle;
+use bevy_ecs::component::Component;
 use bevy_ecs::entity::Entity;
-use bevy_ecs::system::{Commands, IntoSystem, Query, Res, ResMut};
+use bevy_ecs::system::{Commands, Query, Res, ResMut, Resource};
 
 const CONSOLE_WIDTH: u32 = 80;
 const CONSOLE_HEIGHT: u32 = 45;
 
-#[derive(Default, Copy, Clone, PartialEq)]
-struct Position<C> {
+#[derive(Default, Copy, Clone, PartialEq, Component)]
+struct Position<C: Send + Sync + 'static> {
     x: C,
     y: C,
 }
 
-#[derive(Default)]
+#[derive(Default, Component)]
 struct Player;
 
 #[derive(Bundle)]
@@ -23,7 +26,7 @@ struct PlayerBundle {
     position: Position<i32>,
 }
 
-#[derive(Default)]
+#[derive(Default, Component)]
 struct Mouse;
 
 #[derive(Bundle)]
@@ -32,13 +35,14 @@ struct MouseBundle {
     position: Position<f32>,
 }
 
+#[derive(Resource)]
 struct Entities {
     player: Entity,
     mouse: Entity,
 }
 
 fn main() {
-    App::build()
+    App::new()
         .insert_resource(DoryenPluginSettings {
             // here are all the available options.
             // better practice is to use default values (see other examples)
@@ -54,13 +58,14 @@ fn main() {
                 show_cursor: true,
                 resizable: true,
                 intercept_close_request: false,
+                max_fps: 0,
             },
             ..Default::default()
         })
-        .add_plugin(DoryenPlugin)
-        .add_startup_system(init.system())
-        .add_system(input.system())
-        .add_doryen_render_system(render.system())
+        .add_plugins(DoryenPlugin)
+        .add_systems(Startup, init)
+        .add_systems(Update, input)
+        .add_doryen_render_systems(render)
         .run();
 }
 
@@ -70,7 +75,7 @@ fn init(mut root_console: ResMut<RootConsole>, mut commands: Commands) {
     root_console.register_color("blue", (192, 192, 255, 255));
 
     let player = commands
-        .spawn_bundle(PlayerBundle {
+        .spawn(PlayerBundle {
             player: Player,
             position: Position {
                 x: (CONSOLE_WIDTH / 2) as i32,
@@ -80,7 +85,7 @@ fn init(mut root_console: ResMut<RootConsole>, mut commands: Commands) {
         .id();
 
     let mouse = commands
-        .spawn_bundle(MouseBundle {
+        .spawn(MouseBundle {
             mouse: Mouse,
             position: Position { x: 0., y: 0. },
         })
@@ -99,14 +104,14 @@ fn input(
         .get_component_mut::<Position<i32>>(entities.player)
         .unwrap();
 
-    if input.key("ArrowLeft") {
+    if input.key(Key::ArrowLeft) {
         player_position.x = (player_position.x - 1).max(1);
-    } else if input.key("ArrowRight") {
+    } else if input.key(Key::ArrowRight) {
         player_position.x = (player_position.x + 1).min(CONSOLE_WIDTH as i32 - 2);
     }
-    if input.key("ArrowUp") {
+    if input.key(Key::ArrowUp) {
         player_position.y = (player_position.y - 1).max(1);
-    } else if input.key("ArrowDown") {
+    } else if input.key(Key::ArrowDown) {
         player_position.y = (player_position.y + 1).min(CONSOLE_HEIGHT as i32 - 2);
     }
 
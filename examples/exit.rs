@@ -1,15 +1,15 @@
-use bevy_app::{App, AppExit, EventWriter};
+use bevy_app::{App, AppExit, Update};
 use bevy_doryen::doryen::{AppOptions, Color, TextAlign};
+use bevy_doryen::modal::{no_modal_open, Dialog, DialogResult, ModalPlugin, ModalStack};
 use bevy_doryen::{DoryenPlugin, DoryenPluginSettings, Input, RenderSystemExtensions, RootConsole};
-use bevy_ecs::system::{IntoSystem, Res, ResMut};
+use bevy_ecs::event::{EventReader, EventWriter};
+use bevy_ecs::schedule::IntoSystemConfigs;
+use bevy_ecs::system::{Res, ResMut};
 
 const WHITE: Color = (255, 255, 255, 255);
 
-#[derive(Default)]
-struct CloseRequested(bool);
-
 fn main() {
-    App::build()
+    App::new()
         .insert_resource(DoryenPluginSettings {
             app_options: AppOptions {
                 intercept_close_request: true,
@@ -17,48 +17,38 @@ fn main() {
             },
             ..Default::default()
         })
-        .add_plugin(DoryenPlugin)
-        .init_resource::<CloseRequested>()
-        .add_system(process_input.system())
-        .add_doryen_render_system(render.system())
+        .add_plugins((DoryenPlugin, ModalPlugin))
+        .add_systems(
+            Update,
+            (ask_to_exit.run_if(no_modal_open), exit_if_confirmed),
+        )
+        .add_doryen_render_systems(render)
         .run();
 }
 
-fn process_input(
-    input: Res<Input>,
-    mut close_requested: ResMut<CloseRequested>,
-    mut app_exit: EventWriter<AppExit>,
-) {
-    if close_requested.0 {
-        if input.key("KeyY") {
-            app_exit.send(AppExit);
-        } else if input.key("KeyN") {
-            close_requested.0 = false;
-        }
-    } else if input.key("Escape") || input.close_requested() {
-        close_requested.0 = true;
+fn ask_to_exit(input: Res<Input>, mut modals: ResMut<ModalStack>) {
+    if input.key_pressed("Escape") || input.close_requested() {
+        modals.push(Dialog::confirm("exit", "Exit game?"));
     }
 }
 
-fn render(mut root_console: ResMut<RootConsole>, close_requested: Res<CloseRequested>) {
-    root_console.clear(None, None, Some(' ' as u16));
-    if close_requested.0 {
-        root_console.print(
-            5,
-            5,
-            "Exit game ? (press Y or N)",
-            TextAlign::Left,
-            Some(WHITE),
-            None,
-        );
-    } else {
-        root_console.print(
-            5,
-            5,
-            "Press ESC to exit",
-            TextAlign::Left,
-            Some(WHITE),
-            None,
-        );
+fn exit_if_confirmed(mut results: EventReader<DialogResult>, mut app_exit: EventWriter<AppExit>) {
+    if results
+        .iter()
+        .any(|result| result.id == "exit" && result.is_confirmed())
+    {
+        app_exit.send(AppExit);
     }
 }
+
+fn render(mut root_console: ResMut<RootConsole>) {
+    root_console.clear(None, None, Some(' ' as u16));
+    root_console.print(
+        5,
+        5,
+        "Press ESC to exit",
+        TextAlign::Left,
+        Some(WHITE),
+        None,
+    );
+}
@@ -1,9 +1,10 @@
-use bevy_app::{App, EventWriter};
+use bevy_app::{App, Update};
 use bevy_doryen::doryen::{AppOptions, TextAlign};
 use bevy_doryen::{
     DoryenPlugin, DoryenPluginSettings, Input, RenderSystemExtensions, RootConsole, SetFontPath,
 };
-use bevy_ecs::system::{IntoSystem, Res, ResMut};
+use bevy_ecs::event::EventWriter;
+use bevy_ecs::system::{Res, ResMut, Resource};
 
 const CONSOLE_WIDTH: u32 = 40;
 const CONSOLE_HEIGHT: u32 = 25;
@@ -30,14 +31,14 @@ const FONTS: [&str; 19] = [
     "Yayo_tunur_13x13.png",
 ];
 
-#[derive(Default)]
+#[derive(Default, Resource)]
 struct Font {
     current_font: usize,
     current_font_name: &'static str,
 }
 
 fn main() {
-    App::build()
+    App::new()
         .insert_resource(DoryenPluginSettings {
             app_options: AppOptions {
                 console_width: CONSOLE_WIDTH,
@@ -49,10 +50,10 @@ fn main() {
             },
             ..Default::default()
         })
-        .add_plugin(DoryenPlugin)
+        .add_plugins(DoryenPlugin)
         .init_resource::<Font>()
-        .add_system(update.system())
-        .add_doryen_render_system(render.system())
+        .add_systems(Update, update)
+        .add_doryen_render_systems(render)
         .run();
 }
 
@@ -113,7 +114,7 @@ fn render(mut root_console: ResMut<RootConsole>, font: Res<Font>) {
     root_console.print(
         (CONSOLE_WIDTH / 2) as i32,
         (CONSOLE_HEIGHT / 2) as i32,
-        &font.current_font_name,
+        font.current_font_name,
         TextAlign::Center,
         Some((255, 255, 255, 255)),
         None,
@@ -1,27 +1,21 @@
-use bevy_app::App;
+use bevy_app::{App, Update};
+use bevy_doryen::clipboard::{Clipboard, ClipboardPlugin, CopyRequested, PasteEvent};
 use bevy_doryen::doryen::{AppOptions, Color, TextAlign};
 use bevy_doryen::{DoryenPlugin, DoryenPluginSettings, Input, RenderSystemExtensions, RootConsole};
-use bevy_ecs::system::{IntoSystem, Res, ResMut};
+use bevy_ecs::event::EventReader;
+use bevy_ecs::system::{Res, ResMut, Resource};
 use unicode_segmentation::UnicodeSegmentation;
 
 const WHITE: Color = (255, 255, 255, 255);
 
+#[derive(Default, Resource)]
 struct TextInput {
     text: String,
     cursor: usize,
 }
 
-impl Default for TextInput {
-    fn default() -> Self {
-        Self {
-            text: String::new(),
-            cursor: 0,
-        }
-    }
-}
-
 fn main() {
-    App::build()
+    App::new()
         .insert_resource(DoryenPluginSettings {
             app_options: AppOptions {
                 window_title: String::from("bevy_doryen subcell resolution demo"),
@@ -29,18 +23,31 @@ fn main() {
             },
             ..Default::default()
         })
-        .add_plugin(DoryenPlugin)
+        .add_plugins((DoryenPlugin, ClipboardPlugin))
         .init_resource::<TextInput>()
-        .add_system(update.system())
-        .add_doryen_render_system(render.system())
+        .add_systems(Update, update)
+        .add_doryen_render_systems(render)
         .run();
 }
 
-fn update(input: Res<Input>, mut text_input: ResMut<TextInput>) {
+fn update(
+    input: Res<Input>,
+    mut text_input: ResMut<TextInput>,
+    mut clipboard: ResMut<Clipboard>,
+    mut pastes: EventReader<PasteEvent>,
+    mut copies: EventReader<CopyRequested>,
+) {
     // input.text returns the characters typed by the player since last update
     let text = input.text();
     if !text.is_empty() {
-        text_input.text.push_str(&text);
+        text_input.text.push_str(text);
+    }
+    // handle ctrl+v and ctrl+c
+    for paste in pastes.iter() {
+        text_input.text.push_str(&paste.text);
+    }
+    if copies.iter().next().is_some() {
+        clipboard.set_text(text_input.text.clone());
     }
     // handle backspace
     if input.key_released("Backspace") && !text_input.text.is_empty() {
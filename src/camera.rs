@@ -0,0 +1,706 @@
+//! Several views of a world on one screen.
+//!
+//! Draw the world on the [`WorldConsole`], which can be larger than the
+//! screen, and spawn an entity with a [`ConsoleCamera`] for each rectangle of
+//! the root console that should show part of it. Every camera copies the
+//! part of the world around the cell it looks at into its rectangle, which
+//! makes split-screen co-op and side-by-side editor views a matter of
+//! spawning more cameras:
+//!
+//! ```
+//! # use bevy_doryen::camera::ConsoleCamera;
+//! # use bevy_ecs::system::Commands;
+//! fn spawn_cameras(mut commands: Commands) {
+//!     // One half of an 80 by 45 screen for each player
+//!     commands.spawn(ConsoleCamera::new(0, 0, 40, 45).looking_at(12, 20));
+//!     commands.spawn(ConsoleCamera::new(40, 0, 40, 45).looking_at(60, 8));
+//! }
+//! ```
+//!
+//! A camera with a [`CameraWorld`] component shows that console instead of
+//! the shared world, for views of different worlds side by side. A camera
+//! with a [`RenderLayer`] isn't drawn while that layer is disabled in the
+//! [`RenderLayers`], those on the camera entity if it has any, and the
+//! resource otherwise.
+//!
+//! A game with a single view of a world it draws itself can insert a
+//! [`ConsoleCamera`] as a resource instead, and map its world coordinates
+//! through it. [`ConsoleCamera::cell`] draws a world cell in the right place
+//! on the screen, and leaves out those the camera doesn't show, so scrolling
+//! the map is a matter of moving the camera:
+//!
+//! ```
+//! # use bevy_doryen::camera::ConsoleCamera;
+//! # use bevy_doryen::RootConsole;
+//! # use bevy_ecs::system::{Res, ResMut, Resource};
+//! #[derive(Resource)]
+//! struct Map {
+//!     player: (i32, i32),
+//!     walls: Vec<(i32, i32)>,
+//! }
+//!
+//! fn draw_map(
+//!     map: Res<Map>,
+//!     mut camera: ResMut<ConsoleCamera>,
+//!     mut root_console: ResMut<RootConsole>,
+//! ) {
+//!     camera.look_at(map.player.0, map.player.1);
+//!     for &wall in &map.walls {
+//!         camera.cell(&mut root_console, wall, Some(u16::from(b'#')), None, None);
+//!     }
+//! }
+//! ```
+//!
+//! The resource isn't drawn by [`ConsoleCameraPlugin`]; only the camera
+//! entities are.
+//!
+//! A camera with a [`CameraFollow`] keeps the target entity in view, going by
+//! its [`RenderPosition`], or its [`GridPosition`] if it has none. Inserted
+//! as resources, [`CameraFollow`] and [`CameraFollowOptions`] do the same for
+//! the [`ConsoleCamera`] resource. [`CameraFollowOptions`] let the target
+//! move around a dead zone before the camera scrolls, and stop the camera at
+//! the edges of the map:
+//!
+//! ```
+//! # use bevy_doryen::camera::{CameraFollow, CameraFollowOptions, ConsoleCamera};
+//! # use bevy_doryen::path_following::GridPosition;
+//! # use bevy_ecs::system::Commands;
+//! fn spawn_camera(mut commands: Commands) {
+//!     let player = commands.spawn(GridPosition(10, 10)).id();
+//!     commands.spawn((
+//!         ConsoleCamera::new(0, 0, 80, 45),
+//!         CameraFollow(player),
+//!         CameraFollowOptions::default()
+//!             .with_dead_zone(8, 4)
+//!             .within_map(200, 120),
+//!     ));
+//! }
+//! ```
+//!
+//! A camera's [`zoom`](ConsoleCamera::zoom) shows each world cell as a
+//! square of several cells, and a [`WheelZoom`] lets the player change it
+//! with the mouse wheel where the wheel is reported:
+//!
+//! ```
+//! # use bevy_doryen::camera::{ConsoleCamera, WheelZoom};
+//! # use bevy_ecs::system::Commands;
+//! fn spawn_camera(mut commands: Commands) {
+//!     commands.spawn((
+//!         ConsoleCamera::new(0, 0, 80, 45).with_zoom(2),
+//!         WheelZoom { max_zoom: 3 },
+//!     ));
+//! }
+//! ```
+//!
+//! The cameras draw at the start of the [`RenderStage::PostRender`] stage,
+//! in [`PostRenderSet::Compose`], so the world should be drawn in the
+//! [`RenderStage::Render`] stage, where
+//! [`add_doryen_render_systems`](RenderSystemExtensions::add_doryen_render_systems)
+//! puts systems. Consoles composited by the
+//! [`CompositorPlugin`](crate::compositor::CompositorPlugin) are drawn over
+//! every camera, and overlays such as dialogs and tooltips over those.
+
+use crate::doryen::{Color, Console};
+use crate::geometry::Rect;
+use crate::path_following::{GridPosition, RenderPosition};
+use crate::{
+    Input, PostRenderSet, RenderLayer, RenderLayers, RenderStage, RenderSystemExtensions,
+    RootConsole,
+};
+use bevy_app::{App, Plugin, Update};
+use bevy_ecs::component::Component;
+use bevy_ecs::entity::Entity;
+use bevy_ecs::query::AnyOf;
+use bevy_ecs::schedule::IntoSystemConfigs;
+use bevy_ecs::system::{Query, Res, ResMut, Resource};
+use std::ops::{Deref, DerefMut};
+
+/// The camera plugin. Adds a [`WorldConsole`] of 1 by 1 cells, unless one
+/// has already been inserted, and draws the [`ConsoleCamera`]s on the root
+/// console. Cameras with a [`CameraFollow`] are moved to follow their
+/// targets beforehand, in the [`RenderStage::PreRender`] stage, and those with
+/// a [`WheelZoom`] are zoomed in the `Update` schedule.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct ConsoleCameraPlugin;
+
+impl Plugin for ConsoleCameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WorldConsole>()
+            .add_systems(Update, zoom_cameras)
+            .add_doryen_render_systems_to_stage(RenderStage::PreRender, follow_targets)
+            .add_doryen_render_systems_to_stage(
+                RenderStage::PostRender,
+                draw_cameras
+                    .in_set(PostRenderSet::Compose)
+                    .before(crate::compositor::composite_consoles),
+            );
+    }
+}
+
+/// The console the world is drawn on, in world cell coordinates.
+#[derive(Resource)]
+pub struct WorldConsole(pub Console);
+
+impl Default for WorldConsole {
+    fn default() -> Self {
+        Self(Console::new(1, 1))
+    }
+}
+
+impl std::fmt::Debug for WorldConsole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WorldConsole")
+            .field("width", &self.0.get_width())
+            .field("height", &self.0.get_height())
+            .finish()
+    }
+}
+
+impl Deref for WorldConsole {
+    type Target = Console;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for WorldConsole {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// A console a [`ConsoleCamera`] on the same entity shows instead of the
+/// [`WorldConsole`].
+#[derive(Component)]
+pub struct CameraWorld(pub Console);
+
+impl std::fmt::Debug for CameraWorld {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CameraWorld")
+            .field("width", &self.0.get_width())
+            .field("height", &self.0.get_height())
+            .finish()
+    }
+}
+
+/// Component showing part of the world in a rectangle of the root console.
+/// Cameras with a higher `z_index` are drawn on top of those with a lower one.
+/// The z-index only orders cameras among themselves: composited consoles are
+/// drawn over all of them, whatever their [`ZIndex`](crate::compositor::ZIndex).
+///
+/// It's also a resource, for mapping the world coordinates of a single view
+/// onto the screen. See the [module documentation](self).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component, Resource)]
+pub struct ConsoleCamera {
+    /// The x coordinate of the top-left cell of the rectangle on the root
+    /// console.
+    pub x: i32,
+    /// The y coordinate of the top-left cell of the rectangle on the root
+    /// console.
+    pub y: i32,
+    /// The width of the rectangle, in cells.
+    pub width: u32,
+    /// The height of the rectangle, in cells.
+    pub height: u32,
+    /// The world cell shown in the middle of the rectangle.
+    pub center: (i32, i32),
+    /// The z-index the camera is drawn at. Higher ones are on top.
+    pub z_index: i32,
+    /// How many cells of the rectangle each world cell takes up, across and
+    /// down. 1, the default, shows the world cell for cell.
+    pub zoom: u32,
+}
+
+impl ConsoleCamera {
+    /// A camera filling the given rectangle of the root console, looking at
+    /// the world cell in the middle of the rectangle.
+    pub fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+            center: (x + (width / 2) as i32, y + (height / 2) as i32),
+            z_index: 0,
+            zoom: 1,
+        }
+    }
+
+    /// Makes the camera look at the given world cell.
+    pub fn looking_at(mut self, x: i32, y: i32) -> Self {
+        self.center = (x, y);
+        self
+    }
+
+    /// Makes the camera look at the given world cell from now on.
+    pub fn look_at(&mut self, x: i32, y: i32) {
+        self.center = (x, y);
+    }
+
+    /// The [`rect`](Self::rect) of the camera as its top-left cell, width
+    /// and height, the way [`DrawFrame`](crate::window::DrawFrame) takes it.
+    pub fn viewport(&self) -> ((i32, i32), (u32, u32)) {
+        ((self.x, self.y), (self.width, self.height))
+    }
+
+    /// Draws the camera at the given z-index.
+    pub fn with_z_index(mut self, z_index: i32) -> Self {
+        self.z_index = z_index;
+        self
+    }
+
+    /// Shows each world cell as `zoom` by `zoom` cells.
+    pub fn with_zoom(mut self, zoom: u32) -> Self {
+        self.zoom = zoom;
+        self
+    }
+
+    #[inline]
+    fn scale(&self) -> i32 {
+        self.zoom.max(1) as i32
+    }
+
+    /// How many world cells the camera shows across and down, counting those
+    /// that only partly fit at the right and bottom edges.
+    pub fn world_size(&self) -> (u32, u32) {
+        let zoom = self.zoom.max(1);
+        (self.width.div_ceil(zoom), self.height.div_ceil(zoom))
+    }
+
+    /// The world cell shown in the top-left cell of the rectangle.
+    pub fn origin(&self) -> (i32, i32) {
+        let (width, height) = self.world_size();
+        (
+            self.center.0 - (width / 2) as i32,
+            self.center.1 - (height / 2) as i32,
+        )
+    }
+
+    /// The rectangle of the root console the camera shows the world in.
+    pub fn rect(&self) -> Rect {
+        Rect::new(self.x, self.y, self.width, self.height)
+    }
+
+    /// The world position shown at the given root console position, like
+    /// [`Input::mouse_pos`](crate::Input::mouse_pos), or `None` if it's
+    /// outside the rectangle.
+    pub fn screen_to_world(&self, (x, y): (f32, f32)) -> Option<(f32, f32)> {
+        if !self.rect().contains((x.floor() as i32, y.floor() as i32)) {
+            return None;
+        }
+        let (origin_x, origin_y) = self.origin();
+        let zoom = self.scale() as f32;
+        Some((
+            (x - self.x as f32) / zoom + origin_x as f32,
+            (y - self.y as f32) / zoom + origin_y as f32,
+        ))
+    }
+
+    /// The root console cell where the given world cell would be shown,
+    /// whether or not it's inside the rectangle. When zoomed in, this is the
+    /// top-left cell of those showing it.
+    pub fn world_to_console(&self, (x, y): (i32, i32)) -> (i32, i32) {
+        let (origin_x, origin_y) = self.origin();
+        let zoom = self.scale();
+        (
+            (x - origin_x) * zoom + self.x,
+            (y - origin_y) * zoom + self.y,
+        )
+    }
+
+    /// The world cell that would be shown at the given root console cell,
+    /// whether or not it's inside the rectangle.
+    pub fn console_to_world(&self, (x, y): (i32, i32)) -> (i32, i32) {
+        let (origin_x, origin_y) = self.origin();
+        let zoom = self.scale();
+        (
+            (x - self.x).div_euclid(zoom) + origin_x,
+            (y - self.y).div_euclid(zoom) + origin_y,
+        )
+    }
+
+    /// The root console cell the given world cell is shown in, or `None` if
+    /// the camera doesn't show it.
+    pub fn world_to_screen(&self, world: (i32, i32)) -> Option<(i32, i32)> {
+        let screen = self.world_to_console(world);
+        if self.rect().contains(screen) {
+            Some(screen)
+        } else {
+            None
+        }
+    }
+
+    /// Draws the given world cell on `console` where the camera shows it,
+    /// like [`Console::cell`], on every cell showing it when zoomed in.
+    /// Returns `false`, drawing nothing, if the camera doesn't show it.
+    pub fn cell(
+        &self,
+        console: &mut Console,
+        world: (i32, i32),
+        ascii: Option<u16>,
+        fore: Option<Color>,
+        back: Option<Color>,
+    ) -> bool {
+        let (x, y) = match self.world_to_screen(world) {
+            Some(screen) => screen,
+            None => return false,
+        };
+        let rect = self.rect();
+        for dy in 0..self.scale() {
+            for dx in 0..self.scale() {
+                if rect.contains((x + dx, y + dy)) {
+                    console.cell(x + dx, y + dy, ascii, fore, back);
+                }
+            }
+        }
+        true
+    }
+
+    /// Copies the part of `world` the camera shows into its rectangle of
+    /// `destination`. Cells outside the world are left alone.
+    pub fn draw(&self, world: &Console, destination: &mut Console) {
+        let (origin_x, origin_y) = self.origin();
+        let zoom = self.scale();
+        for dy in 0..self.height as i32 {
+            for dx in 0..self.width as i32 {
+                let (world_x, world_y) = (origin_x + dx / zoom, origin_y + dy / zoom);
+                let ascii = match world.get_ascii(world_x, world_y) {
+                    Some(ascii) => ascii,
+                    None => continue,
+                };
+                destination.cell(
+                    self.x + dx,
+                    self.y + dy,
+                    Some(ascii),
+                    world.get_fore(world_x, world_y),
+                    world.get_back(world_x, world_y),
+                );
+            }
+        }
+    }
+}
+
+/// Makes the [`ConsoleCamera`] on the same entity, or the [`ConsoleCamera`]
+/// resource if this is a resource, follow the target entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component, Resource)]
+pub struct CameraFollow(pub Entity);
+
+/// How a camera with a [`CameraFollow`] follows its target. Without these,
+/// the camera stays centered on the target.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Component, Resource)]
+pub struct CameraFollowOptions {
+    /// How far, in cells, the target can move away from the center of the
+    /// camera horizontally and vertically before the camera follows.
+    pub dead_zone: (u32, u32),
+    /// The width and height of the map, in cells, if the camera should stop
+    /// at its edges instead of showing past them. A map smaller than the
+    /// camera is kept centered.
+    pub map_size: Option<(u32, u32)>,
+}
+
+impl CameraFollowOptions {
+    /// Sets the dead zone.
+    pub fn with_dead_zone(mut self, width: u32, height: u32) -> Self {
+        self.dead_zone = (width, height);
+        self
+    }
+
+    /// Stops the camera at the edges of a `width` by `height` map.
+    pub fn within_map(mut self, width: u32, height: u32) -> Self {
+        self.map_size = Some((width, height));
+        self
+    }
+
+    /// Where `camera` should look to follow a target at `target`.
+    pub fn follow(&self, camera: &ConsoleCamera, target: (i32, i32)) -> (i32, i32) {
+        let follow_axis = |current: i32, wanted: i32, dead_zone: u32| {
+            let dead_zone = dead_zone as i32;
+            if wanted > current + dead_zone {
+                wanted - dead_zone
+            } else if wanted < current - dead_zone {
+                wanted + dead_zone
+            } else {
+                current
+            }
+        };
+        let mut center = (
+            follow_axis(camera.center.0, target.0, self.dead_zone.0),
+            follow_axis(camera.center.1, target.1, self.dead_zone.1),
+        );
+
+        if let Some((map_width, map_height)) = self.map_size {
+            let clamp_axis = |unclamped: i32, size: u32, map_size: u32| {
+                let (min, max) = (
+                    (size / 2) as i32,
+                    map_size as i32 - size as i32 + (size / 2) as i32,
+                );
+                if max < min {
+                    map_size as i32 / 2
+                } else {
+                    unclamped.clamp(min, max)
+                }
+            };
+            let (width, height) = camera.world_size();
+            center = (
+                clamp_axis(center.0, width, map_width),
+                clamp_axis(center.1, height, map_height),
+            );
+        }
+        center
+    }
+}
+
+/// Lets the mouse wheel zoom the [`ConsoleCamera`] on the same entity, or the
+/// [`ConsoleCamera`] resource if this is a resource, in and out while the
+/// mouse is over it. Scrolling up zooms in by one step per update, and
+/// scrolling down out, from showing the world cell for cell up to `max_zoom`
+/// by `max_zoom` cells for each world cell.
+///
+/// Only the browser and the terminal runner report the wheel; see
+/// [`Input::mouse_wheel_delta`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component, Resource)]
+pub struct WheelZoom {
+    /// The most cells a world cell can take up across and down.
+    pub max_zoom: u32,
+}
+
+impl Default for WheelZoom {
+    fn default() -> Self {
+        Self { max_zoom: 4 }
+    }
+}
+
+impl WheelZoom {
+    /// The zoom `camera` should have after the wheel was scrolled vertically
+    /// by `wheel` with the mouse at `mouse_cell`.
+    pub fn zoom(&self, camera: &ConsoleCamera, mouse_cell: (i32, i32), wheel: f32) -> u32 {
+        if wheel == 0.0 || !camera.rect().contains(mouse_cell) {
+            return camera.zoom;
+        }
+        let zoom = i64::from(camera.zoom.max(1)) + wheel.signum() as i64;
+        zoom.clamp(1, i64::from(self.max_zoom.max(1))) as u32
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn zoom_cameras(
+    input: Res<'_, Input>,
+    mut cameras: Query<'_, '_, (&mut ConsoleCamera, &WheelZoom)>,
+    main_camera: Option<ResMut<'_, ConsoleCamera>>,
+    main_zoom: Option<Res<'_, WheelZoom>>,
+) {
+    let (_, wheel) = input.mouse_wheel_delta();
+    if wheel == 0.0 {
+        return;
+    }
+    let mouse_cell = input.mouse_cell();
+
+    for (mut camera, wheel_zoom) in &mut cameras {
+        let zoom = wheel_zoom.zoom(&camera, mouse_cell, wheel);
+        if zoom != camera.zoom {
+            camera.zoom = zoom;
+        }
+    }
+    if let (Some(mut camera), Some(wheel_zoom)) = (main_camera, main_zoom) {
+        let zoom = wheel_zoom.zoom(&camera, mouse_cell, wheel);
+        if zoom != camera.zoom {
+            camera.zoom = zoom;
+        }
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn follow_targets(
+    mut cameras: Query<
+        '_,
+        '_,
+        (
+            &mut ConsoleCamera,
+            &CameraFollow,
+            Option<&CameraFollowOptions>,
+        ),
+    >,
+    targets: Query<'_, '_, AnyOf<(&RenderPosition, &GridPosition)>>,
+    main_camera: Option<ResMut<'_, ConsoleCamera>>,
+    main_follow: Option<Res<'_, CameraFollow>>,
+    main_options: Option<Res<'_, CameraFollowOptions>>,
+) {
+    let target_cell = |CameraFollow(target): CameraFollow| match targets.get(target) {
+        Ok((Some(render_position), _)) => Some(render_position.cell()),
+        Ok((None, Some(&GridPosition(x, y)))) => Some((x, y)),
+        _ => None,
+    };
+    // Only the cameras that move are marked as changed
+    let new_center =
+        |camera: &ConsoleCamera, follow: CameraFollow, options: Option<&CameraFollowOptions>| {
+            let target = target_cell(follow)?;
+            let center = options.copied().unwrap_or_default().follow(camera, target);
+            Some(center).filter(|&center| center != camera.center)
+        };
+
+    for (mut camera, &follow, options) in &mut cameras {
+        if let Some(center) = new_center(&camera, follow, options) {
+            camera.center = center;
+        }
+    }
+    if let (Some(mut camera), Some(follow)) = (main_camera, main_follow) {
+        if let Some(center) = new_center(&camera, *follow, main_options.as_deref()) {
+            camera.center = center;
+        }
+    }
+}
+
+type Camera<'a> = (
+    &'a ConsoleCamera,
+    Option<&'a CameraWorld>,
+    Option<&'a RenderLayer>,
+    Option<&'a RenderLayers>,
+);
+
+#[allow(clippy::needless_pass_by_value)]
+fn draw_cameras(
+    world: Res<'_, WorldConsole>,
+    cameras: Query<'_, '_, Camera<'_>>,
+    layers: Option<Res<'_, RenderLayers>>,
+    mut root_console: ResMut<'_, RootConsole>,
+) {
+    let mut cameras: Vec<_> = cameras
+        .iter()
+        .filter(|&(_, _, layer, camera_layers)| {
+            RenderLayers::shows(camera_layers.or(layers.as_deref()), layer)
+        })
+        .collect();
+    cameras.sort_by_key(|(camera, _, _, _)| camera.z_index);
+    for (camera, camera_world, _, _) in cameras {
+        let world = camera_world.map_or(&world.0, |camera_world| &camera_world.0);
+        camera.draw(world, &mut root_console);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::HeadlessInput;
+    use bevy_ecs::schedule::Schedule;
+    use bevy_ecs::world::World;
+
+    #[test]
+    fn follows_the_target_outside_the_dead_zone() {
+        let camera = ConsoleCamera::new(0, 0, 20, 10).looking_at(50, 50);
+        let options = CameraFollowOptions::default().with_dead_zone(3, 2);
+        assert_eq!(options.follow(&camera, (52, 48)), (50, 50));
+        assert_eq!(options.follow(&camera, (60, 40)), (57, 42));
+        assert_eq!(
+            CameraFollowOptions::default().follow(&camera, (60, 40)),
+            (60, 40)
+        );
+    }
+
+    #[test]
+    fn stops_at_the_edges_of_the_map() {
+        let camera = ConsoleCamera::new(0, 0, 20, 10);
+        let options = CameraFollowOptions::default().within_map(100, 50);
+        assert_eq!(options.follow(&camera, (2, 1)), (10, 5));
+        assert_eq!(options.follow(&camera, (99, 49)), (90, 45));
+        assert_eq!(options.follow(&camera, (40, 20)), (40, 20));
+
+        let origin = ConsoleCamera::new(0, 0, 20, 10).looking_at(90, 45).origin();
+        assert_eq!(origin, (80, 40));
+    }
+
+    #[test]
+    fn keeps_a_map_smaller_than_the_camera_centered() {
+        let camera = ConsoleCamera::new(0, 0, 20, 10);
+        let options = CameraFollowOptions::default().within_map(8, 6);
+        assert_eq!(options.follow(&camera, (0, 0)), (4, 3));
+        assert_eq!(options.follow(&camera, (7, 5)), (4, 3));
+    }
+
+    #[test]
+    fn odd_sizes_show_the_last_row_and_column() {
+        let camera = ConsoleCamera::new(0, 0, 21, 11);
+        let options = CameraFollowOptions::default().within_map(100, 50);
+        let center = options.follow(&camera, (99, 49));
+        let origin = camera.looking_at(center.0, center.1).origin();
+        assert_eq!((origin.0 + 20, origin.1 + 10), (99, 49));
+    }
+
+    #[test]
+    fn cameras_go_by_their_own_render_layers() {
+        const MINIMAP: RenderLayer = RenderLayer(1);
+        let mut world = World::new();
+        let mut world_console = Console::new(2, 1);
+        world_console.ascii(0, 0, u16::from(b'#'));
+        world_console.ascii(1, 0, u16::from(b'#'));
+        world.insert_resource(WorldConsole(world_console));
+        world.insert_resource(RootConsole(Some(Console::new(2, 1))));
+        let mut layers = RenderLayers::ALL;
+        layers.disable(MINIMAP);
+        world.insert_resource(layers);
+        world.spawn((ConsoleCamera::new(0, 0, 1, 1).looking_at(0, 0), MINIMAP));
+        world.spawn((
+            ConsoleCamera::new(1, 0, 1, 1).looking_at(1, 0),
+            MINIMAP,
+            RenderLayers::ALL,
+        ));
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(draw_cameras);
+        schedule.run(&mut world);
+        let row = crate::headless::console_row(world.resource::<RootConsole>(), 0);
+        assert_eq!(row, " #");
+    }
+
+    #[test]
+    fn zoomed_in_cameras_show_each_world_cell_as_a_square() {
+        let mut world = Console::new(2, 2);
+        world.ascii(0, 0, u16::from(b'a'));
+        world.ascii(1, 0, u16::from(b'b'));
+        world.ascii(0, 1, u16::from(b'c'));
+        world.ascii(1, 1, u16::from(b'd'));
+        let camera = ConsoleCamera::new(0, 0, 4, 3).looking_at(1, 1).with_zoom(2);
+        assert_eq!((camera.world_size(), camera.origin()), ((2, 2), (0, 0)));
+        assert_eq!(camera.world_to_console((1, 1)), (2, 2));
+        assert_eq!(camera.console_to_world((3, 2)), (1, 1));
+        assert_eq!(camera.screen_to_world((3.0, 1.0)), Some((1.5, 0.5)));
+
+        let mut root_console = Console::new(4, 3);
+        camera.draw(&world, &mut root_console);
+        let rows: Vec<_> = (0..3)
+            .map(|y| crate::headless::console_row(&root_console, y))
+            .collect();
+        assert_eq!(rows, ["aabb", "aabb", "ccdd"]);
+    }
+
+    #[test]
+    fn the_wheel_zooms_the_camera_under_the_mouse() {
+        let mut world = World::new();
+        world.init_resource::<Input>();
+        let left = world
+            .spawn((ConsoleCamera::new(0, 0, 10, 10), WheelZoom { max_zoom: 2 }))
+            .id();
+        let right = world
+            .spawn((ConsoleCamera::new(10, 0, 10, 10), WheelZoom::default()))
+            .id();
+        let mut schedule = Schedule::default();
+        schedule.add_systems(zoom_cameras);
+
+        let mut scroll = |ecs: &mut World, wheel: f32| {
+            let mut source = HeadlessInput::default();
+            source.set_mouse_pos((4.5, 4.5));
+            source.scroll_mouse_wheel((0.0, wheel));
+            ecs.resource_mut::<Input>().handle_input(&[], &mut source);
+            schedule.run(ecs);
+        };
+        let zoom = |ecs: &World, camera| ecs.get::<ConsoleCamera>(camera).unwrap().zoom;
+
+        scroll(&mut world, 1.0);
+        scroll(&mut world, 1.0);
+        assert_eq!((zoom(&world, left), zoom(&world, right)), (2, 1));
+        scroll(&mut world, -3.0);
+        assert_eq!(zoom(&world, left), 1);
+    }
+}
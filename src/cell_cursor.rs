@@ -0,0 +1,113 @@
+//! A cursor drawn on the console, in place of the mouse cursor.
+//!
+//! [`CellCursorPlugin`] highlights the cell under the mouse at the end of
+//! every frame, after everything else has been drawn, and hides the mouse
+//! cursor while it does. The [`CellCursor`] resource says what the highlight
+//! looks like:
+//!
+//! ```
+//! # use bevy_app::App;
+//! # use bevy_doryen::cell_cursor::{CellCursor, CellCursorPlugin};
+//! App::new()
+//!     .add_plugins(CellCursorPlugin)
+//!     .insert_resource(CellCursor {
+//!         glyph: Some(u16::from(b'X')),
+//!         fore: Some((255, 255, 0, 255)),
+//!         ..CellCursor::default()
+//!     });
+//! ```
+//!
+//! The mouse cursor can also be shown and hidden on its own, through
+//! [`DoryenRuntimeSettings::show_cursor`]. Only browsers support that, so
+//! elsewhere both cursors are visible.
+
+use crate::doryen::{color_blend, Color};
+use crate::{
+    DoryenRuntimeSettings, Input, PostRenderSet, RenderStage, RenderSystemExtensions, RootConsole,
+};
+use bevy_app::{App, Plugin, Update};
+use bevy_ecs::change_detection::DetectChanges;
+use bevy_ecs::schedule::IntoSystemConfigs;
+use bevy_ecs::system::{Res, ResMut, Resource};
+
+/// The cell cursor plugin. Adds the [`CellCursor`] resource, unless one has
+/// already been inserted, and draws it on the root console.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct CellCursorPlugin;
+
+impl Plugin for CellCursorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CellCursor>()
+            .add_systems(Update, sync_mouse_cursor)
+            .add_doryen_render_systems_to_stage(
+                RenderStage::PostRender,
+                draw_cell_cursor.in_set(PostRenderSet::Overlay),
+            );
+    }
+}
+
+/// Resource describing the cursor drawn at the cell under the mouse.
+#[derive(Debug, Clone, Copy, PartialEq, Resource)]
+pub struct CellCursor {
+    /// Whether the cursor is drawn. It's never drawn while the mouse is
+    /// outside the window, see [`Input::cursor_inside`].
+    pub visible: bool,
+    /// The glyph to draw, or `None` to keep the cell's own.
+    pub glyph: Option<u16>,
+    /// The foreground color to draw, or `None` to keep the cell's own.
+    pub fore: Option<Color>,
+    /// The color blended into the cell's background, as much as its alpha
+    /// says. `None` leaves the background alone.
+    pub highlight: Option<Color>,
+    /// Whether to hide the mouse cursor while this one is visible. Defaults
+    /// to `true`.
+    pub hide_mouse_cursor: bool,
+}
+
+impl Default for CellCursor {
+    fn default() -> Self {
+        Self {
+            visible: true,
+            glyph: None,
+            fore: None,
+            highlight: Some((255, 255, 255, 96)),
+            hide_mouse_cursor: true,
+        }
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn sync_mouse_cursor(
+    cell_cursor: Res<'_, CellCursor>,
+    runtime_settings: Option<ResMut<'_, DoryenRuntimeSettings>>,
+) {
+    if !cell_cursor.is_changed() {
+        return;
+    }
+    if let Some(mut runtime_settings) = runtime_settings {
+        let show_cursor = !(cell_cursor.visible && cell_cursor.hide_mouse_cursor);
+        if runtime_settings.show_cursor != show_cursor {
+            runtime_settings.show_cursor = show_cursor;
+        }
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn draw_cell_cursor(
+    cell_cursor: Res<'_, CellCursor>,
+    input: Res<'_, Input>,
+    mut root_console: ResMut<'_, RootConsole>,
+) {
+    if !cell_cursor.visible || !input.cursor_inside() {
+        return;
+    }
+    let (x, y) = input.mouse_cell();
+    let back = match (cell_cursor.highlight, root_console.get_back(x, y)) {
+        (Some(highlight), Some(back)) => {
+            let (r, g, b, _) = color_blend(back, highlight, f32::from(highlight.3) / 255.0);
+            Some((r, g, b, back.3))
+        }
+        _ => None,
+    };
+    root_console.cell(x, y, cell_cursor.glyph, cell_cursor.fore, back);
+}
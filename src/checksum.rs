@@ -0,0 +1,287 @@
+//! Checksums of the simulation state, for checking that it's deterministic.
+//!
+//! The components and resources registered with [`ChecksumAppExt`] are
+//! hashed together whenever [`ChecksumCommandsExt::checksum_turn`] runs,
+//! typically at the end of every turn. The result is kept in the
+//! [`StateChecksums`] resource and sent as a [`ChecksumComputed`] event.
+//! Recording the checksums along with a replay, and comparing them when the
+//! replay is played back, tells the turn where the two runs diverged:
+//!
+//! ```
+//! # use bevy_app::App;
+//! # use bevy_doryen::checksum::{ChecksumAppExt, ChecksumCommandsExt, ChecksumPlugin};
+//! # use bevy_ecs::component::Component;
+//! # use bevy_ecs::system::{Commands, Resource};
+//! #[derive(Hash, Component)]
+//! struct Position(i32, i32);
+//!
+//! #[derive(Hash, Default, Resource)]
+//! struct RngSeed(u64);
+//!
+//! fn end_turn(mut commands: Commands) {
+//!     commands.checksum_turn();
+//! }
+//!
+//! App::new()
+//!     .add_plugins(ChecksumPlugin)
+//!     .register_checksum_component::<Position>()
+//!     .register_checksum_resource::<RngSeed>();
+//! ```
+//!
+//! The checksums are stable between runs of the same build on the same kind
+//! of machine. Since [`Hash`] implementations hash numbers in the machine's
+//! byte order, and `usize` in the machine's word size, they can differ
+//! between kinds of machines.
+
+use bevy_app::{App as BevyApp, Plugin};
+use bevy_ecs::component::Component;
+use bevy_ecs::entity::Entity;
+use bevy_ecs::event::{Event, Events};
+use bevy_ecs::system::{Command, Commands, Resource};
+use bevy_ecs::world::{Mut, World};
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+/// The checksum plugin. Adds the [`StateChecksums`] resource, unless one has
+/// already been inserted, and the [`ChecksumComputed`] event.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct ChecksumPlugin;
+
+impl Plugin for ChecksumPlugin {
+    fn build(&self, app: &mut BevyApp) {
+        app.init_resource::<StateChecksums>()
+            .init_resource::<Registry>()
+            .add_event::<ChecksumComputed>();
+    }
+}
+
+/// A 64-bit FNV-1a hasher. Unlike the standard library's default hasher, its
+/// output is guaranteed not to change between Rust versions.
+struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    // Sizes are hashed as 64 bits wide, so lengths and indices hash the same
+    // on 32-bit targets, like the browser, as on 64-bit ones
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.write_i64(i as i64);
+    }
+}
+
+trait StateHasher: Send + Sync + 'static {
+    fn hash(&self, world: &mut World, hasher: &mut StableHasher);
+}
+
+struct ResourceHasher<R>(PhantomData<fn() -> R>);
+
+impl<R: Resource + Hash> StateHasher for ResourceHasher<R> {
+    fn hash(&self, world: &mut World, hasher: &mut StableHasher) {
+        world.get_resource::<R>().hash(hasher);
+    }
+}
+
+struct ComponentHasher<C>(PhantomData<fn() -> C>);
+
+impl<C: Component + Hash> StateHasher for ComponentHasher<C> {
+    fn hash(&self, world: &mut World, hasher: &mut StableHasher) {
+        // Sorted, so the order entities are stored in doesn't matter
+        let mut components: Vec<(Entity, &C)> = world.query::<(Entity, &C)>().iter(world).collect();
+        components.sort_unstable_by_key(|&(entity, _)| entity);
+        components.len().hash(hasher);
+        for (entity, component) in components {
+            entity.to_bits().hash(hasher);
+            component.hash(hasher);
+        }
+    }
+}
+
+/// The components and resources to hash.
+#[derive(Default, Resource)]
+struct Registry {
+    hashers: Vec<Box<dyn StateHasher>>,
+}
+
+/// Emitted when [`ChecksumCommandsExt::checksum_turn`] has computed a
+/// checksum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Event)]
+pub struct ChecksumComputed {
+    /// The number of the turn, counting from `0`.
+    pub turn: u64,
+    /// The checksum of the state at the end of the turn.
+    pub checksum: u64,
+}
+
+/// Resource holding the most recent checksums, oldest first. Once it's full,
+/// computing a checksum drops the oldest one.
+#[derive(Debug, Clone, Resource)]
+pub struct StateChecksums {
+    checksums: VecDeque<ChecksumComputed>,
+    capacity: usize,
+    next_turn: u64,
+}
+
+impl Default for StateChecksums {
+    fn default() -> Self {
+        Self::with_capacity(100)
+    }
+}
+
+impl StateChecksums {
+    /// Creates a resource holding up to `capacity` checksums.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            checksums: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+            next_turn: 0,
+        }
+    }
+
+    /// The most recent checksum.
+    pub fn latest(&self) -> Option<ChecksumComputed> {
+        self.checksums.back().copied()
+    }
+
+    /// The checksums held, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = ChecksumComputed> + '_ {
+        self.checksums.iter().copied()
+    }
+
+    /// The first turn where the held checksums differ from the `expected`
+    /// ones, recorded in an earlier run. Turns only one of them has a
+    /// checksum for are skipped.
+    pub fn first_divergence(
+        &self,
+        expected: impl IntoIterator<Item = ChecksumComputed>,
+    ) -> Option<u64> {
+        let mut expected = expected.into_iter().peekable();
+        for actual in &self.checksums {
+            while expected.peek().is_some_and(|e| e.turn < actual.turn) {
+                expected.next();
+            }
+            match expected.peek() {
+                Some(e) if e.turn == actual.turn && e.checksum != actual.checksum => {
+                    return Some(actual.turn);
+                }
+                Some(_) => (),
+                None => break,
+            }
+        }
+        None
+    }
+
+    /// Drops every checksum and starts counting turns from `0` again.
+    pub fn clear(&mut self) {
+        self.checksums.clear();
+        self.next_turn = 0;
+    }
+}
+
+/// Registration of the components and resources to hash.
+pub trait ChecksumAppExt {
+    /// Includes the resource `R` in checksums. Whether it exists counts too.
+    fn register_checksum_resource<R: Resource + Hash>(&mut self) -> &mut Self;
+    /// Includes every `C` component in checksums, along with the entity it's
+    /// on.
+    fn register_checksum_component<C: Component + Hash>(&mut self) -> &mut Self;
+}
+
+impl ChecksumAppExt for BevyApp {
+    fn register_checksum_resource<R: Resource + Hash>(&mut self) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(Registry::default)
+            .hashers
+            .push(Box::new(ResourceHasher::<R>(PhantomData)));
+        self
+    }
+
+    fn register_checksum_component<C: Component + Hash>(&mut self) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(Registry::default)
+            .hashers
+            .push(Box::new(ComponentHasher::<C>(PhantomData)));
+        self
+    }
+}
+
+/// Extension methods on [`Commands`] for computing checksums.
+pub trait ChecksumCommandsExt {
+    /// Computes the checksum of the registered components and resources as
+    /// they are once the command is applied, as the next turn's.
+    fn checksum_turn(&mut self);
+}
+
+impl ChecksumCommandsExt for Commands<'_, '_> {
+    fn checksum_turn(&mut self) {
+        self.add(ComputeChecksum);
+    }
+}
+
+struct ComputeChecksum;
+
+impl Command for ComputeChecksum {
+    fn apply(self, world: &mut World) {
+        let checksum = world.resource_scope(|world, registry: Mut<'_, Registry>| {
+            let mut hasher = StableHasher::default();
+            for state_hasher in &registry.hashers {
+                state_hasher.hash(world, &mut hasher);
+            }
+            hasher.finish()
+        });
+
+        let mut checksums = world.resource_mut::<StateChecksums>();
+        let computed = ChecksumComputed {
+            turn: checksums.next_turn,
+            checksum,
+        };
+        checksums.next_turn += 1;
+        if checksums.checksums.len() == checksums.capacity {
+            checksums.checksums.pop_front();
+        }
+        checksums.checksums.push_back(computed);
+        world
+            .resource_mut::<Events<ChecksumComputed>>()
+            .send(computed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_hash_the_same_on_every_width() {
+        let mut usize_hasher = StableHasher::default();
+        usize_hasher.write_usize(1234);
+        usize_hasher.write_isize(-5);
+        let mut u64_hasher = StableHasher::default();
+        u64_hasher.write_u64(1234);
+        u64_hasher.write_i64(-5);
+        assert_eq!(usize_hasher.finish(), u64_hasher.finish());
+
+        let mut u32_hasher = StableHasher::default();
+        u32_hasher.write_u32(1234);
+        u32_hasher.write_i32(-5);
+        assert_ne!(usize_hasher.finish(), u32_hasher.finish());
+    }
+}
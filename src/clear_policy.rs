@@ -0,0 +1,113 @@
+use crate::doryen::Color;
+use crate::RootConsole;
+use bevy_ecs::component::Component;
+use bevy_ecs::system::{ResMut, Resource};
+
+/// When a console gets cleared. See [`ClearPolicy`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ClearMode {
+    /// The console is cleared at the start of every render.
+    EveryFrame,
+    /// The console is only cleared at the start of the render following a
+    /// call to [`ClearPolicy::request_clear`].
+    OnDemand,
+    /// The console is never cleared automatically. This is the default, and
+    /// matches how Doryen itself behaves.
+    Persistent,
+}
+
+/// Describes when and how a console is automatically cleared.
+///
+/// When inserted as a resource, it applies to the [`RootConsole`]. As a
+/// component of an entity with an
+/// [`OffscreenConsole`](crate::compositor::OffscreenConsole), it applies to
+/// that console, as long as the
+/// [`CompositorPlugin`](crate::compositor::CompositorPlugin) is added. The
+/// clearing happens in the [`RenderStage::First`](crate::RenderStage::First)
+/// stage, so render systems in later stages always see the result.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Resource, Component)]
+pub struct ClearPolicy {
+    /// When the console gets cleared.
+    pub mode: ClearMode,
+    /// The foreground color to clear with. `None` leaves the foreground
+    /// color of each cell untouched.
+    pub fore: Option<Color>,
+    /// The background color to clear with. `None` leaves the background
+    /// color of each cell untouched.
+    pub back: Option<Color>,
+    /// The character to clear with. `None` leaves the character of each cell
+    /// untouched.
+    pub fillchar: Option<u16>,
+    requested: bool,
+}
+
+impl Default for ClearPolicy {
+    fn default() -> Self {
+        Self::persistent()
+    }
+}
+
+impl ClearPolicy {
+    #[inline]
+    fn with_mode(mode: ClearMode) -> Self {
+        Self {
+            mode,
+            fore: None,
+            back: None,
+            fillchar: Some(' ' as u16),
+            requested: false,
+        }
+    }
+
+    /// A policy that clears the console at the start of every render.
+    pub fn every_frame() -> Self {
+        Self::with_mode(ClearMode::EveryFrame)
+    }
+
+    /// A policy that only clears the console when asked to through
+    /// [`request_clear`](Self::request_clear).
+    pub fn on_demand() -> Self {
+        Self::with_mode(ClearMode::OnDemand)
+    }
+
+    /// A policy that never clears the console automatically.
+    pub fn persistent() -> Self {
+        Self::with_mode(ClearMode::Persistent)
+    }
+
+    /// Sets the colors and the character the console is cleared with.
+    pub fn with_clear_values(
+        mut self,
+        fore: Option<Color>,
+        back: Option<Color>,
+        fillchar: Option<u16>,
+    ) -> Self {
+        self.fore = fore;
+        self.back = back;
+        self.fillchar = fillchar;
+        self
+    }
+
+    /// Asks for the console to be cleared at the start of the next render.
+    /// This works regardless of the [`ClearMode`], so it can also be used to
+    /// reset an otherwise persistent console.
+    pub fn request_clear(&mut self) {
+        self.requested = true;
+    }
+
+    /// Whether the console should be cleared now. Consumes a pending clear
+    /// request.
+    pub(crate) fn take_should_clear(&mut self) -> bool {
+        let requested = std::mem::take(&mut self.requested);
+        requested || self.mode == ClearMode::EveryFrame
+    }
+}
+
+pub(crate) fn clear_root_console(
+    mut clear_policy: ResMut<'_, ClearPolicy>,
+    mut root_console: ResMut<'_, RootConsole>,
+) {
+    if clear_policy.take_should_clear() {
+        root_console.clear(clear_policy.fore, clear_policy.back, clear_policy.fillchar);
+    }
+}
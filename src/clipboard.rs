@@ -0,0 +1,165 @@
+//! Copying and pasting text through the system clipboard.
+//!
+//! [`ClipboardPlugin`] adds the [`Clipboard`] resource and turns the paste
+//! and copy shortcuts into events: a [`PasteEvent`] with the text on the
+//! clipboard when the player pastes, and a [`CopyRequested`] event when they
+//! copy, for the game to answer by putting text on the clipboard. A text
+//! field can handle both:
+//!
+//! ```
+//! # use bevy_doryen::clipboard::{Clipboard, CopyRequested, PasteEvent};
+//! # use bevy_ecs::event::EventReader;
+//! # use bevy_ecs::system::{Local, ResMut};
+//! fn text_field(
+//!     mut text: Local<String>,
+//!     mut clipboard: ResMut<Clipboard>,
+//!     mut pastes: EventReader<PasteEvent>,
+//!     mut copies: EventReader<CopyRequested>,
+//! ) {
+//!     for paste in pastes.iter() {
+//!         text.push_str(&paste.text);
+//!     }
+//!     if copies.iter().next().is_some() {
+//!         clipboard.set_text(text.clone());
+//!     }
+//! }
+//! ```
+//!
+//! With the `clipboard` feature enabled, the system clipboard is used
+//! through arboard. In a browser, the page's own copy and paste handling is
+//! used, and nothing more is needed. Anywhere else, the clipboard is only
+//! shared within the game.
+//!
+//! The shortcuts are Ctrl+C and Ctrl+V, or Cmd+C and Cmd+V on a Mac, see
+//! [`Modifiers::COMMAND`]. The terminal runner uses Ctrl+C to close the
+//! application, so only pasting works there.
+
+use crate::web;
+use crate::{Input, Modifiers};
+use bevy_app::{App, Plugin, PreUpdate};
+use bevy_ecs::event::{Event, EventWriter};
+use bevy_ecs::system::{Res, ResMut, Resource};
+
+/// The clipboard plugin. Adds the [`Clipboard`] resource, unless one has
+/// already been inserted, and the [`PasteEvent`] and [`CopyRequested`]
+/// events.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct ClipboardPlugin;
+
+impl Plugin for ClipboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Clipboard>()
+            .add_event::<PasteEvent>()
+            .add_event::<CopyRequested>()
+            .add_systems(PreUpdate, handle_clipboard_shortcuts);
+    }
+}
+
+/// Resource giving access to the clipboard. See the [module
+/// documentation](self) for which clipboard that is.
+#[derive(Default, Resource)]
+pub struct Clipboard {
+    text: Option<String>,
+    #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+    system: std::sync::Mutex<Option<arboard::Clipboard>>,
+}
+
+impl std::fmt::Debug for Clipboard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Clipboard")
+            .field("text", &self.text)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Clipboard {
+    /// Puts text on the clipboard.
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+        self.with_system(|system| system.set_text(text.as_str()));
+        web::write_clipboard(&text);
+        self.text = Some(text);
+    }
+
+    /// The text on the clipboard, if there is any.
+    ///
+    /// In a browser, reading the clipboard has to wait for the player's
+    /// permission, so this is the text last put on it by the game or pasted
+    /// into it. Listen to [`PasteEvent`]s instead to get what the player
+    /// pastes.
+    pub fn text(&mut self) -> Option<String> {
+        #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+        if let Some(text) = self.with_system(arboard::Clipboard::get_text) {
+            self.text = Some(text);
+        }
+        self.text.clone()
+    }
+
+    /// Runs `f` on the system clipboard, connecting to it first if needed.
+    /// Errors are logged.
+    #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+    fn with_system<T>(
+        &mut self,
+        f: impl FnOnce(&mut arboard::Clipboard) -> Result<T, arboard::Error>,
+    ) -> Option<T> {
+        let system = self
+            .system
+            .get_mut()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if system.is_none() {
+            match arboard::Clipboard::new() {
+                Ok(clipboard) => *system = Some(clipboard),
+                Err(error) => {
+                    bevy_utils::tracing::warn!("could not open the clipboard: {}", error);
+                    return None;
+                }
+            }
+        }
+        match f(system.as_mut()?) {
+            Ok(value) => Some(value),
+            Err(arboard::Error::ContentNotAvailable) => None,
+            Err(error) => {
+                bevy_utils::tracing::warn!("could not use the clipboard: {}", error);
+                None
+            }
+        }
+    }
+}
+
+/// Emitted at the start of the update when the player pasted text.
+#[derive(Debug, Clone, PartialEq, Eq, Event)]
+pub struct PasteEvent {
+    /// The pasted text.
+    pub text: String,
+}
+
+/// Emitted at the start of the update when the player pressed the copy
+/// shortcut. Answer it by putting the selected text on the [`Clipboard`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Event)]
+pub struct CopyRequested;
+
+#[allow(clippy::needless_pass_by_value)]
+fn handle_clipboard_shortcuts(
+    input: Res<'_, Input>,
+    mut clipboard: ResMut<'_, Clipboard>,
+    mut paste_events: EventWriter<'_, PasteEvent>,
+    mut copies: EventWriter<'_, CopyRequested>,
+) {
+    if input.key_with_modifiers("KeyC", Modifiers::COMMAND) {
+        copies.send(CopyRequested);
+    }
+
+    // Browsers paste on their own, and the text comes with the paste
+    let pasted = web::take_pasted();
+    if cfg!(target_arch = "wasm32") {
+        for text in pasted {
+            clipboard.text = Some(text.clone());
+            paste_events.send(PasteEvent { text });
+        }
+    } else if input.key_with_modifiers("KeyV", Modifiers::COMMAND) {
+        if let Some(text) = clipboard.text() {
+            paste_events.send(PasteEvent { text });
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use crate::Input;
+use bevy_app::AppExit;
+use bevy_ecs::event::{Event, EventReader, EventWriter};
+use bevy_ecs::system::Res;
+
+/// Emitted at the start of every update in which the player asked to close
+/// the window, i.e. whenever [`Input::close_requested`] is true.
+///
+/// Doryen only passes close requests on when the
+/// [`intercept_close_request`](crate::doryen::AppOptions::intercept_close_request)
+/// option is set; otherwise it closes the window right away. With it set, the
+/// window stays open until the app exits, which it can do by sending
+/// [`WindowCloseConfirmed`], e.g. after asking the player whether to save
+/// first. Sending [`WindowCloseCancelled`] instead lets other systems know
+/// the request was turned down.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Event)]
+pub struct CloseRequested;
+
+/// Send this event to accept a [`CloseRequested`] and exit the app at the end
+/// of the update.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Event)]
+pub struct WindowCloseConfirmed;
+
+/// Send this event to turn down a [`CloseRequested`]. bevy_doryen doesn't act
+/// on it; it's there so systems that reacted to the request, such as one
+/// showing a confirmation dialog, know it's been dealt with.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Event)]
+pub struct WindowCloseCancelled;
+
+#[allow(clippy::needless_pass_by_value)]
+pub(crate) fn emit_close_requested(
+    input: Res<'_, Input>,
+    mut close_requested: EventWriter<'_, CloseRequested>,
+) {
+    if input.close_requested() {
+        close_requested.send(CloseRequested);
+    }
+}
+
+pub(crate) fn exit_on_close_confirmed(
+    mut confirmed: EventReader<'_, '_, WindowCloseConfirmed>,
+    mut app_exit: EventWriter<'_, AppExit>,
+) {
+    if confirmed.iter().last().is_some() {
+        app_exit.send(AppExit);
+    }
+}
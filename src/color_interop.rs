@@ -0,0 +1,60 @@
+//! Converting between Bevy's [`Color`](BevyColor) and Doryen's colors.
+//!
+//! Doryen colors are `(r, g, b, a)` tuples of sRGB bytes, so neither side
+//! can implement [`From`] for the other. The [`ToDoryenColor`] and
+//! [`ToBevyColor`] traits do the conversions instead:
+//!
+//! ```
+//! # use bevy_doryen::color_interop::{ToBevyColor, ToDoryenColor};
+//! # use bevy_doryen::doryen::Color;
+//! # use bevy_render::color::Color as BevyColor;
+//! let orange_red: Color = (255, 69, 0, 255);
+//! assert_eq!(BevyColor::ORANGE_RED.to_doryen(), orange_red);
+//! assert_eq!(orange_red.to_bevy(), BevyColor::rgba_u8(255, 69, 0, 255));
+//!
+//! // Linear and HSL colors are converted to sRGB first, which makes a
+//! // linear half gray come out lighter than half
+//! let (r, g, b, _) = BevyColor::rgba_linear(0.5, 0.5, 0.5, 1.0).to_doryen();
+//! assert!(r == g && g == b && r > 128);
+//! ```
+//!
+//! Components are rounded to the nearest byte, and clamped to the range a
+//! byte holds, so HDR colors come out at full brightness. Only available
+//! with the `color_interop` feature.
+
+use crate::doryen::Color;
+use bevy_render::color::Color as BevyColor;
+
+/// Converting to a Doryen color. See the [module documentation](self).
+pub trait ToDoryenColor {
+    /// The Doryen color for this color, in sRGB.
+    fn to_doryen(&self) -> Color;
+}
+
+impl ToDoryenColor for BevyColor {
+    fn to_doryen(&self) -> Color {
+        let [r, g, b, a] = self.as_rgba_f32();
+        let byte = |component: f32| (component.clamp(0.0, 1.0) * 255.0).round() as u8;
+        (byte(r), byte(g), byte(b), byte(a))
+    }
+}
+
+/// Converting a Doryen color to a Bevy one. See the [module
+/// documentation](self).
+pub trait ToBevyColor {
+    /// The Bevy color for this color, as [`BevyColor::Rgba`].
+    fn to_bevy(&self) -> BevyColor;
+
+    /// The Bevy color for this color, as [`BevyColor::RgbaLinear`], for
+    /// blending and lighting maths.
+    fn to_bevy_linear(&self) -> BevyColor {
+        self.to_bevy().as_rgba_linear()
+    }
+}
+
+impl ToBevyColor for Color {
+    fn to_bevy(&self) -> BevyColor {
+        let &(r, g, b, a) = self;
+        BevyColor::rgba_u8(r, g, b, a)
+    }
+}
@@ -0,0 +1,700 @@
+//! Off-screen consoles as entities, composited onto the root console.
+//!
+//! Spawn an entity with an [`OffscreenConsole`] for every panel, popup or
+//! layer, and [`CompositorPlugin`] blits them all onto the root console at
+//! the end of every frame, lowest [`ZIndex`] first, so the ones with the
+//! highest end up on top:
+//!
+//! ```
+//! # use bevy_doryen::compositor::{ConsoleBundle, OffscreenConsole, ZIndex};
+//! # use bevy_doryen::doryen::{Console, TextAlign};
+//! # use bevy_ecs::system::{Commands, Query};
+//! fn spawn_sidebar(mut commands: Commands) {
+//!     commands.spawn(ConsoleBundle::new(Console::new(20, 45), 60, 0).with_z_index(ZIndex(1)));
+//! }
+//!
+//! fn draw_sidebar(mut consoles: Query<&mut OffscreenConsole>) {
+//!     for mut console in consoles.iter_mut() {
+//!         console.print(1, 1, "Inventory", TextAlign::Left, None, None);
+//!     }
+//! }
+//! ```
+//!
+//! An [`OffscreenConsole`] without a [`ConsolePosition`] is drawn at the
+//! top-left corner, one without a [`ZIndex`] at `ZIndex(0)`, and one without
+//! [`BlitOptions`] fully opaque. Consoles with the same [`ZIndex`] are drawn
+//! in an unspecified order, which stays the same from frame to frame as long
+//! as they aren't despawned; give overlapping consoles different
+//! [`ZIndex`]es to decide which ends up on top. A
+//! [`ConsoleVisibility::Hidden`] one isn't drawn at all, and neither is one
+//! whose [`RenderLayer`] is disabled in the [`RenderLayers`].
+//!
+//! [`ConsoleBundle`] holds all of these, and its constructors cover the
+//! usual layers, so a HUD covering the whole screen or a popup centered on it
+//! is a single [`Commands::spawn`](bevy_ecs::system::Commands::spawn) call:
+//!
+//! ```
+//! # use bevy_doryen::compositor::{BlitOptions, ConsoleBundle, ZIndex};
+//! # use bevy_doryen::doryen::Console;
+//! # use bevy_ecs::system::Commands;
+//! fn open_popup(mut commands: Commands) {
+//!     commands.spawn(ConsoleBundle::hud(80, 45, ZIndex(1)));
+//!     commands.spawn(
+//!         ConsoleBundle::centered(Console::new(30, 10), 80, 45, ZIndex(2))
+//!             .with_blit_options(BlitOptions::with_alpha(0.9)),
+//!     );
+//! }
+//! ```
+//!
+//! A [`BlendMode`] in the [`BlitOptions`] picks how a console's cells are
+//! combined with those underneath. Besides drawing over them, a console can
+//! brighten or darken them, for lighting, or change only their colors or
+//! only their glyphs, for tints and overlays. [`BlitOptions::blit`] does the
+//! same for consoles that aren't entities.
+//!
+//! Consoles that are drawn once and shown often, such as a static
+//! background or a panel used on several screens, can be kept in the
+//! [`RenderTargets`] resource instead, under a [`ConsoleHandle`] or a name.
+//! Systems draw into them whenever their content changes, blit them wherever
+//! they're needed, or spawn a [`SharedConsole`] to have the compositor do it:
+//!
+//! ```
+//! # use bevy_doryen::compositor::{RenderTargets, SharedConsole, ZIndex};
+//! # use bevy_doryen::doryen::TextAlign;
+//! # use bevy_ecs::system::{Commands, ResMut};
+//! fn draw_background(mut commands: Commands, mut targets: ResMut<RenderTargets>) {
+//!     let background = targets.cached("background", 80, 45, |console| {
+//!         console.print(40, 2, "The Caves", TextAlign::Center, None, None);
+//!     });
+//!     commands.spawn((SharedConsole(background), ZIndex(-1)));
+//! }
+//! ```
+//!
+//! The consoles are composited in the [`RenderStage::PostRender`] stage, in
+//! [`PostRenderSet::Compose`], so they should be drawn on in the
+//! [`RenderStage::Render`] stage, or in the Bevy update, and the root console
+//! in the [`RenderStage::Render`] stage shows underneath them. So do the
+//! views of any [`ConsoleCamera`](crate::camera::ConsoleCamera)s: a
+//! [`ZIndex`] only orders consoles among themselves, and every console is
+//! drawn over every camera.
+//!
+//! Give an [`OffscreenConsole`] a [`ClearPolicy`] to have it cleared at the
+//! start of the render, in the [`RenderStage::First`] stage, like the root
+//! console is. A console cleared that way is best drawn on in the render
+//! stages, since whatever the Bevy update drew on it is cleared too.
+
+use crate::doryen::{color_blend, Color, Console};
+use crate::{
+    ClearPolicy, PostRenderSet, RenderLayer, RenderLayers, RenderStage, RenderSystemExtensions,
+    RootConsole,
+};
+use bevy_app::{App, Plugin};
+use bevy_ecs::bundle::Bundle;
+use bevy_ecs::component::Component;
+use bevy_ecs::entity::Entity;
+use bevy_ecs::query::AnyOf;
+use bevy_ecs::schedule::IntoSystemConfigs;
+use bevy_ecs::system::{Query, Res, ResMut, Resource};
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+
+/// The compositor plugin. Blits every [`OffscreenConsole`] and
+/// [`SharedConsole`] onto the root console, clears those with a
+/// [`ClearPolicy`], and adds the [`RenderTargets`] resource, unless one has
+/// already been inserted.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct CompositorPlugin;
+
+impl Plugin for CompositorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RenderTargets>()
+            .add_doryen_render_systems_to_stage(RenderStage::First, clear_offscreen_consoles)
+            .add_doryen_render_systems_to_stage(
+                RenderStage::PostRender,
+                composite_consoles.in_set(PostRenderSet::Compose),
+            );
+    }
+}
+
+/// An off-screen console, blitted onto the root console by the
+/// [`CompositorPlugin`].
+#[derive(Component)]
+pub struct OffscreenConsole(pub Console);
+
+impl std::fmt::Debug for OffscreenConsole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OffscreenConsole")
+            .field("width", &self.0.get_width())
+            .field("height", &self.0.get_height())
+            .finish()
+    }
+}
+
+impl Deref for OffscreenConsole {
+    type Target = Console;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for OffscreenConsole {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Where the top-left corner of an [`OffscreenConsole`] goes on the root
+/// console, in cells. It can be partly or fully off the screen.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub struct ConsolePosition {
+    /// The x coordinate of the top-left corner.
+    pub x: i32,
+    /// The y coordinate of the top-left corner.
+    pub y: i32,
+}
+
+impl ConsolePosition {
+    /// A position with its top-left corner at `(x, y)`.
+    pub fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+}
+
+/// The order [`OffscreenConsole`]s are composited in. Consoles with a higher
+/// index are drawn over those with a lower one.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Component)]
+pub struct ZIndex(pub i32);
+
+/// Whether an [`OffscreenConsole`] is composited. Defaults to
+/// [`ConsoleVisibility::Visible`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub enum ConsoleVisibility {
+    /// The console is drawn.
+    #[default]
+    Visible,
+    /// The console is skipped, but keeps its content.
+    Hidden,
+}
+
+impl ConsoleVisibility {
+    /// Whether the console is drawn.
+    pub fn is_visible(self) -> bool {
+        self == Self::Visible
+    }
+
+    /// Switches between visible and hidden.
+    pub fn toggle(&mut self) {
+        *self = match self {
+            Self::Visible => Self::Hidden,
+            Self::Hidden => Self::Visible,
+        };
+    }
+}
+
+/// How an [`OffscreenConsole`] is blended onto the root console.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+pub struct BlitOptions {
+    /// How opaque the foreground is, from `0.0` to `1.0`.
+    pub fore_alpha: f32,
+    /// How opaque the background is, from `0.0` to `1.0`.
+    pub back_alpha: f32,
+    /// A background color to leave out, so the cells of that color show
+    /// what's underneath.
+    pub key_color: Option<Color>,
+    /// How the cells are combined with those underneath.
+    pub mode: BlendMode,
+}
+
+impl Default for BlitOptions {
+    fn default() -> Self {
+        Self {
+            fore_alpha: 1.0,
+            back_alpha: 1.0,
+            key_color: None,
+            mode: BlendMode::default(),
+        }
+    }
+}
+
+impl BlitOptions {
+    /// Blending with the same opacity for the foreground and the background.
+    pub fn with_alpha(alpha: f32) -> Self {
+        Self {
+            fore_alpha: alpha,
+            back_alpha: alpha,
+            ..Self::default()
+        }
+    }
+
+    /// Fully opaque blending in the given mode.
+    pub fn with_mode(mode: BlendMode) -> Self {
+        Self {
+            mode,
+            ..Self::default()
+        }
+    }
+
+    /// Blits `source` onto `destination` with its top-left corner at
+    /// `(x, y)`, blended as these options say.
+    pub fn blit(&self, source: &Console, x: i32, y: i32, destination: &mut Console) {
+        if self.mode == BlendMode::AlphaOver {
+            source.blit(
+                x,
+                y,
+                destination,
+                self.fore_alpha,
+                self.back_alpha,
+                self.key_color,
+            );
+            return;
+        }
+
+        let (fore_alpha, back_alpha) = (
+            self.fore_alpha.clamp(0.0, 1.0),
+            self.back_alpha.clamp(0.0, 1.0),
+        );
+        for source_y in 0..source.get_height() as i32 {
+            for source_x in 0..source.get_width() as i32 {
+                let (dest_x, dest_y) = (x + source_x, y + source_y);
+                let (back, dest_back) = match (
+                    source.get_back(source_x, source_y),
+                    destination.get_back(dest_x, dest_y),
+                ) {
+                    (Some(back), Some(dest_back)) => (back, dest_back),
+                    _ => continue,
+                };
+                if self.key_color == Some(back) {
+                    continue;
+                }
+                let fore = source.get_fore(source_x, source_y).unwrap_or(back);
+                let dest_fore = destination.get_fore(dest_x, dest_y).unwrap_or(dest_back);
+
+                let (glyph, fore, back) = match self.mode {
+                    BlendMode::AlphaOver | BlendMode::ColorOnly => (
+                        None,
+                        color_blend(dest_fore, fore, fore_alpha),
+                        color_blend(dest_back, back, back_alpha),
+                    ),
+                    BlendMode::Additive => (
+                        None,
+                        add(dest_fore, fore, fore_alpha),
+                        add(dest_back, back, back_alpha),
+                    ),
+                    BlendMode::Multiply => (
+                        None,
+                        multiply(dest_fore, fore, fore_alpha),
+                        multiply(dest_back, back, back_alpha),
+                    ),
+                    BlendMode::GlyphOnly => match source.get_ascii(source_x, source_y) {
+                        Some(glyph) if glyph != 0 && glyph != u16::from(b' ') => (
+                            Some(glyph),
+                            color_blend(dest_fore, fore, fore_alpha),
+                            dest_back,
+                        ),
+                        _ => continue,
+                    },
+                };
+                destination.cell(dest_x, dest_y, glyph, Some(fore), Some(back));
+            }
+        }
+    }
+}
+
+/// How the cells of an [`OffscreenConsole`] are combined with the cells
+/// underneath. The foreground and background are each weighed by their
+/// alpha in the [`BlitOptions`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlendMode {
+    /// The console is drawn over what's underneath, glyphs included, the
+    /// same as [`Console::blit`].
+    #[default]
+    AlphaOver,
+    /// The colors are added to those underneath, brightening them, for
+    /// lights. The glyphs underneath are kept.
+    Additive,
+    /// The colors underneath are multiplied by these, darkening them, for
+    /// shadows and tints. White leaves them as they are. The glyphs
+    /// underneath are kept.
+    Multiply,
+    /// The colors are drawn over those underneath, but the glyphs underneath
+    /// are kept.
+    ColorOnly,
+    /// The glyphs are drawn, in their foreground color, but the backgrounds
+    /// underneath are kept. Empty cells are skipped.
+    GlyphOnly,
+}
+
+/// `color` added to `base`, weighed by `amount`. Keeps the alpha of `base`.
+fn add(base: Color, color: Color, amount: f32) -> Color {
+    let channel =
+        |under: u8, over: u8| (f32::from(under) + f32::from(over) * amount).min(255.0) as u8;
+    (
+        channel(base.0, color.0),
+        channel(base.1, color.1),
+        channel(base.2, color.2),
+        base.3,
+    )
+}
+
+/// `base` multiplied by `color`, weighed by `amount`. Keeps the alpha of
+/// `base`.
+fn multiply(base: Color, color: Color, amount: f32) -> Color {
+    let channel = |under: u8, over: u8| {
+        let factor = 1.0 - amount + f32::from(over) / 255.0 * amount;
+        (f32::from(under) * factor) as u8
+    };
+    (
+        channel(base.0, color.0),
+        channel(base.1, color.1),
+        channel(base.2, color.2),
+        base.3,
+    )
+}
+
+/// The components of a composited console.
+#[derive(Debug, Bundle)]
+pub struct ConsoleBundle {
+    /// The console.
+    pub console: OffscreenConsole,
+    /// Where it's drawn.
+    pub position: ConsolePosition,
+    /// When it's drawn.
+    pub z_index: ZIndex,
+    /// Whether it's drawn.
+    pub visibility: ConsoleVisibility,
+    /// How it's drawn.
+    pub blit_options: BlitOptions,
+}
+
+impl ConsoleBundle {
+    /// A console drawn with its top-left corner at `(x, y)`.
+    pub fn new(console: Console, x: i32, y: i32) -> Self {
+        Self {
+            console: OffscreenConsole(console),
+            position: ConsolePosition::new(x, y),
+            z_index: ZIndex::default(),
+            visibility: ConsoleVisibility::default(),
+            blit_options: BlitOptions::default(),
+        }
+    }
+
+    /// An empty `width` by `height` layer covering the screen from its
+    /// top-left corner, for a HUD of that size. Only the cells drawn on show,
+    /// since those left at the default black background are keyed out.
+    pub fn hud(width: u32, height: u32, z_index: ZIndex) -> Self {
+        Self::new(Console::new(width, height), 0, 0)
+            .with_z_index(z_index)
+            .with_blit_options(BlitOptions {
+                key_color: Some((0, 0, 0, 255)),
+                ..BlitOptions::default()
+            })
+    }
+
+    /// A console centered on a `screen_width` by `screen_height` root
+    /// console, for a popup.
+    pub fn centered(
+        console: Console,
+        screen_width: u32,
+        screen_height: u32,
+        z_index: ZIndex,
+    ) -> Self {
+        let x = (screen_width as i32 - console.get_width() as i32) / 2;
+        let y = (screen_height as i32 - console.get_height() as i32) / 2;
+        Self::new(console, x, y).with_z_index(z_index)
+    }
+
+    /// Sets the order the console is drawn in.
+    pub fn with_z_index(mut self, z_index: ZIndex) -> Self {
+        self.z_index = z_index;
+        self
+    }
+
+    /// Sets whether the console is drawn.
+    pub fn with_visibility(mut self, visibility: ConsoleVisibility) -> Self {
+        self.visibility = visibility;
+        self
+    }
+
+    /// Spawns the console hidden.
+    pub fn hidden(self) -> Self {
+        self.with_visibility(ConsoleVisibility::Hidden)
+    }
+
+    /// Sets how the console is blended.
+    pub fn with_blit_options(mut self, blit_options: BlitOptions) -> Self {
+        self.blit_options = blit_options;
+        self
+    }
+}
+
+/// Identifies a console in the [`RenderTargets`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ConsoleHandle(u64);
+
+/// Resource holding off-screen consoles that outlive a frame, to draw into
+/// once and blit as often as needed. See the [module documentation](self).
+#[derive(Default, Resource)]
+pub struct RenderTargets {
+    consoles: HashMap<ConsoleHandle, Console>,
+    names: HashMap<String, ConsoleHandle>,
+    next_handle: u64,
+}
+
+impl std::fmt::Debug for RenderTargets {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RenderTargets")
+            .field("consoles", &self.consoles.len())
+            .field("names", &self.names.keys())
+            .finish()
+    }
+}
+
+impl RenderTargets {
+    /// Adds a console, returning its handle.
+    pub fn add(&mut self, console: Console) -> ConsoleHandle {
+        let handle = ConsoleHandle(self.next_handle);
+        self.next_handle += 1;
+        self.consoles.insert(handle, console);
+        handle
+    }
+
+    /// Adds a console under `name`, in place of any console already going by
+    /// that name, which keeps its handle.
+    pub fn insert(&mut self, name: impl Into<String>, console: Console) -> ConsoleHandle {
+        let name = name.into();
+        match self.names.get(&name) {
+            Some(&handle) if self.consoles.contains_key(&handle) => {
+                self.consoles.insert(handle, console);
+                handle
+            }
+            _ => {
+                let handle = self.add(console);
+                self.names.insert(name, handle);
+                handle
+            }
+        }
+    }
+
+    /// The console going by `name`. If there's none, a blank `width` by
+    /// `height` one is added and `draw` is called to fill it in, which makes
+    /// for content drawn only once.
+    pub fn cached(
+        &mut self,
+        name: &str,
+        width: u32,
+        height: u32,
+        draw: impl FnOnce(&mut Console),
+    ) -> ConsoleHandle {
+        if let Some(handle) = self.handle(name) {
+            return handle;
+        }
+        let mut console = Console::new(width, height);
+        draw(&mut console);
+        self.insert(name, console)
+    }
+
+    /// The handle of the console going by `name`.
+    pub fn handle(&self, name: &str) -> Option<ConsoleHandle> {
+        self.names
+            .get(name)
+            .copied()
+            .filter(|handle| self.consoles.contains_key(handle))
+    }
+
+    /// The console with the given handle.
+    pub fn get(&self, handle: ConsoleHandle) -> Option<&Console> {
+        self.consoles.get(&handle)
+    }
+
+    /// The console with the given handle, to draw into.
+    pub fn get_mut(&mut self, handle: ConsoleHandle) -> Option<&mut Console> {
+        self.consoles.get_mut(&handle)
+    }
+
+    /// The console going by `name`.
+    pub fn get_named(&self, name: &str) -> Option<&Console> {
+        self.handle(name).and_then(|handle| self.get(handle))
+    }
+
+    /// The console going by `name`, to draw into.
+    pub fn get_named_mut(&mut self, name: &str) -> Option<&mut Console> {
+        let handle = self.handle(name)?;
+        self.get_mut(handle)
+    }
+
+    /// Removes the console with the given handle, along with its name.
+    pub fn remove(&mut self, handle: ConsoleHandle) -> Option<Console> {
+        self.names.retain(|_, h| *h != handle);
+        self.consoles.remove(&handle)
+    }
+
+    /// Blits the console with the given handle onto `destination`, with its
+    /// top-left corner at `(x, y)`. Returns whether there was such a
+    /// console.
+    pub fn blit(
+        &self,
+        handle: ConsoleHandle,
+        x: i32,
+        y: i32,
+        destination: &mut Console,
+        options: &BlitOptions,
+    ) -> bool {
+        match self.get(handle) {
+            Some(console) => {
+                options.blit(console, x, y, destination);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// A console in the [`RenderTargets`], composited like an
+/// [`OffscreenConsole`]. Any number of entities can share the same console.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub struct SharedConsole(pub ConsoleHandle);
+
+/// What the compositor reads of each console entity.
+type Composited<'a> = (
+    Entity,
+    AnyOf<(&'a OffscreenConsole, &'a SharedConsole)>,
+    Option<&'a ConsolePosition>,
+    Option<&'a ZIndex>,
+    Option<&'a BlitOptions>,
+    Option<&'a ConsoleVisibility>,
+    Option<&'a RenderLayer>,
+);
+
+fn clear_offscreen_consoles(
+    mut consoles: Query<'_, '_, (&mut OffscreenConsole, &mut ClearPolicy)>,
+) {
+    for (mut console, mut clear_policy) in &mut consoles {
+        if clear_policy.take_should_clear() {
+            console.clear(clear_policy.fore, clear_policy.back, clear_policy.fillchar);
+        }
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub(crate) fn composite_consoles(
+    consoles: Query<'_, '_, Composited<'_>>,
+    targets: Res<'_, RenderTargets>,
+    layers: Option<Res<'_, RenderLayers>>,
+    mut root_console: ResMut<'_, RootConsole>,
+) {
+    let mut consoles: Vec<_> = consoles
+        .iter()
+        .filter(|&(_, _, _, _, _, visibility, layer)| {
+            visibility.is_none_or(|v| v.is_visible())
+                && RenderLayers::shows(layers.as_deref(), layer)
+        })
+        .collect();
+    consoles.sort_by_key(|&(entity, _, _, z_index, _, _, _)| {
+        (z_index.copied().unwrap_or_default(), entity)
+    });
+    for (_, (offscreen, shared), position, _, blit_options, _, _) in consoles {
+        let console = match (offscreen, shared) {
+            (Some(offscreen), _) => &offscreen.0,
+            (None, Some(shared)) => match targets.get(shared.0) {
+                Some(target) => target,
+                None => continue,
+            },
+            (None, None) => continue,
+        };
+        let position = position.copied().unwrap_or_default();
+        blit_options.copied().unwrap_or_default().blit(
+            console,
+            position.x,
+            position.y,
+            &mut root_console,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::schedule::Schedule;
+    use bevy_ecs::world::World;
+
+    fn glyph_console(glyph: u8) -> Console {
+        let mut console = Console::new(1, 1);
+        console.ascii(0, 0, u16::from(glyph));
+        console
+    }
+
+    fn composite(world: &mut World) -> String {
+        let mut schedule = Schedule::default();
+        schedule.add_systems(composite_consoles);
+        schedule.run(world);
+        crate::headless::console_row(world.resource::<RootConsole>(), 0)
+    }
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.insert_resource(RootConsole(Some(Console::new(3, 1))));
+        world.init_resource::<RenderTargets>();
+        world
+    }
+
+    #[test]
+    fn consoles_with_a_clear_policy_are_cleared() {
+        let mut world = world();
+        let cleared = world
+            .spawn((
+                OffscreenConsole(glyph_console(b'a')),
+                ClearPolicy::every_frame(),
+            ))
+            .id();
+        let kept = world.spawn(OffscreenConsole(glyph_console(b'b'))).id();
+        let mut schedule = Schedule::default();
+        schedule.add_systems(clear_offscreen_consoles);
+        schedule.run(&mut world);
+
+        let glyph = |entity| {
+            world
+                .get::<OffscreenConsole>(entity)
+                .unwrap()
+                .get_ascii(0, 0)
+        };
+        assert_eq!(glyph(cleared), Some(u16::from(b' ')));
+        assert_eq!(glyph(kept), Some(u16::from(b'b')));
+    }
+
+    #[test]
+    fn higher_z_indices_are_drawn_on_top() {
+        let mut world = world();
+        world.spawn((OffscreenConsole(glyph_console(b'b')), ZIndex(2)));
+        world.spawn((OffscreenConsole(glyph_console(b'a')), ZIndex(1)));
+        world.spawn((
+            OffscreenConsole(glyph_console(b'c')),
+            ConsolePosition::new(1, 0),
+            ZIndex(1),
+        ));
+        world.spawn((
+            OffscreenConsole(glyph_console(b'd')),
+            ConsolePosition::new(1, 0),
+        ));
+
+        assert_eq!(composite(&mut world), "bc ");
+    }
+
+    #[test]
+    fn hidden_consoles_and_missing_targets_are_skipped() {
+        let mut world = world();
+        let mut targets = RenderTargets::default();
+        let shared = targets.add(glyph_console(b's'));
+        let removed = targets.add(glyph_console(b'r'));
+        targets.remove(removed);
+        world.insert_resource(targets);
+
+        world.spawn((SharedConsole(shared), ConsolePosition::new(2, 0)));
+        world.spawn((SharedConsole(removed), ZIndex(1)));
+        world.spawn(ConsoleBundle::new(glyph_console(b'h'), 2, 0).hidden());
+        world.spawn(SharedConsole(shared));
+        assert_eq!(composite(&mut world), "s s");
+    }
+}
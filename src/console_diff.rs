@@ -0,0 +1,225 @@
+use crate::doryen::Color;
+use crate::FrameSnapshot;
+use std::fmt;
+
+/// The contents of a single console cell.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellContents {
+    /// The glyph in the cell.
+    pub glyph: u16,
+    /// The foreground color of the cell.
+    pub fore: Color,
+    /// The background color of the cell.
+    pub back: Color,
+}
+
+impl FrameSnapshot {
+    /// The contents of cell `(x, y)`, if it's inside the snapshot.
+    pub fn cell(&self, x: u32, y: u32) -> Option<CellContents> {
+        let index = self.index(x, y)?;
+        Some(CellContents {
+            glyph: self.glyphs[index],
+            fore: self.fore[index],
+            back: self.back[index],
+        })
+    }
+}
+
+/// A cell that differs between two console snapshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellChange {
+    /// The x coordinate of the cell.
+    pub x: u32,
+    /// The y coordinate of the cell.
+    pub y: u32,
+    /// The cell in the old snapshot, or `None` if the old snapshot didn't
+    /// reach this far.
+    pub before: Option<CellContents>,
+    /// The cell in the new snapshot, or `None` if the new snapshot doesn't
+    /// reach this far.
+    pub after: Option<CellContents>,
+}
+
+/// The differences between two console snapshots, as computed by
+/// [`ConsoleDiff::between`].
+///
+/// Changes are listed row by row, from the top left. The [`Display`]
+/// implementation prints one line per changed cell, which makes for readable
+/// failures in snapshot tests:
+///
+/// ```
+/// # use bevy_doryen::{ConsoleDiff, FrameSnapshot};
+/// # let expected = FrameSnapshot::default();
+/// # let actual = FrameSnapshot::default();
+/// let diff = ConsoleDiff::between(&expected, &actual);
+/// assert!(diff.is_empty(), "console differs:\n{}", diff);
+/// ```
+///
+/// [`Display`]: fmt::Display
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct ConsoleDiff {
+    size_before: (u32, u32),
+    size_after: (u32, u32),
+    changes: Vec<CellChange>,
+}
+
+impl ConsoleDiff {
+    /// Compares two snapshots cell by cell. When their sizes differ, the
+    /// cells covered by only one of them count as changed.
+    pub fn between(before: &FrameSnapshot, after: &FrameSnapshot) -> Self {
+        let width = before.width.max(after.width);
+        let height = before.height.max(after.height);
+
+        let mut changes = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                let (old, new) = (before.cell(x, y), after.cell(x, y));
+                if old != new {
+                    changes.push(CellChange {
+                        x,
+                        y,
+                        before: old,
+                        after: new,
+                    });
+                }
+            }
+        }
+
+        Self {
+            size_before: (before.width, before.height),
+            size_after: (after.width, after.height),
+            changes,
+        }
+    }
+
+    /// Whether the snapshots are identical.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty() && !self.size_changed()
+    }
+
+    /// Whether the snapshots have different sizes.
+    pub fn size_changed(&self) -> bool {
+        self.size_before != self.size_after
+    }
+
+    /// The changed cells, row by row.
+    pub fn changes(&self) -> &[CellChange] {
+        &self.changes
+    }
+
+    /// The number of changed cells.
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+}
+
+struct DisplayCell(Option<CellContents>);
+
+impl fmt::Display for DisplayCell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cell = match self.0 {
+            Some(cell) => cell,
+            None => return f.write_str("(none)"),
+        };
+        match cell.glyph {
+            32..=126 => write!(f, "{:?}", char::from(cell.glyph as u8))?,
+            glyph => write!(f, "#{}", glyph)?,
+        }
+        let (fr, fg, fb, fa) = cell.fore;
+        let (br, bg, bb, ba) = cell.back;
+        write!(
+            f,
+            " {:02x}{:02x}{:02x}{:02x}/{:02x}{:02x}{:02x}{:02x}",
+            fr, fg, fb, fa, br, bg, bb, ba
+        )
+    }
+}
+
+impl fmt::Display for ConsoleDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.size_changed() {
+            writeln!(
+                f,
+                "size: {}x{} -> {}x{}",
+                self.size_before.0, self.size_before.1, self.size_after.0, self.size_after.1
+            )?;
+        }
+        for change in &self.changes {
+            writeln!(
+                f,
+                "({}, {}): {} -> {}",
+                change.x,
+                change.y,
+                DisplayCell(change.before),
+                DisplayCell(change.after)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doryen::{Console, TextAlign};
+
+    const WHITE: Color = (255, 255, 255, 255);
+    const BLACK: Color = (0, 0, 0, 255);
+
+    fn snapshot(width: u32, height: u32, text: &str) -> FrameSnapshot {
+        let mut console = Console::new(width, height);
+        console.clear(Some(WHITE), Some(BLACK), Some(u16::from(b' ')));
+        console.print(0, 0, text, TextAlign::Left, None, None);
+        FrameSnapshot::capture(&console)
+    }
+
+    #[test]
+    fn identical_snapshots_have_no_changes() {
+        let diff = ConsoleDiff::between(&snapshot(4, 2, "ab"), &snapshot(4, 2, "ab"));
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "");
+    }
+
+    #[test]
+    fn changed_cells_are_listed_row_by_row() {
+        let before = snapshot(4, 2, "ab");
+        let mut after = snapshot(4, 2, "xb");
+        let last = after.glyphs.len() - 1;
+        after.glyphs[last] = u16::from(b'z');
+
+        let diff = ConsoleDiff::between(&before, &after);
+        assert!(!diff.size_changed());
+        let cells: Vec<_> = diff
+            .changes()
+            .iter()
+            .map(|change| (change.x, change.y))
+            .collect();
+        assert_eq!(cells, [(0, 0), (3, 1)]);
+        assert_eq!(
+            diff.changes()[0].after,
+            Some(CellContents {
+                glyph: u16::from(b'x'),
+                fore: WHITE,
+                back: BLACK,
+            })
+        );
+        assert_eq!(
+            diff.to_string(),
+            "(0, 0): 'a' ffffffff/000000ff -> 'x' ffffffff/000000ff\n\
+             (3, 1): ' ' ffffffff/000000ff -> 'z' ffffffff/000000ff\n"
+        );
+    }
+
+    #[test]
+    fn cells_outside_one_snapshot_count_as_changed() {
+        let diff = ConsoleDiff::between(&snapshot(2, 1, ""), &snapshot(3, 1, ""));
+        assert!(diff.size_changed());
+        assert_eq!(diff.len(), 1);
+        let change = diff.changes()[0];
+        assert_eq!((change.x, change.y), (2, 0));
+        assert_eq!(change.before, None);
+        assert!(diff
+            .to_string()
+            .starts_with("size: 2x1 -> 3x1\n(2, 0): (none) -> "));
+    }
+}
@@ -0,0 +1,210 @@
+//! Immediate-mode debug drawing on top of the root console.
+//!
+//! Systems queue up shapes on the [`DebugDraw`] resource, and they are drawn
+//! over everything else at the end of each render. The queue is emptied at the
+//! start of every update, so shapes have to be queued again each update to
+//! stay visible, which makes it easy to visualize things like AI decisions or
+//! collision data while they change:
+//!
+//! ```
+//! # use bevy_doryen::debug_draw::DebugDraw;
+//! # use bevy_ecs::system::ResMut;
+//! fn show_path(mut debug_draw: ResMut<DebugDraw>) {
+//!     debug_draw.arrow((2, 2), (10, 6), (255, 255, 0, 255));
+//!     debug_draw.rect((9, 5), (3, 3), (255, 0, 0, 255), Some("goal"));
+//! }
+//! ```
+
+use crate::doryen::{color_blend, Color, TextAlign};
+use crate::{geometry, RenderStage, RenderSystemExtensions, RootConsole};
+use bevy_app::{App as BevyApp, First, Plugin};
+use bevy_ecs::schedule::IntoSystemConfigs;
+use bevy_ecs::system::{Res, ResMut, Resource};
+
+/// The debug drawing plugin.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct DebugDrawPlugin;
+
+impl Plugin for DebugDrawPlugin {
+    fn build(&self, app: &mut BevyApp) {
+        app.init_resource::<DebugDraw>()
+            .add_systems(First, clear_debug_draw)
+            .add_doryen_render_systems_to_stage(
+                RenderStage::Last,
+                render_debug_draw.before(crate::render_error::render_error_overlay),
+            );
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Shape {
+    Cell {
+        position: (i32, i32),
+        color: Color,
+    },
+    Rect {
+        position: (i32, i32),
+        size: (u32, u32),
+        color: Color,
+        label: Option<String>,
+    },
+    Arrow {
+        from: (i32, i32),
+        to: (i32, i32),
+        color: Color,
+    },
+    Text {
+        position: (i32, i32),
+        text: String,
+        color: Color,
+    },
+}
+
+/// Resource queueing debug shapes to draw on top of the root console. See the
+/// [module documentation](self).
+#[derive(Debug, Resource)]
+pub struct DebugDraw {
+    /// Whether queued shapes are drawn at all. Shapes are still queued and
+    /// cleared when this is `false`, so it can be toggled at any time.
+    /// Defaults to `true`.
+    pub enabled: bool,
+    /// How strongly highlights are blended into the background of a cell,
+    /// from `0.0` to `1.0`. Defaults to `0.6`.
+    pub highlight_alpha: f32,
+    shapes: Vec<Shape>,
+}
+
+impl Default for DebugDraw {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            highlight_alpha: 0.6,
+            shapes: Vec::new(),
+        }
+    }
+}
+
+impl DebugDraw {
+    /// Tints the background of a single cell.
+    pub fn highlight_cell(&mut self, position: (i32, i32), color: Color) {
+        self.shapes.push(Shape::Cell { position, color });
+    }
+
+    /// Draws the outline of a rectangle whose top left corner is at
+    /// `position`, with an optional label along its top edge.
+    pub fn rect(
+        &mut self,
+        position: (i32, i32),
+        size: (u32, u32),
+        color: Color,
+        label: Option<&str>,
+    ) {
+        self.shapes.push(Shape::Rect {
+            position,
+            size,
+            color,
+            label: label.map(String::from),
+        });
+    }
+
+    /// Draws an arrow from one cell to another.
+    pub fn arrow(&mut self, from: (i32, i32), to: (i32, i32), color: Color) {
+        self.shapes.push(Shape::Arrow { from, to, color });
+    }
+
+    /// Prints text starting at the given cell.
+    pub fn text(&mut self, position: (i32, i32), text: &str, color: Color) {
+        self.shapes.push(Shape::Text {
+            position,
+            text: String::from(text),
+            color,
+        });
+    }
+
+    /// Removes all queued shapes.
+    pub fn clear(&mut self) {
+        self.shapes.clear();
+    }
+}
+
+fn clear_debug_draw(mut debug_draw: ResMut<'_, DebugDraw>) {
+    debug_draw.clear();
+}
+
+/// The arrowhead glyph pointing from `from` towards `to`.
+fn arrowhead(from: (i32, i32), to: (i32, i32)) -> u16 {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    if dx.abs() >= dy.abs() {
+        if dx >= 0 {
+            26
+        } else {
+            27
+        }
+    } else if dy >= 0 {
+        25
+    } else {
+        24
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn render_debug_draw(debug_draw: Res<'_, DebugDraw>, mut root_console: ResMut<'_, RootConsole>) {
+    if !debug_draw.enabled {
+        return;
+    }
+
+    for shape in &debug_draw.shapes {
+        match shape {
+            &Shape::Cell { position, color } => {
+                let (x, y) = position;
+                if let Some(back) = root_console.get_back(x, y) {
+                    root_console.back(x, y, color_blend(back, color, debug_draw.highlight_alpha));
+                }
+            }
+            Shape::Rect {
+                position,
+                size,
+                color,
+                label,
+            } => {
+                let (x, y) = *position;
+                let (w, h) = (size.0 as i32, size.1 as i32);
+                if w == 0 || h == 0 {
+                    continue;
+                }
+                let (right, bottom) = (x + w - 1, y + h - 1);
+                for cx in x..=right {
+                    root_console.cell(cx, y, Some(196), Some(*color), None);
+                    root_console.cell(cx, bottom, Some(196), Some(*color), None);
+                }
+                for cy in y..=bottom {
+                    root_console.cell(x, cy, Some(179), Some(*color), None);
+                    root_console.cell(right, cy, Some(179), Some(*color), None);
+                }
+                root_console.cell(x, y, Some(218), Some(*color), None);
+                root_console.cell(right, y, Some(191), Some(*color), None);
+                root_console.cell(x, bottom, Some(192), Some(*color), None);
+                root_console.cell(right, bottom, Some(217), Some(*color), None);
+                if let Some(label) = label {
+                    root_console.print(x + 1, y, label, TextAlign::Left, Some(*color), None);
+                }
+            }
+            &Shape::Arrow { from, to, color } => {
+                let cells = geometry::line(from, to);
+                for &(x, y) in &cells[..cells.len() - 1] {
+                    root_console.cell(x, y, Some(250), Some(color), None);
+                }
+                let (x, y) = to;
+                root_console.cell(x, y, Some(arrowhead(from, to)), Some(color), None);
+            }
+            Shape::Text {
+                position,
+                text,
+                color,
+            } => {
+                let (x, y) = *position;
+                root_console.print(x, y, text, TextAlign::Left, Some(*color), None);
+            }
+        }
+    }
+}
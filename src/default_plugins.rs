@@ -0,0 +1,53 @@
+//! A plugin group for getting a new project off the ground.
+
+use crate::DoryenPlugin;
+use bevy_app::{PluginGroup, PluginGroupBuilder};
+use bevy_core::{FrameCountPlugin, TaskPoolPlugin, TypeRegistrationPlugin};
+use bevy_diagnostic::DiagnosticsPlugin;
+use bevy_log::LogPlugin;
+use bevy_time::TimePlugin;
+
+/// The [`DoryenPlugin`] together with the Bevy plugins most games need
+/// alongside it, the way Bevy's `DefaultPlugins` bundles its own:
+///
+/// * [`LogPlugin`]
+/// * [`TaskPoolPlugin`]
+/// * [`TypeRegistrationPlugin`]
+/// * [`FrameCountPlugin`]
+/// * [`TimePlugin`]
+/// * [`DiagnosticsPlugin`]
+/// * [`DoryenPlugin`]
+/// * [`InputInteropPlugin`](crate::input_interop::InputInteropPlugin), with
+///   the `input_interop` feature enabled
+/// * [`GamepadPlugin`](crate::gamepad::GamepadPlugin), with the `gamepad`
+///   feature enabled
+///
+/// ```no_run
+/// # use bevy_app::App;
+/// # use bevy_doryen::default_plugins::DoryenDefaultPlugins;
+/// App::new().add_plugins(DoryenDefaultPlugins).run();
+/// ```
+///
+/// Individual plugins can be left out or configured with the usual
+/// [`PluginGroupBuilder`] methods, e.g.
+/// `DoryenDefaultPlugins.build().disable::<LogPlugin>()`.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct DoryenDefaultPlugins;
+
+impl PluginGroup for DoryenDefaultPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        let group = PluginGroupBuilder::start::<Self>()
+            .add(LogPlugin::default())
+            .add(TaskPoolPlugin::default())
+            .add(TypeRegistrationPlugin)
+            .add(FrameCountPlugin)
+            .add(TimePlugin)
+            .add(DiagnosticsPlugin)
+            .add(DoryenPlugin);
+        #[cfg(feature = "input_interop")]
+        let group = group.add(crate::input_interop::InputInteropPlugin);
+        #[cfg(feature = "gamepad")]
+        let group = group.add(crate::gamepad::GamepadPlugin);
+        group
+    }
+}
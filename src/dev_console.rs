@@ -0,0 +1,471 @@
+//! A drop-down developer console for typing commands while the game runs.
+//!
+//! The backquote key opens the console over the top of the screen. Commands
+//! typed into it are run by the handlers registered for their first word,
+//! which get the rest of the words and the whole [`World`], and answer with
+//! a line of output or an error:
+//!
+//! ```
+//! # use bevy_app::App;
+//! # use bevy_doryen::dev_console::{DevConsoleAppExt, DevConsolePlugin};
+//! # use bevy_ecs::system::Resource;
+//! #[derive(Default, Resource)]
+//! struct Gold(u32);
+//!
+//! App::new()
+//!     .add_plugins(DevConsolePlugin)
+//!     .init_resource::<Gold>()
+//!     .register_dev_command("give", |world, args| {
+//!         let amount: u32 = args
+//!             .first()
+//!             .and_then(|amount| amount.parse().ok())
+//!             .ok_or("usage: give <amount>")?;
+//!         world.resource_mut::<Gold>().0 += amount;
+//!         Ok(format!("Gave {} gold", amount))
+//!     });
+//! ```
+//!
+//! Up and down go through the commands typed before, and Tab completes the
+//! name of a command, listing the candidates when there are several. `help`
+//! lists every command and `clear` empties the output, unless commands of
+//! those names were registered.
+//!
+//! The console is drawn in the [`RenderStage::Last`] stage, on top of
+//! everything else. While it's open, it [captures](InputCapture) input, so
+//! typing a command doesn't also move the player or type into a text field
+//! beneath it. Systems that shouldn't run at all while it's open can check
+//! with the [`dev_console_closed`] run condition. Only available with the
+//! `dev_console` feature.
+
+use crate::doryen::{Color, Console, TextAlign};
+use crate::{Input, InputCapture, Key, RenderStage, RenderSystemExtensions, RootConsole};
+use bevy_app::{App as BevyApp, Plugin, PreUpdate};
+use bevy_ecs::schedule::IntoSystemConfigs;
+use bevy_ecs::system::{Res, ResMut, Resource};
+use bevy_ecs::world::{Mut, World};
+use std::collections::{BTreeMap, VecDeque};
+
+/// The most lines of output the console keeps.
+const OUTPUT_LIMIT: usize = 200;
+
+/// The owner of the [`InputCapture`] taken while the console is open.
+const CAPTURE_OWNER: &str = "bevy_doryen::dev_console";
+
+/// The developer console plugin. Adds the [`DevConsole`] resource, unless
+/// one has already been inserted.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct DevConsolePlugin;
+
+impl Plugin for DevConsolePlugin {
+    fn build(&self, app: &mut BevyApp) {
+        app.init_resource::<DevConsole>()
+            .init_resource::<InputCapture>()
+            .add_systems(PreUpdate, run_dev_console)
+            .add_doryen_render_systems_to_stage(
+                RenderStage::Last,
+                render_dev_console
+                    .after(crate::render_error::render_error_overlay)
+                    .before(crate::dirty_regions::track_dirty_regions),
+            );
+    }
+}
+
+/// The function run for a command. It gets the words typed after the
+/// command's name, and returns the line to print, which can be empty, or
+/// what went wrong. The [`DevConsole`] resource itself isn't in the world
+/// while it runs.
+pub type DevCommandHandler =
+    Box<dyn FnMut(&mut World, &[&str]) -> Result<String, String> + Send + Sync>;
+
+/// Resource holding the state of the developer console and its commands.
+#[derive(Resource)]
+pub struct DevConsole {
+    /// The key that opens and closes the console.
+    pub toggle_key: String,
+    /// How many rows of the root console the open console covers.
+    pub height: u32,
+    /// The foreground color of the console.
+    pub fore: Color,
+    /// The background color of the console.
+    pub back: Color,
+    /// The color of lines of output that are errors.
+    pub error_fore: Color,
+    open: bool,
+    line: String,
+    cursor: usize,
+    history: Vec<String>,
+    browsing: Option<usize>,
+    output: VecDeque<(String, bool)>,
+    commands: BTreeMap<String, DevCommandHandler>,
+    queued: Vec<String>,
+}
+
+impl Default for DevConsole {
+    fn default() -> Self {
+        Self {
+            toggle_key: "Backquote".to_owned(),
+            height: 12,
+            fore: (192, 192, 192, 255),
+            back: (16, 16, 32, 255),
+            error_fore: (255, 96, 96, 255),
+            open: false,
+            line: String::new(),
+            cursor: 0,
+            history: Vec::new(),
+            browsing: None,
+            output: VecDeque::new(),
+            commands: BTreeMap::new(),
+            queued: Vec::new(),
+        }
+    }
+}
+
+impl std::fmt::Debug for DevConsole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DevConsole")
+            .field("open", &self.open)
+            .field("line", &self.line)
+            .field("commands", &self.commands.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl DevConsole {
+    /// Runs `handler` for commands whose first word is `name`, replacing
+    /// any handler already registered for it.
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        handler: impl FnMut(&mut World, &[&str]) -> Result<String, String> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.commands.insert(name.into(), Box::new(handler));
+        self
+    }
+
+    /// Forgets the command called `name`. Returns whether there was one.
+    pub fn unregister(&mut self, name: &str) -> bool {
+        self.commands.remove(name).is_some()
+    }
+
+    /// The names of the registered commands, in alphabetical order.
+    pub fn commands(&self) -> impl Iterator<Item = &str> + '_ {
+        self.commands.keys().map(String::as_str)
+    }
+
+    /// Whether the console is open.
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Opens the console.
+    pub fn open(&mut self) {
+        self.open = true;
+    }
+
+    /// Closes the console.
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    /// Opens the console if it's closed, and closes it if it's open.
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+    }
+
+    /// Adds a line to the output.
+    pub fn print(&mut self, line: impl Into<String>) {
+        self.push_output(&line.into(), false);
+    }
+
+    /// Adds a line to the output, in the error color.
+    pub fn print_error(&mut self, line: impl Into<String>) {
+        self.push_output(&line.into(), true);
+    }
+
+    /// Empties the output.
+    pub fn clear_output(&mut self) {
+        self.output.clear();
+    }
+
+    /// The commands typed so far, oldest first.
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Runs `command` at the start of the next update, as if it had been
+    /// typed in.
+    pub fn run(&mut self, command: impl Into<String>) {
+        self.queued.push(command.into());
+    }
+
+    fn push_output(&mut self, text: &str, error: bool) {
+        for line in text.lines() {
+            if self.output.len() >= OUTPUT_LIMIT {
+                self.output.pop_front();
+            }
+            self.output.push_back((line.to_owned(), error));
+        }
+    }
+
+    fn byte_index(&self, chars: usize) -> usize {
+        self.line
+            .char_indices()
+            .nth(chars)
+            .map_or(self.line.len(), |(i, _)| i)
+    }
+
+    fn set_line(&mut self, line: String) {
+        self.cursor = line.chars().count();
+        self.line = line;
+    }
+
+    /// Completes the command name being typed, if the cursor is in it.
+    fn complete(&mut self) {
+        if self.line.contains(char::is_whitespace) {
+            return;
+        }
+        let candidates: Vec<&String> = self
+            .commands
+            .keys()
+            .filter(|name| name.starts_with(self.line.as_str()))
+            .collect();
+        let completed = match candidates.as_slice() {
+            [] => return,
+            [name] => format!("{} ", name),
+            [first, rest @ ..] => {
+                let mut prefix = first.as_str();
+                for name in rest {
+                    while !name.starts_with(prefix) {
+                        let last = prefix.char_indices().last().map_or(0, |(i, _)| i);
+                        prefix = &prefix[..last];
+                    }
+                }
+                let listing = candidates
+                    .iter()
+                    .map(|name| name.as_str())
+                    .collect::<Vec<_>>()
+                    .join("  ");
+                let prefix = prefix.to_owned();
+                self.print(listing);
+                prefix
+            }
+        };
+        self.set_line(completed);
+    }
+
+    fn handle_input(&mut self, input: &Input) {
+        if input.key_pressed(&self.toggle_key) {
+            self.toggle();
+            // The toggle key's character doesn't go in the line
+            return;
+        }
+        if !self.open {
+            return;
+        }
+
+        for c in input.text().chars().filter(|c| !c.is_control()) {
+            let index = self.byte_index(self.cursor);
+            self.line.insert(index, c);
+            self.cursor += 1;
+        }
+        let len = self.line.chars().count();
+        if input.key_repeated(Key::Backspace) && self.cursor > 0 {
+            self.cursor -= 1;
+            let index = self.byte_index(self.cursor);
+            self.line.remove(index);
+        }
+        if input.key_repeated(Key::Delete) && self.cursor < len {
+            let index = self.byte_index(self.cursor);
+            self.line.remove(index);
+        }
+        if input.key_repeated(Key::ArrowLeft) {
+            self.cursor = self.cursor.saturating_sub(1);
+        }
+        if input.key_repeated(Key::ArrowRight) {
+            self.cursor = (self.cursor + 1).min(self.line.chars().count());
+        }
+        if input.key_pressed(Key::Home) {
+            self.cursor = 0;
+        }
+        if input.key_pressed(Key::End) {
+            self.cursor = self.line.chars().count();
+        }
+        if input.key_pressed(Key::Tab) {
+            self.complete();
+        }
+
+        if input.key_repeated(Key::ArrowUp) && !self.history.is_empty() {
+            let index = self
+                .browsing
+                .map_or(self.history.len() - 1, |index| index.saturating_sub(1));
+            self.browsing = Some(index);
+            self.set_line(self.history[index].clone());
+        }
+        if input.key_repeated(Key::ArrowDown) {
+            if let Some(index) = self.browsing {
+                let line = if index + 1 < self.history.len() {
+                    self.browsing = Some(index + 1);
+                    self.history[index + 1].clone()
+                } else {
+                    self.browsing = None;
+                    String::new()
+                };
+                self.set_line(line);
+            }
+        }
+
+        if input.key_pressed(Key::Enter) || input.key_pressed(Key::NumpadEnter) {
+            let line = std::mem::take(&mut self.line);
+            self.cursor = 0;
+            self.browsing = None;
+            if !line.trim().is_empty() {
+                if self.history.last() != Some(&line) {
+                    self.history.push(line.clone());
+                }
+                self.queued.push(line);
+            }
+        }
+        if input.key_pressed(Key::Escape) {
+            self.close();
+        }
+    }
+
+    fn execute(&mut self, world: &mut World, line: &str) {
+        self.print(format!("> {}", line));
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (name, args) = match words.split_first() {
+            Some((name, args)) => (*name, args),
+            None => return,
+        };
+        let result = match self.commands.get_mut(name) {
+            Some(handler) => handler(world, args),
+            None if name == "help" => Ok(self.commands().collect::<Vec<_>>().join("  ")),
+            None if name == "clear" => {
+                self.clear_output();
+                return;
+            }
+            None => Err(format!("Unknown command: {}", name)),
+        };
+        match result {
+            Ok(output) if output.is_empty() => (),
+            Ok(output) => self.print(output),
+            Err(error) => self.print_error(error),
+        }
+    }
+
+    fn draw(&self, console: &mut Console) {
+        let width = console.get_width();
+        let height = self.height.clamp(2, console.get_height().max(2));
+        console.area(
+            0,
+            0,
+            width,
+            height,
+            Some(self.fore),
+            Some(self.back),
+            Some(u16::from(b' ')),
+        );
+        console.area(
+            0,
+            height as i32 - 2,
+            width,
+            1,
+            Some(self.fore),
+            None,
+            Some(196),
+        );
+
+        let output_rows = height as usize - 2;
+        for (row, (line, error)) in self.output.iter().rev().take(output_rows).enumerate() {
+            let fore = if *error { self.error_fore } else { self.fore };
+            console.print(
+                0,
+                (output_rows - 1 - row) as i32,
+                line,
+                TextAlign::Left,
+                Some(fore),
+                None,
+            );
+        }
+
+        // The line scrolls so the cursor stays on screen
+        let input_y = height as i32 - 1;
+        let room = width.saturating_sub(3) as usize;
+        let scroll = (self.cursor + 1).saturating_sub(room);
+        let shown: String = self.line.chars().skip(scroll).take(room).collect();
+        console.print(0, input_y, "> ", TextAlign::Left, Some(self.fore), None);
+        console.print(2, input_y, &shown, TextAlign::Left, Some(self.fore), None);
+        console.cell(
+            2 + (self.cursor - scroll) as i32,
+            input_y,
+            None,
+            Some(self.back),
+            Some(self.fore),
+        );
+    }
+}
+
+/// Extension methods on [`App`](BevyApp) for registering developer console
+/// commands.
+pub trait DevConsoleAppExt {
+    /// Registers a developer console command, as [`DevConsole::register`]
+    /// does.
+    fn register_dev_command(
+        &mut self,
+        name: impl Into<String>,
+        handler: impl FnMut(&mut World, &[&str]) -> Result<String, String> + Send + Sync + 'static,
+    ) -> &mut Self;
+}
+
+impl DevConsoleAppExt for BevyApp {
+    fn register_dev_command(
+        &mut self,
+        name: impl Into<String>,
+        handler: impl FnMut(&mut World, &[&str]) -> Result<String, String> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(DevConsole::default)
+            .register(name, handler);
+        self
+    }
+}
+
+/// Run condition that's true while the developer console is closed. A
+/// missing [`DevConsole`] counts as closed.
+pub fn dev_console_closed(console: Option<Res<'_, DevConsole>>) -> bool {
+    console.is_none_or(|console| !console.is_open())
+}
+
+fn run_dev_console(world: &mut World) {
+    world.resource_scope(|world, mut console: Mut<'_, DevConsole>| {
+        // An open console reads the input it captured, and a closed one only
+        // looks out for the toggle key
+        let input = if console.is_open() {
+            world
+                .get_resource::<InputCapture>()
+                .and_then(|capture| capture.input(CAPTURE_OWNER))
+        } else {
+            world.get_resource::<Input>()
+        };
+        if let Some(input) = input {
+            console.handle_input(input);
+        }
+
+        let mut capture = world.resource_mut::<InputCapture>();
+        if console.is_open() {
+            capture.capture(CAPTURE_OWNER);
+        } else {
+            capture.release(CAPTURE_OWNER);
+        }
+        for line in std::mem::take(&mut console.queued) {
+            console.execute(world, &line);
+        }
+    });
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn render_dev_console(console: Res<'_, DevConsole>, mut root_console: ResMut<'_, RootConsole>) {
+    if console.is_open() {
+        console.draw(&mut root_console);
+    }
+}
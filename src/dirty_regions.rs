@@ -0,0 +1,167 @@
+use crate::doryen::Console;
+use crate::{FrameSnapshot, RootConsole};
+use bevy_ecs::system::{Res, ResMut, Resource};
+
+/// A rectangle of console cells.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DirtyRect {
+    /// The x coordinate of the top-left cell.
+    pub x: u32,
+    /// The y coordinate of the top-left cell.
+    pub y: u32,
+    /// The width, in cells.
+    pub width: u32,
+    /// The height, in cells.
+    pub height: u32,
+}
+
+impl DirtyRect {
+    /// Whether cell `(x, y)` is inside the rectangle.
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        x >= self.x && y >= self.y && x - self.x < self.width && y - self.y < self.height
+    }
+}
+
+/// Resource tracking which parts of the root console changed since the
+/// previous frame, for renderers that only need to redraw those. Insert it to
+/// have it updated at the end of every frame, once everything has been
+/// drawn, in time for the [frame sinks](crate::FrameSink); it isn't tracked
+/// otherwise, since that takes a copy of the console every frame.
+///
+/// ```
+/// # use bevy_app::App;
+/// # use bevy_doryen::DirtyRegions;
+/// # use bevy_ecs::system::Res;
+/// fn report(dirty: Res<DirtyRegions>) {
+///     if dirty.is_clean() {
+///         return;
+///     }
+///     for rect in dirty.rects() {
+///         println!("redraw {}x{} at {},{}", rect.width, rect.height, rect.x, rect.y);
+///     }
+/// }
+///
+/// App::new().init_resource::<DirtyRegions>();
+/// ```
+///
+/// The changed cells are grouped into rectangles, one for each run of rows
+/// with changes, as wide as the changes in those rows. The terminal runner
+/// skips drawing frames where nothing changed. Doryen itself always uploads
+/// the whole console, so in a window or a browser, the regions are only
+/// useful to frame sinks and other custom renderers.
+#[derive(Default, Resource)]
+pub struct DirtyRegions {
+    rects: Vec<DirtyRect>,
+    full: bool,
+    previous: Option<FrameSnapshot>,
+    current: FrameSnapshot,
+}
+
+impl std::fmt::Debug for DirtyRegions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DirtyRegions")
+            .field("rects", &self.rects)
+            .field("full", &self.full)
+            .finish()
+    }
+}
+
+impl DirtyRegions {
+    /// The rectangles that changed during the latest frame, top to bottom.
+    pub fn rects(&self) -> &[DirtyRect] {
+        &self.rects
+    }
+
+    /// Whether nothing changed during the latest frame.
+    pub fn is_clean(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    /// Whether the whole console counts as changed during the latest frame,
+    /// because it's the first, the console was resized, or
+    /// [`invalidate`](Self::invalidate) was called.
+    pub fn is_full(&self) -> bool {
+        self.full
+    }
+
+    /// Whether cell `(x, y)` changed during the latest frame.
+    pub fn is_dirty(&self, x: u32, y: u32) -> bool {
+        self.rects.iter().any(|rect| rect.contains(x, y))
+    }
+
+    /// Makes the whole console count as changed next frame, such as after
+    /// the screen it's drawn on was lost.
+    pub fn invalidate(&mut self) {
+        self.previous = None;
+    }
+
+    fn track(&mut self, console: &Console) {
+        self.current.capture_into(console);
+        let (width, height) = (self.current.width, self.current.height);
+        self.rects.clear();
+        self.full = !matches!(
+            &self.previous,
+            Some(previous) if (previous.width, previous.height) == (width, height)
+        );
+
+        if self.full {
+            if width > 0 && height > 0 {
+                self.rects.push(DirtyRect {
+                    x: 0,
+                    y: 0,
+                    width,
+                    height,
+                });
+            }
+        } else if let Some(previous) = &self.previous {
+            let current = &self.current;
+            let mut open: Option<DirtyRect> = None;
+            for y in 0..height {
+                let row = (y * width) as usize..((y + 1) * width) as usize;
+                let changed = |i: &usize| {
+                    current.glyphs[*i] != previous.glyphs[*i]
+                        || current.fore[*i] != previous.fore[*i]
+                        || current.back[*i] != previous.back[*i]
+                };
+                let first = row.clone().find(changed);
+                let last = row.clone().rev().find(changed);
+                let (first, last) = if let (Some(first), Some(last)) = (first, last) {
+                    ((first - row.start) as u32, (last - row.start) as u32)
+                } else {
+                    // A row without changes ends the rectangle
+                    self.rects.extend(open.take());
+                    continue;
+                };
+                if let Some(rect) = &mut open {
+                    let right = (rect.x + rect.width).max(last + 1);
+                    rect.x = rect.x.min(first);
+                    rect.width = right - rect.x;
+                    rect.height += 1;
+                } else {
+                    open = Some(DirtyRect {
+                        x: first,
+                        y,
+                        width: last - first + 1,
+                        height: 1,
+                    });
+                }
+            }
+            self.rects.extend(open);
+        }
+
+        match &mut self.previous {
+            Some(previous) => std::mem::swap(previous, &mut self.current),
+            None => self.previous = Some(std::mem::take(&mut self.current)),
+        }
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub(crate) fn track_dirty_regions(
+    dirty_regions: Option<ResMut<'_, DirtyRegions>>,
+    root_console: Res<'_, RootConsole>,
+) {
+    if let Some(mut dirty_regions) = dirty_regions {
+        dirty_regions.track(&root_console);
+    }
+}
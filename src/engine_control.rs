@@ -0,0 +1,167 @@
+use crate::Input;
+use bevy_ecs::system::Resource;
+use bevy_ecs::world::World;
+use std::borrow::Cow;
+
+/// Resource for pausing the Bevy update while Doryen keeps running, and for
+/// stepping through it one update at a time.
+///
+/// While paused, Doryen's update phase still refreshes the [`Input`],
+/// [`FpsInfo`] and [`ScreenInfo`] resources, but the Bevy update schedules
+/// don't run, freezing the simulation. The [`DoryenRender`] schedule keeps
+/// running, so render systems can still draw a pause screen, and since they
+/// can read [`Input`], they are also where the game gets resumed from.
+///
+/// For debugging, [`step`](Self::step) runs exactly one Bevy update while
+/// paused. The [`pause_key`](Self::pause_key) and
+/// [`step_key`](Self::step_key) hotkeys do the same without writing any
+/// systems for it.
+///
+/// [`FpsInfo`]: crate::FpsInfo
+/// [`ScreenInfo`]: crate::ScreenInfo
+/// [`DoryenRender`]: crate::DoryenRender
+#[derive(Default, Debug, Clone, PartialEq, Eq, Resource)]
+pub struct DoryenEngineControl {
+    /// A key that toggles between paused and running when pressed, checked
+    /// on every Doryen update. `None`, the default, disables the hotkey.
+    pub pause_key: Option<Cow<'static, str>>,
+    /// A key that does the same as [`step`](Self::step) when pressed.
+    /// `None`, the default, disables the hotkey.
+    pub step_key: Option<Cow<'static, str>>,
+    paused: bool,
+    pending_steps: u32,
+}
+
+impl DoryenEngineControl {
+    /// Stops running the Bevy update, starting with the next Doryen update.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resumes running the Bevy update, starting with the next Doryen update.
+    pub fn resume(&mut self) {
+        self.paused = false;
+        self.pending_steps = 0;
+    }
+
+    /// Pauses if running, and resumes if paused.
+    pub fn toggle_pause(&mut self) {
+        if self.paused {
+            self.resume();
+        } else {
+            self.pause();
+        }
+    }
+
+    /// Whether the Bevy update is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Runs a single Bevy update on the next Doryen update, then stays
+    /// paused. Pauses first if running. Calling this several times runs that
+    /// many updates, one per Doryen update.
+    pub fn step(&mut self) {
+        self.paused = true;
+        self.pending_steps += 1;
+    }
+}
+
+/// Whether the engine should run the Bevy update on this Doryen update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum UpdateGate {
+    /// Run it, subject to the frame limits.
+    Run,
+    /// Run it regardless of the frame limits, as a single step.
+    Step,
+    /// Don't run it.
+    Hold,
+}
+
+/// Applies the hotkeys of the [`DoryenEngineControl`] resource, if there is
+/// one, and decides whether this update runs.
+pub(crate) fn update_gate(world: &mut World) -> UpdateGate {
+    let control = match world.get_resource::<DoryenEngineControl>() {
+        Some(control) => control,
+        None => return UpdateGate::Run,
+    };
+    let input = world.resource::<Input>();
+    let pressed =
+        |key: &Option<Cow<'static, str>>| key.clone().filter(|key| input.key_pressed(key));
+    let pause_pressed = pressed(&control.pause_key);
+    let step_pressed = pressed(&control.step_key);
+
+    if pause_pressed.is_none() && step_pressed.is_none() {
+        match (control.paused, control.pending_steps) {
+            (false, _) => return UpdateGate::Run,
+            (true, 0) => return UpdateGate::Hold,
+            (true, _) => (),
+        }
+    }
+
+    // Input is kept around for the next update when this one ends up being
+    // skipped, which would see the same press again
+    let mut input_mut = world.resource_mut::<Input>();
+    for key in pause_pressed.iter().chain(&step_pressed) {
+        input_mut.consume_key_press(key);
+    }
+
+    let mut control_mut = world.resource_mut::<DoryenEngineControl>();
+    if pause_pressed.is_some() {
+        control_mut.toggle_pause();
+    }
+    if step_pressed.is_some() {
+        control_mut.step();
+    }
+    if !control_mut.paused {
+        UpdateGate::Run
+    } else if control_mut.pending_steps > 0 {
+        control_mut.pending_steps -= 1;
+        UpdateGate::Step
+    } else {
+        UpdateGate::Hold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::HeadlessInput;
+
+    fn press(world: &mut World, key: Option<&str>, accumulate: bool) {
+        let mut source = HeadlessInput::default();
+        if let Some(key) = key {
+            source.press_key(key);
+        }
+        let mut input = world.resource_mut::<Input>();
+        if accumulate {
+            input.accumulate_input(&[], &mut source);
+        } else {
+            input.handle_input(&[], &mut source);
+        }
+    }
+
+    #[test]
+    fn hotkeys_act_once_per_press() {
+        let mut world = World::new();
+        world.init_resource::<Input>();
+        world.insert_resource(DoryenEngineControl {
+            pause_key: Some(Cow::Borrowed("KeyP")),
+            step_key: Some(Cow::Borrowed("Period")),
+            ..DoryenEngineControl::default()
+        });
+
+        press(&mut world, Some("KeyP"), false);
+        assert_eq!(update_gate(&mut world), UpdateGate::Hold);
+        press(&mut world, Some("KeyP"), false);
+        assert_eq!(update_gate(&mut world), UpdateGate::Run);
+        // The update is skipped, so its input is added to by the next one
+        press(&mut world, None, true);
+        assert_eq!(update_gate(&mut world), UpdateGate::Run);
+
+        press(&mut world, Some("Period"), false);
+        assert_eq!(update_gate(&mut world), UpdateGate::Step);
+        press(&mut world, None, true);
+        assert_eq!(update_gate(&mut world), UpdateGate::Hold);
+    }
+}
@@ -0,0 +1,86 @@
+use crate::{
+    CaptureScreen, CharSizeChanged, ConsoleResized, CurrentFont, DoryenRender, DoryenShutdown,
+    FontChanged, FpsInfo, Input, LoadingProgress, Resized, RootConsole, ScreenCaptured, ScreenInfo,
+    ScreenInfoChanged, SetFontPath,
+};
+use bevy_ecs::event::Events;
+use bevy_ecs::schedule::{ScheduleLabel, Schedules};
+use bevy_ecs::system::Resource;
+use bevy_ecs::world::World;
+use std::error::Error;
+use std::fmt;
+
+/// Something wrong with the Bevy app that keeps bevy_doryen from running it.
+///
+/// The runners check the app for these before starting, and again on every
+/// update, so that removing something bevy_doryen relies on stops the app
+/// with a message saying what's missing instead of a panic somewhere in the
+/// middle of a frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DoryenError {
+    /// A resource bevy_doryen relies on isn't in the world. This is usually
+    /// because it was removed, or because the [`DoryenPlugin`] wasn't added.
+    ///
+    /// [`DoryenPlugin`]: crate::DoryenPlugin
+    MissingResource(&'static str),
+    /// A schedule bevy_doryen relies on doesn't exist.
+    MissingSchedule(&'static str),
+}
+
+impl fmt::Display for DoryenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingResource(name) => write!(
+                f,
+                "the {} resource is missing; was it removed, or was the DoryenPlugin not added?",
+                name
+            ),
+            Self::MissingSchedule(name) => write!(
+                f,
+                "the {} schedule is missing; was it removed, or was the DoryenPlugin not added?",
+                name
+            ),
+        }
+    }
+}
+
+impl Error for DoryenError {}
+
+/// Checks that everything the runners rely on is in the world.
+pub(crate) fn validate_world(world: &World) -> Result<(), DoryenError> {
+    fn require<T: Resource>(world: &World, name: &'static str) -> Result<(), DoryenError> {
+        if world.contains_resource::<T>() {
+            Ok(())
+        } else {
+            Err(DoryenError::MissingResource(name))
+        }
+    }
+
+    require::<RootConsole>(world, "RootConsole")?;
+    require::<Input>(world, "Input")?;
+    require::<FpsInfo>(world, "FpsInfo")?;
+    require::<ScreenInfo>(world, "ScreenInfo")?;
+    require::<CurrentFont>(world, "CurrentFont")?;
+    require::<Events<SetFontPath>>(world, "Events<SetFontPath>")?;
+    require::<Events<FontChanged>>(world, "Events<FontChanged>")?;
+    require::<Events<CaptureScreen>>(world, "Events<CaptureScreen>")?;
+    require::<Events<ScreenCaptured>>(world, "Events<ScreenCaptured>")?;
+    require::<Events<Resized>>(world, "Events<Resized>")?;
+    require::<Events<ConsoleResized>>(world, "Events<ConsoleResized>")?;
+    require::<Events<CharSizeChanged>>(world, "Events<CharSizeChanged>")?;
+    require::<Events<ScreenInfoChanged>>(world, "Events<ScreenInfoChanged>")?;
+    require::<LoadingProgress>(world, "LoadingProgress")?;
+
+    let has_schedule = |label: &dyn ScheduleLabel| {
+        world
+            .get_resource::<Schedules>()
+            .is_some_and(|schedules| schedules.get(label).is_some())
+    };
+    if !has_schedule(&DoryenRender) {
+        return Err(DoryenError::MissingSchedule("DoryenRender"));
+    }
+    if !has_schedule(&DoryenShutdown) {
+        return Err(DoryenError::MissingSchedule("DoryenShutdown"));
+    }
+    Ok(())
+}
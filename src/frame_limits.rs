@@ -0,0 +1,248 @@
+use crate::{Input, ScreenInfo, UPDATES_PER_SECOND};
+use bevy_ecs::system::Resource;
+use bevy_utils::{Duration, Instant};
+
+/// Resource limiting how often the Bevy update and the Doryen render schedule
+/// run. It can be changed at any time, e.g. to drop to a low frame rate while
+/// a pause menu is showing.
+///
+/// Doryen itself keeps running its update phase 60 times per second and
+/// presenting the root console every frame; these limits decide how many of
+/// those updates and renders bevy_doryen passes on to Bevy. Input from
+/// skipped updates isn't lost, it's handed to the next update that runs.
+///
+/// With [`on_demand`](Self::on_demand) set, the Bevy update and the render
+/// schedule only run when something happened: the player provided input,
+/// the window changed size, or a system asked for it through
+/// [`UpdateRequests`]. The terminal runner sleeps until then. Doryen's own
+/// window and the browser keep their frame loop going, but skip all of the
+/// Bevy work.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Resource)]
+pub struct DoryenFrameLimits {
+    /// The highest number of times per second to run the
+    /// [`DoryenRender`](crate::DoryenRender) schedule. `None`, the default,
+    /// renders every frame.
+    pub render_fps: Option<u32>,
+    /// How many times per second to run the Bevy update. Values of 60 and up,
+    /// as well as `None`, the default, update on every Doryen update.
+    pub updates_per_second: Option<u32>,
+    /// Whether to only update and render when there's something to do.
+    /// Defaults to `false`. Meant for turn-based games, which otherwise have
+    /// nothing to do between the player's moves.
+    pub on_demand: bool,
+}
+
+impl DoryenFrameLimits {
+    /// Limits that render at most `render_fps` times per second and update
+    /// `updates_per_second` times per second.
+    pub fn new(render_fps: Option<u32>, updates_per_second: Option<u32>) -> Self {
+        Self {
+            render_fps,
+            updates_per_second,
+            on_demand: false,
+        }
+    }
+
+    /// Limits that only update and render when there's something to do.
+    /// See [`on_demand`](Self::on_demand).
+    pub fn on_demand() -> Self {
+        Self {
+            on_demand: true,
+            ..Self::default()
+        }
+    }
+}
+
+/// Resource for asking for Bevy updates when the [`DoryenFrameLimits`] are
+/// [`on_demand`](DoryenFrameLimits::on_demand), for animations and timers
+/// that have to keep going without input. Without on-demand limits, every
+/// update runs anyway and the requests are ignored.
+#[derive(Default, Debug, Clone, Copy, Resource)]
+pub struct UpdateRequests {
+    next: Option<Instant>,
+}
+
+impl UpdateRequests {
+    /// Asks for the next Bevy update to run.
+    pub fn request_update(&mut self) {
+        self.request_update_at(Instant::now());
+    }
+
+    /// Asks for a Bevy update to run once `secs` seconds have passed.
+    pub fn request_update_after(&mut self, secs: f32) {
+        self.request_update_at(Instant::now() + Duration::from_secs_f32(secs.max(0.0)));
+    }
+
+    fn request_update_at(&mut self, at: Instant) {
+        self.next = Some(self.next.map_or(at, |next| next.min(at)));
+    }
+
+    /// How long until the earliest requested update, if any was requested.
+    #[cfg(feature = "terminal")]
+    pub(crate) fn time_until_next(&self, now: Instant) -> Option<Duration> {
+        self.next.map(|next| next.saturating_duration_since(now))
+    }
+
+    /// Whether a requested update is due. Consumes the request if it is, so
+    /// it's only honored once.
+    pub(crate) fn take_due(&mut self, now: Instant) -> bool {
+        match self.next {
+            Some(next) if next <= now => {
+                self.next = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Decides which updates and renders to run under a [`DoryenFrameLimits`].
+#[derive(Default, Debug)]
+pub(crate) struct FrameLimiter {
+    update_budget: f32,
+    render_budget: f32,
+    render_pending: bool,
+    mouse_position: Option<(f32, f32)>,
+    screen_info: Option<ScreenInfo>,
+}
+
+impl FrameLimiter {
+    /// Whether there's anything for the Bevy update to do on this Doryen
+    /// update. Always the case unless the limits are on demand.
+    pub(crate) fn should_wake(
+        &mut self,
+        limits: DoryenFrameLimits,
+        input: &Input,
+        screen_info: ScreenInfo,
+        requested: bool,
+    ) -> bool {
+        let mouse_position = input.mouse_pos();
+        let mouse_moved = self.mouse_position.replace(mouse_position) != Some(mouse_position);
+        let screen_changed = self.screen_info.replace(screen_info) != Some(screen_info);
+        if !limits.on_demand {
+            return true;
+        }
+        let wake = requested || mouse_moved || screen_changed || input.has_new_input();
+        self.render_pending |= wake;
+        wake
+    }
+
+    /// Whether the Bevy update should run on this Doryen update.
+    pub(crate) fn should_update(&mut self, limits: DoryenFrameLimits) -> bool {
+        let rate = match limits.updates_per_second {
+            Some(rate) if (rate as f32) < UPDATES_PER_SECOND => rate as f32,
+            _ => {
+                self.update_budget = 0.0;
+                return true;
+            }
+        };
+        Self::spend(&mut self.update_budget, rate / UPDATES_PER_SECOND)
+    }
+
+    /// Whether the render schedule should run on this frame, given the frame
+    /// rate Doryen currently runs at.
+    pub(crate) fn should_render(&mut self, limits: DoryenFrameLimits, fps: u32) -> bool {
+        // Nothing can have changed since the last render
+        if limits.on_demand && !std::mem::take(&mut self.render_pending) {
+            return false;
+        }
+        match limits.render_fps {
+            Some(max_fps) if fps > max_fps => {
+                Self::spend(&mut self.render_budget, max_fps as f32 / fps as f32)
+            }
+            _ => {
+                self.render_budget = 0.0;
+                true
+            }
+        }
+    }
+
+    #[inline]
+    fn spend(budget: &mut f32, step: f32) -> bool {
+        *budget = (*budget + step).min(1.0);
+        if *budget >= 1.0 {
+            *budget -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(runs: impl FnMut() -> bool, times: usize) -> usize {
+        std::iter::repeat_with(runs)
+            .take(times)
+            .filter(|&ran| ran)
+            .count()
+    }
+
+    #[test]
+    fn updates_run_at_the_requested_rate() {
+        let mut limiter = FrameLimiter::default();
+        let limits = DoryenFrameLimits::new(None, Some(20));
+        assert_eq!(count(|| limiter.should_update(limits), 60), 20);
+
+        let unlimited = DoryenFrameLimits::new(None, Some(120));
+        assert_eq!(count(|| limiter.should_update(unlimited), 60), 60);
+    }
+
+    #[test]
+    fn renders_run_at_the_requested_rate() {
+        let mut limiter = FrameLimiter::default();
+        let limits = DoryenFrameLimits::new(Some(30), None);
+        assert_eq!(count(|| limiter.should_render(limits, 60), 60), 30);
+
+        // Below the limit, every frame is rendered
+        assert_eq!(count(|| limiter.should_render(limits, 20), 20), 20);
+    }
+
+    #[test]
+    fn unused_budget_does_not_pile_up() {
+        let mut limiter = FrameLimiter::default();
+        let limits = DoryenFrameLimits::new(None, Some(30));
+        // Switching to unlimited updates and back starts the budget over
+        limiter.should_update(limits);
+        limiter.should_update(DoryenFrameLimits::default());
+        assert!(!limiter.should_update(limits));
+        assert!(limiter.should_update(limits));
+    }
+
+    #[test]
+    fn on_demand_limits_only_wake_for_something_new() {
+        let mut limiter = FrameLimiter::default();
+        let limits = DoryenFrameLimits::on_demand();
+        let (input, screen_info) = (Input::default(), ScreenInfo::default());
+
+        // The first update always has something new to show
+        assert!(limiter.should_wake(limits, &input, screen_info, false));
+        assert!(limiter.should_render(limits, 60));
+        assert!(!limiter.should_render(limits, 60));
+
+        assert!(!limiter.should_wake(limits, &input, screen_info, false));
+        assert!(!limiter.should_render(limits, 60));
+
+        assert!(limiter.should_wake(limits, &input, screen_info, true));
+        let resized = ScreenInfo {
+            console_width: 80,
+            ..screen_info
+        };
+        assert!(limiter.should_wake(limits, &input, resized, false));
+    }
+
+    #[test]
+    fn update_requests_are_honored_once() {
+        let now = Instant::now();
+        let mut requests = UpdateRequests::default();
+        assert!(!requests.take_due(now));
+
+        requests.request_update_at(now + Duration::from_secs(2));
+        requests.request_update_at(now + Duration::from_secs(1));
+        assert!(!requests.take_due(now));
+        assert!(requests.take_due(now + Duration::from_secs(1)));
+        assert!(!requests.take_due(now + Duration::from_secs(1)));
+    }
+}
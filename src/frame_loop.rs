@@ -0,0 +1,111 @@
+//! The steps every runner takes on each frame, whether it drives a Doryen
+//! window, a headless app or a terminal, so they all honor the same frame
+//! limits, pausing and shutdown.
+
+use crate::engine_control::{self, UpdateGate};
+use crate::frame_limits::FrameLimiter;
+use crate::input::InputSource;
+use crate::{
+    DoryenFrameLimits, DoryenShutdown, Input, InputCapture, MouseButton, ScreenInfo, UpdateRequests,
+};
+use bevy_app::AppExit;
+use bevy_ecs::event::{Events, ManualEventReader};
+use bevy_ecs::world::World;
+
+/// What a runner keeps around from one frame to the next.
+#[derive(Default, Debug)]
+pub(crate) struct FrameLoop {
+    frame_limiter: FrameLimiter,
+    accumulating_input: bool,
+    app_exit_event_reader: ManualEventReader<AppExit>,
+}
+
+impl FrameLoop {
+    /// Refreshes [`ScreenInfo`] from the size of the screen and of the root
+    /// console, hands the input from `source` to the [`Input`] resource and
+    /// decides whether the Bevy update runs on this frame.
+    pub(crate) fn begin_frame<I: InputSource + ?Sized>(
+        &mut self,
+        world: &mut World,
+        screen_size: (u32, u32),
+        console_size: (u32, u32),
+        mouse_button_listeners: &[MouseButton],
+        source: &mut I,
+    ) -> bool {
+        crate::refresh_screen_info(world, screen_size, console_size);
+        crate::idle::feed_input(
+            world,
+            mouse_button_listeners,
+            source,
+            self.accumulating_input,
+        );
+
+        // Skipped updates keep their input around for the next update that
+        // runs, except while paused, when input is handled as usual
+        let limits = frame_limits(world);
+        let wake = self.should_wake(world, limits);
+        match engine_control::update_gate(world) {
+            UpdateGate::Run => {
+                let run_update = wake && self.frame_limiter.should_update(limits);
+                self.accumulating_input = !run_update;
+                run_update
+            }
+            UpdateGate::Step => {
+                self.accumulating_input = false;
+                true
+            }
+            UpdateGate::Hold => {
+                self.accumulating_input = false;
+                false
+            }
+        }
+    }
+
+    /// Whether an [`AppExit`] event was sent since the last time this was
+    /// asked.
+    pub(crate) fn exit_requested(&mut self, world: &World) -> bool {
+        world
+            .get_resource::<Events<AppExit>>()
+            .is_some_and(|app_exit_events| {
+                self.app_exit_event_reader
+                    .iter(app_exit_events)
+                    .last()
+                    .is_some()
+            })
+    }
+
+    /// Whether the render schedule should run on this frame, given the frame
+    /// rate the runner currently runs at.
+    pub(crate) fn should_render(&mut self, world: &World, fps: u32) -> bool {
+        self.frame_limiter.should_render(frame_limits(world), fps)
+    }
+
+    fn should_wake(&mut self, world: &mut World, limits: DoryenFrameLimits) -> bool {
+        let requested = world
+            .get_resource_mut::<UpdateRequests>()
+            .is_some_and(|mut requests| requests.take_due(bevy_utils::Instant::now()));
+        let screen_info = *world.resource::<ScreenInfo>();
+        let input = InputCapture::live(world.get_resource(), world.resource::<Input>());
+        self.frame_limiter
+            .should_wake(limits, input, screen_info, requested)
+    }
+}
+
+/// Gives the [`DoryenShutdown`] schedule its final run. The app is exiting
+/// anyway, so a missing schedule is only logged.
+pub(crate) fn shut_down(world: &mut World) {
+    if world.try_run_schedule(DoryenShutdown).is_err() {
+        bevy_utils::tracing::error!(
+            "bevy_doryen: {}",
+            crate::DoryenError::MissingSchedule("DoryenShutdown")
+        );
+    }
+}
+
+#[inline]
+pub(crate) fn frame_limits(world: &World) -> DoryenFrameLimits {
+    world
+        .get_resource::<DoryenFrameLimits>()
+        .copied()
+        .unwrap_or_default()
+}
@@ -0,0 +1,176 @@
+use crate::doryen::{Color, Console};
+use crate::RootConsole;
+use bevy_ecs::system::{Res, ResMut, Resource};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// A secondary output for the root console, such as a spectator stream or a
+/// shared-memory buffer. Every frame, after all render systems have run, each
+/// registered sink is handed the fully composited root console.
+///
+/// Sinks are registered through the [`FrameSinks`] resource. Any
+/// `FnMut(&Console)` closure is a sink.
+pub trait FrameSink: Send + Sync + 'static {
+    /// Called once per frame with the finished root console.
+    fn frame(&mut self, console: &Console);
+}
+
+impl<F: FnMut(&Console) + Send + Sync + 'static> FrameSink for F {
+    fn frame(&mut self, console: &Console) {
+        self(console)
+    }
+}
+
+/// Resource holding the registered [`FrameSink`]s.
+#[derive(Default, Resource)]
+pub struct FrameSinks {
+    sinks: Vec<Box<dyn FrameSink>>,
+}
+
+impl std::fmt::Debug for FrameSinks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FrameSinks")
+            .field("sinks", &self.sinks.len())
+            .finish()
+    }
+}
+
+impl FrameSinks {
+    /// Registers a sink. Sinks receive frames in the order they were added.
+    pub fn add(&mut self, sink: impl FrameSink) {
+        self.sinks.push(Box::new(sink));
+    }
+
+    /// Removes every registered sink.
+    pub fn clear(&mut self) {
+        self.sinks.clear();
+    }
+
+    /// Whether no sinks are registered.
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+}
+
+/// A copy of the contents of a console at one point in time.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct FrameSnapshot {
+    /// The width of the console, in cells.
+    pub width: u32,
+    /// The height of the console, in cells.
+    pub height: u32,
+    /// The glyph of every cell, row by row.
+    pub glyphs: Vec<u16>,
+    /// The foreground color of every cell, row by row.
+    pub fore: Vec<Color>,
+    /// The background color of every cell, row by row.
+    pub back: Vec<Color>,
+}
+
+impl FrameSnapshot {
+    /// Copies the contents of `console`.
+    pub fn capture(console: &Console) -> Self {
+        let mut snapshot = Self::default();
+        snapshot.capture_into(console);
+        snapshot
+    }
+
+    /// Copies the contents of `console` into this snapshot, reusing its
+    /// allocations.
+    pub fn capture_into(&mut self, console: &Console) {
+        let (width, height) = console.get_size();
+        self.width = width;
+        self.height = height;
+        self.glyphs.clear();
+        self.fore.clear();
+        self.back.clear();
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                self.glyphs.push(console.get_ascii(x, y).unwrap_or(0));
+                self.fore
+                    .push(console.get_fore(x, y).unwrap_or((0, 0, 0, 0)));
+                self.back
+                    .push(console.get_back(x, y).unwrap_or((0, 0, 0, 0)));
+            }
+        }
+    }
+
+    /// The index into the cell vectors of cell `(x, y)`, if it's inside the
+    /// snapshot.
+    #[inline]
+    pub fn index(&self, x: u32, y: u32) -> Option<usize> {
+        if x < self.width && y < self.height {
+            Some((y * self.width + x) as usize)
+        } else {
+            None
+        }
+    }
+}
+
+/// A [`FrameSink`] that keeps the latest frame in a buffer shared with other
+/// threads. Clone it before adding it to [`FrameSinks`] and read the frames
+/// from the clone.
+#[derive(Default, Debug, Clone)]
+pub struct SharedFrame {
+    inner: Arc<Mutex<SharedFrameInner>>,
+}
+
+#[derive(Default, Debug)]
+struct SharedFrameInner {
+    snapshot: FrameSnapshot,
+    frame: u64,
+}
+
+impl SharedFrame {
+    /// Creates an empty shared frame.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Locks the buffer and returns the latest frame along with its number,
+    /// which increases by one with every frame. Frame number 0 means no frame
+    /// has been received yet.
+    pub fn latest(&self) -> (u64, SharedFrameGuard<'_>) {
+        let guard = self.lock();
+        (guard.frame, SharedFrameGuard { guard })
+    }
+
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, SharedFrameInner> {
+        self.inner
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl FrameSink for SharedFrame {
+    fn frame(&mut self, console: &Console) {
+        let mut inner = self.lock();
+        inner.snapshot.capture_into(console);
+        inner.frame += 1;
+    }
+}
+
+/// Gives access to the frame in a [`SharedFrame`] while holding its lock.
+#[derive(Debug)]
+pub struct SharedFrameGuard<'a> {
+    guard: MutexGuard<'a, SharedFrameInner>,
+}
+
+impl std::ops::Deref for SharedFrameGuard<'_> {
+    type Target = FrameSnapshot;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.guard.snapshot
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub(crate) fn feed_frame_sinks(
+    mut frame_sinks: ResMut<'_, FrameSinks>,
+    root_console: Res<'_, RootConsole>,
+) {
+    for sink in &mut frame_sinks.sinks {
+        sink.frame(&root_console);
+    }
+}
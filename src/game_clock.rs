@@ -0,0 +1,232 @@
+//! In-game time.
+//!
+//! The [`GameClock`] resource counts game minutes, which pass at a set speed
+//! while the clock isn't paused, and tells the hour, day and date they add up
+//! to. [`HourChanged`] and [`DayChanged`] events are sent as the clock
+//! passes those boundaries, for shops that open in the morning or crops that
+//! grow overnight:
+//!
+//! ```
+//! # use bevy_doryen::game_clock::{DayChanged, GameClock};
+//! # use bevy_ecs::event::EventReader;
+//! # use bevy_ecs::system::Res;
+//! fn grow_crops(mut days: EventReader<DayChanged>) {
+//!     for day in days.iter() {
+//!         // Grow every crop by a day.
+//!     }
+//! }
+//!
+//! fn lighting(clock: Res<GameClock>) {
+//!     // 0.0 at midnight, 1.0 at noon
+//!     let daylight = clock.daylight();
+//! }
+//! ```
+//!
+//! Turn-based games can pause the clock and [`advance`](GameClock::advance)
+//! it by however long each turn takes instead.
+
+use crate::UPDATES_PER_SECOND;
+use bevy_app::{App, First, Plugin};
+use bevy_ecs::event::{Event, EventWriter};
+use bevy_ecs::system::{ResMut, Resource};
+
+/// How many minutes there are in an hour.
+const MINUTES_PER_HOUR: u64 = 60;
+/// How many hours there are in a day.
+const HOURS_PER_DAY: u64 = 24;
+/// How many minutes there are in a day.
+const MINUTES_PER_DAY: u64 = MINUTES_PER_HOUR * HOURS_PER_DAY;
+
+/// The game clock plugin. Adds the [`GameClock`] resource, unless one has
+/// already been inserted, and the [`HourChanged`] and [`DayChanged`] events.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct GameClockPlugin;
+
+impl Plugin for GameClockPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameClock>()
+            .add_event::<HourChanged>()
+            .add_event::<DayChanged>()
+            .add_systems(First, tick_game_clock);
+    }
+}
+
+/// A date on the [`GameClock`]'s calendar. All the fields start at `1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GameDate {
+    /// The year.
+    pub year: u64,
+    /// The month of the year.
+    pub month: u32,
+    /// The day of the month.
+    pub day: u32,
+}
+
+/// Resource keeping the in-game time. See the [module documentation](self).
+#[derive(Debug, Clone, Copy, PartialEq, Resource)]
+pub struct GameClock {
+    /// How many game minutes pass per second. Defaults to `1.0`. Like the
+    /// other durations, seconds are measured in Doryen update ticks.
+    pub minutes_per_second: f64,
+    /// How many days each month has. Defaults to `30`.
+    pub days_per_month: u32,
+    /// How many months each year has. Defaults to `12`.
+    pub months_per_year: u32,
+    minutes: f64,
+    paused: bool,
+    pending_minutes: f64,
+}
+
+impl Default for GameClock {
+    fn default() -> Self {
+        Self {
+            minutes_per_second: 1.0,
+            days_per_month: 30,
+            months_per_year: 12,
+            minutes: 0.0,
+            paused: false,
+            pending_minutes: 0.0,
+        }
+    }
+}
+
+impl GameClock {
+    /// A clock that starts at the given time of the first day.
+    pub fn starting_at(hour: u32, minute: u32) -> Self {
+        Self {
+            minutes: f64::from(hour) * MINUTES_PER_HOUR as f64 + f64::from(minute),
+            ..Self::default()
+        }
+    }
+
+    /// Sets how many game minutes pass per second.
+    pub fn with_speed(mut self, minutes_per_second: f64) -> Self {
+        self.minutes_per_second = minutes_per_second;
+        self
+    }
+
+    /// Sets the length of the months and years of the calendar.
+    pub fn with_calendar(mut self, days_per_month: u32, months_per_year: u32) -> Self {
+        self.days_per_month = days_per_month;
+        self.months_per_year = months_per_year;
+        self
+    }
+
+    /// Stops time from passing on its own.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Lets time pass on its own again.
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Whether time is kept from passing on its own.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Moves the clock forward by the given number of game minutes, paused or
+    /// not. The clock moves at the start of the next update, when the events
+    /// for the boundaries passed are sent.
+    pub fn advance(&mut self, minutes: f64) {
+        self.pending_minutes += minutes.max(0.0);
+    }
+
+    /// How many game minutes have passed since the first day started.
+    pub fn elapsed_minutes(&self) -> f64 {
+        self.minutes
+    }
+
+    /// The minute of the hour, from `0` to `59`.
+    pub fn minute(&self) -> u32 {
+        (self.whole_minutes() % MINUTES_PER_HOUR) as u32
+    }
+
+    /// The hour of the day, from `0` to `23`.
+    pub fn hour(&self) -> u32 {
+        (self.whole_minutes() / MINUTES_PER_HOUR % HOURS_PER_DAY) as u32
+    }
+
+    /// How many whole days have passed, so `0` on the first day.
+    pub fn day(&self) -> u64 {
+        self.whole_minutes() / MINUTES_PER_DAY
+    }
+
+    /// The date on the calendar.
+    pub fn date(&self) -> GameDate {
+        let days_per_month = u64::from(self.days_per_month.max(1));
+        let months_per_year = u64::from(self.months_per_year.max(1));
+        let months = self.day() / days_per_month;
+        GameDate {
+            year: months / months_per_year + 1,
+            month: (months % months_per_year) as u32 + 1,
+            day: (self.day() % days_per_month) as u32 + 1,
+        }
+    }
+
+    /// How far into the day it is, from `0.0` at midnight to just under `1.0`
+    /// right before the next one.
+    pub fn time_of_day(&self) -> f32 {
+        (self.minutes.rem_euclid(MINUTES_PER_DAY as f64) / MINUTES_PER_DAY as f64) as f32
+    }
+
+    /// How light it is outside, from `0.0` at midnight to `1.0` at noon,
+    /// following a smooth curve in between. Use it to tint the map for day
+    /// and night.
+    pub fn daylight(&self) -> f32 {
+        (1.0 - (self.time_of_day() * std::f32::consts::TAU).cos()) / 2.0
+    }
+
+    fn whole_minutes(&self) -> u64 {
+        self.minutes.max(0.0) as u64
+    }
+}
+
+/// Emitted at the start of the update when the [`GameClock`] has passed the
+/// start of an hour, once for each hour passed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Event)]
+pub struct HourChanged {
+    /// The day the hour is on, as [`GameClock::day`].
+    pub day: u64,
+    /// The hour that started.
+    pub hour: u32,
+}
+
+/// Emitted at the start of the update when the [`GameClock`] has passed
+/// midnight, once for each day passed. Sent after the [`HourChanged`] event
+/// for midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Event)]
+pub struct DayChanged {
+    /// The day that started, as [`GameClock::day`].
+    pub day: u64,
+}
+
+fn tick_game_clock(
+    mut clock: ResMut<'_, GameClock>,
+    mut hours: EventWriter<'_, HourChanged>,
+    mut days: EventWriter<'_, DayChanged>,
+) {
+    let mut minutes = std::mem::take(&mut clock.pending_minutes);
+    if !clock.paused {
+        minutes += clock.minutes_per_second.max(0.0) / f64::from(UPDATES_PER_SECOND);
+    }
+    if minutes <= 0.0 {
+        return;
+    }
+
+    let previous_hours = clock.whole_minutes() / MINUTES_PER_HOUR;
+    clock.minutes += minutes;
+    let current_hours = clock.whole_minutes() / MINUTES_PER_HOUR;
+    for hours_passed in previous_hours + 1..=current_hours {
+        let (day, hour) = (
+            hours_passed / HOURS_PER_DAY,
+            (hours_passed % HOURS_PER_DAY) as u32,
+        );
+        hours.send(HourChanged { day, hour });
+        if hour == 0 {
+            days.send(DayChanged { day });
+        }
+    }
+}
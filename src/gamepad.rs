@@ -0,0 +1,240 @@
+//! Gamepad input, through Bevy's gamepad handling.
+//!
+//! [`GamepadPlugin`] adds Bevy's gilrs backend and makes the state of the
+//! connected gamepads available on the [`Input`] resource, next to the
+//! keyboard and mouse, with [`Input::gamepad_button`] and friends. Buttons
+//! and axes are combined over all connected gamepads, which suits a single
+//! player game; use Bevy's own `Gamepads`, `Input<GamepadButton>` and
+//! `Axis<GamepadAxis>` resources to tell gamepads apart.
+//!
+//! The plugin also turns the D-pad and the left stick into
+//! [`GridMoveIntent`] events, one step in one of eight directions at a time,
+//! repeating while the direction is held the way keyboard auto-repeat does:
+//!
+//! ```
+//! # use bevy_doryen::gamepad::GridMoveIntent;
+//! # use bevy_ecs::event::EventReader;
+//! fn move_player(mut intents: EventReader<GridMoveIntent>) {
+//!     for intent in intents.iter() {
+//!         // Try to move the player by (intent.dx, intent.dy).
+//!     }
+//! }
+//! ```
+
+use crate::{Input, UPDATES_PER_SECOND};
+use bevy_app::{App as BevyApp, Plugin, PreUpdate};
+use bevy_ecs::event::{Event, EventWriter};
+use bevy_ecs::schedule::IntoSystemConfigs;
+use bevy_ecs::system::{Res, ResMut, Resource};
+use bevy_gilrs::GilrsPlugin;
+use bevy_input::gamepad::{
+    GamepadAxis, GamepadAxisType, GamepadButton, GamepadButtonType, Gamepads,
+};
+use bevy_input::{Axis, Input as ButtonInput, InputPlugin, InputSystem};
+use bevy_time::TimePlugin;
+use std::collections::{HashMap, HashSet};
+
+/// The gamepad plugin. Adds Bevy's input, time and gilrs plugins unless they
+/// have already been added, the [`GamepadMovement`] resource, unless one has
+/// already been inserted, and the [`GridMoveIntent`] event.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct GamepadPlugin;
+
+impl Plugin for GamepadPlugin {
+    fn build(&self, app: &mut BevyApp) {
+        if !app.is_plugin_added::<InputPlugin>() {
+            app.add_plugins(InputPlugin);
+        }
+        if !app.is_plugin_added::<TimePlugin>() {
+            app.add_plugins(TimePlugin);
+        }
+        if !app.is_plugin_added::<GilrsPlugin>() {
+            app.add_plugins(GilrsPlugin);
+        }
+        app.init_resource::<GamepadMovement>()
+            .add_event::<GridMoveIntent>()
+            .add_systems(
+                PreUpdate,
+                (sync_gamepad_input, send_grid_move_intents)
+                    .chain()
+                    .after(InputSystem),
+            );
+    }
+}
+
+/// The gamepad part of [`Input`].
+#[derive(Default, Debug)]
+pub(crate) struct GamepadState {
+    buttons_down: HashSet<GamepadButtonType>,
+    buttons_pressed: HashSet<GamepadButtonType>,
+    buttons_released: HashSet<GamepadButtonType>,
+    axes: HashMap<GamepadAxisType, f32>,
+}
+
+impl Input {
+    /// Whether the given button is down on any gamepad.
+    pub fn gamepad_button(&self, button: GamepadButtonType) -> bool {
+        self.gamepad.buttons_down.contains(&button)
+    }
+
+    /// Whether the given button was pressed on any gamepad since the last
+    /// update.
+    pub fn gamepad_button_pressed(&self, button: GamepadButtonType) -> bool {
+        self.gamepad.buttons_pressed.contains(&button)
+    }
+
+    /// Whether the given button was released on any gamepad since the last
+    /// update.
+    pub fn gamepad_button_released(&self, button: GamepadButtonType) -> bool {
+        self.gamepad.buttons_released.contains(&button)
+    }
+
+    /// The position of the given axis, from `-1.0` to `1.0`. With several
+    /// gamepads connected, this is the one that's furthest from the center.
+    pub fn gamepad_axis(&self, axis: GamepadAxisType) -> f32 {
+        self.gamepad.axes.get(&axis).copied().unwrap_or(0.0)
+    }
+}
+
+pub(crate) fn sync_gamepad_input(
+    gamepads: Res<'_, Gamepads>,
+    buttons: Res<'_, ButtonInput<GamepadButton>>,
+    axes: Res<'_, Axis<GamepadAxis>>,
+    mut input: ResMut<'_, Input>,
+) {
+    let state = &mut input.gamepad;
+    state.buttons_down = buttons.get_pressed().map(|b| b.button_type).collect();
+    state.buttons_pressed = buttons.get_just_pressed().map(|b| b.button_type).collect();
+    state.buttons_released = buttons.get_just_released().map(|b| b.button_type).collect();
+
+    state.axes.clear();
+    for gamepad in gamepads.iter() {
+        for axis_type in [
+            GamepadAxisType::LeftStickX,
+            GamepadAxisType::LeftStickY,
+            GamepadAxisType::LeftZ,
+            GamepadAxisType::RightStickX,
+            GamepadAxisType::RightStickY,
+            GamepadAxisType::RightZ,
+        ]
+        .iter()
+        .copied()
+        {
+            let value = match axes.get(GamepadAxis::new(gamepad, axis_type)) {
+                Some(value) => value,
+                None => continue,
+            };
+            let entry = state.axes.entry(axis_type).or_insert(0.0);
+            if value.abs() > entry.abs() {
+                *entry = value;
+            }
+        }
+    }
+}
+
+/// The eight directions by angle, going clockwise on the console from the
+/// right.
+const DIRECTIONS: [(i32, i32); 8] = [
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+];
+
+/// Sent when the D-pad or left stick asks to move one cell. `dx` and `dy` are
+/// each `-1`, `0` or `1`, with positive `dy` pointing down the console.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Event)]
+pub struct GridMoveIntent {
+    /// The horizontal step.
+    pub dx: i32,
+    /// The vertical step.
+    pub dy: i32,
+}
+
+/// Resource configuring how D-pad and stick input turns into
+/// [`GridMoveIntent`]s.
+#[derive(Debug, Clone, Resource)]
+pub struct GamepadMovement {
+    /// How far the left stick has to be pushed before it counts, from `0.0`
+    /// to `1.0`. Defaults to `0.5`.
+    pub dead_zone: f32,
+    /// How long a direction has to be held before it repeats, in seconds.
+    /// Defaults to `0.3`.
+    pub repeat_delay_secs: f32,
+    /// How long to wait between repeats, in seconds. Defaults to `0.1`.
+    pub repeat_interval_secs: f32,
+    held: Option<(i32, i32)>,
+    held_secs: f32,
+    next_repeat_secs: f32,
+}
+
+impl Default for GamepadMovement {
+    fn default() -> Self {
+        Self {
+            dead_zone: 0.5,
+            repeat_delay_secs: 0.3,
+            repeat_interval_secs: 0.1,
+            held: None,
+            held_secs: 0.0,
+            next_repeat_secs: 0.0,
+        }
+    }
+}
+
+impl GamepadMovement {
+    /// The direction the gamepads point in, if any. The D-pad wins over the
+    /// stick.
+    fn direction(&self, input: &Input) -> Option<(i32, i32)> {
+        let down = |button| i32::from(input.gamepad_button(button));
+        let dpad = (
+            down(GamepadButtonType::DPadRight) - down(GamepadButtonType::DPadLeft),
+            down(GamepadButtonType::DPadDown) - down(GamepadButtonType::DPadUp),
+        );
+        if dpad != (0, 0) {
+            return Some(dpad);
+        }
+
+        // Stick up is positive, console up is negative
+        let (x, y) = (
+            input.gamepad_axis(GamepadAxisType::LeftStickX),
+            -input.gamepad_axis(GamepadAxisType::LeftStickY),
+        );
+        if x.hypot(y) < self.dead_zone {
+            return None;
+        }
+        // Snap to the nearest of the eight directions
+        let octant = (y.atan2(x) / std::f32::consts::FRAC_PI_4).round() as i32;
+        Some(DIRECTIONS[octant.rem_euclid(8) as usize])
+    }
+}
+
+fn send_grid_move_intents(
+    input: Res<'_, Input>,
+    mut movement: ResMut<'_, GamepadMovement>,
+    mut intents: EventWriter<'_, GridMoveIntent>,
+) {
+    let direction = movement.direction(&input);
+    if direction != movement.held {
+        movement.held = direction;
+        movement.held_secs = 0.0;
+        movement.next_repeat_secs = movement.repeat_delay_secs;
+        if let Some((dx, dy)) = direction {
+            intents.send(GridMoveIntent { dx, dy });
+        }
+        return;
+    }
+
+    let (dx, dy) = match direction {
+        Some(direction) => direction,
+        None => return,
+    };
+    movement.held_secs += 1.0 / UPDATES_PER_SECOND;
+    if movement.held_secs >= movement.next_repeat_secs {
+        movement.next_repeat_secs += movement.repeat_interval_secs.max(0.01);
+        intents.send(GridMoveIntent { dx, dy });
+    }
+}
@@ -0,0 +1,183 @@
+//! Grid geometry helpers for working with console cells.
+
+/// A rectangle of console cells.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rect {
+    /// The x coordinate of the top-left cell.
+    pub x: i32,
+    /// The y coordinate of the top-left cell.
+    pub y: i32,
+    /// The width, in cells.
+    pub width: u32,
+    /// The height, in cells.
+    pub height: u32,
+}
+
+impl Rect {
+    /// A rectangle with its top-left cell at `(x, y)`.
+    pub fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Whether cell `(x, y)` is inside the rectangle.
+    pub fn contains(&self, (x, y): (i32, i32)) -> bool {
+        x >= self.x
+            && y >= self.y
+            && ((x - self.x) as u32) < self.width
+            && ((y - self.y) as u32) < self.height
+    }
+}
+
+/// Returns the cells on the line between `from` and `to`, both ends included,
+/// in order from `from` to `to`. The line is computed with Bresenham's
+/// algorithm.
+pub fn line(from: (i32, i32), to: (i32, i32)) -> Vec<(i32, i32)> {
+    let (mut x, mut y) = from;
+    let (x1, y1) = to;
+    let dx = (x1 - x).abs();
+    let dy = -(y1 - y).abs();
+    let sx = if x < x1 { 1 } else { -1 };
+    let sy = if y < y1 { 1 } else { -1 };
+    let mut err = dx + dy;
+
+    let mut cells = Vec::with_capacity((dx.max(-dy) + 1) as usize);
+    loop {
+        cells.push((x, y));
+        if x == x1 && y == y1 {
+            break;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+    }
+    cells
+}
+
+/// Returns the squared distance between two cells.
+#[inline]
+pub fn distance_squared(a: (i32, i32), b: (i32, i32)) -> i32 {
+    let dx = b.0 - a.0;
+    let dy = b.1 - a.1;
+    dx * dx + dy * dy
+}
+
+/// Returns all the cells whose center lies within `radius` cells of `center`,
+/// `center` itself included.
+pub fn disc(center: (i32, i32), radius: u32) -> Vec<(i32, i32)> {
+    let r = radius as i32;
+    let r2 = r * r + r;
+    let mut cells = Vec::new();
+    for y in center.1 - r..=center.1 + r {
+        for x in center.0 - r..=center.0 + r {
+            if distance_squared(center, (x, y)) <= r2 {
+                cells.push((x, y));
+            }
+        }
+    }
+    cells
+}
+
+/// Returns all the cells within `radius` cells of `origin` that lie inside
+/// the cone pointing from `origin` towards `toward`, spreading
+/// `half_angle_degrees` to either side. `origin` itself is not included.
+///
+/// If `origin` and `toward` are the same cell, the cone has no direction and
+/// no cells are returned.
+pub fn cone(
+    origin: (i32, i32),
+    toward: (i32, i32),
+    radius: u32,
+    half_angle_degrees: f32,
+) -> Vec<(i32, i32)> {
+    if origin == toward {
+        return Vec::new();
+    }
+
+    let direction = ((toward.1 - origin.1) as f32).atan2((toward.0 - origin.0) as f32);
+    let half_angle = half_angle_degrees.to_radians();
+    disc(origin, radius)
+        .into_iter()
+        .filter(|&cell| {
+            if cell == origin {
+                return false;
+            }
+            let angle = ((cell.1 - origin.1) as f32).atan2((cell.0 - origin.0) as f32);
+            let mut difference = (angle - direction).abs();
+            if difference > std::f32::consts::PI {
+                difference = 2.0 * std::f32::consts::PI - difference;
+            }
+            difference <= half_angle
+        })
+        .collect()
+}
+
+/// Returns the first cell on the line from `from` to `to` for which
+/// `is_blocking` returns true, if any. `from` itself is never tested, but `to`
+/// is, so a projectile fired at a wall stops at the wall.
+pub fn first_blocking_cell(
+    from: (i32, i32),
+    to: (i32, i32),
+    mut is_blocking: impl FnMut((i32, i32)) -> bool,
+) -> Option<(i32, i32)> {
+    line(from, to)
+        .into_iter()
+        .skip(1)
+        .find(|&cell| is_blocking(cell))
+}
+
+/// Returns whether `to` is visible from `from`, i.e. whether none of the
+/// cells between them are blocking. Neither end is tested, so a wall is
+/// visible even though it blocks sight past it.
+pub fn los(
+    from: (i32, i32),
+    to: (i32, i32),
+    mut is_blocking: impl FnMut((i32, i32)) -> bool,
+) -> bool {
+    match first_blocking_cell(from, to, &mut is_blocking) {
+        None => true,
+        Some(cell) => cell == to,
+    }
+}
+
+/// How much cover a target has from an observer. See [`cover`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Cover {
+    /// The target is in plain sight.
+    None,
+    /// The target is visible along some lines of sight but not others, such
+    /// as when it's peeking around a corner.
+    Partial,
+    /// The target can't be seen at all.
+    Full,
+}
+
+/// Returns how much cover `to` has from `from`.
+///
+/// A Bresenham line isn't symmetric, so the line from `from` to `to` can pass
+/// through different cells than the line from `to` to `from`. Both are
+/// tested, and the target has partial cover when only one of them is
+/// blocked.
+pub fn cover(
+    from: (i32, i32),
+    to: (i32, i32),
+    mut is_blocking: impl FnMut((i32, i32)) -> bool,
+) -> Cover {
+    let forward = los(from, to, &mut is_blocking);
+    let backward = los(to, from, &mut is_blocking);
+    match (forward, backward) {
+        (true, true) => Cover::None,
+        (false, false) => Cover::Full,
+        _ => Cover::Partial,
+    }
+}
@@ -0,0 +1,132 @@
+//! Gradient fills that use shading glyphs to look smoother than the colors
+//! alone would.
+//!
+//! A cell can only show two colors, so a gradient between two colors is
+//! drawn with the background set to the first color, the foreground set to
+//! the second, and a shading glyph (` `, `░`, `▒`, `▓` or `█`) picking how
+//! much of each shows. Cells between two shading steps are dithered, so the
+//! steps blend into each other:
+//!
+//! ```
+//! # use bevy_doryen::doryen::Console;
+//! # use bevy_doryen::gradient::{gradient_fill, Gradient};
+//! # let mut root_console = Console::new(80, 45);
+//! // A night sky fading towards the horizon
+//! gradient_fill(
+//!     &mut root_console,
+//!     (0, 0),
+//!     (80, 20),
+//!     (5, 5, 30, 255),
+//!     (60, 40, 90, 255),
+//!     Gradient::Vertical,
+//! );
+//! ```
+//!
+//! The glyphs are the ones at those positions in code page 437, like in the
+//! fonts that come with Doryen.
+
+use crate::doryen::{Color, Console};
+
+/// The shading glyphs from none to all of the foreground color showing.
+pub const SHADES: [u16; 5] = [' ' as u16, 176, 177, 178, 219];
+
+/// A 4×4 ordered dithering matrix, spreading the thresholds out evenly.
+const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Which way a gradient runs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gradient {
+    /// From the left edge to the right edge.
+    Horizontal,
+    /// From the top edge to the bottom edge.
+    Vertical,
+    /// From the center out to the corners, for vignettes.
+    Radial,
+}
+
+impl Gradient {
+    /// How far along the gradient the cell `(x, y)` of a `width` by `height`
+    /// area is, from `0.0` to `1.0`.
+    fn amount(self, (x, y): (i32, i32), (width, height): (u32, u32)) -> f32 {
+        let fraction = |position: i32, size: u32| {
+            if size <= 1 {
+                0.0
+            } else {
+                position as f32 / (size - 1) as f32
+            }
+        };
+        match self {
+            Self::Horizontal => fraction(x, width),
+            Self::Vertical => fraction(y, height),
+            Self::Radial => {
+                let dx = fraction(x, width) * 2.0 - 1.0;
+                let dy = fraction(y, height) * 2.0 - 1.0;
+                ((dx * dx + dy * dy) / 2.0).sqrt()
+            }
+        }
+    }
+}
+
+/// Fills the `size` cells wide and high area at `position` with a gradient
+/// from `from` to `to`.
+pub fn gradient_fill(
+    console: &mut Console,
+    position: (i32, i32),
+    size: (u32, u32),
+    from: Color,
+    to: Color,
+    gradient: Gradient,
+) {
+    gradient_fill_with(console, position, size, from, to, |cell| {
+        gradient.amount(cell, size)
+    });
+}
+
+/// Like [`gradient_fill`], but with `amount` saying how far along the
+/// gradient each cell is, from `0.0` for all `from` to `1.0` for all `to`.
+/// `amount` gets the position of the cell relative to the top left corner of
+/// the area.
+pub fn gradient_fill_with(
+    console: &mut Console,
+    (x, y): (i32, i32),
+    (width, height): (u32, u32),
+    from: Color,
+    to: Color,
+    mut amount: impl FnMut((i32, i32)) -> f32,
+) {
+    for dy in 0..height as i32 {
+        for dx in 0..width as i32 {
+            let t = amount((dx, dy));
+            dithered_cell(console, (x + dx, y + dy), from, to, t);
+        }
+    }
+}
+
+/// Draws a single cell of a gradient from `from` to `to`, `amount` of the way
+/// along. The dithering pattern depends on the position of the cell, so
+/// neighbouring cells of the same amount make an even pattern.
+pub fn dithered_cell(
+    console: &mut Console,
+    (x, y): (i32, i32),
+    from: Color,
+    to: Color,
+    amount: f32,
+) {
+    let steps = (SHADES.len() - 1) as f32;
+    let level = amount.clamp(0.0, 1.0) * steps;
+    let (lower, fraction) = (level.floor(), level.fract());
+    let threshold =
+        (f32::from(BAYER[y.rem_euclid(4) as usize][x.rem_euclid(4) as usize]) + 0.5) / 16.0;
+    let step = if fraction > threshold {
+        lower as usize + 1
+    } else {
+        lower as usize
+    };
+    console.cell(
+        x,
+        y,
+        Some(SHADES[step.min(SHADES.len() - 1)]),
+        Some(to),
+        Some(from),
+    );
+}
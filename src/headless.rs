@@ -0,0 +1,326 @@
+//! Running bevy_doryen without a window.
+//!
+//! [`HeadlessDoryenPlugin`] sets up everything [`DoryenPlugin`] does, but
+//! backs the [`RootConsole`] with an in-memory console instead of a Doryen
+//! window and OpenGL context. Frames are driven by calling
+//! [`run_doryen_frame`](HeadlessDoryenExtensions::run_doryen_frame), which
+//! makes it possible to test render systems in CI:
+//!
+//! ```
+//! use bevy_app::App;
+//! use bevy_doryen::headless::{console_row, HeadlessDoryenExtensions, HeadlessDoryenPlugin};
+//! use bevy_doryen::doryen::TextAlign;
+//! use bevy_doryen::{RenderSystemExtensions, RootConsole};
+//! use bevy_ecs::system::ResMut;
+//!
+//! fn render(mut root_console: ResMut<RootConsole>) {
+//!     root_console.print(0, 0, "Hello", TextAlign::Left, None, None);
+//! }
+//!
+//! let mut app = App::new();
+//! app.add_plugins(HeadlessDoryenPlugin)
+//!     .add_doryen_render_systems(render);
+//! app.run_doryen_frame();
+//!
+//! let root_console = app.world.resource::<RootConsole>();
+//! assert!(console_row(&root_console, 0).starts_with("Hello"));
+//! ```
+//!
+//! Input can be simulated through the [`HeadlessInput`] resource, and
+//! resizing the window with
+//! [`resize_window`](HeadlessDoryenExtensions::resize_window).
+//!
+//! [`DoryenPlugin`]: crate::DoryenPlugin
+
+use crate::doryen::Console;
+use crate::frame_loop::FrameLoop;
+use crate::input::InputSource;
+use crate::touch::TouchInput;
+use crate::{
+    CurrentFont, DoryenPluginSettings, DoryenRender, FpsInfo, MouseButton, ResizeMode, Resized,
+    RootConsole, TouchPhase,
+};
+use bevy_app::{App as BevyApp, Plugin};
+use bevy_ecs::system::Resource;
+use bevy_ecs::world::Mut;
+use std::borrow::Cow;
+
+/// A replacement for [`DoryenPlugin`](crate::DoryenPlugin) that runs without
+/// a window. See the [module documentation](self).
+///
+/// The console size, screen size and [`ResizeMode`] are taken from the
+/// [`DoryenPluginSettings`] resource if it has been inserted before the
+/// plugin is added. When the app is [`run`](BevyApp::run), frames are run
+/// back to back until an [`AppExit`](bevy_app::AppExit) event is sent.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct HeadlessDoryenPlugin;
+
+impl Plugin for HeadlessDoryenPlugin {
+    fn build(&self, app: &mut BevyApp) {
+        let settings = app
+            .world
+            .get_resource_or_insert_with(DoryenPluginSettings::default);
+        let options = &settings.app_options;
+        let (console_width, console_height) = (options.console_width, options.console_height);
+        let current_font = CurrentFont(Cow::Owned(options.font_path.clone()));
+        let frame_state = HeadlessFrameState {
+            frame_loop: FrameLoop::default(),
+            screen_size: (options.screen_width, options.screen_height),
+            resize_mode: settings.resize_mode,
+        };
+
+        crate::add_doryen_core(app)
+            .insert_resource(RootConsole(Some(Console::new(
+                console_width,
+                console_height,
+            ))))
+            .insert_resource(current_font)
+            .init_resource::<HeadlessInput>()
+            .insert_resource(frame_state)
+            .set_runner(headless_runner);
+    }
+}
+
+/// Adds methods to the [`App`](BevyApp) for driving a headless Doryen app.
+pub trait HeadlessDoryenExtensions {
+    /// Runs a single Doryen frame: [`ScreenInfo`](crate::ScreenInfo) is
+    /// refreshed and the input in [`HeadlessInput`] is handed to the
+    /// [`Input`](crate::Input) resource, then the app is updated once and the
+    /// [`DoryenRender`] schedule is run once. Like in a window, updates and
+    /// renders are skipped as the
+    /// [`DoryenFrameLimits`](crate::DoryenFrameLimits) say, and updates while
+    /// the [`DoryenEngineControl`](crate::DoryenEngineControl) is paused.
+    ///
+    /// Returns `false` if an [`AppExit`](bevy_app::AppExit) event was sent
+    /// during the update, in which case the
+    /// [`DoryenShutdown`](crate::DoryenShutdown) schedule is run instead of
+    /// the render schedule.
+    fn run_doryen_frame(&mut self) -> bool;
+
+    /// Resizes the simulated window to `width` by `height` pixels, as if the
+    /// player had dragged its border. A [`Resized`] event is sent and the root
+    /// console is resized as the [`ResizeMode`] says, right away like Doryen
+    /// does. The new sizes show up in [`ScreenInfo`](crate::ScreenInfo) on the
+    /// next frame, along with a [`ConsoleResized`](crate::ConsoleResized)
+    /// event if the console changed size.
+    fn resize_window(&mut self, width: u32, height: u32);
+}
+
+impl HeadlessDoryenExtensions for BevyApp {
+    fn run_doryen_frame(&mut self) -> bool {
+        let fps = crate::UPDATES_PER_SECOND as u32;
+        *self.world.resource_mut::<FpsInfo>() = FpsInfo {
+            fps,
+            average_fps: fps,
+        };
+
+        let console_size = self
+            .world
+            .resource::<RootConsole>()
+            .0
+            .as_ref()
+            .map_or((0, 0), Console::get_size);
+        let run_update =
+            self.world
+                .resource_scope(|world, mut state: Mut<'_, HeadlessFrameState>| {
+                    world.resource_scope(|world, mut headless_input: Mut<'_, HeadlessInput>| {
+                        let mouse_button_listeners = world
+                            .resource::<DoryenPluginSettings>()
+                            .mouse_button_listeners
+                            .clone();
+                        let screen_size = state.screen_size;
+                        let run_update = state.frame_loop.begin_frame(
+                            world,
+                            screen_size,
+                            console_size,
+                            &mouse_button_listeners,
+                            &mut *headless_input,
+                        );
+                        headless_input.clear();
+                        run_update
+                    })
+                });
+
+        if run_update {
+            self.update();
+
+            let exited =
+                self.world
+                    .resource_scope(|world, mut state: Mut<'_, HeadlessFrameState>| {
+                        state.frame_loop.exit_requested(world)
+                    });
+            if exited {
+                crate::frame_loop::shut_down(&mut self.world);
+                return false;
+            }
+        }
+
+        let render = self
+            .world
+            .resource_scope(|world, mut state: Mut<'_, HeadlessFrameState>| {
+                state.frame_loop.should_render(world, fps)
+            });
+        if render {
+            self.world.run_schedule(DoryenRender);
+        }
+        true
+    }
+
+    fn resize_window(&mut self, width: u32, height: u32) {
+        let mut state = self.world.resource_mut::<HeadlessFrameState>();
+        let (previous_width, previous_height) =
+            std::mem::replace(&mut state.screen_size, (width, height));
+        let resize_mode = state.resize_mode;
+        let resized = Resized {
+            previous_width,
+            previous_height,
+            new_width: width,
+            new_height: height,
+        };
+        let previous_console_size = self.world.resource::<RootConsole>().get_size();
+        crate::resize_root_console(&mut self.world, resize_mode, resized, previous_console_size);
+    }
+}
+
+#[derive(Resource)]
+struct HeadlessFrameState {
+    frame_loop: FrameLoop,
+    screen_size: (u32, u32),
+    resize_mode: ResizeMode,
+}
+
+fn headless_runner(mut app: BevyApp) {
+    while app.run_doryen_frame() {}
+}
+
+/// Simulated input for a headless Doryen app. Whatever is queued up here is
+/// seen by the [`Input`](crate::Input) resource on the next frame.
+#[derive(Default, Debug, Resource)]
+pub struct HeadlessInput {
+    keys_pressed: Vec<String>,
+    keys_released: Vec<String>,
+    mouse_buttons_pressed: Vec<usize>,
+    mouse_buttons_released: Vec<usize>,
+    text: String,
+    mouse_position: (f32, f32),
+    mouse_wheel: (f32, f32),
+    touches: Vec<TouchInput>,
+    close_requested: bool,
+}
+
+impl HeadlessInput {
+    fn clear(&mut self) {
+        self.keys_pressed.clear();
+        self.keys_released.clear();
+        self.mouse_buttons_pressed.clear();
+        self.mouse_buttons_released.clear();
+        self.text.clear();
+        self.mouse_wheel = (0.0, 0.0);
+        self.touches.clear();
+        self.close_requested = false;
+    }
+
+    /// Presses the given key. It stays down until it's released.
+    pub fn press_key(&mut self, key: &str) {
+        self.keys_pressed.push(String::from(key));
+    }
+
+    /// Releases the given key.
+    pub fn release_key(&mut self, key: &str) {
+        self.keys_released.push(String::from(key));
+    }
+
+    /// Presses the given mouse button. It stays down until it's released.
+    pub fn press_mouse_button(&mut self, mouse_button: MouseButton) {
+        self.mouse_buttons_pressed.push(mouse_button.to_usize());
+    }
+
+    /// Releases the given mouse button.
+    pub fn release_mouse_button(&mut self, mouse_button: MouseButton) {
+        self.mouse_buttons_released.push(mouse_button.to_usize());
+    }
+
+    /// Moves the mouse to the given position, in console cell coordinates.
+    pub fn set_mouse_pos(&mut self, mouse_position: (f32, f32)) {
+        self.mouse_position = mouse_position;
+    }
+
+    /// Scrolls the mouse wheel by the given amount during the next frame. See
+    /// [`Input::mouse_wheel_delta`](crate::Input::mouse_wheel_delta) for the
+    /// units.
+    pub fn scroll_mouse_wheel(&mut self, (x, y): (f32, f32)) {
+        self.mouse_wheel.0 += x;
+        self.mouse_wheel.1 += y;
+    }
+
+    /// Reports a change to the touch `id` at the given position, in console
+    /// cell coordinates, during the next frame.
+    pub fn touch(&mut self, id: u64, phase: TouchPhase, position: (f32, f32)) {
+        self.touches.push(TouchInput {
+            id,
+            phase,
+            position,
+        });
+    }
+
+    /// Adds to the text typed during the next frame.
+    pub fn type_text(&mut self, text: &str) {
+        self.text.push_str(text);
+    }
+
+    /// Makes [`Input::close_requested`](crate::Input::close_requested) return
+    /// true during the next frame.
+    pub fn request_close(&mut self) {
+        self.close_requested = true;
+    }
+}
+
+impl InputSource for HeadlessInput {
+    fn for_each_key_pressed(&self, f: &mut dyn FnMut(&str)) {
+        self.keys_pressed.iter().map(String::as_str).for_each(f);
+    }
+
+    fn for_each_key_released(&self, f: &mut dyn FnMut(&str)) {
+        self.keys_released.iter().map(String::as_str).for_each(f);
+    }
+
+    fn mouse_button_pressed(&mut self, num: usize) -> bool {
+        self.mouse_buttons_pressed.contains(&num)
+    }
+
+    fn mouse_button_released(&mut self, num: usize) -> bool {
+        self.mouse_buttons_released.contains(&num)
+    }
+
+    fn text(&self) -> String {
+        self.text.clone()
+    }
+
+    fn mouse_pos(&self) -> (f32, f32) {
+        self.mouse_position
+    }
+
+    fn mouse_wheel(&mut self) -> (f32, f32) {
+        self.mouse_wheel
+    }
+
+    fn touches(&mut self) -> Vec<TouchInput> {
+        self.touches.clone()
+    }
+
+    fn close_requested(&self) -> bool {
+        self.close_requested
+    }
+}
+
+/// Returns the characters on row `y` of the console as a string. Cells that
+/// don't hold a printable ASCII character come out as spaces. Meant for
+/// asserting on console contents in tests.
+pub fn console_row(console: &Console, y: i32) -> String {
+    (0..console.get_width() as i32)
+        .map(|x| match console.get_ascii(x, y) {
+            Some(ascii @ 32..=126) => char::from(ascii as u8),
+            _ => ' ',
+        })
+        .collect()
+}
@@ -0,0 +1,99 @@
+//! Resolving which entity is under a given console cell.
+
+use crate::geometry::Rect;
+use crate::Input;
+use bevy_app::{App, Plugin, PostUpdate};
+use bevy_ecs::component::Component;
+use bevy_ecs::entity::Entity;
+use bevy_ecs::system::{Query, ResMut, Resource};
+use std::cmp::Reverse;
+
+/// The plugin that keeps the [`HitTest`] resource up to date with all the
+/// [`HitRegion`] components in the world.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct HitTestPlugin;
+
+impl Plugin for HitTestPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HitTest>()
+            .add_systems(PostUpdate, update_hit_test);
+    }
+}
+
+/// A component marking the rectangle of console cells an entity occupies for
+/// the purpose of hit-testing. Entities on a higher `layer` are considered to
+/// be on top of those on a lower one.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Component)]
+pub struct HitRegion {
+    /// The cells the region covers.
+    pub rect: Rect,
+    /// The layer the region is on. Higher layers are on top.
+    pub layer: i32,
+}
+
+impl HitRegion {
+    /// A region covering `rect`.
+    pub fn new(rect: Rect, layer: i32) -> Self {
+        Self { rect, layer }
+    }
+
+    /// A region covering a single cell.
+    pub fn cell(x: i32, y: i32, layer: i32) -> Self {
+        Self::new(Rect::new(x, y, 1, 1), layer)
+    }
+}
+
+/// Resource answering "what is under this cell" queries. It is rebuilt from
+/// all [`HitRegion`] components during [`PostUpdate`].
+#[derive(Default, Debug, Clone, Resource)]
+pub struct HitTest {
+    /// Sorted from the topmost layer to the bottommost one.
+    regions: Vec<(Entity, HitRegion)>,
+}
+
+impl HitTest {
+    /// Returns the topmost entity whose region covers the given cell.
+    pub fn at(&self, cell: (i32, i32)) -> Option<Entity> {
+        self.regions
+            .iter()
+            .find(|(_, region)| region.rect.contains(cell))
+            .map(|&(entity, _)| entity)
+    }
+
+    /// Returns all the entities whose region covers the given cell, from the
+    /// topmost to the bottommost.
+    pub fn all_at(&self, cell: (i32, i32)) -> impl Iterator<Item = Entity> + '_ {
+        self.regions
+            .iter()
+            .filter(move |(_, region)| region.rect.contains(cell))
+            .map(|&(entity, _)| entity)
+    }
+
+    /// Returns the topmost entity under the mouse cursor.
+    pub fn under_mouse(&self, input: &Input) -> Option<Entity> {
+        let (x, y) = input.mouse_pos();
+        self.at((x.floor() as i32, y.floor() as i32))
+    }
+
+    /// The region of the given entity as of the last rebuild, if it has one.
+    pub fn region(&self, entity: Entity) -> Option<HitRegion> {
+        self.regions
+            .iter()
+            .find(|&&(e, _)| e == entity)
+            .map(|&(_, region)| region)
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn update_hit_test(
+    mut hit_test: ResMut<'_, HitTest>,
+    regions: Query<'_, '_, (Entity, &HitRegion)>,
+) {
+    hit_test.regions.clear();
+    hit_test
+        .regions
+        .extend(regions.iter().map(|(entity, &region)| (entity, region)));
+    hit_test
+        .regions
+        .sort_by_key(|(_, region)| Reverse(region.layer));
+}
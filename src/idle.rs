@@ -0,0 +1,814 @@
+//! Detecting when the player has stopped playing, and an attract mode for
+//! when they have.
+//!
+//! [`IdlePlugin`] watches the [`Input`] resource and sends [`IdleStarted`]
+//! once nothing has been pressed, typed or moved for
+//! [`IdleDetector::timeout_secs`] seconds, and [`IdleEnded`] as soon as the
+//! player touches anything again. That's enough for things like dimming the
+//! screen or returning to the title screen in a kiosk build.
+//!
+//! For a proper attract mode, record a session with [`InputRecorder`] and hand
+//! it to [`AttractMode`]. While the game is idle, the recording is played back
+//! through the [`Input`] resource as if the player were pressing the keys, so
+//! the game plays itself. Playback stops when the player takes over.
+//!
+//! Recordings can also be [saved](InputRecording::save) to a file and
+//! [loaded](InputRecording::load) again, to reproduce a bug or play a
+//! scripted demo with [`InputPlayback`]. Unlike attract mode, playback
+//! replaces the player's input entirely until the recording ends, so the
+//! game sees exactly what was recorded. Start the game from the same state,
+//! random seed included, for it to play out the same way.
+//!
+//! ```
+//! # use bevy_doryen::idle::{AttractMode, IdleStarted, InputRecorder};
+//! # use bevy_ecs::event::EventReader;
+//! # use bevy_ecs::system::ResMut;
+//! fn start_demo(
+//!     mut idle_started: EventReader<IdleStarted>,
+//!     mut recorder: ResMut<InputRecorder>,
+//!     mut attract_mode: ResMut<AttractMode>,
+//! ) {
+//!     if idle_started.iter().last().is_some() {
+//!         // Reset the game to the state the recording was made in here.
+//!         attract_mode.recording = recorder.stop();
+//!     }
+//! }
+//! ```
+
+use crate::input::{InputCapture, InputSource};
+use crate::run_stats::{escape, unescape};
+use crate::touch::TouchInput;
+use crate::{Input, MouseButton, UpdateRequests};
+use bevy_app::{App as BevyApp, First, Plugin};
+use bevy_ecs::event::{Event, EventWriter};
+use bevy_ecs::schedule::IntoSystemConfigs;
+use bevy_ecs::system::{Res, ResMut, Resource};
+use bevy_ecs::world::{Mut, World};
+use bevy_utils::Instant;
+use std::fmt::Write as _;
+use std::io;
+use std::path::Path;
+
+/// The first line of a saved [`InputRecording`].
+const RECORDING_HEADER: &str = "bevy_doryen input recording 1";
+
+/// The idle detection plugin. Adds the [`IdleDetector`], [`InputRecorder`],
+/// [`AttractMode`] and [`InputPlayback`] resources, unless they have already
+/// been inserted, and the [`IdleStarted`] and [`IdleEnded`] events.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct IdlePlugin;
+
+impl Plugin for IdlePlugin {
+    fn build(&self, app: &mut BevyApp) {
+        app.init_resource::<IdleDetector>()
+            .init_resource::<InputRecorder>()
+            .init_resource::<AttractMode>()
+            .init_resource::<InputPlayback>()
+            .add_event::<IdleStarted>()
+            .add_event::<IdleEnded>()
+            .add_systems(
+                First,
+                (
+                    detect_idle,
+                    record_input,
+                    advance_playback,
+                    advance_attract_mode,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Emitted at the start of the update in which the player has been idle for
+/// [`IdleDetector::timeout_secs`] seconds.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Event)]
+pub struct IdleStarted;
+
+/// Emitted at the start of the update in which the player provides input
+/// after having been idle.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Event)]
+pub struct IdleEnded {
+    /// How many seconds the player was idle for, including the time before
+    /// [`IdleStarted`] was sent.
+    pub idle_secs: f32,
+}
+
+/// Resource tracking how long it's been since the player last provided
+/// input. See the [module documentation](self).
+///
+/// Time is measured on the wall clock. With
+/// [`on_demand`](crate::DoryenFrameLimits::on_demand) frame limits, the
+/// detector asks for a Bevy update for when the timeout runs out, through
+/// the [`UpdateRequests`] resource.
+#[derive(Debug, Clone, Copy, PartialEq, Resource)]
+pub struct IdleDetector {
+    /// How many seconds without input it takes to become idle. Defaults to
+    /// `60.0`.
+    pub timeout_secs: f32,
+    last_input: Option<Instant>,
+    idle_secs: f32,
+    idle: bool,
+    mouse_position: Option<(f32, f32)>,
+}
+
+impl Default for IdleDetector {
+    fn default() -> Self {
+        Self::new(60.0)
+    }
+}
+
+impl IdleDetector {
+    /// Creates a detector that becomes idle after `timeout_secs` seconds
+    /// without input.
+    pub fn new(timeout_secs: f32) -> Self {
+        Self {
+            timeout_secs,
+            last_input: None,
+            idle_secs: 0.0,
+            idle: false,
+            mouse_position: None,
+        }
+    }
+
+    /// Whether the player is idle.
+    pub fn is_idle(&self) -> bool {
+        self.idle
+    }
+
+    /// How many seconds it had been since the player last provided input at
+    /// the start of the current update.
+    pub fn idle_secs(&self) -> f32 {
+        self.idle_secs
+    }
+
+    /// Starts counting from zero again, as if the player had just provided
+    /// input. Doesn't send [`IdleEnded`] if the player was idle; the next
+    /// input does that.
+    pub fn reset(&mut self) {
+        self.last_input = Some(Instant::now());
+        self.idle_secs = 0.0;
+    }
+}
+
+/// The input of a single Bevy update, as recorded by [`InputRecorder`].
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct RecordedInput {
+    /// The keys pressed during the update.
+    pub keys_pressed: Vec<String>,
+    /// The keys released during the update.
+    pub keys_released: Vec<String>,
+    /// The mouse buttons pressed during the update.
+    pub mouse_buttons_pressed: Vec<MouseButton>,
+    /// The mouse buttons released during the update.
+    pub mouse_buttons_released: Vec<MouseButton>,
+    /// The text typed during the update.
+    pub text: String,
+    /// The mouse position, in console cell coordinates.
+    pub mouse_position: (f32, f32),
+    /// How far the mouse wheel was scrolled during the update.
+    pub mouse_wheel: (f32, f32),
+}
+
+impl RecordedInput {
+    fn from_input(input: &Input) -> Self {
+        let (pressed, released) = input.mouse_button_changes();
+        Self {
+            keys_pressed: input.keys_pressed().map(String::from).collect(),
+            keys_released: input.keys_released().map(String::from).collect(),
+            mouse_buttons_pressed: pressed.into_iter().map(MouseButton::Any).collect(),
+            mouse_buttons_released: released.into_iter().map(MouseButton::Any).collect(),
+            text: input.text().to_owned(),
+            mouse_position: input.mouse_pos(),
+            mouse_wheel: input.mouse_wheel_delta(),
+        }
+    }
+
+    fn to_line(&self) -> String {
+        let buttons = |buttons: &[MouseButton]| {
+            buttons
+                .iter()
+                .map(|button| button.to_usize().to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.keys_pressed.join(" "),
+            self.keys_released.join(" "),
+            buttons(&self.mouse_buttons_pressed),
+            buttons(&self.mouse_buttons_released),
+            escape(&self.text),
+            self.mouse_position.0,
+            self.mouse_position.1,
+            self.mouse_wheel.0,
+            self.mouse_wheel.1
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let keys = |field: &str| field.split_whitespace().map(String::from).collect();
+        let buttons = |field: &str| {
+            field
+                .split_whitespace()
+                .map(|button| button.parse().ok().map(MouseButton::Any))
+                .collect::<Option<_>>()
+        };
+        let mut fields = line.split('\t');
+        Some(Self {
+            keys_pressed: keys(fields.next()?),
+            keys_released: keys(fields.next()?),
+            mouse_buttons_pressed: buttons(fields.next()?)?,
+            mouse_buttons_released: buttons(fields.next()?)?,
+            text: unescape(fields.next()?),
+            mouse_position: (fields.next()?.parse().ok()?, fields.next()?.parse().ok()?),
+            mouse_wheel: (fields.next()?.parse().ok()?, fields.next()?.parse().ok()?),
+        })
+    }
+
+    fn mouse_buttons(&self) -> Vec<MouseButton> {
+        self.mouse_buttons_pressed
+            .iter()
+            .chain(&self.mouse_buttons_released)
+            .copied()
+            .collect()
+    }
+}
+
+impl InputSource for RecordedInput {
+    fn for_each_key_pressed(&self, f: &mut dyn FnMut(&str)) {
+        self.keys_pressed.iter().map(String::as_str).for_each(f);
+    }
+
+    fn for_each_key_released(&self, f: &mut dyn FnMut(&str)) {
+        self.keys_released.iter().map(String::as_str).for_each(f);
+    }
+
+    fn mouse_button_pressed(&mut self, num: usize) -> bool {
+        self.mouse_buttons_pressed
+            .iter()
+            .any(|button| button.to_usize() == num)
+    }
+
+    fn mouse_button_released(&mut self, num: usize) -> bool {
+        self.mouse_buttons_released
+            .iter()
+            .any(|button| button.to_usize() == num)
+    }
+
+    fn text(&self) -> String {
+        self.text.clone()
+    }
+
+    fn mouse_pos(&self) -> (f32, f32) {
+        self.mouse_position
+    }
+
+    fn mouse_wheel(&mut self) -> (f32, f32) {
+        self.mouse_wheel
+    }
+
+    fn touches(&mut self) -> Vec<TouchInput> {
+        Vec::new()
+    }
+
+    fn close_requested(&self) -> bool {
+        false
+    }
+}
+
+/// A recorded input session, one [`RecordedInput`] per Bevy update.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct InputRecording {
+    /// The recorded updates, in order.
+    pub updates: Vec<RecordedInput>,
+}
+
+impl InputRecording {
+    /// The number of recorded updates.
+    pub fn len(&self) -> usize {
+        self.updates.len()
+    }
+
+    /// Whether the recording has no updates in it.
+    pub fn is_empty(&self) -> bool {
+        self.updates.is_empty()
+    }
+
+    /// Loads a recording saved with [`save`](Self::save).
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+        let mut lines = contents.lines();
+        if lines.next() != Some(RECORDING_HEADER) {
+            return Err(invalid(String::from("not a bevy_doryen input recording")));
+        }
+        let updates = lines
+            .enumerate()
+            .map(|(i, line)| {
+                RecordedInput::from_line(line)
+                    .ok_or_else(|| invalid(format!("line {} isn't a recorded update", i + 2)))
+            })
+            .collect::<io::Result<_>>()?;
+        Ok(Self { updates })
+    }
+
+    /// Saves the recording to the given file, one line per update.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut contents = String::new();
+        writeln!(contents, "{}", RECORDING_HEADER).unwrap();
+        for update in &self.updates {
+            writeln!(contents, "{}", update.to_line()).unwrap();
+        }
+        std::fs::write(path, contents)
+    }
+}
+
+/// Resource recording the [`Input`] of every Bevy update into an
+/// [`InputRecording`]. Updates during attract mode or [`InputPlayback`]
+/// playback aren't recorded.
+#[derive(Default, Debug, Clone, Resource)]
+pub struct InputRecorder {
+    recording: Option<InputRecording>,
+}
+
+impl InputRecorder {
+    /// Starts a new recording, throwing away the current one, if any.
+    pub fn start(&mut self) {
+        self.recording = Some(InputRecording::default());
+    }
+
+    /// Stops recording and returns what was recorded, or `None` if nothing
+    /// was being recorded.
+    pub fn stop(&mut self) -> Option<InputRecording> {
+        self.recording.take()
+    }
+
+    /// Whether a recording is in progress.
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+}
+
+/// Resource playing back an [`InputRecording`] while the player is idle. See
+/// the [module documentation](self).
+///
+/// Like [`InputPlayback`], attract mode hands the [`Input`] resource one
+/// recorded update per Bevy update. The player's own input is kept aside
+/// meanwhile, for the [`IdleDetector`] to notice them taking over.
+#[derive(Debug, Resource)]
+pub struct AttractMode {
+    /// The recording to play back. `None`, the default, disables attract mode.
+    pub recording: Option<InputRecording>,
+    /// Whether to start over once the end of the recording is reached.
+    /// Defaults to `true`.
+    pub looping: bool,
+    position: Option<usize>,
+    fed: bool,
+    live: Option<Input>,
+}
+
+impl Default for AttractMode {
+    fn default() -> Self {
+        Self {
+            recording: None,
+            looping: true,
+            position: None,
+            fed: false,
+            live: None,
+        }
+    }
+}
+
+impl AttractMode {
+    /// Whether the recording is being played back.
+    pub fn is_playing(&self) -> bool {
+        self.live.is_some()
+    }
+
+    /// The update to hand to the [`Input`] resource in place of the player's
+    /// input, while `idle`.
+    fn next_update(&mut self, idle: bool) -> PlaybackInput {
+        let recording = match &self.recording {
+            Some(recording) if idle && !recording.is_empty() => recording,
+            _ => {
+                self.position = None;
+                self.fed = false;
+                return PlaybackInput::Live {
+                    stopped: self.live.take().is_some(),
+                };
+            }
+        };
+        let position = self.position.get_or_insert(0);
+        if *position >= recording.len() {
+            if !self.looping {
+                // Stays at the end until the player is back
+                return PlaybackInput::Live {
+                    stopped: self.live.take().is_some(),
+                };
+            }
+            *position = 0;
+        }
+        if self.fed {
+            return PlaybackInput::Pending;
+        }
+        self.fed = true;
+        self.live.get_or_insert_with(Input::default);
+        PlaybackInput::Recorded {
+            update: recording.updates[*position].clone(),
+            from_start: *position == 0,
+        }
+    }
+}
+
+/// Resource playing back an [`InputRecording`] in place of the player's
+/// input. See the [module documentation](self).
+///
+/// Like recording, playback goes one Bevy update at a time: a recorded update
+/// is kept in the [`Input`] resource until a Bevy update has run with it, so
+/// nothing is lost to skipped updates or pauses, whatever the frame limits.
+/// Closing the window still works during playback.
+#[derive(Default, Debug, Clone, Resource)]
+pub struct InputPlayback {
+    /// Whether to start over once the end of the recording is reached.
+    /// Defaults to `false`, which stops playback there.
+    pub looping: bool,
+    recording: Option<InputRecording>,
+    position: usize,
+    fed: bool,
+}
+
+impl InputPlayback {
+    /// Starts playing back `recording` from the start, starting with the next
+    /// Bevy update.
+    pub fn play(&mut self, recording: InputRecording) {
+        self.recording = Some(recording);
+        self.position = 0;
+        self.fed = false;
+    }
+
+    /// Stops playback and returns the recording, or `None` if nothing was
+    /// being played back.
+    pub fn stop(&mut self) -> Option<InputRecording> {
+        self.recording.take()
+    }
+
+    /// Whether a recording is being played back.
+    pub fn is_playing(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// How many updates of the recording have been played back.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// The update to hand to the [`Input`] resource, if playing and it hasn't
+    /// been handed over yet.
+    fn next_update(&mut self) -> PlaybackInput {
+        let update = match &self.recording {
+            Some(recording) => recording.updates.get(self.position),
+            None => return PlaybackInput::Live { stopped: false },
+        };
+        match update {
+            Some(_) if self.fed => PlaybackInput::Pending,
+            Some(update) => {
+                let update = update.clone();
+                self.fed = true;
+                PlaybackInput::Recorded {
+                    update,
+                    from_start: false,
+                }
+            }
+            None => {
+                self.recording = None;
+                PlaybackInput::Live { stopped: true }
+            }
+        }
+    }
+}
+
+/// Where the input of a Doryen update comes from.
+enum PlaybackInput {
+    /// The player, `stopped` being whether playback just ended.
+    Live { stopped: bool },
+    /// The recorded update already in the [`Input`] resource, which no Bevy
+    /// update has run with yet.
+    Pending,
+    /// The next recorded update, `from_start` being whether it's the first
+    /// one of a recording that's being played back from the start again.
+    Recorded {
+        update: RecordedInput,
+        from_start: bool,
+    },
+}
+
+/// Hands the input of a Doryen update to the [`Input`] resource, or to the
+/// [`InputCapture`] while input is captured: the next recorded update while
+/// an [`InputPlayback`] is playing, and the input from `source` otherwise.
+pub(crate) fn feed_input<I: InputSource + ?Sized>(
+    world: &mut World,
+    mouse_button_listeners: &[MouseButton],
+    source: &mut I,
+    accumulate: bool,
+) {
+    let mut playback_input = match world.get_resource_mut::<InputPlayback>() {
+        Some(mut playback) => playback.next_update(),
+        None => PlaybackInput::Live { stopped: false },
+    };
+    // The player's input while attract mode plays, which is thrown away
+    // like it is during playback, but kept for the IdleDetector to see
+    let mut live = None;
+    let mut attract_mode_playing = false;
+    if let PlaybackInput::Live { stopped: false } = playback_input {
+        let idle = world
+            .get_resource::<IdleDetector>()
+            .is_some_and(IdleDetector::is_idle);
+        if let Some(mut attract_mode) = world.get_resource_mut::<AttractMode>() {
+            playback_input = attract_mode.next_update(idle);
+            live = attract_mode.live.take();
+            attract_mode_playing = !matches!(playback_input, PlaybackInput::Live { .. });
+        }
+    }
+    let mut ignored = live.unwrap_or_default();
+    world.resource_scope(|world, mut input: Mut<'_, Input>| {
+        let mut capture = world.get_resource_mut::<InputCapture>();
+        if let Some(capture) = &mut capture {
+            capture.start_update(&mut input);
+        }
+        match capture.as_mut().and_then(|capture| capture.input_mut()) {
+            Some(captured) => {
+                feed(
+                    captured,
+                    &mut ignored,
+                    playback_input,
+                    mouse_button_listeners,
+                    source,
+                    accumulate,
+                );
+                input.clear_captured();
+                if captured.close_requested() {
+                    input.request_close();
+                }
+            }
+            None => feed(
+                &mut input,
+                &mut ignored,
+                playback_input,
+                mouse_button_listeners,
+                source,
+                accumulate,
+            ),
+        }
+    });
+    if attract_mode_playing {
+        world.resource_mut::<AttractMode>().live = Some(ignored);
+    }
+}
+
+fn feed<I: InputSource + ?Sized>(
+    input: &mut Input,
+    ignored: &mut Input,
+    playback_input: PlaybackInput,
+    mouse_button_listeners: &[MouseButton],
+    source: &mut I,
+    accumulate: bool,
+) {
+    let mut recorded = match playback_input {
+        PlaybackInput::Live { stopped } => {
+            if accumulate {
+                input.accumulate_input(mouse_button_listeners, source);
+            } else {
+                input.handle_input(mouse_button_listeners, source);
+            }
+            if stopped {
+                // Don't leave keys held down by the recording stuck.
+                input.forget_held();
+            }
+            return;
+        }
+        PlaybackInput::Pending => None,
+        PlaybackInput::Recorded { update, from_start } => Some((update, from_start)),
+    };
+
+    // The real input is thrown away, so none of it is left over for after
+    // playback
+    if accumulate {
+        ignored.accumulate_input(mouse_button_listeners, source);
+    } else {
+        ignored.handle_input(mouse_button_listeners, source);
+    }
+    if let Some((recorded, from_start)) = &mut recorded {
+        input.handle_input(&recorded.mouse_buttons(), recorded);
+        if *from_start {
+            // Nor keys held down by the end of the recording
+            input.forget_held();
+        }
+    }
+    if ignored.close_requested() {
+        input.request_close();
+    }
+}
+
+/// Moves [`InputPlayback`] on to the next recorded update once a Bevy update
+/// has run with the current one.
+fn advance_playback(mut playback: ResMut<'_, InputPlayback>) {
+    let len = match &playback.recording {
+        Some(recording) if playback.fed => recording.len(),
+        _ => return,
+    };
+    playback.fed = false;
+    playback.position += 1;
+    if playback.looping && playback.position >= len {
+        playback.position = 0;
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn detect_idle(
+    input: Res<'_, Input>,
+    capture: Option<Res<'_, InputCapture>>,
+    attract_mode: Option<Res<'_, AttractMode>>,
+    update_requests: Option<ResMut<'_, UpdateRequests>>,
+    mut idle_detector: ResMut<'_, IdleDetector>,
+    mut idle_started: EventWriter<'_, IdleStarted>,
+    mut idle_ended: EventWriter<'_, IdleEnded>,
+) {
+    // Typing into a dialog or the developer console isn't being idle, and
+    // neither is taking over from attract mode
+    let input = match attract_mode
+        .as_ref()
+        .and_then(|attract_mode| attract_mode.live.as_ref())
+    {
+        Some(live) => live,
+        None => InputCapture::live(capture.as_deref(), &input),
+    };
+    let now = Instant::now();
+    let mouse_position = input.mouse_pos();
+    let mouse_moved = idle_detector
+        .mouse_position
+        .is_some_and(|previous| previous != mouse_position);
+    idle_detector.mouse_position = Some(mouse_position);
+
+    let last_input = *idle_detector.last_input.get_or_insert(now);
+    idle_detector.idle_secs = now.saturating_duration_since(last_input).as_secs_f32();
+    if input.has_new_input() || mouse_moved {
+        if idle_detector.idle {
+            idle_ended.send(IdleEnded {
+                idle_secs: idle_detector.idle_secs,
+            });
+            idle_detector.idle = false;
+        }
+        idle_detector.last_input = Some(now);
+        idle_detector.idle_secs = 0.0;
+    }
+
+    if idle_detector.idle {
+        return;
+    }
+    if idle_detector.idle_secs >= idle_detector.timeout_secs {
+        idle_detector.idle = true;
+        idle_started.send(IdleStarted);
+    } else if let Some(mut update_requests) = update_requests {
+        update_requests.request_update_after(idle_detector.timeout_secs - idle_detector.idle_secs);
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn record_input(
+    input: Res<'_, Input>,
+    attract_mode: Res<'_, AttractMode>,
+    playback: Res<'_, InputPlayback>,
+    mut recorder: ResMut<'_, InputRecorder>,
+) {
+    if attract_mode.is_playing() || playback.is_playing() {
+        return;
+    }
+    if let Some(recording) = &mut recorder.recording {
+        recording.updates.push(RecordedInput::from_input(&input));
+    }
+}
+
+/// Moves [`AttractMode`] on to the next recorded update once a Bevy update
+/// has run with the current one.
+fn advance_attract_mode(mut attract_mode: ResMut<'_, AttractMode>) {
+    if !attract_mode.fed {
+        return;
+    }
+    attract_mode.fed = false;
+    if let Some(position) = &mut attract_mode.position {
+        *position += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::HeadlessInput;
+    use crate::UPDATES_PER_SECOND;
+    use bevy_ecs::event::Events;
+    use bevy_ecs::schedule::Schedule;
+    use bevy_utils::Duration;
+
+    fn run_detect_idle(world: &mut World) {
+        let mut schedule = Schedule::default();
+        schedule.add_systems(detect_idle);
+        schedule.run(world);
+    }
+
+    #[test]
+    fn idles_on_the_wall_clock_between_updates() {
+        let mut world = World::new();
+        world.init_resource::<Input>();
+        world.init_resource::<UpdateRequests>();
+        world.insert_resource(IdleDetector::new(0.05));
+        world.init_resource::<Events<IdleStarted>>();
+        world.init_resource::<Events<IdleEnded>>();
+
+        // A single update, however long after the last one, is enough
+        run_detect_idle(&mut world);
+        assert!(!world.resource::<IdleDetector>().is_idle());
+        let later = Instant::now() + Duration::from_millis(50);
+        assert!(world.resource_mut::<UpdateRequests>().take_due(later));
+
+        std::thread::sleep(Duration::from_millis(60));
+        run_detect_idle(&mut world);
+        assert!(world.resource::<IdleDetector>().is_idle());
+        assert_eq!(world.resource::<Events<IdleStarted>>().len(), 1);
+    }
+
+    #[test]
+    fn plays_back_one_recorded_update_per_bevy_update() {
+        let recorded = |key: &str| RecordedInput {
+            keys_pressed: vec![key.to_owned()],
+            ..RecordedInput::default()
+        };
+        let mut world = World::new();
+        world.init_resource::<Input>();
+        world.init_resource::<InputPlayback>();
+        world.resource_mut::<InputPlayback>().play(InputRecording {
+            updates: vec![recorded("A"), recorded("B")],
+        });
+        let mut advance = Schedule::default();
+        advance.add_systems(advance_playback);
+        let mut source = HeadlessInput::default();
+
+        feed_input(&mut world, &[], &mut source, false);
+        assert!(world.resource::<Input>().key_pressed("A"));
+
+        // The Bevy update was skipped, so the same recorded update stays
+        source.press_key("Enter");
+        feed_input(&mut world, &[], &mut source, true);
+        assert!(world.resource::<Input>().key_pressed("A"));
+        assert!(!world.resource::<Input>().key_pressed("Enter"));
+
+        advance.run(&mut world);
+        feed_input(&mut world, &[], &mut source, false);
+        assert!(world.resource::<Input>().key_pressed("B"));
+
+        advance.run(&mut world);
+        feed_input(&mut world, &[], &mut source, false);
+        assert!(!world.resource::<InputPlayback>().is_playing());
+        assert!(!world.resource::<Input>().key("B"));
+    }
+
+    #[test]
+    fn attract_mode_plays_back_through_the_input_once_per_update() {
+        let recorded = |keys: &[&str]| RecordedInput {
+            keys_pressed: keys.iter().map(|&key| key.to_owned()).collect(),
+            ..RecordedInput::default()
+        };
+        let mut world = World::new();
+        world.init_resource::<Input>();
+        let mut idle_detector = IdleDetector::new(0.0);
+        idle_detector.idle = true;
+        world.insert_resource(idle_detector);
+        world.insert_resource(AttractMode {
+            recording: Some(InputRecording {
+                updates: vec![recorded(&["A"]), recorded(&[])],
+            }),
+            looping: false,
+            ..AttractMode::default()
+        });
+        let mut advance = Schedule::default();
+        advance.add_systems(advance_attract_mode);
+        let mut source = HeadlessInput::default();
+
+        source.press_key("Enter");
+        feed_input(&mut world, &[], &mut source, false);
+        assert!(world.resource::<AttractMode>().is_playing());
+        assert!(world.resource::<Input>().key_pressed("A"));
+        assert!(!world.resource::<Input>().key("Enter"));
+
+        advance.run(&mut world);
+        feed_input(&mut world, &[], &mut source, false);
+        let held_secs = world.resource::<Input>().key_held_secs("A");
+        assert!((held_secs - 1.0 / UPDATES_PER_SECOND).abs() < f32::EPSILON);
+
+        // The end of the recording lets go of the keys it held down
+        advance.run(&mut world);
+        feed_input(&mut world, &[], &mut source, false);
+        assert!(!world.resource::<AttractMode>().is_playing());
+        assert!(!world.resource::<Input>().key("A"));
+    }
+}
@@ -1,23 +1,46 @@
 use crate::doryen::InputApi;
+use crate::touch::{Touch, TouchInput, TouchState};
+use crate::{ScreenInfo, UPDATES_PER_SECOND};
+use bevy_ecs::event::{Event, EventWriter};
+use bevy_ecs::system::{Res, ResMut, Resource};
+use bevy_utils::Instant;
 use std::collections::{HashMap, HashSet};
 use std::iter::Filter;
 
 /// Provides access to the input events handled by the Doryen engine. See the
 /// documentation for the [`InputApi`] type for details on what values should
 /// be used with the various `key` methods.
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Resource)]
 pub struct Input {
     keys_down: HashMap<String, bool>,
     keys_pressed: HashMap<String, bool>,
     keys_released: HashMap<String, bool>,
+    keys_held: HashMap<String, u32>,
+    keys_repeated: HashSet<String>,
+    repeat_ticks_seen: HashMap<String, u32>,
     mouse_buttons_down: HashMap<usize, bool>,
     mouse_buttons_pressed: HashSet<usize>,
     mouse_buttons_released: HashSet<usize>,
+    mouse_buttons_held: HashMap<usize, u32>,
     text: String,
     close_requested: bool,
     mouse_position: (f32, f32),
+    mouse_wheel: (f32, f32),
+    reported_modifiers: Option<Modifiers>,
+    reported_cursor_inside: Option<bool>,
+    cursor_inside: bool,
+    logical_keys: HashMap<String, String>,
+    touch: TouchState,
+    #[cfg(feature = "gamepad")]
+    pub(crate) gamepad: crate::gamepad::GamepadState,
+    key_events: Vec<(String, bool, Instant)>,
+    received_at: Option<Instant>,
 }
 
+/// The name [`Input`] went by in earlier versions of bevy_doryen.
+#[deprecated(since = "0.3.0", note = "renamed to `Input`")]
+pub type DoryenInput = Input;
+
 type KeyMapFilter<'a> =
     Filter<std::collections::hash_map::Iter<'a, String, bool>, fn(&(&'a String, &'a bool)) -> bool>;
 
@@ -45,16 +68,41 @@ impl Input {
         }
         self.mouse_buttons_pressed.clear();
         self.mouse_buttons_released.clear();
+        self.text.clear();
         self.close_requested = false;
+        self.mouse_wheel = (0.0, 0.0);
+        self.touch.clear();
+        self.key_events.clear();
+        self.received_at = None;
     }
 
-    pub(crate) fn handle_input(
+    pub(crate) fn handle_input<I: InputSource + ?Sized>(
         &mut self,
         mouse_button_listeners: &[MouseButton],
-        input: &mut dyn InputApi,
+        input: &mut I,
     ) {
         self.clear();
-        for k in input.keys_pressed() {
+        self.accumulate_input(mouse_button_listeners, input);
+    }
+
+    /// Like [`handle_input`](Self::handle_input), but adds to the input of
+    /// the previous call instead of replacing it, so nothing gets lost when
+    /// the Bevy update is skipped for a tick.
+    pub(crate) fn accumulate_input<I: InputSource + ?Sized>(
+        &mut self,
+        mouse_button_listeners: &[MouseButton],
+        input: &mut I,
+    ) {
+        for v in self.keys_held.values_mut() {
+            *v += 1;
+        }
+        for v in self.mouse_buttons_held.values_mut() {
+            *v += 1;
+        }
+        self.touch.tick();
+
+        let now = Instant::now();
+        input.for_each_key_pressed(&mut |k: &str| {
             if let Some(v) = self.keys_pressed.get_mut(k) {
                 *v = true;
             } else {
@@ -66,8 +114,11 @@ impl Input {
             } else {
                 self.keys_down.insert(String::from(k), true);
             }
-        }
-        for k in input.keys_released() {
+
+            self.keys_held.entry(String::from(k)).or_insert(0);
+            self.key_events.push((String::from(k), true, now));
+        });
+        input.for_each_key_released(&mut |k: &str| {
             if let Some(v) = self.keys_released.get_mut(k) {
                 *v = true;
             } else {
@@ -79,33 +130,60 @@ impl Input {
             } else {
                 self.keys_down.insert(String::from(k), false);
             }
-        }
+
+            self.keys_held.remove(k);
+            self.key_events.push((String::from(k), false, now));
+        });
         for &mouse_button in mouse_button_listeners {
             let mouse_button_num = mouse_button.to_usize();
             if input.mouse_button_pressed(mouse_button_num) {
                 self.mouse_buttons_pressed.insert(mouse_button_num);
                 let down = self.mouse_buttons_down.entry(mouse_button_num).or_default();
                 *down = true;
+                self.mouse_buttons_held.entry(mouse_button_num).or_insert(0);
             }
             if input.mouse_button_released(mouse_button_num) {
                 self.mouse_buttons_released.insert(mouse_button_num);
                 let down = self.mouse_buttons_down.entry(mouse_button_num).or_default();
                 *down = false;
+                self.mouse_buttons_held.remove(&mouse_button_num);
+            }
+        }
+        for (key, pressed, _) in &self.key_events {
+            if let Some(logical) = input.logical_key(key).filter(|_| *pressed) {
+                self.logical_keys.insert(key.clone(), logical);
             }
         }
-        self.text = input.text();
+        self.text.push_str(&input.text());
         self.mouse_position = input.mouse_pos();
-        self.close_requested = input.close_requested();
+        let (wheel_x, wheel_y) = input.mouse_wheel();
+        self.mouse_wheel.0 += wheel_x;
+        self.mouse_wheel.1 += wheel_y;
+        self.reported_modifiers = input.modifiers();
+        if let Some(inside) = input.cursor_inside() {
+            self.reported_cursor_inside = Some(inside);
+        }
+        for touch in input.touches() {
+            self.touch.handle(touch, now);
+        }
+        self.close_requested |= input.close_requested();
+
+        if self.received_at.is_none() && self.has_new_input() {
+            self.received_at = Some(now);
+        }
     }
 
     /// Returns the current status of the given key (true if currently pressed).
-    pub fn key(&self, key: &str) -> bool {
-        matches!(self.keys_down.get(key), Some(&true))
+    ///
+    /// Like every method taking a key, it takes either a [`Key`](crate::Key)
+    /// or a key name.
+    pub fn key(&self, key: impl AsRef<str>) -> bool {
+        matches!(self.keys_down.get(key.as_ref()), Some(&true))
     }
 
     /// Returns true if the given key was pressed since the last update.
-    pub fn key_pressed(&self, key: &str) -> bool {
-        matches!(self.keys_pressed.get(key), Some(&true))
+    pub fn key_pressed(&self, key: impl AsRef<str>) -> bool {
+        matches!(self.keys_pressed.get(key.as_ref()), Some(&true))
     }
 
     /// Returns an iterator over all the keys that were pressed since the last
@@ -116,9 +194,32 @@ impl Input {
         }
     }
 
+    /// Returns for how many seconds the given key has been held down. A key
+    /// that was pressed since the last update has been held for `0.0`
+    /// seconds, as has a key that is not currently pressed at all.
+    ///
+    /// The duration is measured in Doryen update ticks, so it is unaffected
+    /// by how long it takes to render a frame.
+    pub fn key_held_secs(&self, key: impl AsRef<str>) -> f32 {
+        self.keys_held
+            .get(key.as_ref())
+            .map_or(0.0, |&ticks| ticks as f32 / UPDATES_PER_SECOND)
+    }
+
+    /// Returns true if the given key was pressed since the last update, or
+    /// has been held down long enough to repeat since then, the way keyboard
+    /// auto-repeat works in a text field. The [`KeyRepeat`] resource sets
+    /// how fast keys repeat.
+    ///
+    /// Use this instead of [`key_pressed`](Self::key_pressed) for keys that
+    /// should keep acting while held, like the arrow keys moving a player.
+    pub fn key_repeated(&self, key: impl AsRef<str>) -> bool {
+        self.keys_repeated.contains(key.as_ref())
+    }
+
     /// Returns true if the given key was released since the last update.
-    pub fn key_released(&self, key: &str) -> bool {
-        matches!(self.keys_released.get(key), Some(&true))
+    pub fn key_released(&self, key: impl AsRef<str>) -> bool {
+        matches!(self.keys_released.get(key.as_ref()), Some(&true))
     }
 
     /// Returns an iterator over all the keys that were released since the last
@@ -129,7 +230,99 @@ impl Input {
         }
     }
 
-    /// Characters typed since last update.
+    /// The name of the key the keyboard layout puts at the position of the
+    /// given key, like `"KeyZ"` for `"KeyW"` on a French AZERTY keyboard.
+    /// `None` until the key has been pressed, or if the backend can't tell.
+    ///
+    /// Key names are physical positions on a US keyboard, so a game asking
+    /// for `"KeyW"` gets the key where W is on a US keyboard, whatever the
+    /// layout. That's usually right for movement, but bindings shown to the
+    /// player as letters should match the letter instead, see
+    /// [`resolve_key`](Self::resolve_key).
+    ///
+    /// Only browsers tell both apart. Doryen's native window and the
+    /// terminal report a single name for each key, which is used for both.
+    pub fn logical_key(&self, key: impl AsRef<str>) -> Option<&str> {
+        self.logical_keys.get(key.as_ref()).map(String::as_str)
+    }
+
+    /// The name of the key to check for `key`, matched as `matching` says.
+    /// For [`KeyMatching::Logical`], that's the key the layout puts `key` at,
+    /// once it's been pressed. `None` if no key gives `key` on this layout.
+    pub fn resolve_key<'a>(&'a self, key: &'a str, matching: KeyMatching) -> Option<&'a str> {
+        if matching == KeyMatching::Physical {
+            return Some(key);
+        }
+        let physical = self
+            .logical_keys
+            .iter()
+            .find(|(_, logical)| logical.as_str() == key)
+            .map(|(physical, _)| physical.as_str());
+        match (physical, self.logical_keys.get(key)) {
+            (Some(physical), _) => Some(physical),
+            // The key at this position gives something else
+            (None, Some(logical)) if logical != key => None,
+            (None, _) => Some(key),
+        }
+    }
+
+    /// The modifier keys currently held down.
+    pub fn modifiers(&self) -> Modifiers {
+        if let Some(modifiers) = self.reported_modifiers {
+            return modifiers;
+        }
+        let down = |keys: &[&str]| keys.iter().any(|key| self.key(key));
+        let mut modifiers = Modifiers::NONE;
+        if down(&["ShiftLeft", "ShiftRight"]) {
+            modifiers |= Modifiers::SHIFT;
+        }
+        if down(&["ControlLeft", "ControlRight"]) {
+            modifiers |= Modifiers::CTRL;
+        }
+        if down(&["AltLeft", "AltRight"]) {
+            modifiers |= Modifiers::ALT;
+        }
+        // Older versions of Firefox call the meta keys "OS"
+        if down(&["MetaLeft", "MetaRight", "OSLeft", "OSRight"]) {
+            modifiers |= Modifiers::META;
+        }
+        modifiers
+    }
+
+    /// Whether either shift key is held down.
+    pub fn shift(&self) -> bool {
+        self.modifiers().contains(Modifiers::SHIFT)
+    }
+
+    /// Whether either control key is held down.
+    pub fn ctrl(&self) -> bool {
+        self.modifiers().contains(Modifiers::CTRL)
+    }
+
+    /// Whether either alt key is held down. This is the option key on a Mac.
+    pub fn alt(&self) -> bool {
+        self.modifiers().contains(Modifiers::ALT)
+    }
+
+    /// Whether either meta key is held down. This is the Windows key on a PC
+    /// and the command key on a Mac.
+    pub fn meta(&self) -> bool {
+        self.modifiers().contains(Modifiers::META)
+    }
+
+    /// Returns true if the given key was pressed since the last update while
+    /// exactly the given modifiers were held down, so
+    /// `key_with_modifiers("KeyS", Modifiers::CTRL)` doesn't also match
+    /// Ctrl+Shift+S.
+    ///
+    /// Use [`Modifiers::COMMAND`] for shortcuts that should use the command
+    /// key on a Mac and the control key elsewhere.
+    pub fn key_with_modifiers(&self, key: impl AsRef<str>, modifiers: Modifiers) -> bool {
+        self.key_pressed(key) && self.modifiers() == modifiers
+    }
+
+    /// Characters typed since last update. These are also sent as
+    /// [`ReceivedCharacter`] events.
     pub fn text(&self) -> &str {
         &self.text
     }
@@ -157,16 +350,583 @@ impl Input {
             .contains(&mouse_button.to_usize())
     }
 
+    /// Returns for how many seconds the given mouse button has been held down.
+    /// See [`key_held_secs`](Self::key_held_secs) for details.
+    pub fn mouse_button_held_secs(&self, mouse_button: MouseButton) -> f32 {
+        self.mouse_buttons_held
+            .get(&mouse_button.to_usize())
+            .map_or(0.0, |&ticks| ticks as f32 / UPDATES_PER_SECOND)
+    }
+
     /// Returns the current mouse position in console cells coordinates.
     /// The decimal part of the value indicates sub-cell location.
     pub fn mouse_pos(&self) -> (f32, f32) {
         self.mouse_position
     }
 
+    /// Whether the mouse cursor is over the window. When it isn't,
+    /// [`mouse_pos`](Self::mouse_pos) is where it was last seen, so hover
+    /// effects should be hidden. [`CursorEntered`] and [`CursorLeft`] events
+    /// are sent when this changes.
+    ///
+    /// Only browsers say when the cursor leaves the window. Everywhere else,
+    /// the cursor counts as inside while it's over the console.
+    pub fn cursor_inside(&self) -> bool {
+        self.cursor_inside
+    }
+
+    /// Returns the console cell the mouse is over.
+    pub fn mouse_cell(&self) -> (i32, i32) {
+        let (x, y) = self.mouse_position;
+        (x.floor() as i32, y.floor() as i32)
+    }
+
+    /// Returns where in its cell the mouse is, from `(0.0, 0.0)` at the
+    /// top-left corner of the cell up to `(1.0, 1.0)` at the bottom-right.
+    pub fn mouse_cell_offset(&self) -> (f32, f32) {
+        let (x, y) = self.mouse_position;
+        (x - x.floor(), y - y.floor())
+    }
+
+    /// Returns the mouse position in pixels from the top-left corner of the
+    /// window, given the current [`ScreenInfo`].
+    pub fn mouse_pixel_pos(&self, screen_info: &ScreenInfo) -> (f32, f32) {
+        screen_info.cells_to_pixels(self.mouse_position)
+    }
+
+    /// How far the mouse wheel was scrolled since the last update, roughly in
+    /// wheel notches. Positive `y` is scrolling up, away from the user, and
+    /// positive `x` is scrolling right. The same movement is sent as a
+    /// [`MouseWheelEvent`].
+    ///
+    /// This is only reported in a browser and by the terminal runner. Doryen
+    /// doesn't report the wheel in a native window, where this is always
+    /// `(0.0, 0.0)`.
+    pub fn mouse_wheel_delta(&self) -> (f32, f32) {
+        self.mouse_wheel
+    }
+
+    /// Returns an iterator over the fingers currently on the screen. See
+    /// [`TouchEvent`](crate::TouchEvent) for where touches are reported.
+    pub fn touches(&self) -> std::slice::Iter<'_, Touch> {
+        self.touch.active.iter()
+    }
+
+    /// The positions of the taps since the last update, in console cell
+    /// coordinates. These are also sent as [`TapEvent`](crate::TapEvent)s.
+    pub fn taps(&self) -> &[(f32, f32)] {
+        &self.touch.taps
+    }
+
+    /// The positions of the touches that became long presses since the last
+    /// update, in console cell coordinates. These are also sent as
+    /// [`LongPressEvent`](crate::LongPressEvent)s.
+    pub fn long_presses(&self) -> &[(f32, f32)] {
+        &self.touch.long_presses
+    }
+
+    pub(crate) fn touch_state(&self) -> &TouchState {
+        &self.touch
+    }
+
     /// Whether the window close button has been activated.
     pub fn close_requested(&self) -> bool {
         self.close_requested
     }
+
+    /// When the runner received the oldest of the input since the last
+    /// update, or `None` if nothing was pressed, released, typed, scrolled or
+    /// touched since.
+    /// Doryen doesn't say when input happened, so this is when bevy_doryen
+    /// got it from Doryen, which is at most a frame later.
+    pub fn received_at(&self) -> Option<Instant> {
+        self.received_at
+    }
+
+    /// Whether anything was pressed, released, typed, scrolled or touched
+    /// since the last update. Mouse movement isn't included.
+    pub(crate) fn has_new_input(&self) -> bool {
+        self.keys_pressed().next().is_some()
+            || self.keys_released().next().is_some()
+            || !self.mouse_buttons_pressed.is_empty()
+            || !self.mouse_buttons_released.is_empty()
+            || !self.text.is_empty()
+            || self.mouse_wheel != (0.0, 0.0)
+            || !self.touch.events.is_empty()
+    }
+
+    /// The mouse buttons pressed and released since the last update.
+    pub(crate) fn mouse_button_changes(&self) -> (Vec<usize>, Vec<usize>) {
+        (
+            self.mouse_buttons_pressed.iter().copied().collect(),
+            self.mouse_buttons_released.iter().copied().collect(),
+        )
+    }
+
+    /// Stops reporting `key` as pressed since the last update, for a press
+    /// that has already been acted on.
+    pub(crate) fn consume_key_press(&mut self, key: &str) {
+        if let Some(pressed) = self.keys_pressed.get_mut(key) {
+            *pressed = false;
+        }
+    }
+
+    /// Empties the input for the update, as seen by everything input has
+    /// been captured away from. The mouse is put outside the console.
+    pub(crate) fn clear_captured(&mut self) {
+        *self = Self {
+            mouse_position: (-1.0, -1.0),
+            ..Self::default()
+        };
+    }
+
+    /// Passes on a close request that didn't come through the input source.
+    pub(crate) fn request_close(&mut self) {
+        self.close_requested = true;
+    }
+
+    /// Forgets about keys and mouse buttons being held down, except for the
+    /// ones pressed since the last update, without reporting them as
+    /// released.
+    pub(crate) fn forget_held(&mut self) {
+        let keys_pressed = &self.keys_pressed;
+        let mouse_buttons_pressed = &self.mouse_buttons_pressed;
+        self.keys_down
+            .retain(|key, _| matches!(keys_pressed.get(key), Some(&true)));
+        self.keys_held
+            .retain(|key, _| matches!(keys_pressed.get(key), Some(&true)));
+        self.mouse_buttons_down
+            .retain(|button, _| mouse_buttons_pressed.contains(button));
+        self.mouse_buttons_held
+            .retain(|button, _| mouse_buttons_pressed.contains(button));
+    }
+}
+
+/// Resource taking the keyboard and the mouse away from every system but one,
+/// such as an open dialog or developer console.
+///
+/// While input is captured, the [`Input`] resource reports nothing pressed,
+/// released or typed, and the mouse outside the console, so nothing beneath
+/// the owner acts on the same keys. Only a close request still comes
+/// through. The owner reads the real input through [`input`](Self::input)
+/// instead. Capturing and releasing take effect with the next Doryen update.
+///
+/// Captures stack: the owner that captured input last gets it, until it
+/// releases it again.
+#[derive(Default, Debug, Resource)]
+pub struct InputCapture {
+    owners: Vec<&'static str>,
+    input: Input,
+    active: bool,
+}
+
+impl InputCapture {
+    /// Takes input away from everything but `owner`, putting it on top of
+    /// the owners that captured input before it. Does nothing if `owner` has
+    /// already captured input.
+    pub fn capture(&mut self, owner: &'static str) {
+        if !self.owners.contains(&owner) {
+            self.owners.push(owner);
+        }
+    }
+
+    /// Gives up the capture taken by `owner`, if any.
+    pub fn release(&mut self, owner: &'static str) {
+        self.owners.retain(|&captured| captured != owner);
+    }
+
+    /// The owner getting input, if input is captured.
+    pub fn owner(&self) -> Option<&'static str> {
+        self.owners.last().copied()
+    }
+
+    /// Whether input is captured.
+    pub fn is_captured(&self) -> bool {
+        !self.owners.is_empty()
+    }
+
+    /// The input taken away from everything else, if `owner` is getting it.
+    pub fn input(&self, owner: &str) -> Option<&Input> {
+        (self.active && self.owner() == Some(owner)).then_some(&self.input)
+    }
+
+    /// Moves the input between the [`Input`] resource and the capture as
+    /// captures are taken and released, ahead of a Doryen update handing
+    /// input to whichever of them gets it.
+    pub(crate) fn start_update(&mut self, input: &mut Input) {
+        match (self.is_captured(), self.active) {
+            (true, false) => self.input = std::mem::take(input),
+            (false, true) => *input = std::mem::take(&mut self.input),
+            _ => (),
+        }
+        self.active = self.is_captured();
+    }
+
+    /// The input the player is giving: the captured input while input is
+    /// captured, and `input` otherwise.
+    pub(crate) fn live<'a>(capture: Option<&'a Self>, input: &'a Input) -> &'a Input {
+        match capture {
+            Some(capture) if capture.active => &capture.input,
+            _ => input,
+        }
+    }
+
+    /// The captured input, while input is captured.
+    pub(crate) fn input_mut(&mut self) -> Option<&mut Input> {
+        self.active.then_some(&mut self.input)
+    }
+}
+
+/// Whether key names are matched by where the key is on the keyboard, or by
+/// what the keyboard layout says the key is. See [`Input::logical_key`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyMatching {
+    /// By position, as if the keyboard were a US keyboard. WASD is always
+    /// the same four keys.
+    #[default]
+    Physical,
+    /// By what the layout prints on the key. `"KeyW"` is whichever key types
+    /// a W.
+    Logical,
+}
+
+/// A set of modifier keys, for [`Input::key_with_modifiers`]. Combine them
+/// with `|`, as in `Modifiers::CTRL | Modifiers::SHIFT`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Modifiers(u8);
+
+impl Modifiers {
+    /// No modifiers.
+    pub const NONE: Self = Self(0);
+    /// Either shift key.
+    pub const SHIFT: Self = Self(1);
+    /// Either control key.
+    pub const CTRL: Self = Self(1 << 1);
+    /// Either alt key, which is the option key on a Mac.
+    pub const ALT: Self = Self(1 << 2);
+    /// Either meta key, which is the Windows key on a PC and the command key
+    /// on a Mac.
+    pub const META: Self = Self(1 << 3);
+    /// The modifier used for keyboard shortcuts: [`META`](Self::META) on a
+    /// Mac and [`CTRL`](Self::CTRL) everywhere else. In a browser, this is
+    /// decided by the platform the program was built for, not the one the
+    /// browser runs on.
+    #[cfg(target_os = "macos")]
+    pub const COMMAND: Self = Self::META;
+    /// The modifier used for keyboard shortcuts: [`META`](Self::META) on a
+    /// Mac and [`CTRL`](Self::CTRL) everywhere else. In a browser, this is
+    /// decided by the platform the program was built for, not the one the
+    /// browser runs on.
+    #[cfg(not(target_os = "macos"))]
+    pub const COMMAND: Self = Self::CTRL;
+
+    /// Whether all the modifiers of `other` are in this set.
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Whether the set is empty.
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl std::ops::BitOr for Modifiers {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for Modifiers {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Resource setting how fast held keys repeat for
+/// [`Input::key_repeated`]. Like the other held durations, the timing is
+/// measured in Doryen update ticks. The [`InputMap`](crate::input_map::InputMap)
+/// can override it for individual actions.
+#[derive(Debug, Clone, Copy, PartialEq, Resource)]
+pub struct KeyRepeat {
+    /// How long a key has to be held before it starts repeating, in seconds.
+    /// Defaults to `0.3`.
+    pub delay_secs: f32,
+    /// How long to wait between repeats, in seconds. Defaults to `0.1`.
+    pub interval_secs: f32,
+}
+
+impl Default for KeyRepeat {
+    fn default() -> Self {
+        Self {
+            delay_secs: 0.3,
+            interval_secs: 0.1,
+        }
+    }
+}
+
+impl KeyRepeat {
+    /// How many times something held for `ticks` update ticks has repeated.
+    pub(crate) fn repeats(&self, ticks: u32) -> u32 {
+        let held_secs = ticks as f32 / UPDATES_PER_SECOND;
+        if held_secs < self.delay_secs {
+            return 0;
+        }
+        ((held_secs - self.delay_secs) / self.interval_secs.max(0.01)) as u32 + 1
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub(crate) fn repeat_keys(
+    key_repeat: Res<'_, KeyRepeat>,
+    mut input: ResMut<'_, Input>,
+    capture: Option<ResMut<'_, InputCapture>>,
+) {
+    match capture.and_then(|capture| capture.into_inner().input_mut()) {
+        Some(captured) => repeat(&key_repeat, captured),
+        None => repeat(&key_repeat, &mut input),
+    }
+}
+
+fn repeat(key_repeat: &KeyRepeat, input: &mut Input) {
+    input.keys_repeated.clear();
+    let keys_held = &input.keys_held;
+    input
+        .repeat_ticks_seen
+        .retain(|key, _| keys_held.contains_key(key));
+    for (key, &ticks) in &input.keys_held {
+        // Compare against the last update this key was seen, since several
+        // ticks pass in one update when updates are skipped
+        let seen = input.repeat_ticks_seen.insert(key.clone(), ticks);
+        let pressed = matches!(input.keys_pressed.get(key), Some(&true));
+        let repeated = match seen {
+            Some(seen) if !pressed => key_repeat.repeats(ticks) > key_repeat.repeats(seen),
+            _ => true,
+        };
+        if repeated {
+            input.keys_repeated.insert(key.clone());
+        }
+    }
+}
+
+/// Emitted at the start of the update when the mouse cursor has moved over
+/// the window. See [`Input::cursor_inside`].
+#[derive(Debug, Clone, Copy, PartialEq, Event)]
+pub struct CursorEntered {
+    /// Where the cursor entered, in console cell coordinates.
+    pub position: (f32, f32),
+}
+
+/// Emitted at the start of the update when the mouse cursor has left the
+/// window. See [`Input::cursor_inside`].
+#[derive(Debug, Clone, Copy, PartialEq, Event)]
+pub struct CursorLeft {
+    /// Where the cursor was last seen, in console cell coordinates.
+    pub position: (f32, f32),
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub(crate) fn track_cursor(
+    screen_info: Res<'_, ScreenInfo>,
+    mut input: ResMut<'_, Input>,
+    mut cursor_entered: EventWriter<'_, CursorEntered>,
+    mut cursor_left: EventWriter<'_, CursorLeft>,
+) {
+    let (x, y) = input.mouse_position;
+    let inside = input.reported_cursor_inside.unwrap_or_else(|| {
+        x >= 0.0
+            && y >= 0.0
+            && x < screen_info.console_width as f32
+            && y < screen_info.console_height as f32
+    });
+    if inside == input.cursor_inside {
+        return;
+    }
+    input.cursor_inside = inside;
+    let position = (x, y);
+    if inside {
+        cursor_entered.send(CursorEntered { position });
+    } else {
+        cursor_left.send(CursorLeft { position });
+    }
+}
+
+/// Emitted at the start of the update for every key pressed since the last
+/// update.
+///
+/// [`KeyPressedEvent`]s and [`KeyReleasedEvent`]s are sent in the order the
+/// keys changed, as far as the backend reports it. Doryen itself only reports
+/// which keys changed between two of its updates, so keys that changed
+/// together come in no particular order, but always with all presses before
+/// all releases.
+#[derive(Debug, Clone, PartialEq, Event)]
+pub struct KeyPressedEvent {
+    /// The name of the key. See [`Input`] for the names used.
+    pub key: String,
+    /// When the runner received the key press. See [`Input::received_at`].
+    pub received_at: Instant,
+}
+
+/// Emitted at the start of the update for every key released since the last
+/// update. See [`KeyPressedEvent`] for the order they come in.
+#[derive(Debug, Clone, PartialEq, Event)]
+pub struct KeyReleasedEvent {
+    /// The name of the key. See [`Input`] for the names used.
+    pub key: String,
+    /// When the runner received the key release. See
+    /// [`Input::received_at`].
+    pub received_at: Instant,
+}
+
+/// Emitted at the start of the update for every character typed since the
+/// last update, in the order they were typed. Unlike [`KeyPressedEvent`],
+/// this takes the keyboard layout and modifiers into account, so it's what
+/// text fields should listen to.
+#[derive(Debug, Clone, Copy, PartialEq, Event)]
+pub struct ReceivedCharacter {
+    /// The character typed.
+    pub char: char,
+    /// When the runner received the character. See [`Input::received_at`].
+    pub received_at: Instant,
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub(crate) fn send_character_events(
+    input: Res<'_, Input>,
+    mut received_character: EventWriter<'_, ReceivedCharacter>,
+) {
+    let received_at = match input.received_at() {
+        Some(received_at) => received_at,
+        None => return,
+    };
+    received_character.send_batch(
+        input
+            .text()
+            .chars()
+            .map(|char| ReceivedCharacter { char, received_at }),
+    );
+}
+
+/// Emitted at the start of the update when the mouse wheel was scrolled since
+/// the last update. See [`Input::mouse_wheel_delta`] for the units and where
+/// the wheel is reported.
+#[derive(Debug, Clone, Copy, PartialEq, Event)]
+pub struct MouseWheelEvent {
+    /// How far the wheel was scrolled right.
+    pub x: f32,
+    /// How far the wheel was scrolled up.
+    pub y: f32,
+    /// When the runner received the scrolling. See [`Input::received_at`].
+    pub received_at: Instant,
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub(crate) fn send_mouse_wheel_events(
+    input: Res<'_, Input>,
+    mut mouse_wheel: EventWriter<'_, MouseWheelEvent>,
+) {
+    let (x, y) = input.mouse_wheel_delta();
+    if let (Some(received_at), true) = (input.received_at(), (x, y) != (0.0, 0.0)) {
+        mouse_wheel.send(MouseWheelEvent { x, y, received_at });
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub(crate) fn send_key_events(
+    input: Res<'_, Input>,
+    mut key_pressed: EventWriter<'_, KeyPressedEvent>,
+    mut key_released: EventWriter<'_, KeyReleasedEvent>,
+) {
+    for (key, pressed, received_at) in &input.key_events {
+        let (key, received_at) = (key.clone(), *received_at);
+        if *pressed {
+            key_pressed.send(KeyPressedEvent { key, received_at });
+        } else {
+            key_released.send(KeyReleasedEvent { key, received_at });
+        }
+    }
+}
+
+/// Where [`Input`] gets its input from. This is Doryen's [`InputApi`] when
+/// running in a window, but other runners provide their own.
+pub(crate) trait InputSource {
+    fn for_each_key_pressed(&self, f: &mut dyn FnMut(&str));
+    fn for_each_key_released(&self, f: &mut dyn FnMut(&str));
+    fn mouse_button_pressed(&mut self, num: usize) -> bool;
+    fn mouse_button_released(&mut self, num: usize) -> bool;
+    fn text(&self) -> String;
+    fn mouse_pos(&self) -> (f32, f32);
+    fn mouse_wheel(&mut self) -> (f32, f32);
+    fn touches(&mut self) -> Vec<TouchInput>;
+    fn close_requested(&self) -> bool;
+
+    /// The modifier keys held down, for backends that report them apart from
+    /// the keys. `None` has [`Input`] work them out from the keys held down.
+    fn modifiers(&self) -> Option<Modifiers> {
+        None
+    }
+
+    /// Whether the cursor is over the window, if it's changed and the backend
+    /// can tell. `None` has [`Input`] go by the mouse position instead.
+    fn cursor_inside(&self) -> Option<bool> {
+        None
+    }
+
+    /// The name of the key the layout puts at the position of the key named
+    /// `key`, if the backend knows. See [`Input::logical_key`].
+    fn logical_key(&self, _key: &str) -> Option<String> {
+        None
+    }
+}
+
+impl<'a> InputSource for dyn InputApi + 'a {
+    fn for_each_key_pressed(&self, f: &mut dyn FnMut(&str)) {
+        self.keys_pressed().for_each(f);
+    }
+
+    fn for_each_key_released(&self, f: &mut dyn FnMut(&str)) {
+        self.keys_released().for_each(f);
+    }
+
+    fn mouse_button_pressed(&mut self, num: usize) -> bool {
+        InputApi::mouse_button_pressed(self, num)
+    }
+
+    fn mouse_button_released(&mut self, num: usize) -> bool {
+        InputApi::mouse_button_released(self, num)
+    }
+
+    fn text(&self) -> String {
+        InputApi::text(self)
+    }
+
+    fn mouse_pos(&self) -> (f32, f32) {
+        InputApi::mouse_pos(self)
+    }
+
+    fn mouse_wheel(&mut self) -> (f32, f32) {
+        // Doryen itself doesn't report the wheel, but in a browser it's
+        // picked up from the canvas directly
+        crate::web::take_mouse_wheel()
+    }
+
+    fn touches(&mut self) -> Vec<TouchInput> {
+        crate::web::take_touches()
+    }
+
+    fn cursor_inside(&self) -> Option<bool> {
+        crate::web::cursor_inside()
+    }
+
+    fn logical_key(&self, key: &str) -> Option<String> {
+        crate::web::logical_key(key)
+    }
+
+    fn close_requested(&self) -> bool {
+        InputApi::close_requested(self)
+    }
 }
 
 /// Represents buttons on a mouse.
@@ -184,12 +944,144 @@ pub enum MouseButton {
 
 impl MouseButton {
     #[inline]
-    fn to_usize(&self) -> usize {
+    pub(crate) fn to_usize(self) -> usize {
         match self {
             Self::Left => 0,
             Self::Middle => 1,
             Self::Right => 2,
-            Self::Any(which) => *which,
+            Self::Any(which) => which,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::HeadlessInput;
+    use bevy_ecs::schedule::Schedule;
+    use bevy_ecs::world::World;
+
+    /// Hands one Doryen update's worth of key presses and releases to the
+    /// [`Input`] in `world`, then works out which keys repeat.
+    fn update(world: &mut World, pressed: &[&str], released: &[&str], accumulate: bool) {
+        let mut source = HeadlessInput::default();
+        for key in pressed {
+            source.press_key(key);
         }
+        for key in released {
+            source.release_key(key);
+        }
+        let mut input = world.resource_mut::<Input>();
+        if accumulate {
+            input.accumulate_input(&[], &mut source);
+        } else {
+            input.handle_input(&[], &mut source);
+        }
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(repeat_keys);
+        schedule.run(world);
+    }
+
+    fn input(world: &World) -> &Input {
+        world.resource::<Input>()
+    }
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.init_resource::<Input>();
+        world.init_resource::<KeyRepeat>();
+        world
+    }
+
+    #[test]
+    fn keys_are_held_until_released() {
+        let mut world = world();
+
+        update(&mut world, &["KeyA"], &[], false);
+        assert!(input(&world).key("KeyA"));
+        assert!(input(&world).key_pressed("KeyA"));
+        assert_eq!(input(&world).key_held_secs("KeyA"), 0.0);
+
+        update(&mut world, &[], &[], false);
+        assert!(input(&world).key("KeyA"));
+        assert!(!input(&world).key_pressed("KeyA"));
+        assert_eq!(
+            input(&world).key_held_secs("KeyA"),
+            1.0 / UPDATES_PER_SECOND
+        );
+
+        update(&mut world, &[], &["KeyA"], false);
+        assert!(!input(&world).key("KeyA"));
+        assert!(input(&world).key_released("KeyA"));
+        assert_eq!(input(&world).key_held_secs("KeyA"), 0.0);
+    }
+
+    #[test]
+    fn skipped_updates_keep_their_input() {
+        let mut world = world();
+
+        update(&mut world, &[], &[], false);
+        update(&mut world, &["KeyA"], &[], true);
+        update(&mut world, &[], &["KeyA"], true);
+        assert!(!input(&world).key("KeyA"));
+        assert!(input(&world).key_pressed("KeyA"));
+        assert!(input(&world).key_released("KeyA"));
+
+        update(&mut world, &[], &[], false);
+        assert!(!input(&world).key_pressed("KeyA"));
+        assert!(!input(&world).key_released("KeyA"));
+    }
+
+    #[test]
+    fn held_keys_repeat_after_the_delay() {
+        let mut world = world();
+        *world.resource_mut::<KeyRepeat>() = KeyRepeat {
+            delay_secs: 0.5,
+            interval_secs: 0.25,
+        };
+
+        let mut repeated = Vec::new();
+        for tick in 0..=60 {
+            let pressed: &[&str] = if tick == 0 { &["KeyA"] } else { &[] };
+            update(&mut world, pressed, &[], false);
+            if input(&world).key_repeated("KeyA") {
+                repeated.push(tick);
+            }
+        }
+        assert_eq!(repeated, [0, 30, 45, 60]);
+    }
+
+    #[test]
+    fn repeats_during_skipped_updates_are_not_lost() {
+        let mut world = world();
+        *world.resource_mut::<KeyRepeat>() = KeyRepeat {
+            delay_secs: 0.5,
+            interval_secs: 0.25,
+        };
+
+        update(&mut world, &["KeyA"], &[], false);
+        for _ in 1..30 {
+            update(&mut world, &[], &[], false);
+        }
+        // The update the key would repeat on is skipped
+        let mut source = HeadlessInput::default();
+        world
+            .resource_mut::<Input>()
+            .accumulate_input(&[], &mut source);
+        update(&mut world, &[], &[], true);
+        assert!(input(&world).key_repeated("KeyA"));
+    }
+
+    #[test]
+    fn forgetting_held_keys_keeps_new_presses() {
+        let mut world = world();
+
+        update(&mut world, &["KeyA"], &[], false);
+        update(&mut world, &["KeyB"], &[], false);
+        world.resource_mut::<Input>().forget_held();
+        assert!(!input(&world).key("KeyA"));
+        assert!(!input(&world).key_released("KeyA"));
+        assert!(input(&world).key("KeyB"));
     }
 }
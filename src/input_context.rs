@@ -0,0 +1,190 @@
+//! Deciding which part of the game gets the player's input.
+//!
+//! Opening a menu shouldn't also move the player, and typing a name into a
+//! text field shouldn't open the inventory when the player types an I. The
+//! [`InputContexts<C>`] resource is a stack of contexts of a type `C` the
+//! game defines, and only the context on top of the stack is active. Push a
+//! context when a menu opens and pop it when the menu closes:
+//!
+//! ```
+//! # use bevy_app::App;
+//! # use bevy_doryen::input_context::{InputContextAppExt, InputContexts};
+//! # use bevy_doryen::input_map::InputMapPlugin;
+//! # use bevy_doryen::Input;
+//! # use bevy_ecs::system::Res;
+//! #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+//! enum Context {
+//!     Gameplay,
+//!     Menu,
+//!     TextEntry,
+//! }
+//!
+//! #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+//! enum GameAction {
+//!     OpenMenu,
+//! }
+//!
+//! #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+//! enum MenuAction {
+//!     Close,
+//! }
+//!
+//! fn type_name(input: Res<Input>, contexts: Res<InputContexts<Context>>) {
+//!     if let Some(input) = contexts.input_for(&Context::TextEntry, &input) {
+//!         // Only typed into while the text field has the focus.
+//!         let typed = input.text();
+//!     }
+//! }
+//!
+//! App::new()
+//!     .insert_resource(InputContexts::new(Context::Gameplay))
+//!     .add_plugins(InputMapPlugin::<GameAction>::default())
+//!     .add_plugins(InputMapPlugin::<MenuAction>::default())
+//!     .bind_input_context::<GameAction, _>(Context::Gameplay)
+//!     .bind_input_context::<MenuAction, _>(Context::Menu);
+//! ```
+//!
+//! An [`InputMap<A>`] bound to contexts is disabled while none of them is
+//! active, so its actions don't activate. The [`Input`] resource itself is
+//! shared by everything, so systems reading it directly ask
+//! [`InputContexts::input_for`] whether their context is the active one.
+//!
+//! Context changes take effect on the input maps at the start of the next
+//! update.
+
+use crate::input_map::{update_input_map, Action, InputMap};
+use crate::Input;
+use bevy_app::{App as BevyApp, PreUpdate};
+use bevy_ecs::schedule::IntoSystemConfigs;
+use bevy_ecs::system::{Res, ResMut, Resource};
+use std::marker::PhantomData;
+
+/// The types that can be used as contexts in [`InputContexts`].
+pub trait InputContext: Clone + Eq + Send + Sync + 'static {}
+
+impl<C: Clone + Eq + Send + Sync + 'static> InputContext for C {}
+
+/// Resource holding the stack of input contexts. The context on top is the
+/// active one. See the [module documentation](self).
+#[derive(Debug, Clone, Resource)]
+pub struct InputContexts<C: InputContext> {
+    // Never empty; the first context is the base
+    stack: Vec<C>,
+}
+
+impl<C: InputContext> InputContexts<C> {
+    /// Creates a stack holding only the `base` context, which is never
+    /// popped.
+    pub fn new(base: C) -> Self {
+        Self { stack: vec![base] }
+    }
+
+    /// Makes `context` the active context, until it's popped.
+    pub fn push(&mut self, context: C) {
+        self.stack.push(context);
+    }
+
+    /// Removes the active context, making the one below it active again.
+    /// Returns `None` instead if only the base context is left.
+    pub fn pop(&mut self) -> Option<C> {
+        if self.stack.len() > 1 {
+            self.stack.pop()
+        } else {
+            None
+        }
+    }
+
+    /// Pops every context but the base one.
+    pub fn reset(&mut self) {
+        self.stack.truncate(1);
+    }
+
+    /// The active context.
+    pub fn active(&self) -> &C {
+        self.stack.last().expect("the base context is never popped")
+    }
+
+    /// Whether `context` is the active context.
+    pub fn is_active(&self, context: &C) -> bool {
+        self.active() == context
+    }
+
+    /// Whether `context` is anywhere on the stack.
+    pub fn contains(&self, context: &C) -> bool {
+        self.stack.contains(context)
+    }
+
+    /// The contexts on the stack, from the base context to the active one.
+    pub fn stack(&self) -> &[C] {
+        &self.stack
+    }
+
+    /// Returns `input` if `context` is the active context, and `None`
+    /// otherwise, for systems that read the [`Input`] resource directly.
+    pub fn input_for<'a>(&self, context: &C, input: &'a Input) -> Option<&'a Input> {
+        if self.is_active(context) {
+            Some(input)
+        } else {
+            None
+        }
+    }
+}
+
+/// Binding input maps to contexts.
+pub trait InputContextAppExt {
+    /// Disables the [`InputMap<A>`] resource while `context` isn't the
+    /// active [`InputContexts<C>`] context. A map bound to several contexts
+    /// is enabled while any of them is active.
+    fn bind_input_context<A: Action, C: InputContext>(&mut self, context: C) -> &mut Self;
+}
+
+impl InputContextAppExt for BevyApp {
+    fn bind_input_context<A: Action, C: InputContext>(&mut self, context: C) -> &mut Self {
+        if !self.world.contains_resource::<MapContexts<A, C>>() {
+            self.add_systems(
+                PreUpdate,
+                enable_input_map::<A, C>.before(update_input_map::<A>),
+            );
+        }
+        self.world
+            .get_resource_or_insert_with(MapContexts::<A, C>::default)
+            .contexts
+            .push(context);
+        self
+    }
+}
+
+/// The contexts an [`InputMap<A>`] is bound to.
+#[derive(Resource)]
+struct MapContexts<A: Action, C: InputContext> {
+    contexts: Vec<C>,
+    marker: PhantomData<fn() -> A>,
+}
+
+impl<A: Action, C: InputContext> Default for MapContexts<A, C> {
+    fn default() -> Self {
+        Self {
+            contexts: Vec::new(),
+            marker: PhantomData,
+        }
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn enable_input_map<A: Action, C: InputContext>(
+    map_contexts: Res<'_, MapContexts<A, C>>,
+    contexts: Option<Res<'_, InputContexts<C>>>,
+    input_map: Option<ResMut<'_, InputMap<A>>>,
+) {
+    let (contexts, mut input_map) = match (contexts, input_map) {
+        (Some(contexts), Some(input_map)) => (contexts, input_map),
+        _ => return,
+    };
+    let enabled = map_contexts
+        .contexts
+        .iter()
+        .any(|context| contexts.is_active(context));
+    if input_map.is_enabled() != enabled {
+        input_map.set_enabled(enabled);
+    }
+}
@@ -0,0 +1,279 @@
+//! Feeding Doryen's input to Bevy's own input handling.
+//!
+//! [`InputInteropPlugin`] turns the [`Input`] resource of every update into
+//! the events Bevy's windowing backend would send, [`KeyboardInput`],
+//! [`MouseButtonInput`], [`MouseWheel`], [`CursorMoved`] and
+//! [`ReceivedCharacter`], and adds Bevy's [`InputPlugin`]
+//! to turn those into the usual `Input<KeyCode>` and `Input<MouseButton>`
+//! resources. Crates written against those, like input managers, then work on
+//! top of bevy_doryen without changes.
+//!
+//! Key names Bevy has no [`KeyCode`] for are sent with a `key_code` of
+//! `None`. Since there is no Bevy window, the events' `window` is
+//! [`Entity::PLACEHOLDER`].
+//!
+//! [`Input`]: crate::Input
+
+use crate::{Input, ScreenInfo};
+use bevy_app::{App, Plugin, PreUpdate};
+use bevy_ecs::entity::Entity;
+use bevy_ecs::event::EventWriter;
+use bevy_ecs::schedule::IntoSystemConfigs;
+use bevy_ecs::system::{Local, Res};
+use bevy_input::keyboard::{KeyCode, KeyboardInput};
+use bevy_input::mouse::{MouseButton, MouseButtonInput, MouseScrollUnit, MouseWheel};
+use bevy_input::{ButtonState, InputPlugin, InputSystem};
+use bevy_math::Vec2;
+use bevy_window::{CursorMoved, ReceivedCharacter};
+
+/// The plugin that passes Doryen's input on to Bevy's input handling. See the
+/// [module documentation](self).
+#[derive(Default, Clone, Copy, Debug)]
+pub struct InputInteropPlugin;
+
+impl Plugin for InputInteropPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<InputPlugin>() {
+            app.add_plugins(InputPlugin);
+        }
+        app.add_event::<CursorMoved>()
+            .add_event::<ReceivedCharacter>()
+            .add_systems(
+                PreUpdate,
+                (send_bevy_input_events, send_bevy_window_events).before(InputSystem),
+            );
+    }
+}
+
+const LETTERS: [KeyCode; 26] = [
+    KeyCode::A,
+    KeyCode::B,
+    KeyCode::C,
+    KeyCode::D,
+    KeyCode::E,
+    KeyCode::F,
+    KeyCode::G,
+    KeyCode::H,
+    KeyCode::I,
+    KeyCode::J,
+    KeyCode::K,
+    KeyCode::L,
+    KeyCode::M,
+    KeyCode::N,
+    KeyCode::O,
+    KeyCode::P,
+    KeyCode::Q,
+    KeyCode::R,
+    KeyCode::S,
+    KeyCode::T,
+    KeyCode::U,
+    KeyCode::V,
+    KeyCode::W,
+    KeyCode::X,
+    KeyCode::Y,
+    KeyCode::Z,
+];
+const DIGITS: [KeyCode; 10] = [
+    KeyCode::Key0,
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+const NUMPAD: [KeyCode; 10] = [
+    KeyCode::Numpad0,
+    KeyCode::Numpad1,
+    KeyCode::Numpad2,
+    KeyCode::Numpad3,
+    KeyCode::Numpad4,
+    KeyCode::Numpad5,
+    KeyCode::Numpad6,
+    KeyCode::Numpad7,
+    KeyCode::Numpad8,
+    KeyCode::Numpad9,
+];
+const FUNCTION: [KeyCode; 12] = [
+    KeyCode::F1,
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+    KeyCode::F10,
+    KeyCode::F11,
+    KeyCode::F12,
+];
+
+/// The Bevy [`KeyCode`] for a Doryen key name, if there is one.
+pub fn key_code(key: &str) -> Option<KeyCode> {
+    let single = |rest: &str, first: u8, table: &[KeyCode]| {
+        let index = match rest.as_bytes() {
+            [byte] => byte.checked_sub(first)? as usize,
+            _ => return None,
+        };
+        table.get(index).copied()
+    };
+    if let Some(letter) = key.strip_prefix("Key") {
+        return single(letter, b'A', &LETTERS);
+    }
+    if let Some(digit) = key.strip_prefix("Digit") {
+        return single(digit, b'0', &DIGITS);
+    }
+    if let Some(digit) = key.strip_prefix("Numpad") {
+        if let Some(code) = single(digit, b'0', &NUMPAD) {
+            return Some(code);
+        }
+    }
+    if let Some(number) = key.strip_prefix('F') {
+        if let Ok(number) = number.parse::<usize>() {
+            return FUNCTION.get(number.checked_sub(1)?).copied();
+        }
+    }
+
+    let code = match key {
+        "Escape" => KeyCode::Escape,
+        "Enter" => KeyCode::Return,
+        "Space" => KeyCode::Space,
+        "Tab" => KeyCode::Tab,
+        "Backspace" => KeyCode::Back,
+        "Insert" => KeyCode::Insert,
+        "Delete" => KeyCode::Delete,
+        "Home" => KeyCode::Home,
+        "End" => KeyCode::End,
+        "PageUp" => KeyCode::PageUp,
+        "PageDown" => KeyCode::PageDown,
+        "ArrowLeft" => KeyCode::Left,
+        "ArrowUp" => KeyCode::Up,
+        "ArrowRight" => KeyCode::Right,
+        "ArrowDown" => KeyCode::Down,
+        "ShiftLeft" => KeyCode::ShiftLeft,
+        "ShiftRight" => KeyCode::ShiftRight,
+        "ControlLeft" => KeyCode::ControlLeft,
+        "ControlRight" => KeyCode::ControlRight,
+        "AltLeft" => KeyCode::AltLeft,
+        "AltRight" => KeyCode::AltRight,
+        "MetaLeft" => KeyCode::SuperLeft,
+        "MetaRight" => KeyCode::SuperRight,
+        "Minus" => KeyCode::Minus,
+        "Equal" => KeyCode::Equals,
+        "BracketLeft" => KeyCode::BracketLeft,
+        "BracketRight" => KeyCode::BracketRight,
+        "Backslash" => KeyCode::Backslash,
+        "Semicolon" => KeyCode::Semicolon,
+        "Quote" => KeyCode::Apostrophe,
+        "Backquote" => KeyCode::Grave,
+        "Comma" => KeyCode::Comma,
+        "Period" => KeyCode::Period,
+        "Slash" => KeyCode::Slash,
+        "CapsLock" => KeyCode::Capital,
+        "NumLock" => KeyCode::Numlock,
+        "ScrollLock" => KeyCode::Scroll,
+        "Pause" => KeyCode::Pause,
+        "PrintScreen" => KeyCode::Snapshot,
+        "NumpadAdd" => KeyCode::NumpadAdd,
+        "NumpadSubtract" => KeyCode::NumpadSubtract,
+        "NumpadMultiply" => KeyCode::NumpadMultiply,
+        "NumpadDivide" => KeyCode::NumpadDivide,
+        "NumpadDecimal" => KeyCode::NumpadDecimal,
+        "NumpadEnter" => KeyCode::NumpadEnter,
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// The Bevy [`MouseButton`] for a Doryen mouse button number.
+pub fn mouse_button(num: usize) -> MouseButton {
+    match num {
+        0 => MouseButton::Left,
+        1 => MouseButton::Middle,
+        2 => MouseButton::Right,
+        other => MouseButton::Other(other as u16),
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn send_bevy_input_events(
+    input: Res<'_, Input>,
+    mut keyboard_input: EventWriter<'_, KeyboardInput>,
+    mut mouse_button_input: EventWriter<'_, MouseButtonInput>,
+    mut mouse_wheel: EventWriter<'_, MouseWheel>,
+) {
+    let key_event = |key: &str, state| KeyboardInput {
+        scan_code: 0,
+        key_code: key_code(key),
+        state,
+        window: Entity::PLACEHOLDER,
+    };
+    keyboard_input.send_batch(
+        input
+            .keys_released()
+            .map(|key| key_event(key, ButtonState::Released)),
+    );
+    keyboard_input.send_batch(
+        input
+            .keys_pressed()
+            .map(|key| key_event(key, ButtonState::Pressed)),
+    );
+
+    let (pressed, released) = input.mouse_button_changes();
+    let button_event = |num, state| MouseButtonInput {
+        button: mouse_button(num),
+        state,
+        window: Entity::PLACEHOLDER,
+    };
+    mouse_button_input.send_batch(
+        released
+            .into_iter()
+            .map(|num| button_event(num, ButtonState::Released)),
+    );
+    mouse_button_input.send_batch(
+        pressed
+            .into_iter()
+            .map(|num| button_event(num, ButtonState::Pressed)),
+    );
+
+    let (x, y) = input.mouse_wheel_delta();
+    if (x, y) != (0.0, 0.0) {
+        mouse_wheel.send(MouseWheel {
+            unit: MouseScrollUnit::Line,
+            x,
+            y,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn send_bevy_window_events(
+    input: Res<'_, Input>,
+    screen_info: Res<'_, ScreenInfo>,
+    mut previous_mouse_pos: Local<'_, Option<(f32, f32)>>,
+    mut cursor_moved: EventWriter<'_, CursorMoved>,
+    mut received_character: EventWriter<'_, ReceivedCharacter>,
+) {
+    let mouse_pos = input.mouse_pos();
+    if *previous_mouse_pos != Some(mouse_pos) {
+        *previous_mouse_pos = Some(mouse_pos);
+        let (char_width, char_height) = screen_info.char_size();
+        cursor_moved.send(CursorMoved {
+            window: Entity::PLACEHOLDER,
+            position: Vec2::new(
+                mouse_pos.0 * char_width as f32,
+                mouse_pos.1 * char_height as f32,
+            ),
+        });
+    }
+
+    received_character.send_batch(input.text().chars().map(|char| ReceivedCharacter {
+        window: Entity::PLACEHOLDER,
+        char,
+    }));
+}
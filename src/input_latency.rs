@@ -0,0 +1,92 @@
+use crate::Input;
+use bevy_ecs::system::{Res, ResMut, Resource};
+use bevy_utils::{Duration, Instant};
+use std::collections::VecDeque;
+
+/// How many samples the averages are taken over.
+const SAMPLES: usize = 60;
+
+/// Resource measuring how long input takes to make it through bevy_doryen,
+/// for checking how responsive the game feels. On the web, this uses the
+/// browser's high-resolution clock.
+///
+/// Two delays are measured from the moment the runner receives input, see
+/// [`Input::received_at`]:
+///
+/// * the update latency, until the Bevy update that first sees it starts;
+/// * the render latency, until the end of the first render after that
+///   update, which is about when it becomes visible.
+///
+/// Both include any time spent waiting on [`DoryenFrameLimits`] or while the
+/// update is paused. Only updates that receive input are measured.
+///
+/// [`DoryenFrameLimits`]: crate::DoryenFrameLimits
+#[derive(Default, Debug, Clone, Resource)]
+pub struct InputLatency {
+    update: VecDeque<Duration>,
+    render: VecDeque<Duration>,
+    awaiting_render: Option<Instant>,
+}
+
+impl InputLatency {
+    /// The update latency of the most recent input.
+    pub fn update_latency(&self) -> Option<Duration> {
+        self.update.back().copied()
+    }
+
+    /// The average update latency over the last 60 inputs.
+    pub fn average_update_latency(&self) -> Option<Duration> {
+        average(&self.update)
+    }
+
+    /// The highest update latency of the last 60 inputs.
+    pub fn max_update_latency(&self) -> Option<Duration> {
+        self.update.iter().max().copied()
+    }
+
+    /// The render latency of the most recent input.
+    pub fn render_latency(&self) -> Option<Duration> {
+        self.render.back().copied()
+    }
+
+    /// The average render latency over the last 60 inputs.
+    pub fn average_render_latency(&self) -> Option<Duration> {
+        average(&self.render)
+    }
+
+    /// The highest render latency of the last 60 inputs.
+    pub fn max_render_latency(&self) -> Option<Duration> {
+        self.render.iter().max().copied()
+    }
+}
+
+fn average(samples: &VecDeque<Duration>) -> Option<Duration> {
+    if samples.is_empty() {
+        return None;
+    }
+    Some(samples.iter().sum::<Duration>() / samples.len() as u32)
+}
+
+fn push(samples: &mut VecDeque<Duration>, sample: Duration) {
+    if samples.len() == SAMPLES {
+        samples.pop_front();
+    }
+    samples.push_back(sample);
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub(crate) fn measure_update_latency(
+    input: Res<'_, Input>,
+    mut input_latency: ResMut<'_, InputLatency>,
+) {
+    if let Some(received_at) = input.received_at() {
+        push(&mut input_latency.update, received_at.elapsed());
+        input_latency.awaiting_render.get_or_insert(received_at);
+    }
+}
+
+pub(crate) fn measure_render_latency(mut input_latency: ResMut<'_, InputLatency>) {
+    if let Some(received_at) = input_latency.awaiting_render.take() {
+        push(&mut input_latency.render, received_at.elapsed());
+    }
+}
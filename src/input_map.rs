@@ -0,0 +1,384 @@
+//! Binding logical actions to keys, mouse buttons and gamepad buttons.
+//!
+//! Instead of checking for `"KeyY"` all over the game, define the actions
+//! the player can take, bind them to inputs in an [`InputMap`], and ask the
+//! map about the actions. Rebinding a control is then a matter of changing
+//! the map:
+//!
+//! ```
+//! # use bevy_app::App;
+//! # use bevy_doryen::input_map::{Binding, InputMap, InputMapPlugin};
+//! # use bevy_doryen::MouseButton;
+//! # use bevy_ecs::system::Res;
+//! #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+//! enum Action {
+//!     MoveNorth,
+//!     OpenInventory,
+//! }
+//!
+//! fn open_inventory(input_map: Res<InputMap<Action>>) {
+//!     if input_map.just_activated(&Action::OpenInventory) {
+//!         // Show the inventory.
+//!     }
+//! }
+//!
+//! let input_map = InputMap::new()
+//!     .with_binding(Action::MoveNorth, Binding::key("ArrowUp"))
+//!     .with_binding(Action::MoveNorth, Binding::key("KeyK"))
+//!     .with_binding(Action::OpenInventory, Binding::key("KeyI"))
+//!     .with_binding(Action::OpenInventory, Binding::MouseButton(MouseButton::Right));
+//! App::new()
+//!     .insert_resource(input_map)
+//!     .add_plugins(InputMapPlugin::<Action>::default());
+//! ```
+//!
+//! Keys are bound by their position on the keyboard, so WASD movement works
+//! on any layout. Bindings picked for their letter, like I for the
+//! inventory, can be matched by letter instead with
+//! [`InputMap::with_key_matching`].
+
+use crate::{Input, KeyMatching, KeyRepeat, MouseButton};
+use bevy_app::{App as BevyApp, Plugin, PreUpdate};
+#[cfg(feature = "gamepad")]
+use bevy_ecs::schedule::IntoSystemConfigs;
+use bevy_ecs::system::{Res, ResMut, Resource};
+#[cfg(feature = "gamepad")]
+use bevy_input::gamepad::GamepadButtonType;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+/// The types that can be used as actions in an [`InputMap`].
+pub trait Action: Clone + Eq + Hash + Send + Sync + 'static {}
+
+impl<A: Clone + Eq + Hash + Send + Sync + 'static> Action for A {}
+
+/// The plugin keeping the [`InputMap<A>`] resource up to date with the
+/// [`Input`] resource. Adds an empty map, unless one has already been
+/// inserted.
+pub struct InputMapPlugin<A>(PhantomData<fn() -> A>);
+
+impl<A> Default for InputMapPlugin<A> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<A> Clone for InputMapPlugin<A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A> Copy for InputMapPlugin<A> {}
+
+impl<A> std::fmt::Debug for InputMapPlugin<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InputMapPlugin").finish()
+    }
+}
+
+impl<A: Action> Plugin for InputMapPlugin<A> {
+    fn build(&self, app: &mut BevyApp) {
+        app.init_resource::<InputMap<A>>();
+        #[cfg(feature = "gamepad")]
+        app.add_systems(
+            PreUpdate,
+            update_input_map::<A>.after(crate::gamepad::sync_gamepad_input),
+        );
+        #[cfg(not(feature = "gamepad"))]
+        app.add_systems(PreUpdate, update_input_map::<A>);
+    }
+}
+
+/// An input an action can be bound to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Binding {
+    /// A key, by the name Doryen uses for it. See [`Input`].
+    Key(String),
+    /// A mouse button.
+    MouseButton(MouseButton),
+    /// A button on any gamepad.
+    #[cfg(feature = "gamepad")]
+    GamepadButton(GamepadButtonType),
+}
+
+impl Binding {
+    /// A binding to the key with the given name.
+    pub fn key(key: impl Into<String>) -> Self {
+        Self::Key(key.into())
+    }
+
+    /// Whether the input is currently down.
+    fn is_down(&self, input: &Input, matching: KeyMatching) -> bool {
+        match self {
+            Self::Key(key) => input
+                .resolve_key(key, matching)
+                .is_some_and(|key| input.key(key)),
+            Self::MouseButton(mouse_button) => input.mouse_button(*mouse_button),
+            #[cfg(feature = "gamepad")]
+            Self::GamepadButton(button) => input.gamepad_button(*button),
+        }
+    }
+
+    /// Whether the input was pressed since the last update.
+    fn was_pressed(&self, input: &Input, matching: KeyMatching) -> bool {
+        match self {
+            Self::Key(key) => input
+                .resolve_key(key, matching)
+                .is_some_and(|key| input.key_pressed(key)),
+            Self::MouseButton(mouse_button) => input.mouse_button_pressed(*mouse_button),
+            #[cfg(feature = "gamepad")]
+            Self::GamepadButton(button) => input.gamepad_button_pressed(*button),
+        }
+    }
+}
+
+/// Shows the binding the way players know it, like `"I"` for `"KeyI"` and
+/// `"Up"` for `"ArrowUp"`.
+impl std::fmt::Display for Binding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Key(key) => {
+                let short = ["Key", "Digit", "Arrow"]
+                    .iter()
+                    .find_map(|prefix| key.strip_prefix(prefix))
+                    .filter(|rest| !rest.is_empty());
+                match (short, key.as_str()) {
+                    (Some(short), _) => f.write_str(short),
+                    (None, "Escape") => f.write_str("Esc"),
+                    (None, _) => match key.strip_prefix("Numpad") {
+                        Some(rest) => write!(f, "Num {}", rest),
+                        None => f.write_str(key),
+                    },
+                }
+            }
+            Self::MouseButton(MouseButton::Left) => f.write_str("Left click"),
+            Self::MouseButton(MouseButton::Middle) => f.write_str("Middle click"),
+            Self::MouseButton(MouseButton::Right) => f.write_str("Right click"),
+            Self::MouseButton(MouseButton::Any(button)) => write!(f, "Mouse {}", button),
+            #[cfg(feature = "gamepad")]
+            Self::GamepadButton(button) => write!(f, "{:?}", button),
+        }
+    }
+}
+
+/// Resource binding actions of type `A` to inputs, and tracking which of
+/// them are active. See the [module documentation](self).
+///
+/// An action is active while any of its bindings is down. It's just
+/// activated in the update it becomes active, and just deactivated in the
+/// update it stops being active. A press and release that both happen
+/// between two updates still count as a just activated action.
+///
+/// An action held down also repeats, the way [`Input::key_repeated`] does,
+/// at the rate of the [`KeyRepeat`] resource unless the action has a rate of
+/// its own.
+#[derive(Debug, Clone, Resource)]
+pub struct InputMap<A: Action> {
+    bindings: HashMap<A, Vec<Binding>>,
+    repeat_overrides: HashMap<A, KeyRepeat>,
+    active: HashMap<A, u32>,
+    just_activated: HashSet<A>,
+    just_deactivated: HashSet<A>,
+    repeated: HashSet<A>,
+    key_matching: KeyMatching,
+    enabled: bool,
+}
+
+impl<A: Action> Default for InputMap<A> {
+    fn default() -> Self {
+        Self {
+            bindings: HashMap::new(),
+            repeat_overrides: HashMap::new(),
+            active: HashMap::new(),
+            just_activated: HashSet::new(),
+            just_deactivated: HashSet::new(),
+            repeated: HashSet::new(),
+            key_matching: KeyMatching::default(),
+            enabled: true,
+        }
+    }
+}
+
+impl<A: Action> InputMap<A> {
+    /// Creates a map without any bindings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a binding for an action.
+    pub fn with_binding(mut self, action: A, binding: Binding) -> Self {
+        self.bind(action, binding);
+        self
+    }
+
+    /// Adds a binding for an action, unless it's already bound to it.
+    pub fn bind(&mut self, action: A, binding: Binding) -> &mut Self {
+        let bindings = self.bindings.entry(action).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+        self
+    }
+
+    /// Removes a binding from an action.
+    pub fn unbind(&mut self, action: &A, binding: &Binding) -> &mut Self {
+        if let Some(bindings) = self.bindings.get_mut(action) {
+            bindings.retain(|b| b != binding);
+        }
+        self
+    }
+
+    /// Removes all the bindings of an action.
+    pub fn clear_bindings(&mut self, action: &A) -> &mut Self {
+        self.bindings.remove(action);
+        self
+    }
+
+    /// The bindings of an action, in the order they were added.
+    pub fn bindings(&self, action: &A) -> &[Binding] {
+        self.bindings.get(action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Makes an action repeat at its own rate instead of the one of the
+    /// [`KeyRepeat`] resource.
+    pub fn with_repeat(mut self, action: A, repeat: KeyRepeat) -> Self {
+        self.set_repeat(action, repeat);
+        self
+    }
+
+    /// Makes an action repeat at its own rate instead of the one of the
+    /// [`KeyRepeat`] resource.
+    pub fn set_repeat(&mut self, action: A, repeat: KeyRepeat) -> &mut Self {
+        self.repeat_overrides.insert(action, repeat);
+        self
+    }
+
+    /// Makes an action repeat at the rate of the [`KeyRepeat`] resource
+    /// again.
+    pub fn clear_repeat(&mut self, action: &A) -> &mut Self {
+        self.repeat_overrides.remove(action);
+        self
+    }
+
+    /// The rate an action repeats at, if it has one of its own.
+    pub fn repeat(&self, action: &A) -> Option<&KeyRepeat> {
+        self.repeat_overrides.get(action)
+    }
+
+    /// Sets whether key bindings are matched by the key's position or by
+    /// what the keyboard layout says it is. Defaults to
+    /// [`KeyMatching::Physical`].
+    pub fn with_key_matching(mut self, key_matching: KeyMatching) -> Self {
+        self.key_matching = key_matching;
+        self
+    }
+
+    /// Sets whether key bindings are matched by the key's position or by
+    /// what the keyboard layout says it is.
+    pub fn set_key_matching(&mut self, key_matching: KeyMatching) -> &mut Self {
+        self.key_matching = key_matching;
+        self
+    }
+
+    /// Whether key bindings are matched by the key's position or by what the
+    /// keyboard layout says it is.
+    pub fn key_matching(&self) -> KeyMatching {
+        self.key_matching
+    }
+
+    /// Enables or disables the whole map. While disabled, no action is
+    /// active, as if none of the bindings were down. The
+    /// [`input_context`](crate::input_context) module does this for maps
+    /// bound to contexts that aren't active.
+    pub fn set_enabled(&mut self, enabled: bool) -> &mut Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Whether the map is enabled. Maps start out enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// The actions bound to the given input.
+    pub fn actions_bound_to<'a>(&'a self, binding: &'a Binding) -> impl Iterator<Item = &'a A> {
+        self.bindings
+            .iter()
+            .filter(move |(_, bindings)| bindings.contains(binding))
+            .map(|(action, _)| action)
+    }
+
+    /// Whether any binding of the action is down.
+    pub fn active(&self, action: &A) -> bool {
+        self.active.contains_key(action)
+    }
+
+    /// Whether the action became active since the last update.
+    pub fn just_activated(&self, action: &A) -> bool {
+        self.just_activated.contains(action)
+    }
+
+    /// Whether the action stopped being active since the last update.
+    pub fn just_deactivated(&self, action: &A) -> bool {
+        self.just_deactivated.contains(action)
+    }
+
+    /// Whether the action was just activated, or has been active long enough
+    /// to repeat since the last update. How long an action has been active
+    /// is counted in updates.
+    pub fn repeated(&self, action: &A) -> bool {
+        self.repeated.contains(action)
+    }
+
+    fn update(&mut self, input: &Input, default_repeat: &KeyRepeat) {
+        let mut active = HashMap::new();
+        self.just_activated.clear();
+        self.repeated.clear();
+        // A disabled map has no active actions
+        let enabled = self.enabled;
+        for (action, bindings) in self.bindings.iter().filter(|_| enabled) {
+            let previous_ticks = self.active.get(action).copied();
+            let matching = self.key_matching;
+            let pressed = bindings
+                .iter()
+                .any(|binding| binding.was_pressed(input, matching));
+            if bindings
+                .iter()
+                .any(|binding| binding.is_down(input, matching))
+            {
+                let ticks = previous_ticks.map_or(0, |ticks| ticks + 1);
+                active.insert(action.clone(), ticks);
+
+                let repeat = self.repeat_overrides.get(action).unwrap_or(default_repeat);
+                if previous_ticks.is_some_and(|previous_ticks| {
+                    repeat.repeats(ticks) > repeat.repeats(previous_ticks)
+                }) {
+                    self.repeated.insert(action.clone());
+                }
+            }
+            if pressed || (active.contains_key(action) && previous_ticks.is_none()) {
+                self.just_activated.insert(action.clone());
+                self.repeated.insert(action.clone());
+            }
+        }
+        self.just_deactivated = self
+            .active
+            .keys()
+            .filter(|action| !active.contains_key(*action))
+            .cloned()
+            .collect();
+        self.active = active;
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub(crate) fn update_input_map<A: Action>(
+    input: Res<'_, Input>,
+    key_repeat: Option<Res<'_, KeyRepeat>>,
+    mut input_map: ResMut<'_, InputMap<A>>,
+) {
+    let key_repeat = key_repeat.map_or_else(KeyRepeat::default, |key_repeat| *key_repeat);
+    input_map.update(&input, &key_repeat);
+}
@@ -0,0 +1,205 @@
+//! A typed alternative to key name strings.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+macro_rules! keys {
+    ($($variant:ident,)*) => {
+        /// A key, by the name Doryen uses for it. Every method of
+        /// [`Input`](crate::Input) that takes a key name also takes a `Key`,
+        /// so a typo is caught by the compiler instead of silently never
+        /// matching:
+        ///
+        /// ```
+        /// # use bevy_doryen::{Input, Key};
+        /// # use bevy_ecs::system::Res;
+        /// fn jump(input: Res<Input>) {
+        ///     if input.key_pressed(Key::Space) {
+        ///         // Jump.
+        ///     }
+        /// }
+        /// ```
+        ///
+        /// The variants are named after the keys' positions on a US layout,
+        /// like Doryen's key names, so [`Key::KeyZ`] is the key left of
+        /// [`Key::KeyX`] whatever the keyboard layout prints on it. Converting
+        /// a `Key` to a string and back gives the Doryen key name, for
+        /// storing bindings in a settings file.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #[allow(missing_docs)]
+        pub enum Key {
+            $($variant,)*
+        }
+
+        impl Key {
+            /// Every key, in declaration order.
+            pub const ALL: &'static [Self] = &[$(Self::$variant,)*];
+
+            /// The name Doryen uses for the key.
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Self::$variant => stringify!($variant),)*
+                }
+            }
+        }
+    };
+}
+
+keys! {
+    // Letters
+    KeyA,
+    KeyB,
+    KeyC,
+    KeyD,
+    KeyE,
+    KeyF,
+    KeyG,
+    KeyH,
+    KeyI,
+    KeyJ,
+    KeyK,
+    KeyL,
+    KeyM,
+    KeyN,
+    KeyO,
+    KeyP,
+    KeyQ,
+    KeyR,
+    KeyS,
+    KeyT,
+    KeyU,
+    KeyV,
+    KeyW,
+    KeyX,
+    KeyY,
+    KeyZ,
+    // Number row
+    Digit0,
+    Digit1,
+    Digit2,
+    Digit3,
+    Digit4,
+    Digit5,
+    Digit6,
+    Digit7,
+    Digit8,
+    Digit9,
+    // Function keys
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    // Numeric keypad
+    Numpad0,
+    Numpad1,
+    Numpad2,
+    Numpad3,
+    Numpad4,
+    Numpad5,
+    Numpad6,
+    Numpad7,
+    Numpad8,
+    Numpad9,
+    NumpadAdd,
+    NumpadSubtract,
+    NumpadMultiply,
+    NumpadDivide,
+    NumpadDecimal,
+    NumpadEnter,
+    // Navigation
+    ArrowUp,
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    Delete,
+    // Editing and whitespace
+    Escape,
+    Enter,
+    Space,
+    Tab,
+    Backspace,
+    // Modifiers
+    ShiftLeft,
+    ShiftRight,
+    ControlLeft,
+    ControlRight,
+    AltLeft,
+    AltRight,
+    MetaLeft,
+    MetaRight,
+    // Punctuation
+    Minus,
+    Equal,
+    BracketLeft,
+    BracketRight,
+    Backslash,
+    Semicolon,
+    Quote,
+    Backquote,
+    Comma,
+    Period,
+    Slash,
+    // Locks and system keys
+    CapsLock,
+    NumLock,
+    ScrollLock,
+    Pause,
+    PrintScreen,
+    ContextMenu,
+}
+
+impl AsRef<str> for Key {
+    fn as_ref(&self) -> &str {
+        self.name()
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl From<Key> for String {
+    fn from(key: Key) -> Self {
+        Self::from(key.name())
+    }
+}
+
+impl FromStr for Key {
+    type Err = ParseKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|key| key.name() == s)
+            .ok_or_else(|| ParseKeyError(s.to_owned()))
+    }
+}
+
+/// The error returned when parsing a string that isn't the name of a [`Key`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseKeyError(String);
+
+impl fmt::Display for ParseKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} isn't the name of a key", self.0)
+    }
+}
+
+impl Error for ParseKeyError {}
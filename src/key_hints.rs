@@ -0,0 +1,190 @@
+//! A bar along the bottom of the screen listing what the player can do.
+//!
+//! [`KeyHints<A>`] holds the actions worth a hint, each with a label, and
+//! draws them along with the inputs they're bound to in the
+//! [`InputMap<A>`]. Actions without bindings are left out, so the bar only
+//! shows what the player can actually do, and nothing is drawn while the
+//! map is disabled by its [input context](crate::input_context).
+//! [`KeyHintsPlugin<A>`] draws the
+//! [`KeyHints<A>`] resource on the bottom row of the root console every
+//! frame; replace its hints whenever the game changes what's being played,
+//! such as when a menu opens:
+//!
+//! ```
+//! # use bevy_doryen::key_hints::KeyHints;
+//! # use bevy_ecs::system::ResMut;
+//! #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+//! enum Action {
+//!     Confirm,
+//!     Cancel,
+//!     OpenInventory,
+//! }
+//!
+//! fn open_menu(mut hints: ResMut<KeyHints<Action>>) {
+//!     hints.set_hints(vec![(Action::Confirm, "Select"), (Action::Cancel, "Back")]);
+//! }
+//! ```
+//!
+//! The bar looks like `I Inventory  Esc Menu`, using the first binding of
+//! each action. Hints that don't fit on the row are left out.
+
+use crate::doryen::{Color, Console, TextAlign};
+use crate::input_map::{Action, InputMap};
+use crate::{PostRenderSet, RenderStage, RenderSystemExtensions, RootConsole};
+use bevy_app::{App, Plugin};
+use bevy_ecs::schedule::IntoSystemConfigs;
+use bevy_ecs::system::{Res, ResMut, Resource};
+use std::marker::PhantomData;
+
+/// The plugin drawing the [`KeyHints<A>`] resource on the bottom row of the
+/// root console, using the [`InputMap<A>`] resource for the bindings. Adds
+/// an empty [`KeyHints<A>`], unless one has already been inserted.
+pub struct KeyHintsPlugin<A>(PhantomData<fn() -> A>);
+
+impl<A> Default for KeyHintsPlugin<A> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<A> Clone for KeyHintsPlugin<A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A> Copy for KeyHintsPlugin<A> {}
+
+impl<A> std::fmt::Debug for KeyHintsPlugin<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("KeyHintsPlugin").finish()
+    }
+}
+
+impl<A: Action> Plugin for KeyHintsPlugin<A> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<KeyHints<A>>()
+            .add_doryen_render_systems_to_stage(
+                RenderStage::PostRender,
+                draw_key_hints::<A>.in_set(PostRenderSet::Overlay),
+            );
+    }
+}
+
+/// The actions to show hints for, and how the hints look. See the [module
+/// documentation](self).
+#[derive(Debug, Clone, PartialEq, Eq, Resource)]
+pub struct KeyHints<A: Action> {
+    /// Whether the hints are drawn. Defaults to `true`.
+    pub visible: bool,
+    /// The color of the keys.
+    pub key_color: Color,
+    /// The color of the labels.
+    pub label_color: Color,
+    /// The background color of the bar, or `None` to draw the hints over
+    /// what's already there.
+    pub back: Option<Color>,
+    hints: Vec<(A, String)>,
+}
+
+impl<A: Action> Default for KeyHints<A> {
+    fn default() -> Self {
+        Self {
+            visible: true,
+            key_color: (255, 255, 0, 255),
+            label_color: (192, 192, 192, 255),
+            back: Some((0, 0, 0, 255)),
+            hints: Vec::new(),
+        }
+    }
+}
+
+impl<A: Action> KeyHints<A> {
+    /// Creates an empty bar.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a hint for `action`, in place of any it already had.
+    pub fn with_hint(mut self, action: A, label: impl Into<String>) -> Self {
+        self.add_hint(action, label);
+        self
+    }
+
+    /// Adds a hint for `action`, in place of any it already had.
+    pub fn add_hint(&mut self, action: A, label: impl Into<String>) -> &mut Self {
+        let label = label.into();
+        match self.hints.iter_mut().find(|(a, _)| *a == action) {
+            Some((_, existing)) => *existing = label,
+            None => self.hints.push((action, label)),
+        }
+        self
+    }
+
+    /// Removes the hint for `action`.
+    pub fn remove_hint(&mut self, action: &A) -> &mut Self {
+        self.hints.retain(|(a, _)| a != action);
+        self
+    }
+
+    /// Replaces all the hints, in the order given.
+    pub fn set_hints<L: Into<String>>(
+        &mut self,
+        hints: impl IntoIterator<Item = (A, L)>,
+    ) -> &mut Self {
+        self.hints.clear();
+        for (action, label) in hints {
+            self.add_hint(action, label);
+        }
+        self
+    }
+
+    /// The actions with hints and their labels, in the order they're drawn.
+    pub fn hints(&self) -> impl Iterator<Item = (&A, &str)> + '_ {
+        self.hints
+            .iter()
+            .map(|(action, label)| (action, label.as_str()))
+    }
+
+    /// Draws the hints for the actions bound in `input_map` on row `y` of
+    /// `console`.
+    pub fn draw(&self, input_map: &InputMap<A>, console: &mut Console, y: i32) {
+        let width = console.get_width() as i32;
+        if let Some(back) = self.back {
+            console.area(0, y, width as u32, 1, None, Some(back), Some(' ' as u16));
+        }
+
+        let mut x = 0;
+        for (action, label) in &self.hints {
+            let key = match input_map.bindings(action).first() {
+                Some(binding) => binding.to_string(),
+                None => continue,
+            };
+            let (key_width, label_width) =
+                (key.chars().count() as i32, label.chars().count() as i32);
+            let gap = if x == 0 { 0 } else { 2 };
+            if x + gap + key_width + 1 + label_width > width {
+                break;
+            }
+            x += gap;
+            console.print(x, y, &key, TextAlign::Left, Some(self.key_color), None);
+            x += key_width + 1;
+            console.print(x, y, label, TextAlign::Left, Some(self.label_color), None);
+            x += label_width;
+        }
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn draw_key_hints<A: Action>(
+    hints: Res<'_, KeyHints<A>>,
+    input_map: Option<Res<'_, InputMap<A>>>,
+    mut root_console: ResMut<'_, RootConsole>,
+) {
+    let input_map = match input_map {
+        Some(input_map) if hints.visible && input_map.is_enabled() => input_map,
+        _ => return,
+    };
+    let y = root_console.get_height() as i32 - 1;
+    hints.draw(&input_map, &mut root_console, y);
+}
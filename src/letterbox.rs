@@ -0,0 +1,150 @@
+//! Drawing a root console of a fixed size into the middle of Doryen's
+//! window-sized console, for [`ResizeMode::Letterbox`](crate::ResizeMode).
+
+use crate::doryen::Console;
+use crate::input::InputSource;
+use crate::touch::TouchInput;
+use crate::Modifiers;
+
+const BORDER: (u8, u8, u8, u8) = (0, 0, 0, 255);
+
+/// Where the top left corner of a console of `console_size` goes to center
+/// it in a window console of `window_size`.
+pub(crate) fn offset(
+    (window_width, window_height): (u32, u32),
+    (console_width, console_height): (u32, u32),
+) -> (i32, i32) {
+    (
+        (window_width as i32 - console_width as i32) / 2,
+        (window_height as i32 - console_height as i32) / 2,
+    )
+}
+
+/// The size of the window console for a window of `screen_size` pixels with
+/// cells of `char_size` pixels. It never gets smaller than the console it
+/// shows, which Doryen then shrinks to fit the window.
+pub(crate) fn window_console_size(
+    (screen_width, screen_height): (u32, u32),
+    (char_width, char_height): (u32, u32),
+    (console_width, console_height): (u32, u32),
+) -> (u32, u32) {
+    (
+        (screen_width / char_width.max(1)).max(console_width),
+        (screen_height / char_height.max(1)).max(console_height),
+    )
+}
+
+/// The part of a window of `screen_size` pixels, showing `window_console`,
+/// that `console` takes up.
+pub(crate) fn screen_size(
+    (screen_width, screen_height): (u32, u32),
+    (window_width, window_height): (u32, u32),
+    (console_width, console_height): (u32, u32),
+) -> (u32, u32) {
+    (
+        screen_width * console_width / window_width.max(1),
+        screen_height * console_height / window_height.max(1),
+    )
+}
+
+/// Draws `console` in the middle of `window_console`, with black borders
+/// around it.
+pub(crate) fn draw(console: &Console, window_console: &mut Console) {
+    let (x, y) = offset(window_console.get_size(), console.get_size());
+    window_console.clear(Some(BORDER), Some(BORDER), Some(u16::from(b' ')));
+    console.blit(x, y, window_console, 1.0, 1.0, None);
+}
+
+/// Input from a window console, moved so that positions are relative to the
+/// console centered in it.
+pub(crate) struct LetterboxedInput<'a, I: InputSource + ?Sized> {
+    pub(crate) source: &'a mut I,
+    pub(crate) offset: (i32, i32),
+}
+
+impl<I: InputSource + ?Sized> LetterboxedInput<'_, I> {
+    #[inline]
+    fn unoffset(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        (x - self.offset.0 as f32, y - self.offset.1 as f32)
+    }
+}
+
+impl<I: InputSource + ?Sized> InputSource for LetterboxedInput<'_, I> {
+    fn for_each_key_pressed(&self, f: &mut dyn FnMut(&str)) {
+        self.source.for_each_key_pressed(f);
+    }
+
+    fn for_each_key_released(&self, f: &mut dyn FnMut(&str)) {
+        self.source.for_each_key_released(f);
+    }
+
+    fn mouse_button_pressed(&mut self, num: usize) -> bool {
+        self.source.mouse_button_pressed(num)
+    }
+
+    fn mouse_button_released(&mut self, num: usize) -> bool {
+        self.source.mouse_button_released(num)
+    }
+
+    fn text(&self) -> String {
+        self.source.text()
+    }
+
+    fn mouse_pos(&self) -> (f32, f32) {
+        self.unoffset(self.source.mouse_pos())
+    }
+
+    fn mouse_wheel(&mut self) -> (f32, f32) {
+        self.source.mouse_wheel()
+    }
+
+    fn touches(&mut self) -> Vec<TouchInput> {
+        let mut touches = self.source.touches();
+        for touch in &mut touches {
+            touch.position = self.unoffset(touch.position);
+        }
+        touches
+    }
+
+    fn close_requested(&self) -> bool {
+        self.source.close_requested()
+    }
+
+    fn modifiers(&self) -> Option<Modifiers> {
+        self.source.modifiers()
+    }
+
+    fn cursor_inside(&self) -> Option<bool> {
+        self.source.cursor_inside()
+    }
+
+    fn logical_key(&self, key: &str) -> Option<String> {
+        self.source.logical_key(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consoles_are_centered_with_black_borders() {
+        let mut console = Console::new(2, 1);
+        console.ascii(0, 0, u16::from(b'a'));
+        console.ascii(1, 0, u16::from(b'b'));
+        let mut window_console = Console::new(6, 3);
+
+        draw(&console, &mut window_console);
+
+        assert_eq!(crate::headless::console_row(&window_console, 0), "      ");
+        assert_eq!(crate::headless::console_row(&window_console, 1), "  ab  ");
+        assert_eq!(window_console.get_back(0, 0), Some(BORDER));
+    }
+
+    #[test]
+    fn window_consoles_are_never_smaller_than_the_console() {
+        assert_eq!(window_console_size((800, 600), (8, 8), (80, 50)), (100, 75));
+        assert_eq!(window_console_size((320, 600), (8, 8), (80, 50)), (80, 75));
+        assert_eq!(screen_size((800, 600), (100, 75), (80, 50)), (640, 400));
+    }
+}
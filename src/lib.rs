@@ -3,17 +3,17 @@
 //!
 //! Usage:
 //! ```no_run
-//! # use bevy_app::App;
+//! # use bevy_app::{App, Startup, Update};
 //! # use bevy_doryen::{
 //! #     DoryenPluginSettings,
 //! #     DoryenPlugin,
 //! #     RenderSystemExtensions,
 //! #     ResizeMode,
-//! #     MouseButton
+//! #     MouseButton,
+//! #     WebOptions
 //! # };
 //! # use bevy_doryen::doryen::AppOptions;
-//! # use bevy_ecs::system::IntoSystem;
-//! App::build()
+//! App::new()
 //!     // Insert a `DoryenPluginSettings` resource to configure the plugin.
 //!     .insert_resource(DoryenPluginSettings {
 //!         // `app_options` lets you configure Doryen just as if you were
@@ -33,18 +33,21 @@
 //!         // Lets you configure how the application should behave when resized.
 //!         // The default is `ResizeMode::Nothing`. See `ResizeMode`'s
 //!         // documentation for more information.
-//!         resize_mode: ResizeMode::Nothing
+//!         resize_mode: ResizeMode::Nothing,
+//!         // Settings that only apply when running in a browser. The default
+//!         // is `WebOptions::default()`.
+//!         web_options: WebOptions::default(),
 //!     })
 //!     // Add the `DoryenPlugin` to Bevy.
-//!     .add_plugin(DoryenPlugin)
+//!     .add_plugins(DoryenPlugin)
 //!     // Add your Bevy systems like usual. Excluding startup systems, which
 //!     // only run once, these systems are run during Doryen's update phase;
 //!     // i.e. 60 times per second.
-//!     .add_startup_system(init.system())
-//!     .add_system(input.system())
+//!     .add_systems(Startup, init)
+//!     .add_systems(Update, input)
 //!     // The `RenderSystemExtensions` trait lets you add systems that should
 //!     // be run during Doryen's render phase.
-//!     .add_doryen_render_system(render.system())
+//!     .add_doryen_render_systems(render)
 //! .run();
 //!
 //! # fn init() { }
@@ -67,7 +70,7 @@
 #![deny(nonstandard_style)]
 #![deny(rust_2018_idioms)]
 #![deny(trivial_numeric_casts)]
-#![deny(broken_intra_doc_links)]
+#![deny(rustdoc::broken_intra_doc_links)]
 //#![deny(unused)]
 //
 // Warn (try not to do this)
@@ -81,12 +84,11 @@
 // Deny (don't do this)
 #![deny(clippy::cast_lossless)]
 #![deny(clippy::default_trait_access)]
-#![deny(clippy::empty_enum)]
+#![deny(clippy::empty_enums)]
 #![deny(clippy::enum_glob_use)]
 #![deny(clippy::expl_impl_clone_on_copy)]
 #![deny(clippy::explicit_into_iter_loop)]
 #![deny(clippy::explicit_iter_loop)]
-#![deny(clippy::filter_map)]
 #![deny(clippy::filter_map_next)]
 #![deny(clippy::manual_find_map)]
 #![deny(clippy::if_not_else)]
@@ -97,6 +99,8 @@
 #![deny(clippy::match_same_arms)]
 #![deny(clippy::mut_mut)]
 #![deny(clippy::needless_continue)]
+// Bevy systems can't take their parameters by reference, so the systems that
+// only read them allow this one
 #![deny(clippy::needless_pass_by_value)]
 #![deny(clippy::map_unwrap_or)]
 #![deny(clippy::redundant_closure_for_method_calls)]
@@ -110,37 +114,147 @@
 //
 // Warn (try not to do this)
 //#![warn(clippy::must_use_candidate)]
-#![warn(clippy::pub_enum_variant_names)]
 #![warn(clippy::shadow_unrelated)]
 #![warn(clippy::similar_names)]
 #![warn(clippy::too_many_lines)]
 // </editor-fold>
 
+mod clear_policy;
+mod close_request;
+mod console_diff;
+mod dirty_regions;
+mod engine_control;
+mod error;
+mod frame_limits;
+mod frame_loop;
+mod frame_sink;
 mod input;
+mod input_latency;
+mod key;
+mod letterbox;
+mod loading;
+mod reduced_motion;
+mod render_error;
+mod render_layer;
 mod render_system;
 mod root_console;
+mod runtime_settings;
+mod settings_builder;
+mod touch;
+mod web;
+
+pub mod camera;
+pub mod cell_cursor;
+#[cfg(feature = "checksum")]
+pub mod checksum;
+pub mod clipboard;
+#[cfg(feature = "color_interop")]
+pub mod color_interop;
+pub mod compositor;
+pub mod debug_draw;
+#[cfg(feature = "default_plugins")]
+pub mod default_plugins;
+#[cfg(feature = "dev_console")]
+pub mod dev_console;
+pub mod game_clock;
+#[cfg(feature = "gamepad")]
+pub mod gamepad;
+pub mod geometry;
+pub mod gradient;
+pub mod headless;
+pub mod hit_test;
+pub mod idle;
+pub mod input_context;
+#[cfg(feature = "input_interop")]
+pub mod input_interop;
+pub mod input_map;
+pub mod key_hints;
+pub mod localization;
+pub mod mapgen;
+pub mod message_log;
+pub mod modal;
+pub mod paint;
+pub mod palette;
+pub mod path_following;
+pub mod path_preview;
+pub mod pathfinding;
+pub mod profiler;
+pub mod progress_bar;
+pub mod rich_text;
+pub mod rollback;
+pub mod roguelike;
+pub mod run_stats;
+#[cfg(feature = "spectate")]
+pub mod spectate;
+pub mod subcell;
+pub mod targeting;
+#[cfg(feature = "terminal")]
+pub mod terminal;
+pub mod title_screen;
+pub mod tooltip;
+#[cfg(feature = "ui")]
+pub mod ui;
+pub mod undo;
+pub mod window;
 
 /// Re-export of the Doryen library types.
 pub mod doryen {
     pub use doryen_rs::*;
 }
 
-pub use input::{Input, Keys, MouseButton};
-pub use render_system::{RenderStage, RenderState, RenderSystemExtensions};
+pub use clear_policy::{ClearMode, ClearPolicy};
+pub use close_request::{CloseRequested, WindowCloseCancelled, WindowCloseConfirmed};
+pub use console_diff::{CellChange, CellContents, ConsoleDiff};
+pub use dirty_regions::{DirtyRect, DirtyRegions};
+pub use engine_control::DoryenEngineControl;
+pub use error::DoryenError;
+pub use frame_limits::{DoryenFrameLimits, UpdateRequests};
+pub use frame_sink::{FrameSink, FrameSinks, FrameSnapshot, SharedFrame, SharedFrameGuard};
+#[allow(deprecated)]
+pub use input::DoryenInput;
+pub use input::{
+    CursorEntered, CursorLeft, Input, InputCapture, KeyMatching, KeyPressedEvent, KeyReleasedEvent,
+    KeyRepeat, Keys, Modifiers, MouseButton, MouseWheelEvent, ReceivedCharacter,
+};
+pub use input_latency::InputLatency;
+pub use key::{Key, ParseKeyError};
+pub use loading::{AssetsReady, LoadingProgress};
+pub use reduced_motion::ReducedMotion;
+pub use render_error::{RenderErrorHandler, RenderErrorPolicy};
+pub use render_layer::{RenderLayer, RenderLayers};
+pub use render_system::{
+    DoryenRender, DoryenShutdown, PostRenderSet, RenderStage, RenderSystemExtensions,
+};
+#[allow(deprecated)]
+pub use root_console::DoryenRootConsole;
 pub use root_console::RootConsole;
+pub use runtime_settings::{DoryenRuntimeSettings, RuntimeSettingsApplied};
+pub use settings_builder::{DoryenPluginSettingsBuilder, SettingsError};
+pub use touch::{LongPressEvent, TapEvent, Touch, TouchEvent, TouchPhase};
+pub use web::{PixelRatio, WebOptions};
 
 use crate::doryen::{AppOptions, Console};
-use crate::render_system::DoryenRenderSystems;
-use bevy_app::{App as BevyApp, AppBuilder, AppExit, Events, ManualEventReader, Plugin};
-use bevy_ecs::schedule::{Schedule, Stage};
+use crate::frame_loop::FrameLoop;
+use crate::letterbox::LetterboxedInput;
+use bevy_app::{App as BevyApp, First, Last, Plugin};
+use bevy_ecs::change_detection::DetectChangesMut;
+use bevy_ecs::event::{Event, Events, ManualEventReader};
+use bevy_ecs::schedule::IntoSystemConfigs;
+use bevy_ecs::system::Resource;
+use bevy_ecs::world::World;
 use doryen_rs::{App as DoryenApp, DoryenApi, Engine, UpdateEvent};
 use std::borrow::Cow;
+use std::collections::VecDeque;
+
+/// How many times per second Doryen runs its update phase.
+pub(crate) const UPDATES_PER_SECOND: f32 = 60.0;
 
 /// The Bevy Doryen plugin.
 #[derive(Default, Clone, Copy, Debug)]
 pub struct DoryenPlugin;
 
 /// DoryenPlugin settings.
+#[derive(Resource)]
 pub struct DoryenPluginSettings {
     /// The [`AppOptions`] passed to the [`DoryenApp`].
     pub app_options: AppOptions,
@@ -150,6 +264,8 @@ pub struct DoryenPluginSettings {
     pub mouse_button_listeners: Vec<MouseButton>,
     /// What to do when the Doryen window is resized.
     pub resize_mode: ResizeMode,
+    /// Settings that only apply when running in a browser.
+    pub web_options: WebOptions,
 }
 
 impl std::fmt::Debug for DoryenPluginSettings {
@@ -158,6 +274,7 @@ impl std::fmt::Debug for DoryenPluginSettings {
             .field("app_options", &"<Not Debug>")
             .field("mouse_button_listeners", &self.mouse_button_listeners)
             .field("resize_mode", &self.resize_mode)
+            .field("web_options", &self.web_options)
             .finish()
     }
 }
@@ -172,195 +289,379 @@ impl Default for DoryenPluginSettings {
                 MouseButton::Right,
             ],
             resize_mode: ResizeMode::Nothing,
+            web_options: WebOptions::default(),
         }
     }
 }
 
 impl Plugin for DoryenPlugin {
-    fn build(&self, app: &mut AppBuilder) {
-        app.init_resource::<RootConsole>()
-            .init_resource::<Input>()
-            .init_resource::<FpsInfo>()
-            .add_event::<SetFontPath>()
-            .add_event::<Resized>()
-            .init_resource::<DoryenRenderSystems>()
-            .init_resource::<RenderState>()
-            .set_runner(doryen_runner);
+    fn build(&self, app: &mut BevyApp) {
+        add_doryen_core(app).set_runner(doryen_runner);
     }
 }
 
+/// Adds the resources, events and render schedule shared by every way of
+/// running bevy_doryen.
+pub(crate) fn add_doryen_core(app: &mut BevyApp) -> &mut BevyApp {
+    app.init_resource::<RootConsole>()
+        .init_resource::<Input>()
+        .init_resource::<InputCapture>()
+        .init_resource::<FpsInfo>()
+        .init_resource::<ScreenInfo>()
+        .add_event::<SetFontPath>()
+        .add_event::<FontChanged>()
+        .init_resource::<CurrentFont>()
+        .add_event::<CaptureScreen>()
+        .add_event::<ScreenCaptured>()
+        .add_event::<Resized>()
+        .add_event::<ConsoleResized>()
+        .add_event::<CharSizeChanged>()
+        .add_event::<ScreenInfoChanged>()
+        .add_event::<RuntimeSettingsApplied>()
+        .add_event::<CloseRequested>()
+        .add_event::<KeyPressedEvent>()
+        .add_event::<KeyReleasedEvent>()
+        .add_event::<ReceivedCharacter>()
+        .add_event::<MouseWheelEvent>()
+        .add_event::<CursorEntered>()
+        .add_event::<CursorLeft>()
+        .add_event::<TouchEvent>()
+        .add_event::<TapEvent>()
+        .add_event::<LongPressEvent>()
+        .add_event::<WindowCloseConfirmed>()
+        .add_event::<WindowCloseCancelled>()
+        .init_resource::<ClearPolicy>()
+        .init_resource::<RenderErrorHandler>()
+        .init_resource::<FrameSinks>()
+        .init_resource::<DoryenFrameLimits>()
+        .init_resource::<UpdateRequests>()
+        .init_resource::<DoryenEngineControl>()
+        .init_resource::<InputLatency>()
+        .init_resource::<KeyRepeat>()
+        .init_resource::<ReducedMotion>()
+        .init_resource::<RenderLayers>()
+        .init_resource::<LoadingProgress>()
+        .add_event::<AssetsReady>()
+        .add_systems(
+            First,
+            (
+                close_request::emit_close_requested,
+                input::send_key_events,
+                input::repeat_keys,
+                input::send_character_events,
+                input::send_mouse_wheel_events,
+                input::track_cursor,
+                touch::send_touch_events,
+                input_latency::measure_update_latency,
+                loading::track_loading,
+            ),
+        )
+        .add_systems(Last, close_request::exit_on_close_confirmed)
+        .edit_schedule(
+            DoryenRender,
+            render_system::configure_doryen_render_schedule,
+        )
+        .edit_schedule(
+            DoryenShutdown,
+            render_system::configure_doryen_shutdown_schedule,
+        )
+        .add_doryen_render_systems_to_stage(
+            RenderStage::First,
+            (
+                clear_policy::clear_root_console,
+                render_error::clear_render_errors,
+            ),
+        )
+        .add_doryen_render_systems_to_stage(
+            RenderStage::Last,
+            (
+                render_error::render_error_overlay,
+                dirty_regions::track_dirty_regions,
+                frame_sink::feed_frame_sinks,
+                input_latency::measure_render_latency,
+            )
+                .chain(),
+        )
+}
+
 struct DoryenPluginEngine {
     bevy_app: BevyApp,
-    app_exit_event_reader: ManualEventReader<AppExit>,
+    frame_loop: FrameLoop,
     set_font_path_event_reader: ManualEventReader<SetFontPath>,
+    capture_screen_event_reader: ManualEventReader<CaptureScreen>,
+    pending_captures: VecDeque<Cow<'static, str>>,
+    capture_in_progress: Option<Cow<'static, str>>,
     swap_console: Option<Console>,
     mouse_button_listeners: Vec<MouseButton>,
     previous_screen_size: (u32, u32),
     previous_console_size: (u32, u32),
     resize_mode: ResizeMode,
+    exiting: bool,
+    runtime_settings: DoryenRuntimeSettings,
+    web_options: WebOptions,
 }
 
 impl DoryenPluginEngine {
+    /// Makes sure the world still has everything the engine relies on. If it
+    /// doesn't, logs what's missing and starts exiting.
+    fn check_world(&mut self) -> bool {
+        match error::validate_world(&self.bevy_app.world) {
+            Ok(()) => true,
+            Err(e) => {
+                bevy_utils::tracing::error!("stopping bevy_doryen: {}", e);
+                self.exiting = true;
+                false
+            }
+        }
+    }
+
+    /// Passes on changes to the [`DoryenRuntimeSettings`] resource.
+    fn apply_runtime_settings(&mut self) {
+        let current = match self.bevy_app.world.get_resource::<DoryenRuntimeSettings>() {
+            Some(current) if *current != self.runtime_settings => current,
+            _ => return,
+        };
+        runtime_settings::apply(&self.runtime_settings, current, &self.web_options);
+        let previous = std::mem::replace(&mut self.runtime_settings, current.clone());
+        if let Some(mut runtime_settings_applied_events) = self
+            .bevy_app
+            .world
+            .get_resource_mut::<Events<RuntimeSettingsApplied>>()
+        {
+            runtime_settings_applied_events.send(RuntimeSettingsApplied {
+                previous,
+                current: self.runtime_settings.clone(),
+            });
+        }
+    }
+
     #[inline]
     fn take_root_console_ownership(&mut self, api: &mut dyn DoryenApi) {
         use std::mem::swap;
 
-        // Take ownership of the Doryen root console
-        swap(api.con(), &mut self.swap_console.as_mut().unwrap());
+        // Take ownership of the Doryen root console. A letterboxed root
+        // console never leaves the engine, Doryen draws the window console
+        // it's copied into
+        if !self.letterboxing() {
+            swap(api.con(), self.swap_console.as_mut().unwrap());
+        }
 
-        // Insert it into the DoryenRootConsole resource
-        let mut doryen_root_console = self
-            .bevy_app
-            .world
-            .get_resource_mut::<RootConsole>()
-            .unwrap();
-        doryen_root_console.0 = self.swap_console.take();
+        // Insert it into the RootConsole resource. Handing the same console
+        // back and forth doesn't change it, so it doesn't count as a change
+        let mut doryen_root_console = self.bevy_app.world.resource_mut::<RootConsole>();
+        doryen_root_console.bypass_change_detection().0 = self.swap_console.take();
     }
 
     #[inline]
     fn restore_root_console_ownership(&mut self, api: &mut dyn DoryenApi) {
         use std::mem::swap;
 
-        // Take the root console out of the DoryenRootConsole resource
-        let mut doryen_root_console = self
+        // Take the root console out of the RootConsole resource
+        self.swap_console = self
             .bevy_app
             .world
             .get_resource_mut::<RootConsole>()
-            .unwrap();
-        self.swap_console = doryen_root_console.0.take();
-
-        // Hand ownership of the Doryen root console back to Doryen
-        swap(api.con(), &mut self.swap_console.as_mut().unwrap());
+            .and_then(|mut doryen_root_console| {
+                doryen_root_console.bypass_change_detection().0.take()
+            });
+
+        // Hand ownership of the Doryen root console back to Doryen. If a
+        // system took it, Doryen keeps the placeholder until the app exits
+        let letterboxing = self.letterboxing();
+        if let Some(console) = self.swap_console.as_mut() {
+            if !letterboxing {
+                swap(api.con(), console);
+            }
+        } else {
+            bevy_utils::tracing::error!(
+                "stopping bevy_doryen: the root console was taken out of the RootConsole resource"
+            );
+            self.swap_console = Some(Console::new(1, 1));
+            self.exiting = true;
+        }
     }
 
     #[inline]
-    fn take_doryen_render_schedule(&mut self) -> Schedule {
-        let mut doryen_render_systems = self
-            .bevy_app
-            .world
-            .get_resource_mut::<DoryenRenderSystems>()
-            .unwrap();
-        doryen_render_systems.0.take().unwrap()
+    fn letterboxing(&self) -> bool {
+        matches!(self.resize_mode, ResizeMode::Letterbox)
     }
 
     #[inline]
-    fn restore_doryen_render_schedule(&mut self, doryen_render_schedule: Schedule) {
-        let mut doryen_render_systems = self
-            .bevy_app
-            .world
-            .get_resource_mut::<DoryenRenderSystems>()
-            .unwrap();
-        doryen_render_systems.0.replace(doryen_render_schedule);
+    fn begin_frame(&mut self, api: &mut dyn DoryenApi) -> bool {
+        let window_console_size = api.con().get_size();
+        web::set_console_size(window_console_size);
+        let screen_size = api.get_screen_size();
+        if !self.letterboxing() {
+            return self.frame_loop.begin_frame(
+                &mut self.bevy_app.world,
+                screen_size,
+                window_console_size,
+                &self.mouse_button_listeners,
+                api.input(),
+            );
+        }
+
+        // The game only sees the letterboxed console, so screen and mouse
+        // positions go by the part of the window it takes up
+        let console_size = self.swap_console.as_ref().map_or((0, 0), Console::get_size);
+        self.frame_loop.begin_frame(
+            &mut self.bevy_app.world,
+            letterbox::screen_size(screen_size, window_console_size, console_size),
+            console_size,
+            &self.mouse_button_listeners,
+            &mut LetterboxedInput {
+                source: api.input(),
+                offset: letterbox::offset(window_console_size, console_size),
+            },
+        )
     }
 
+    /// Copies the letterboxed root console into the middle of the window
+    /// console Doryen draws.
     #[inline]
-    fn handle_input(&mut self, api: &mut dyn DoryenApi) {
-        let mut doryen_input = self.bevy_app.world.get_resource_mut::<Input>().unwrap();
-        let input = api.input();
-        doryen_input.handle_input(&self.mouse_button_listeners, input);
+    fn draw_letterbox(&self, api: &mut dyn DoryenApi) {
+        if let (true, Some(console)) = (self.letterboxing(), self.swap_console.as_ref()) {
+            letterbox::draw(console, api.con());
+        }
     }
 }
 
 impl Engine for DoryenPluginEngine {
     fn update(&mut self, api: &mut dyn DoryenApi) -> Option<UpdateEvent> {
-        let mut doryen_fps_info = self.bevy_app.world.get_resource_mut::<FpsInfo>().unwrap();
+        if self.exiting || !self.check_world() {
+            return Some(UpdateEvent::Exit);
+        }
+
+        let mut doryen_fps_info = self.bevy_app.world.resource_mut::<FpsInfo>();
         doryen_fps_info.fps = api.fps();
         doryen_fps_info.average_fps = api.average_fps();
-        drop(doryen_fps_info);
 
-        self.handle_input(api);
+        if !self.begin_frame(api) {
+            return None;
+        }
+
+        // Doryen takes the capture requested by the previous update between
+        // that update and this one
+        if let Some(path) = self.capture_in_progress.take() {
+            let mut screen_captured_events =
+                self.bevy_app.world.resource_mut::<Events<ScreenCaptured>>();
+            screen_captured_events.send(ScreenCaptured(path));
+        }
 
         self.take_root_console_ownership(api);
         self.bevy_app.update();
         self.restore_root_console_ownership(api);
+        if !self.check_world() {
+            return None;
+        }
+        self.apply_runtime_settings();
 
         // Process the latest SetFontPath event
-        let doryen_set_font_path_events = self
-            .bevy_app
-            .world
-            .get_resource_mut::<Events<SetFontPath>>()
-            .unwrap();
-        if let Some(doryen_set_font_path) = self
+        let doryen_set_font_path_events = self.bevy_app.world.resource_mut::<Events<SetFontPath>>();
+        if let Some(font_path) = self
             .set_font_path_event_reader
             .iter(&doryen_set_font_path_events)
             .last()
+            .map(|doryen_set_font_path| doryen_set_font_path.0.clone())
         {
-            api.set_font_path(doryen_set_font_path.0.as_ref());
+            api.set_font_path(font_path.as_ref());
+
+            let mut current_font = self.bevy_app.world.resource_mut::<CurrentFont>();
+            let previous = std::mem::replace(&mut current_font.0, font_path.clone());
+            if previous != font_path {
+                let mut font_changed_events =
+                    self.bevy_app.world.resource_mut::<Events<FontChanged>>();
+                font_changed_events.send(FontChanged {
+                    previous,
+                    current: font_path,
+                });
+            }
         }
 
-        if let Some(app_exit_events) = self.bevy_app.world.get_resource_mut::<Events<AppExit>>() {
-            if self
-                .app_exit_event_reader
-                .iter(&app_exit_events)
-                .last()
-                .is_some()
-            {
-                return Some(UpdateEvent::Exit);
-            }
+        // Queue up the CaptureScreen events
+        let capture_screen_events = self.bevy_app.world.resource::<Events<CaptureScreen>>();
+        self.pending_captures.extend(
+            self.capture_screen_event_reader
+                .iter(capture_screen_events)
+                .map(|capture_screen| capture_screen.0.clone()),
+        );
+
+        if self.frame_loop.exit_requested(&self.bevy_app.world) {
+            // Give the shutdown schedule its final run and a frame to show
+            // what it drew, then exit on the next update
+            self.take_root_console_ownership(api);
+            frame_loop::shut_down(&mut self.bevy_app.world);
+            self.restore_root_console_ownership(api);
+            self.draw_letterbox(api);
+            self.exiting = true;
+            return None;
+        }
+
+        // Doryen handles a single capture per update
+        if let Some(path) = self.pending_captures.pop_front() {
+            let capture = UpdateEvent::Capture(path.to_string());
+            self.capture_in_progress = Some(path);
+            return Some(capture);
         }
 
         None
     }
 
     fn render(&mut self, api: &mut dyn DoryenApi) {
-        self.take_root_console_ownership(api);
-
-        let wc = self.bevy_app.world.cell();
-        let mut rs = wc.get_resource_mut::<RenderState>().unwrap();
-        if rs.0 {
-            for f in &rs.1 {
-                f(&wc);
-            }
-            rs.0 = false;
+        if self.exiting || !self.check_world() {
+            return;
         }
-        drop(rs);
-        drop(wc);
 
-        let mut doryen_render_schedule = self.take_doryen_render_schedule();
-        doryen_render_schedule.run(&mut self.bevy_app.world);
-        self.restore_doryen_render_schedule(doryen_render_schedule);
+        if !self
+            .frame_loop
+            .should_render(&self.bevy_app.world, api.fps())
+        {
+            return;
+        }
 
+        self.take_root_console_ownership(api);
+        self.bevy_app.world.run_schedule(DoryenRender);
         self.restore_root_console_ownership(api);
+        self.draw_letterbox(api);
     }
 
     fn resize(&mut self, api: &mut dyn DoryenApi) {
+        if self.exiting || !self.check_world() {
+            return;
+        }
+
         let (previous_width, previous_height) = self.previous_screen_size;
         let (new_width, new_height) = api.get_screen_size();
-
-        let mut resized_events = self
-            .bevy_app
-            .world
-            .get_resource_mut::<Events<Resized>>()
-            .unwrap();
         let resized = Resized {
             previous_width,
             previous_height,
             new_width,
             new_height,
         };
-        resized_events.send(resized);
-        drop(resized_events);
 
-        match self.resize_mode {
-            ResizeMode::Nothing => (),
-            ResizeMode::Automatic => {
-                let (previous_console_width, previous_console_height) = self.previous_console_size;
-
-                let w_ratio = previous_width / previous_console_width;
-                let h_ratio = previous_height / previous_console_height;
+        self.take_root_console_ownership(api);
+        resize_root_console(
+            &mut self.bevy_app.world,
+            self.resize_mode,
+            resized,
+            self.previous_console_size,
+        );
+        self.restore_root_console_ownership(api);
 
-                let new_console_width = new_width / w_ratio;
-                let new_console_height = new_height / h_ratio;
-                api.con().resize(new_console_width, new_console_height);
-            }
-            ResizeMode::Callback(callback) => {
-                self.take_root_console_ownership(api);
-                callback(
-                    &mut *self.bevy_app.world.get_resource_mut().unwrap(),
-                    resized,
-                );
-                self.restore_root_console_ownership(api);
-            }
+        if let (true, Some(console)) = (self.letterboxing(), self.swap_console.as_ref()) {
+            // Keep the cells the size they had, which is how many pixels the
+            // window console had per cell before
+            let char_size = (
+                previous_width / self.previous_console_size.0.max(1),
+                previous_height / self.previous_console_size.1.max(1),
+            );
+            let (width, height) = letterbox::window_console_size(
+                (new_width, new_height),
+                char_size,
+                console.get_size(),
+            );
+            api.con().resize(width, height);
+            self.draw_letterbox(api);
         }
 
         self.previous_screen_size = (new_width, new_height);
@@ -368,7 +669,94 @@ impl Engine for DoryenPluginEngine {
     }
 }
 
+/// Refreshes the [`ScreenInfo`] resource from the size of the window, in
+/// pixels, and of the root console, in cells, and sends the events announcing
+/// what changed.
+pub(crate) fn refresh_screen_info(
+    world: &mut World,
+    (screen_width, screen_height): (u32, u32),
+    (console_width, console_height): (u32, u32),
+) {
+    let new_screen_info = ScreenInfo {
+        screen_width,
+        screen_height,
+        console_width,
+        console_height,
+        char_width: screen_width / console_width.max(1),
+        char_height: screen_height / console_height.max(1),
+    };
+
+    let mut screen_info = world.resource_mut::<ScreenInfo>();
+    if *screen_info == new_screen_info {
+        return;
+    }
+    let char_size_changed = screen_info.char_size() != new_screen_info.char_size();
+    let console_resized = screen_info.console_width != 0
+        && (screen_info.console_width, screen_info.console_height)
+            != (console_width, console_height);
+    let previous = std::mem::replace(&mut *screen_info, new_screen_info);
+
+    let mut screen_info_changed_events = world.resource_mut::<Events<ScreenInfoChanged>>();
+    screen_info_changed_events.send(ScreenInfoChanged {
+        previous,
+        current: new_screen_info,
+    });
+
+    if console_resized {
+        let mut console_resized_events = world.resource_mut::<Events<ConsoleResized>>();
+        console_resized_events.send(ConsoleResized {
+            width: console_width,
+            height: console_height,
+        });
+    }
+
+    if char_size_changed {
+        let mut char_size_changed_events = world.resource_mut::<Events<CharSizeChanged>>();
+        char_size_changed_events.send(CharSizeChanged {
+            width: new_screen_info.char_width,
+            height: new_screen_info.char_height,
+        });
+    }
+}
+
+/// Sends the [`Resized`] event for a resize of the window and resizes the root
+/// console, which has to be in the [`RootConsole`] resource, as `resize_mode`
+/// says.
+pub(crate) fn resize_root_console(
+    world: &mut World,
+    resize_mode: ResizeMode,
+    resized: Resized,
+    (previous_console_width, previous_console_height): (u32, u32),
+) {
+    let mut resized_events = world.resource_mut::<Events<Resized>>();
+    resized_events.send(resized);
+
+    match resize_mode {
+        // A letterboxed console keeps its size, only the window console Doryen
+        // draws is resized around it
+        ResizeMode::Nothing | ResizeMode::Letterbox => (),
+        ResizeMode::Automatic => {
+            let w_ratio = (resized.previous_width / previous_console_width.max(1)).max(1);
+            let h_ratio = (resized.previous_height / previous_console_height.max(1)).max(1);
+
+            let new_console_width = resized.new_width / w_ratio;
+            let new_console_height = resized.new_height / h_ratio;
+            world
+                .resource_mut::<RootConsole>()
+                .resize(new_console_width, new_console_height);
+        }
+        ResizeMode::Callback(callback) => {
+            callback(&mut world.resource_mut::<RootConsole>(), resized);
+        }
+    }
+}
+
 fn doryen_runner(mut app: BevyApp) {
+    if let Err(e) = error::validate_world(&app.world) {
+        bevy_utils::tracing::error!("can't start bevy_doryen: {}", e);
+        return;
+    }
+
     let mut resource_settings = app
         .world
         .get_resource_or_insert_with(DoryenPluginSettings::default);
@@ -376,8 +764,8 @@ fn doryen_runner(mut app: BevyApp) {
         app_options,
         mouse_button_listeners,
         resize_mode,
+        web_options,
     } = std::mem::take(&mut *resource_settings);
-    drop(resource_settings);
 
     let AppOptions {
         screen_height,
@@ -387,17 +775,32 @@ fn doryen_runner(mut app: BevyApp) {
         ..
     } = app_options;
 
+    let runtime_settings = DoryenRuntimeSettings::from_app_options(&app_options);
+    app.insert_resource(CurrentFont(Cow::Owned(app_options.font_path.clone())))
+        .insert_resource(runtime_settings.clone());
+
+    let font_path = app_options.font_path.clone();
     let mut doryen_app = DoryenApp::new(app_options);
+    web::configure_canvas(&web_options, &font_path);
 
     doryen_app.set_engine(Box::new(DoryenPluginEngine {
         bevy_app: app,
-        app_exit_event_reader: ManualEventReader::default(),
+        frame_loop: FrameLoop::default(),
         set_font_path_event_reader: ManualEventReader::default(),
-        swap_console: Some(Console::new(1, 1)),
+        capture_screen_event_reader: ManualEventReader::default(),
+        pending_captures: VecDeque::new(),
+        capture_in_progress: None,
+        swap_console: Some(match resize_mode {
+            ResizeMode::Letterbox => Console::new(console_width, console_height),
+            _ => Console::new(1, 1),
+        }),
         mouse_button_listeners,
         previous_screen_size: (screen_width, screen_height),
         previous_console_size: (console_width, console_height),
         resize_mode,
+        exiting: false,
+        runtime_settings,
+        web_options,
     }));
 
     doryen_app.run();
@@ -405,7 +808,7 @@ fn doryen_runner(mut app: BevyApp) {
 
 /// This resource contains the values given by [`fps`](DoryenApi::fps) and
 /// [`average_fps`](DoryenApi::average_fps) on the current update tick.
-#[derive(Default, Debug, Clone, Copy)]
+#[derive(Default, Debug, Clone, Copy, Resource)]
 pub struct FpsInfo {
     /// The value given by [`fps`](DoryenApi::fps) on the current update tick.
     pub fps: u32,
@@ -414,15 +817,136 @@ pub struct FpsInfo {
     pub average_fps: u32,
 }
 
+/// This resource describes the size of the Doryen game window and its root
+/// console, as well as the resulting size of a single console cell, in pixels.
+/// It is refreshed at the start of every update tick.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Resource)]
+pub struct ScreenInfo {
+    /// The width of the Doryen game window, in pixels.
+    pub screen_width: u32,
+    /// The height of the Doryen game window, in pixels.
+    pub screen_height: u32,
+    /// The width of the root console, in cells.
+    pub console_width: u32,
+    /// The height of the root console, in cells.
+    pub console_height: u32,
+    /// The width of a single console cell, in pixels.
+    pub char_width: u32,
+    /// The height of a single console cell, in pixels.
+    pub char_height: u32,
+}
+
+impl ScreenInfo {
+    /// The size of a single console cell, in pixels, as a `(width, height)`
+    /// tuple.
+    #[inline]
+    pub fn char_size(&self) -> (u32, u32) {
+        (self.char_width, self.char_height)
+    }
+
+    /// Converts a position in console cells, like
+    /// [`Input::mouse_pos`], to pixels from the top-left corner of the
+    /// window.
+    pub fn cells_to_pixels(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        (x * self.char_width as f32, y * self.char_height as f32)
+    }
+
+    /// Converts a position in pixels from the top-left corner of the window
+    /// to console cells. The fractional part is where in the cell the
+    /// position is. Before the first update, when the cell size isn't known
+    /// yet, this is `(0.0, 0.0)`.
+    pub fn pixels_to_cells(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        let to_cells = |pixels: f32, char_size: u32| {
+            if char_size == 0 {
+                0.0
+            } else {
+                pixels / char_size as f32
+            }
+        };
+        (to_cells(x, self.char_width), to_cells(y, self.char_height))
+    }
+}
+
+/// Whenever the pixel size of a console cell changes, e.g. because the font,
+/// the window size or the console size changed, an event of this type is
+/// emitted. An event is also emitted on the very first update tick, when
+/// [`ScreenInfo`] is populated for the first time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Event)]
+pub struct CharSizeChanged {
+    /// The new width of a single console cell, in pixels.
+    pub width: u32,
+    /// The new height of a single console cell, in pixels.
+    pub height: u32,
+}
+
+/// Emitted whenever [`ScreenInfo`] changes, right after it has been updated at
+/// the start of an update tick, including the first time it's populated.
+/// Caches that depend on the window, console or cell size can listen to this
+/// instead of checking whether the resource changed.
+///
+/// The more specific [`ConsoleResized`] and [`CharSizeChanged`] events are
+/// sent alongside this one when they apply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Event)]
+pub struct ScreenInfoChanged {
+    /// The screen info before the change.
+    pub previous: ScreenInfo,
+    /// The screen info after the change.
+    pub current: ScreenInfo,
+}
+
 /// When you want to change Doryen's font path, emit an event of this type.
 /// bevy_doryen will call [`set_font_path`](DoryenApi::set_font_path) with the
 /// provided value.
-#[derive(Debug, Clone)]
+///
+/// A settings menu can use this to switch fonts at runtime; the
+/// [`CurrentFont`] resource tells it which font is in use. Once the new font
+/// has loaded, a change in glyph size shows up in [`ScreenInfo`] and as a
+/// [`CharSizeChanged`] event.
+#[derive(Debug, Clone, Event)]
 pub struct SetFontPath(pub Cow<'static, str>);
 
+/// This resource holds the path of the font Doryen is using. It starts out as
+/// the [`font_path`](AppOptions::font_path) of the app options, and is
+/// updated whenever a [`SetFontPath`] event is processed.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Resource)]
+pub struct CurrentFont(pub(crate) Cow<'static, str>);
+
+impl CurrentFont {
+    /// The path of the font Doryen is using.
+    pub fn path(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Emitted when a [`SetFontPath`] event switches Doryen to a different font.
+#[derive(Debug, Clone, Event)]
+pub struct FontChanged {
+    /// The path of the font that was in use before.
+    pub previous: Cow<'static, str>,
+    /// The path of the font now in use.
+    pub current: Cow<'static, str>,
+}
+
+/// When you want to save a screenshot of the Doryen window, emit an event of
+/// this type with the path of the PNG file to write. bevy_doryen hands the
+/// request to Doryen through [`UpdateEvent::Capture`], and emits a
+/// [`ScreenCaptured`] event once the screenshot has been taken.
+///
+/// Doryen takes at most one screenshot per update, so several requests made
+/// during the same update are handled over the following updates, in order.
+/// Only the Doryen window runner supports captures.
+#[derive(Debug, Clone, Event)]
+pub struct CaptureScreen(pub Cow<'static, str>);
+
+/// Emitted on the update after a screenshot requested through
+/// [`CaptureScreen`] has been taken, with the path it was written to. Doryen
+/// doesn't report whether writing the file succeeded.
+#[derive(Debug, Clone, Event)]
+pub struct ScreenCaptured(pub Cow<'static, str>);
+
 /// Resized event object. Whenever Doryen's [`resize`](Engine::resize) method is
 /// called, an event of this type is emitted.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Event)]
 pub struct Resized {
     /// The previous width of the Doryen game window.
     pub previous_width: u32,
@@ -434,15 +958,47 @@ pub struct Resized {
     pub new_height: u32,
 }
 
+/// Emitted at the start of the first update after the size of the root console
+/// has changed, whether because of the [`ResizeMode`] reacting to the window
+/// being [`Resized`] or because a system resized the console itself. Systems
+/// can use it to lay out their UI again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Event)]
+pub struct ConsoleResized {
+    /// The new width of the root console, in cells.
+    pub width: u32,
+    /// The new height of the root console, in cells.
+    pub height: u32,
+}
+
 /// How the [`DoryenPlugin`] reacts to the resize event from Doryen.
+///
+/// Whichever mode is used, the resulting console size is reflected in
+/// [`ScreenInfo`] and announced with a [`ConsoleResized`] event.
 #[derive(Clone, Copy)]
 pub enum ResizeMode {
     /// Do nothing when the window is resized. This is the default behavior.
+    ///
+    /// The root console keeps its size in cells, and Doryen stretches it over
+    /// the whole window, so the cells grow and shrink with the window.
     Nothing,
     /// Set the console size to match the window size automatically. This
     /// retains the ratio defined between the console size and the screen size
     /// as given in the [`AppOptions`] at the start of the program.
+    ///
+    /// The cells keep their size in pixels, and the root console gains or
+    /// loses cells as the window grows or shrinks.
     Automatic,
+    /// Keep both the console size in cells and the cell size in pixels, and
+    /// center the root console in the window with black borders around it.
+    /// If the window gets smaller than the console, the cells shrink so it
+    /// still fits.
+    ///
+    /// Doryen always stretches its console over the whole window, so the root
+    /// console is copied into a window-sized console after every render.
+    /// [`ScreenInfo`] and the mouse position go by the root console rather
+    /// than the window. The headless and terminal runners leave the root
+    /// console as it is, like [`Nothing`](Self::Nothing).
+    Letterbox,
     /// Call the given function when the resize event is triggered. Because
     /// Doryen is sensitive to when the root console is resized, the safest
     /// place to make a call to do so and always have the correct behavior is
@@ -458,6 +1014,7 @@ impl std::fmt::Debug for ResizeMode {
         match self {
             Self::Nothing => f.write_str("Nothing"),
             Self::Automatic => f.write_str("Automatic"),
+            Self::Letterbox => f.write_str("Letterbox"),
             Self::Callback(_) => f.write_str("Callback"),
         }
     }
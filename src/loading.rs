@@ -0,0 +1,55 @@
+use crate::web;
+use bevy_ecs::event::{Event, EventWriter};
+use bevy_ecs::system::{ResMut, Resource};
+
+/// Resource telling how far along loading the font is. Only the browser
+/// loads it in the background; everywhere else it's loaded before the first
+/// update, and this starts out ready.
+///
+/// While the font downloads, the browser shows a loading screen over the
+/// canvas, unless [`WebOptions::loading_screen`](crate::WebOptions) is
+/// turned off. Once it's done, an [`AssetsReady`] event is sent.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Resource)]
+pub struct LoadingProgress {
+    fraction: Option<f32>,
+    ready: bool,
+}
+
+impl LoadingProgress {
+    /// How much has been loaded, from `0.0` to `1.0`, or `None` while the
+    /// size of what's left to load isn't known yet.
+    pub fn fraction(&self) -> Option<f32> {
+        self.fraction
+    }
+
+    /// Whether loading is done.
+    pub fn is_ready(&self) -> bool {
+        self.ready
+    }
+}
+
+/// Emitted at the start of the first update after loading is done, see
+/// [`LoadingProgress`]. Games that start out in a loading state can move on
+/// to their first real state when this arrives.
+///
+/// If the font fails to load, this is still sent, since there's nothing more
+/// to wait for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Event)]
+pub struct AssetsReady;
+
+pub(crate) fn track_loading(
+    mut progress: ResMut<'_, LoadingProgress>,
+    mut assets_ready: EventWriter<'_, AssetsReady>,
+) {
+    if progress.ready {
+        return;
+    }
+    let (fraction, ready) = web::loading_progress();
+    if progress.fraction != fraction {
+        progress.fraction = fraction;
+    }
+    if ready {
+        progress.ready = true;
+        assets_ready.send(AssetsReady);
+    }
+}
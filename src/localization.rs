@@ -0,0 +1,237 @@
+//! Translated text for printing on consoles.
+//!
+//! Translations are plain key-value tables, one per language, kept in the
+//! [`Localization`] resource. They can be added from code or parsed from a
+//! simple text format:
+//!
+//! ```text
+//! # Lines starting with # are comments
+//! menu.new_game = New game
+//! status.hp = HP: {current}/{max}
+//! ```
+//!
+//! Text is looked up with [`Localization::get`], or with the [`tr!`] macro
+//! when it has placeholders to fill in:
+//!
+//! ```
+//! # use bevy_doryen::localization::Localization;
+//! # use bevy_doryen::tr;
+//! let mut localization = Localization::new("en");
+//! localization.parse_language("en", "status.hp = HP: {current}/{max}");
+//! assert_eq!(tr!(localization, "status.hp", current = 7, max = 10), "HP: 7/10");
+//! ```
+//!
+//! Switching languages with [`Localization::set_language`] sends a
+//! [`LanguageChanged`] event, so systems that only redraw text on demand know
+//! to do it again.
+//!
+//! [`tr!`]: crate::tr
+
+use bevy_app::{App as BevyApp, First, Plugin};
+use bevy_ecs::change_detection::DetectChanges;
+use bevy_ecs::event::{Event, EventWriter};
+use bevy_ecs::system::{Local, Res, Resource};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// The localization plugin. Adds the [`Localization`] resource, unless one
+/// has already been inserted, and the [`LanguageChanged`] event.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct LocalizationPlugin;
+
+impl Plugin for LocalizationPlugin {
+    fn build(&self, app: &mut BevyApp) {
+        app.init_resource::<Localization>()
+            .add_event::<LanguageChanged>()
+            .add_systems(First, detect_language_change);
+    }
+}
+
+/// Emitted at the start of the update after the language of the
+/// [`Localization`] resource changed.
+#[derive(Debug, Clone, PartialEq, Eq, Event)]
+pub struct LanguageChanged {
+    /// The language now in use.
+    pub language: String,
+}
+
+/// Resource holding the translations for every language, and which language
+/// is in use. See the [module documentation](self).
+#[derive(Debug, Clone, Resource)]
+pub struct Localization {
+    languages: HashMap<String, HashMap<String, String>>,
+    language: String,
+    fallback: Option<String>,
+}
+
+impl Default for Localization {
+    fn default() -> Self {
+        Self::new("en")
+    }
+}
+
+impl Localization {
+    /// Creates a localization with no translations, using the given language.
+    pub fn new(language: &str) -> Self {
+        Self {
+            languages: HashMap::new(),
+            language: String::from(language),
+            fallback: None,
+        }
+    }
+
+    /// The language in use.
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Switches to another language.
+    pub fn set_language(&mut self, language: &str) {
+        if self.language != language {
+            self.language = String::from(language);
+        }
+    }
+
+    /// Sets the language to look keys up in when the current language doesn't
+    /// have them.
+    pub fn set_fallback(&mut self, language: Option<&str>) {
+        self.fallback = language.map(String::from);
+    }
+
+    /// The languages that have translations, in no particular order.
+    pub fn languages(&self) -> impl Iterator<Item = &str> {
+        self.languages.keys().map(String::as_str)
+    }
+
+    /// Adds translations to a language, replacing existing ones with the same
+    /// keys.
+    pub fn add_language<K, V>(&mut self, language: &str, entries: impl IntoIterator<Item = (K, V)>)
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.languages
+            .entry(String::from(language))
+            .or_default()
+            .extend(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.into(), value.into())),
+            );
+    }
+
+    /// Adds translations to a language from text with one `key = value` entry
+    /// per line. Blank lines and lines starting with `#` are skipped, as are
+    /// lines without a `=`. In values, `\n` stands for a line break.
+    pub fn parse_language(&mut self, language: &str, source: &str) {
+        let entries = source
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let (key, value) = line.split_at(line.find('=')?);
+                Some((key.trim(), value[1..].trim().replace("\\n", "\n")))
+            });
+        self.add_language(language, entries);
+    }
+
+    /// Looks up the text for `key` in the current language, then in the
+    /// fallback language. If neither has it, the key itself is returned, so
+    /// missing translations are easy to spot.
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.lookup(key).unwrap_or(key)
+    }
+
+    /// Whether the current or fallback language has text for `key`.
+    pub fn contains(&self, key: &str) -> bool {
+        self.lookup(key).is_some()
+    }
+
+    fn lookup(&self, key: &str) -> Option<&str> {
+        let find = |language: &str| {
+            self.languages
+                .get(language)
+                .and_then(|entries| entries.get(key))
+                .map(String::as_str)
+        };
+        find(&self.language).or_else(|| self.fallback.as_deref().and_then(find))
+    }
+
+    /// Looks up the text for `key` like [`get`](Self::get) does, and replaces
+    /// every `{name}` placeholder in it with the matching argument.
+    /// Placeholders without an argument are left as they are.
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let text = self.get(key);
+        if args.is_empty() {
+            return String::from(text);
+        }
+
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find('{') {
+            result.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let end = match rest.find('}') {
+                Some(end) => end,
+                None => break,
+            };
+            let name = &rest[1..end];
+            match args.iter().find(|(arg, _)| *arg == name) {
+                Some((_, value)) => result.push_str(&value.to_string()),
+                None => result.push_str(&rest[..=end]),
+            }
+            rest = &rest[end + 1..];
+        }
+        result.push_str(rest);
+        result
+    }
+
+    /// Looks up the text for `key`. Like [`get`](Self::get), but returns a
+    /// [`Cow`] so it can be stored alongside owned strings.
+    pub fn get_cow(&self, key: &str) -> Cow<'_, str> {
+        match self.lookup(key) {
+            Some(text) => Cow::Borrowed(text),
+            None => Cow::Owned(String::from(key)),
+        }
+    }
+}
+
+/// Looks up translated text in a [`Localization`].
+///
+/// `tr!(localization, "key")` returns the text for the key as a `String`.
+/// Placeholders are filled in from named arguments:
+/// `tr!(localization, "key", name = value)`, where each value implements
+/// [`Display`].
+///
+/// [`Localization`]: crate::localization::Localization
+/// [`Display`]: std::fmt::Display
+#[macro_export]
+macro_rules! tr {
+    ($localization:expr, $key:expr $(,)?) => {
+        ::std::string::String::from($localization.get($key))
+    };
+    ($localization:expr, $key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $localization.format(
+            $key,
+            &[$((::std::stringify!($name), &$value as &dyn ::std::fmt::Display)),+],
+        )
+    };
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn detect_language_change(
+    localization: Res<'_, Localization>,
+    mut previous: Local<'_, Option<String>>,
+    mut language_changed: EventWriter<'_, LanguageChanged>,
+) {
+    if !localization.is_changed() || previous.as_deref() == Some(localization.language()) {
+        return;
+    }
+    if previous.is_some() {
+        language_changed.send(LanguageChanged {
+            language: String::from(localization.language()),
+        });
+    }
+    *previous = Some(String::from(localization.language()));
+}
@@ -0,0 +1,378 @@
+//! Map generation utilities.
+
+use crate::doryen::{color_blend, Color};
+
+/// The kinds of noise a [`Noise`] generator can produce.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NoiseKind {
+    /// Smoothly interpolated random values on an integer lattice. Cheap, but
+    /// blocky.
+    Value,
+    /// Classic Perlin gradient noise.
+    Perlin,
+    /// Simplex noise. Fewer directional artifacts than Perlin noise.
+    Simplex,
+}
+
+const GRAD3: [(f32, f32, f32); 12] = [
+    (1.0, 1.0, 0.0),
+    (-1.0, 1.0, 0.0),
+    (1.0, -1.0, 0.0),
+    (-1.0, -1.0, 0.0),
+    (1.0, 0.0, 1.0),
+    (-1.0, 0.0, 1.0),
+    (1.0, 0.0, -1.0),
+    (-1.0, 0.0, -1.0),
+    (0.0, 1.0, 1.0),
+    (0.0, -1.0, 1.0),
+    (0.0, 1.0, -1.0),
+    (0.0, -1.0, -1.0),
+];
+
+/// A seedable noise generator. All the noise functions return values in the
+/// range `-1.0..=1.0`, and the same seed always produces the same noise.
+#[derive(Clone)]
+pub struct Noise {
+    seed: u64,
+    perm: [usize; 512],
+}
+
+impl std::fmt::Debug for Noise {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Noise").field("seed", &self.seed).finish()
+    }
+}
+
+impl Noise {
+    /// Creates a noise generator from the given seed.
+    pub fn new(seed: u64) -> Self {
+        let mut state = seed;
+        let mut next = move || {
+            // splitmix64
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^ (z >> 31)
+        };
+
+        let mut p = [0; 256];
+        for (i, v) in p.iter_mut().enumerate() {
+            *v = i;
+        }
+        for i in (1..256).rev() {
+            let j = (next() % (i as u64 + 1)) as usize;
+            p.swap(i, j);
+        }
+
+        let mut perm = [0; 512];
+        for (i, v) in perm.iter_mut().enumerate() {
+            *v = p[i & 255];
+        }
+        Self { seed, perm }
+    }
+
+    /// The seed this generator was created from.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Samples 2D noise of the given kind.
+    pub fn sample2(&self, kind: NoiseKind, x: f32, y: f32) -> f32 {
+        match kind {
+            NoiseKind::Value => self.value2(x, y),
+            NoiseKind::Perlin => self.perlin2(x, y),
+            NoiseKind::Simplex => self.simplex2(x, y),
+        }
+    }
+
+    /// Samples 3D noise of the given kind.
+    pub fn sample3(&self, kind: NoiseKind, x: f32, y: f32, z: f32) -> f32 {
+        match kind {
+            NoiseKind::Value => self.value3(x, y, z),
+            NoiseKind::Perlin => self.perlin3(x, y, z),
+            NoiseKind::Simplex => self.simplex3(x, y, z),
+        }
+    }
+
+    /// Samples 2D fractal noise by summing `octaves` layers of noise of the
+    /// given kind, each at twice the frequency and half the amplitude of the
+    /// previous one.
+    pub fn fbm2(&self, kind: NoiseKind, x: f32, y: f32, octaves: u32) -> f32 {
+        let mut sum = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+        let mut max = 0.0;
+        for _ in 0..octaves.max(1) {
+            sum += self.sample2(kind, x * frequency, y * frequency) * amplitude;
+            max += amplitude;
+            amplitude *= 0.5;
+            frequency *= 2.0;
+        }
+        sum / max
+    }
+
+    /// Samples 3D fractal noise. See [`fbm2`](Self::fbm2).
+    pub fn fbm3(&self, kind: NoiseKind, x: f32, y: f32, z: f32, octaves: u32) -> f32 {
+        let mut sum = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+        let mut max = 0.0;
+        for _ in 0..octaves.max(1) {
+            sum += self.sample3(kind, x * frequency, y * frequency, z * frequency) * amplitude;
+            max += amplitude;
+            amplitude *= 0.5;
+            frequency *= 2.0;
+        }
+        sum / max
+    }
+
+    #[inline]
+    fn hash2(&self, x: i32, y: i32) -> usize {
+        self.perm[(x & 255) as usize + self.perm[(y & 255) as usize]]
+    }
+
+    #[inline]
+    fn hash3(&self, x: i32, y: i32, z: i32) -> usize {
+        self.perm
+            [(x & 255) as usize + self.perm[(y & 255) as usize + self.perm[(z & 255) as usize]]]
+    }
+
+    #[inline]
+    fn lattice_value(hash: usize) -> f32 {
+        hash as f32 / 127.5 - 1.0
+    }
+
+    /// Samples 2D value noise.
+    pub fn value2(&self, x: f32, y: f32) -> f32 {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (xi, yi) = (x0 as i32, y0 as i32);
+        let (u, v) = (smooth(x - x0), smooth(y - y0));
+
+        let a = Self::lattice_value(self.hash2(xi, yi));
+        let b = Self::lattice_value(self.hash2(xi + 1, yi));
+        let c = Self::lattice_value(self.hash2(xi, yi + 1));
+        let d = Self::lattice_value(self.hash2(xi + 1, yi + 1));
+        lerp(lerp(a, b, u), lerp(c, d, u), v)
+    }
+
+    /// Samples 3D value noise.
+    pub fn value3(&self, x: f32, y: f32, z: f32) -> f32 {
+        let (x0, y0, z0) = (x.floor(), y.floor(), z.floor());
+        let (xi, yi, zi) = (x0 as i32, y0 as i32, z0 as i32);
+        let (u, v, w) = (smooth(x - x0), smooth(y - y0), smooth(z - z0));
+
+        let corner = |dx, dy, dz| Self::lattice_value(self.hash3(xi + dx, yi + dy, zi + dz));
+        let front = lerp(
+            lerp(corner(0, 0, 0), corner(1, 0, 0), u),
+            lerp(corner(0, 1, 0), corner(1, 1, 0), u),
+            v,
+        );
+        let back = lerp(
+            lerp(corner(0, 0, 1), corner(1, 0, 1), u),
+            lerp(corner(0, 1, 1), corner(1, 1, 1), u),
+            v,
+        );
+        lerp(front, back, w)
+    }
+
+    /// Samples 2D Perlin noise.
+    pub fn perlin2(&self, x: f32, y: f32) -> f32 {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (xi, yi) = (x0 as i32, y0 as i32);
+        let (xf, yf) = (x - x0, y - y0);
+        let (u, v) = (fade(xf), fade(yf));
+
+        let grad = |hash: usize, dx: f32, dy: f32| match hash & 7 {
+            0 => dx + dy,
+            1 => -dx + dy,
+            2 => dx - dy,
+            3 => -dx - dy,
+            4 => dx,
+            5 => -dx,
+            6 => dy,
+            _ => -dy,
+        };
+        let n00 = grad(self.hash2(xi, yi), xf, yf);
+        let n10 = grad(self.hash2(xi + 1, yi), xf - 1.0, yf);
+        let n01 = grad(self.hash2(xi, yi + 1), xf, yf - 1.0);
+        let n11 = grad(self.hash2(xi + 1, yi + 1), xf - 1.0, yf - 1.0);
+        (lerp(lerp(n00, n10, u), lerp(n01, n11, u), v)).clamp(-1.0, 1.0)
+    }
+
+    /// Samples 3D Perlin noise.
+    pub fn perlin3(&self, x: f32, y: f32, z: f32) -> f32 {
+        let (x0, y0, z0) = (x.floor(), y.floor(), z.floor());
+        let (xi, yi, zi) = (x0 as i32, y0 as i32, z0 as i32);
+        let (xf, yf, zf) = (x - x0, y - y0, z - z0);
+        let (u, v, w) = (fade(xf), fade(yf), fade(zf));
+
+        let corner = |dx: i32, dy: i32, dz: i32| {
+            let (gx, gy, gz) = GRAD3[self.hash3(xi + dx, yi + dy, zi + dz) % 12];
+            gx * (xf - dx as f32) + gy * (yf - dy as f32) + gz * (zf - dz as f32)
+        };
+        let front = lerp(
+            lerp(corner(0, 0, 0), corner(1, 0, 0), u),
+            lerp(corner(0, 1, 0), corner(1, 1, 0), u),
+            v,
+        );
+        let back = lerp(
+            lerp(corner(0, 0, 1), corner(1, 0, 1), u),
+            lerp(corner(0, 1, 1), corner(1, 1, 1), u),
+            v,
+        );
+        lerp(front, back, w).clamp(-1.0, 1.0)
+    }
+
+    /// Samples 2D simplex noise.
+    pub fn simplex2(&self, x: f32, y: f32) -> f32 {
+        let f2 = 0.5 * (3.0_f32.sqrt() - 1.0);
+        let g2 = (3.0 - 3.0_f32.sqrt()) / 6.0;
+
+        let s = (x + y) * f2;
+        let (i, j) = ((x + s).floor(), (y + s).floor());
+        let t = (i + j) * g2;
+        let (x0, y0) = (x - (i - t), y - (j - t));
+        let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
+        let (x1, y1) = (x0 - i1 as f32 + g2, y0 - j1 as f32 + g2);
+        let (x2, y2) = (x0 - 1.0 + 2.0 * g2, y0 - 1.0 + 2.0 * g2);
+        let (i, j) = (i as i32, j as i32);
+
+        let corner = |hash: usize, dx: f32, dy: f32| {
+            let falloff = 0.5 - dx * dx - dy * dy;
+            if falloff < 0.0 {
+                0.0
+            } else {
+                let (gx, gy, _) = GRAD3[hash % 12];
+                falloff * falloff * falloff * falloff * (gx * dx + gy * dy)
+            }
+        };
+        let n0 = corner(self.hash2(i, j), x0, y0);
+        let n1 = corner(self.hash2(i + i1, j + j1), x1, y1);
+        let n2 = corner(self.hash2(i + 1, j + 1), x2, y2);
+        (70.0 * (n0 + n1 + n2)).clamp(-1.0, 1.0)
+    }
+
+    /// Samples 3D simplex noise.
+    pub fn simplex3(&self, x: f32, y: f32, z: f32) -> f32 {
+        let f3 = 1.0 / 3.0;
+        let g3 = 1.0 / 6.0;
+
+        let s = (x + y + z) * f3;
+        let (i, j, k) = ((x + s).floor(), (y + s).floor(), (z + s).floor());
+        let t = (i + j + k) * g3;
+        let (x0, y0, z0) = (x - (i - t), y - (j - t), z - (k - t));
+
+        let ((i1, j1, k1), (i2, j2, k2)) = if x0 >= y0 {
+            if y0 >= z0 {
+                ((1, 0, 0), (1, 1, 0))
+            } else if x0 >= z0 {
+                ((1, 0, 0), (1, 0, 1))
+            } else {
+                ((0, 0, 1), (1, 0, 1))
+            }
+        } else if y0 < z0 {
+            ((0, 0, 1), (0, 1, 1))
+        } else if x0 < z0 {
+            ((0, 1, 0), (0, 1, 1))
+        } else {
+            ((0, 1, 0), (1, 1, 0))
+        };
+
+        let offset = |coord: f32, d: i32, n: f32| coord - d as f32 + n * g3;
+        let p1 = (
+            offset(x0, i1, 1.0),
+            offset(y0, j1, 1.0),
+            offset(z0, k1, 1.0),
+        );
+        let p2 = (
+            offset(x0, i2, 2.0),
+            offset(y0, j2, 2.0),
+            offset(z0, k2, 2.0),
+        );
+        let p3 = (offset(x0, 1, 3.0), offset(y0, 1, 3.0), offset(z0, 1, 3.0));
+        let (i, j, k) = (i as i32, j as i32, k as i32);
+
+        let corner = |hash: usize, (dx, dy, dz): (f32, f32, f32)| {
+            let falloff = 0.6 - dx * dx - dy * dy - dz * dz;
+            if falloff < 0.0 {
+                0.0
+            } else {
+                let (gx, gy, gz) = GRAD3[hash % 12];
+                falloff * falloff * falloff * falloff * (gx * dx + gy * dy + gz * dz)
+            }
+        };
+        let n0 = corner(self.hash3(i, j, k), (x0, y0, z0));
+        let n1 = corner(self.hash3(i + i1, j + j1, k + k1), p1);
+        let n2 = corner(self.hash3(i + i2, j + j2, k + k2), p2);
+        let n3 = corner(self.hash3(i + 1, j + 1, k + 1), p3);
+        (32.0 * (n0 + n1 + n2 + n3)).clamp(-1.0, 1.0)
+    }
+}
+
+#[inline]
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+#[inline]
+fn smooth(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+#[inline]
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+/// Samples `sample` for every cell of a `width` by `height` map, row by row.
+/// `scale` is how many noise units a single cell spans, so smaller values
+/// give smoother maps.
+pub fn noise_map(
+    width: u32,
+    height: u32,
+    scale: f32,
+    mut sample: impl FnMut(f32, f32) -> f32,
+) -> Vec<f32> {
+    let mut map = Vec::with_capacity((width * height) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            map.push(sample(x as f32 * scale, y as f32 * scale));
+        }
+    }
+    map
+}
+
+/// Maps a noise value to a tile type. `thresholds` must be sorted by
+/// ascending limit; the first tile whose limit is above `value` is returned,
+/// or `fallback` if there is none.
+///
+/// ```
+/// # use bevy_doryen::mapgen::classify;
+/// let thresholds = [(-0.2, "water"), (0.0, "sand"), (0.6, "grass")];
+/// assert_eq!(classify(-0.5, &thresholds, "mountain"), "water");
+/// assert_eq!(classify(0.3, &thresholds, "mountain"), "grass");
+/// assert_eq!(classify(0.9, &thresholds, "mountain"), "mountain");
+/// ```
+pub fn classify<T: Clone>(value: f32, thresholds: &[(f32, T)], fallback: T) -> T {
+    thresholds
+        .iter()
+        .find(|(limit, _)| value < *limit)
+        .map_or(fallback, |(_, tile)| tile.clone())
+}
+
+/// Animates a color with noise, for effects like water shimmer. Blends from
+/// `base` towards `highlight` by an amount that varies smoothly over space and
+/// `time`. `strength` is the maximum blend amount, from `0.0` to `1.0`.
+pub fn shimmer(
+    noise: &Noise,
+    base: Color,
+    highlight: Color,
+    (x, y): (i32, i32),
+    time: f32,
+    strength: f32,
+) -> Color {
+    let n = noise.perlin3(x as f32 * 0.3, y as f32 * 0.3, time);
+    let amount = (n * 0.5 + 0.5) * strength;
+    color_blend(base, highlight, amount.clamp(0.0, 1.0))
+}
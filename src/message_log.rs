@@ -0,0 +1,345 @@
+//! The scrolling log of what happened, found at the bottom of most roguelike
+//! screens.
+//!
+//! Any system can push messages onto the [`MessageLog`] resource, and a
+//! [`MessageLogView`] shows the latest of them in a rectangle of the root
+//! console, newest at the bottom:
+//!
+//! ```
+//! # use bevy_doryen::message_log::{MessageLog, MessageLogView};
+//! # use bevy_ecs::system::{Commands, ResMut};
+//! fn spawn_log(mut commands: Commands) {
+//!     commands.spawn(MessageLogView::new(0, 44, 80, 6));
+//! }
+//!
+//! fn attack(mut log: ResMut<MessageLog>) {
+//!     log.push("The {red}goblin{/} hits you for 3 damage.");
+//!     log.push_colored("You feel weaker.", (255, 128, 0, 255));
+//! }
+//! ```
+//!
+//! Messages are [markup](crate::rich_text), and are word-wrapped to the
+//! width of the view. A message pushed again right after itself isn't
+//! repeated; the previous one gets an `x2`, `x3` and so on after it instead.
+//! Focused views scroll back through older messages with the arrow keys,
+//! page up, page down, home and end, and every view scrolls with the mouse
+//! wheel while the mouse is over it.
+
+use crate::doryen::{Color, Console};
+use crate::geometry::Rect;
+use crate::rich_text::{self, WrapAlign};
+use crate::{Input, Key, RenderSystemExtensions, RootConsole};
+use bevy_app::{App, Plugin, Update};
+use bevy_ecs::component::Component;
+use bevy_ecs::system::{Query, Res, ResMut, Resource};
+use std::collections::VecDeque;
+
+/// The plugin that adds the [`MessageLog`] resource, unless it has already
+/// been inserted, and draws and scrolls [`MessageLogView`] components.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct MessageLogPlugin;
+
+impl Plugin for MessageLogPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MessageLog>()
+            .add_systems(Update, scroll_message_log_views)
+            .add_doryen_render_systems(render_message_log_views);
+    }
+}
+
+/// A message in the [`MessageLog`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Message {
+    /// The text of the message, as markup.
+    pub text: String,
+    /// The foreground color of the text outside of any markup tag.
+    pub fore: Color,
+    /// How many times in a row the message was pushed.
+    pub count: u32,
+}
+
+impl Message {
+    /// The text of the message as it's shown, with the repeat count after it
+    /// if there's more than one.
+    pub fn display_text(&self) -> String {
+        if self.count > 1 {
+            format!("{} {{dark_gray}}x{}{{/}}", self.text, self.count)
+        } else {
+            self.text.clone()
+        }
+    }
+
+    fn line_count(&self, width: u32) -> usize {
+        rich_text::wrapped_line_count(&self.display_text(), width)
+    }
+}
+
+/// Resource holding the messages of the log, oldest first. Once there are
+/// more than its capacity, the oldest ones are forgotten.
+#[derive(Debug, Clone, Resource)]
+pub struct MessageLog {
+    messages: VecDeque<Message>,
+    capacity: usize,
+    /// The foreground color of messages pushed with
+    /// [`push`](Self::push).
+    pub fore: Color,
+}
+
+impl Default for MessageLog {
+    fn default() -> Self {
+        Self::new(1000)
+    }
+}
+
+impl MessageLog {
+    /// An empty log keeping up to `capacity` messages.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            messages: VecDeque::new(),
+            capacity: capacity.max(1),
+            fore: (192, 192, 192, 255),
+        }
+    }
+
+    /// Adds a message in the log's [`fore`](Self::fore) color.
+    pub fn push(&mut self, text: impl Into<String>) {
+        let fore = self.fore;
+        self.push_colored(text, fore);
+    }
+
+    /// Adds a message with `fore` as its color outside of any markup tag.
+    pub fn push_colored(&mut self, text: impl Into<String>, fore: Color) {
+        let text = text.into();
+        if let Some(last) = self.messages.back_mut() {
+            if last.text == text && last.fore == fore {
+                last.count += 1;
+                return;
+            }
+        }
+        if self.messages.len() >= self.capacity {
+            self.messages.pop_front();
+        }
+        self.messages.push_back(Message {
+            text,
+            fore,
+            count: 1,
+        });
+    }
+
+    /// The messages, oldest first.
+    pub fn messages(&self) -> std::collections::vec_deque::Iter<'_, Message> {
+        self.messages.iter()
+    }
+
+    /// The newest message.
+    pub fn last(&self) -> Option<&Message> {
+        self.messages.back()
+    }
+
+    /// The number of messages.
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Whether there are no messages.
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Forgets every message.
+    pub fn clear(&mut self) {
+        self.messages.clear();
+    }
+
+    /// The most messages the log keeps.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Changes the most messages the log keeps, forgetting the oldest ones
+    /// if there are more.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        while self.messages.len() > self.capacity {
+            self.messages.pop_front();
+        }
+    }
+
+    /// The total number of lines the messages take word-wrapped `width`
+    /// cells wide.
+    pub fn line_count(&self, width: u32) -> usize {
+        self.messages
+            .iter()
+            .map(|message| message.line_count(width))
+            .sum()
+    }
+}
+
+/// A component that displays the latest messages of the [`MessageLog`] in
+/// a rectangle of the [`RootConsole`], newest at the bottom, with scrolling
+/// back through older ones.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct MessageLogView {
+    /// The x coordinate of the top-left corner of the view.
+    pub x: i32,
+    /// The y coordinate of the top-left corner of the view.
+    pub y: i32,
+    /// The width of the view, in cells.
+    pub width: u32,
+    /// The height of the view, in cells.
+    pub height: u32,
+    /// How the lines of the messages are lined up.
+    pub align: WrapAlign,
+    /// The background color of the view, if it's cleared before drawing.
+    pub back: Option<Color>,
+    /// Whether the view responds to the scrolling keys (arrow keys, page up,
+    /// page down, home and end).
+    pub focused: bool,
+    scroll: usize,
+}
+
+impl MessageLogView {
+    /// Creates a view in the given rectangle.
+    pub fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+            align: WrapAlign::Left,
+            back: None,
+            focused: false,
+            scroll: 0,
+        }
+    }
+
+    /// How many lines the view is scrolled back from the newest one.
+    pub fn scroll(&self) -> usize {
+        self.scroll
+    }
+
+    /// Scrolls the view back so the bottom line is `lines` from the newest
+    /// one, as far as the messages in `log` allow.
+    pub fn set_scroll(&mut self, log: &MessageLog, lines: usize) {
+        let max_scroll = log
+            .line_count(self.width)
+            .saturating_sub(self.height as usize);
+        self.scroll = lines.min(max_scroll);
+    }
+
+    /// Scrolls the view back, towards older messages, by the given number of
+    /// lines; negative numbers scroll towards newer ones.
+    pub fn scroll_by(&mut self, log: &MessageLog, lines: isize) {
+        let scroll = if lines < 0 {
+            self.scroll.saturating_sub(lines.unsigned_abs())
+        } else {
+            self.scroll.saturating_add(lines as usize)
+        };
+        self.set_scroll(log, scroll);
+    }
+
+    /// Scrolls the view to the newest message.
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll = 0;
+    }
+
+    /// The cells of the view.
+    pub fn rect(&self) -> Rect {
+        Rect::new(self.x, self.y, self.width, self.height)
+    }
+
+    /// Draws the messages of `log` on `console`.
+    pub fn draw(&self, log: &MessageLog, console: &mut Console) {
+        if let Some(back) = self.back {
+            console.area(
+                self.x,
+                self.y,
+                self.width,
+                self.height,
+                None,
+                Some(back),
+                Some(u16::from(b' ')),
+            );
+        }
+
+        let mut skip = self.scroll;
+        let mut bottom = self.y + self.height as i32;
+        for message in log.messages().rev() {
+            let remaining = (bottom - self.y) as usize;
+            if remaining == 0 {
+                break;
+            }
+            let document = rich_text::markup_document(&message.display_text());
+            let layout = document.layout(self.width);
+            let lines = layout.lines.len();
+            if skip >= lines {
+                skip -= lines;
+                continue;
+            }
+            // Only the lines above those scrolled past are shown, and of
+            // those, only the ones that still fit
+            let shown = lines - skip;
+            skip = 0;
+            let skip_top = shown.saturating_sub(remaining);
+            let height = shown - skip_top;
+            bottom -= height as i32;
+            layout.print(
+                &document,
+                console,
+                ((self.x, bottom), (self.width, height as u32)),
+                self.align,
+                (Some(message.fore), None),
+                skip_top,
+            );
+        }
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn scroll_message_log_views(
+    input: Res<'_, Input>,
+    log: Res<'_, MessageLog>,
+    mut views: Query<'_, '_, &mut MessageLogView>,
+) {
+    let mouse_cell = input.mouse_cell();
+    let (_, wheel) = input.mouse_wheel_delta();
+
+    for mut view in &mut views {
+        if view.focused {
+            let page = view.height.max(1) as isize;
+            if input.key_repeated(Key::ArrowUp) {
+                view.scroll_by(&log, 1);
+            }
+            if input.key_repeated(Key::ArrowDown) {
+                view.scroll_by(&log, -1);
+            }
+            if input.key_pressed(Key::PageUp) {
+                view.scroll_by(&log, page);
+            }
+            if input.key_pressed(Key::PageDown) {
+                view.scroll_by(&log, -page);
+            }
+            if input.key_pressed(Key::Home) {
+                view.set_scroll(&log, usize::MAX);
+            }
+            if input.key_pressed(Key::End) {
+                view.scroll_to_bottom();
+            }
+        }
+
+        if wheel != 0.0 && view.rect().contains(mouse_cell) {
+            view.scroll_by(&log, wheel.signum() as isize);
+        }
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn render_message_log_views(
+    log: Res<'_, MessageLog>,
+    views: Query<'_, '_, &MessageLogView>,
+    mut root_console: ResMut<'_, RootConsole>,
+) {
+    for view in views.iter() {
+        view.draw(&log, &mut root_console);
+    }
+}
@@ -0,0 +1,468 @@
+//! Modal dialogs, stacked on top of everything else.
+//!
+//! Pushing a [`Dialog`] onto the [`ModalStack`] shows it centered on the
+//! root console, over a dimmed copy of whatever was drawn beneath, until the
+//! player picks one of its buttons or cancels it with Escape. Either way, it
+//! comes off the stack and the outcome is sent as a [`DialogResult`]:
+//!
+//! ```
+//! # use bevy_app::AppExit;
+//! # use bevy_doryen::modal::{no_modal_open, Dialog, DialogResult, ModalStack};
+//! # use bevy_doryen::CloseRequested;
+//! # use bevy_ecs::event::{EventReader, EventWriter};
+//! # use bevy_ecs::system::ResMut;
+//! fn ask_to_exit(mut close: EventReader<CloseRequested>, mut modals: ResMut<ModalStack>) {
+//!     if close.iter().last().is_some() && !modals.is_open("exit") {
+//!         modals.push(Dialog::confirm("exit", "Exit game?"));
+//!     }
+//! }
+//!
+//! fn exit_if_confirmed(mut results: EventReader<DialogResult>, mut exit: EventWriter<AppExit>) {
+//!     if results.iter().any(|result| result.id == "exit" && result.is_confirmed()) {
+//!         exit.send(AppExit);
+//!     }
+//! }
+//! ```
+//!
+//! The dialogs take the keyboard and the mouse while they're open: the
+//! arrow keys and Tab move between the buttons, Enter or Space picks the
+//! selected one, and a button whose label starts with a letter is picked
+//! with that letter too, so a "Yes" button goes with Y. Input is [captured](InputCapture) from the
+//! update after a dialog opens until the one after the last one closes, so
+//! the game and the other built-in widgets beneath don't act on the same
+//! keys. Systems that shouldn't run at all while a dialog is open can check
+//! for one with the [`no_modal_open`] run condition:
+//!
+//! ```
+//! # use bevy_app::{App, Update};
+//! # use bevy_doryen::modal::no_modal_open;
+//! # use bevy_ecs::schedule::IntoSystemConfigs;
+//! # fn advance_turn() {}
+//! App::new().add_systems(Update, advance_turn.run_if(no_modal_open));
+//! ```
+//!
+//! The buttons [`Dialog::new`] and [`Dialog::confirm`] start out with are
+//! translated through the [`Localization`] resource, if there is one and it
+//! has text for the `dialog.ok`, `dialog.yes` and `dialog.no` keys.
+
+use crate::doryen::{color_blend, Color, Console, TextAlign};
+use crate::localization::Localization;
+use crate::rich_text::{self, PrintMarkup, WrapAlign};
+use crate::window::{DrawFrame, FrameFill, FrameStyle};
+use crate::{
+    InputCapture, Key, MouseButton, PostRenderSet, RenderStage, RenderSystemExtensions, RootConsole,
+};
+use bevy_app::{App, Last, Plugin, Update};
+use bevy_ecs::event::{Event, EventWriter};
+use bevy_ecs::schedule::IntoSystemConfigs;
+use bevy_ecs::system::{Res, ResMut, Resource};
+
+/// The owner of the [`InputCapture`] taken while dialogs are open.
+const CAPTURE_OWNER: &str = "bevy_doryen::modal";
+
+/// The plugin that adds the [`ModalStack`] resource, unless it has already
+/// been inserted, and shows and runs the dialogs on it.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct ModalPlugin;
+
+impl Plugin for ModalPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ModalStack>()
+            .init_resource::<InputCapture>()
+            .add_event::<DialogResult>()
+            .add_systems(Update, run_top_dialog)
+            .add_systems(Last, capture_input)
+            .add_doryen_render_systems_to_stage(
+                RenderStage::PostRender,
+                render_modals.in_set(PostRenderSet::Modal),
+            );
+    }
+}
+
+/// A dialog with a message and a row of buttons.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dialog {
+    /// What the dialog is for, passed on in its [`DialogResult`].
+    pub id: String,
+    /// The text printed along the top of the frame, if any.
+    pub title: Option<String>,
+    /// The message, as [markup](crate::rich_text), word-wrapped to the
+    /// width of the dialog.
+    pub message: String,
+    /// The labels of the buttons, from left to right. The default ones are
+    /// shown translated, see the [module documentation](self).
+    pub buttons: Vec<String>,
+    /// The button that cancels the dialog rather than confirming it, if
+    /// any.
+    pub cancel_button: Option<usize>,
+    /// The width of the dialog, frame included, in cells.
+    pub width: u32,
+    /// The frame around the dialog, and its colors.
+    pub frame: FrameStyle,
+    /// Whether everything beneath the dialog is dimmed.
+    pub dim: bool,
+    selected: usize,
+    /// The [`Localization`] keys of the buttons, while they're the default
+    /// ones.
+    button_keys: &'static [&'static str],
+}
+
+impl Dialog {
+    /// A dialog showing `message`, with an "OK" button.
+    pub fn new(id: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            title: None,
+            message: message.into(),
+            buttons: vec!["OK".to_owned()],
+            cancel_button: None,
+            width: 40,
+            frame: FrameStyle::DOUBLE,
+            dim: true,
+            selected: 0,
+            button_keys: &["dialog.ok"],
+        }
+    }
+
+    /// A dialog asking `question`, with a "Yes" button confirming it and a
+    /// "No" button cancelling it.
+    pub fn confirm(id: impl Into<String>, question: impl Into<String>) -> Self {
+        let mut dialog = Self::new(id, question)
+            .with_buttons(["Yes", "No"])
+            .with_cancel_button(1);
+        dialog.button_keys = &["dialog.yes", "dialog.no"];
+        dialog
+    }
+
+    /// Sets the text printed along the top of the frame.
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Replaces the buttons, selecting the first one.
+    pub fn with_buttons<S: Into<String>>(mut self, buttons: impl IntoIterator<Item = S>) -> Self {
+        self.buttons = buttons.into_iter().map(Into::into).collect();
+        self.selected = 0;
+        self.button_keys = &[];
+        self
+    }
+
+    /// Makes the button at `index` cancel the dialog.
+    pub fn with_cancel_button(mut self, index: usize) -> Self {
+        self.cancel_button = Some(index);
+        self
+    }
+
+    /// Sets the width of the dialog, frame included.
+    pub fn with_width(mut self, width: u32) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets the frame around the dialog.
+    pub fn with_frame(mut self, frame: FrameStyle) -> Self {
+        self.frame = frame;
+        self
+    }
+
+    /// Sets whether everything beneath the dialog is dimmed.
+    pub fn with_dim(mut self, dim: bool) -> Self {
+        self.dim = dim;
+        self
+    }
+
+    /// The position of the selected button.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// The position of the top-left cell and the size of the dialog,
+    /// centered on a console of the given size.
+    pub fn rect(&self, (console_width, console_height): (u32, u32)) -> ((i32, i32), (u32, u32)) {
+        let width = self.width.max(5);
+        let lines = rich_text::wrapped_line_count(&self.message, width - 4) as u32;
+        // The frame, a line of space around the message, and the buttons
+        let height = lines + 5;
+        let x = (console_width as i32 - width as i32) / 2;
+        let y = (console_height as i32 - height as i32) / 2;
+        ((x, y), (width, height))
+    }
+
+    /// The label of the button at `index`, translated if it's a default one
+    /// `localization` has text for.
+    fn label<'a>(&'a self, index: usize, localization: Option<&'a Localization>) -> &'a str {
+        match (self.button_keys.get(index), localization) {
+            (Some(key), Some(localization)) if localization.contains(key) => localization.get(key),
+            _ => &self.buttons[index],
+        }
+    }
+
+    /// The cell the label of every button starts at, and how wide it is.
+    fn button_cells(
+        &self,
+        ((x, y), (width, height)): ((i32, i32), (u32, u32)),
+        localization: Option<&Localization>,
+    ) -> Vec<(i32, i32, u32)> {
+        let labels: Vec<u32> = (0..self.buttons.len())
+            .map(|i| self.label(i, localization).chars().count() as u32 + 4)
+            .collect();
+        let total = labels.iter().sum::<u32>() + 2 * labels.len().saturating_sub(1) as u32;
+        let button_y = y + height as i32 - 2;
+        let mut button_x = x + (width as i32 - total as i32) / 2;
+        labels
+            .into_iter()
+            .map(|label_width| {
+                let cell = (button_x, button_y, label_width);
+                button_x += label_width as i32 + 2;
+                cell
+            })
+            .collect()
+    }
+
+    fn response(&self, button: usize) -> DialogResponse {
+        if self.cancel_button == Some(button) {
+            DialogResponse::Cancelled
+        } else {
+            DialogResponse::Confirmed(button)
+        }
+    }
+
+    fn draw(&self, console: &mut Console, localization: Option<&Localization>) {
+        let rect = self.rect((console.get_width(), console.get_height()));
+        let ((x, y), (width, height)) = rect;
+        console.draw_frame_filled(rect, &self.frame, self.title.as_deref(), FrameFill::Clear);
+        console.print_wrapped(
+            ((x + 2, y + 2), (width - 4, height - 5)),
+            &self.message,
+            WrapAlign::Center,
+            Some(self.frame.fore),
+            None,
+        );
+        let button_cells = self.button_cells(rect, localization);
+        for (i, (button_x, button_y, _)) in button_cells.into_iter().enumerate() {
+            let (fore, back) = if i == self.selected {
+                (self.frame.back, self.frame.fore)
+            } else {
+                (self.frame.fore, self.frame.back)
+            };
+            console.print(
+                button_x,
+                button_y,
+                &format!("[ {} ]", self.label(i, localization)),
+                TextAlign::Left,
+                Some(fore),
+                Some(back),
+            );
+        }
+    }
+}
+
+/// What the player did with a [`Dialog`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DialogResponse {
+    /// Picked the button at the given position.
+    Confirmed(usize),
+    /// Pressed Escape, or picked the dialog's
+    /// [cancel button](Dialog::cancel_button).
+    Cancelled,
+}
+
+/// Sent when a [`Dialog`] is closed by the player.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Event)]
+pub struct DialogResult {
+    /// The [`id`](Dialog::id) of the dialog.
+    pub id: String,
+    /// What the player did with it.
+    pub response: DialogResponse,
+}
+
+impl DialogResult {
+    /// Whether the player picked a button other than the cancel button.
+    pub fn is_confirmed(&self) -> bool {
+        matches!(self.response, DialogResponse::Confirmed(_))
+    }
+
+    /// Whether the player cancelled the dialog.
+    pub fn is_cancelled(&self) -> bool {
+        self.response == DialogResponse::Cancelled
+    }
+}
+
+/// Resource holding the open dialogs. Only the one on top takes input; the
+/// ones beneath wait their turn.
+#[derive(Default, Debug, Clone, Resource)]
+pub struct ModalStack {
+    /// The dialogs, bottom first, each with whether it was pushed since
+    /// input was last handled.
+    dialogs: Vec<(Dialog, bool)>,
+}
+
+impl ModalStack {
+    /// Opens `dialog` on top of the others. It starts taking input next
+    /// update, so the key that opened it doesn't also close it.
+    pub fn push(&mut self, dialog: Dialog) {
+        self.dialogs.push((dialog, true));
+    }
+
+    /// Closes the dialog on top, without sending a [`DialogResult`].
+    pub fn pop(&mut self) -> Option<Dialog> {
+        self.dialogs.pop().map(|(dialog, _)| dialog)
+    }
+
+    /// Closes every dialog with the given id, without sending a
+    /// [`DialogResult`]. Returns whether there were any.
+    pub fn close(&mut self, id: &str) -> bool {
+        let len = self.dialogs.len();
+        self.dialogs.retain(|(dialog, _)| dialog.id != id);
+        self.dialogs.len() != len
+    }
+
+    /// The dialog on top.
+    pub fn top(&self) -> Option<&Dialog> {
+        self.dialogs.last().map(|(dialog, _)| dialog)
+    }
+
+    /// Whether a dialog with the given id is open.
+    pub fn is_open(&self, id: &str) -> bool {
+        self.dialogs.iter().any(|(dialog, _)| dialog.id == id)
+    }
+
+    /// The number of open dialogs.
+    pub fn len(&self) -> usize {
+        self.dialogs.len()
+    }
+
+    /// Whether there are no open dialogs.
+    pub fn is_empty(&self) -> bool {
+        self.dialogs.is_empty()
+    }
+}
+
+/// Run condition that's true while there are no open dialogs. A missing
+/// [`ModalStack`] counts as empty.
+pub fn no_modal_open(modals: Option<Res<'_, ModalStack>>) -> bool {
+    modals.is_none_or(|modals| modals.is_empty())
+}
+
+/// The key picking the button with the given label, if it starts with a
+/// letter.
+fn hotkey(label: &str) -> Option<Key> {
+    label
+        .chars()
+        .next()
+        .filter(char::is_ascii_alphabetic)
+        .and_then(|letter| format!("Key{}", letter.to_ascii_uppercase()).parse().ok())
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn run_top_dialog(
+    capture: Res<'_, InputCapture>,
+    mut modals: ResMut<'_, ModalStack>,
+    mut results: EventWriter<'_, DialogResult>,
+    root_console: Res<'_, RootConsole>,
+    localization: Option<Res<'_, Localization>>,
+) {
+    let localization = localization.as_deref();
+    let (dialog, fresh) = match modals.dialogs.last_mut() {
+        Some((dialog, fresh)) => (dialog, fresh),
+        None => return,
+    };
+    if *fresh {
+        *fresh = false;
+        return;
+    }
+    let input = match capture.input(CAPTURE_OWNER) {
+        Some(input) => input,
+        None => return,
+    };
+
+    let count = dialog.buttons.len();
+    let mut response = None;
+    if count > 0 {
+        if input.key_repeated(Key::ArrowRight) || (input.key_pressed(Key::Tab) && !input.shift()) {
+            dialog.selected = (dialog.selected + 1) % count;
+        }
+        if input.key_repeated(Key::ArrowLeft) || (input.key_pressed(Key::Tab) && input.shift()) {
+            dialog.selected = (dialog.selected + count - 1) % count;
+        }
+
+        let rect = dialog.rect((root_console.get_width(), root_console.get_height()));
+        let (mouse_x, mouse_y) = input.mouse_cell();
+        let hovered = dialog
+            .button_cells(rect, localization)
+            .into_iter()
+            .position(|(x, y, width)| {
+                mouse_y == y && mouse_x >= x && ((mouse_x - x) as u32) < width
+            });
+        if let Some(hovered) = hovered {
+            if input.mouse_button_pressed(MouseButton::Left) {
+                response = Some(dialog.response(hovered));
+            }
+        }
+
+        if input.key_pressed(Key::Enter)
+            || input.key_pressed(Key::NumpadEnter)
+            || input.key_pressed(Key::Space)
+        {
+            response = Some(dialog.response(dialog.selected));
+        }
+        let picked = (0..count).position(|i| {
+            hotkey(dialog.label(i, localization)).is_some_and(|key| input.key_pressed(key))
+        });
+        if let Some(picked) = picked {
+            response = Some(dialog.response(picked));
+        }
+    }
+    if input.key_pressed(Key::Escape) {
+        response = Some(DialogResponse::Cancelled);
+    }
+
+    if let Some(response) = response {
+        if let Some(closed) = modals.pop() {
+            results.send(DialogResult {
+                id: closed.id,
+                response,
+            });
+        }
+    }
+}
+
+/// Captures input while there are dialogs open.
+#[allow(clippy::needless_pass_by_value)]
+fn capture_input(modals: Res<'_, ModalStack>, mut capture: ResMut<'_, InputCapture>) {
+    if modals.is_empty() {
+        capture.release(CAPTURE_OWNER);
+    } else {
+        capture.capture(CAPTURE_OWNER);
+    }
+}
+
+/// Blends every cell of `console` halfway to black.
+fn dim(console: &mut Console) {
+    const BLACK: Color = (0, 0, 0, 255);
+    for y in 0..console.get_height() as i32 {
+        for x in 0..console.get_width() as i32 {
+            if let Some(fore) = console.get_fore(x, y) {
+                console.fore(x, y, color_blend(fore, BLACK, 0.5));
+            }
+            if let Some(back) = console.get_back(x, y) {
+                console.back(x, y, color_blend(back, BLACK, 0.5));
+            }
+        }
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn render_modals(
+    modals: Res<'_, ModalStack>,
+    localization: Option<Res<'_, Localization>>,
+    mut root_console: ResMut<'_, RootConsole>,
+) {
+    for (dialog, _) in &modals.dialogs {
+        if dialog.dim {
+            dim(&mut root_console);
+        }
+        dialog.draw(&mut root_console, localization.as_deref());
+    }
+}
@@ -0,0 +1,452 @@
+//! Painting console cells with the mouse.
+//!
+//! A [`PaintTool`] holds the current [`Brush`] and [`PaintMode`], and paints
+//! on a target console as the left mouse button is dragged across it. The
+//! target can be any console: the root console, a layer drawn on top of
+//! it or the [`WorldConsole`](crate::camera::WorldConsole) of an editor.
+//!
+//! ```
+//! # use bevy_doryen::camera::WorldConsole;
+//! # use bevy_doryen::paint::PaintTool;
+//! # use bevy_doryen::Input;
+//! # use bevy_ecs::system::{Local, Res, ResMut};
+//! fn paint(
+//!     input: Res<Input>,
+//!     mut tool: Local<PaintTool>,
+//!     mut canvas: ResMut<WorldConsole>,
+//! ) {
+//!     tool.update(&input, &mut canvas);
+//! }
+//! ```
+//!
+//! Lines and rectangles are only painted once the button is released; until
+//! then, [`PaintTool::draw_preview`] shows where they'll go.
+//!
+//! Every completed stroke, line, rectangle or fill is returned as a
+//! [`PaintStroke`] holding the cells it changed, as they were before and
+//! after. Executing it as a [`ReversibleCommand`] on the resource that holds
+//! the target records it on the [`UndoStack`](crate::undo::UndoStack), ready
+//! to be undone. The cells have already been painted at that point, so
+//! executing it changes nothing:
+//!
+//! ```
+//! # use bevy_doryen::camera::WorldConsole;
+//! # use bevy_doryen::paint::PaintTool;
+//! # use bevy_doryen::undo::UndoCommandsExt;
+//! # use bevy_doryen::Input;
+//! # use bevy_ecs::system::{Commands, Local, Res, ResMut};
+//! fn paint(
+//!     input: Res<Input>,
+//!     mut tool: Local<PaintTool>,
+//!     mut canvas: ResMut<WorldConsole>,
+//!     mut commands: Commands,
+//! ) {
+//!     if let Some(stroke) = tool.update(&input, &mut canvas) {
+//!         commands.execute_reversible(stroke.on::<WorldConsole>());
+//!     }
+//! }
+//! ```
+
+use crate::doryen::{Color, Console};
+use crate::geometry::{disc, line};
+use crate::undo::ReversibleCommand;
+use crate::{CellContents, Input, MouseButton};
+use bevy_ecs::system::Resource;
+use bevy_ecs::world::World;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::marker::PhantomData;
+use std::ops::DerefMut;
+
+/// What a [`PaintTool`] paints with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Brush {
+    /// The glyph to paint, or `None` to leave the glyphs alone.
+    pub glyph: Option<u16>,
+    /// The foreground color to paint, or `None` to leave it alone.
+    pub fore: Option<Color>,
+    /// The background color to paint, or `None` to leave it alone.
+    pub back: Option<Color>,
+    /// The radius of the brush, in cells. A size of `0` paints single cells.
+    /// Only freehand painting and lines use it.
+    pub size: u32,
+}
+
+impl Default for Brush {
+    fn default() -> Self {
+        Self {
+            glyph: Some(u16::from(b'#')),
+            fore: Some((255, 255, 255, 255)),
+            back: Some((0, 0, 0, 255)),
+            size: 0,
+        }
+    }
+}
+
+/// How a [`PaintTool`] turns a mouse drag into painted cells.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PaintMode {
+    /// Paints every cell the mouse is dragged over.
+    #[default]
+    Freehand,
+    /// Paints a line from where the drag started to where it ended.
+    Line,
+    /// Paints the outline of the rectangle between where the drag started
+    /// and where it ended.
+    Rect,
+    /// Paints all of the rectangle between where the drag started and where
+    /// it ended.
+    FilledRect,
+    /// Paints the clicked cell and every cell connected to it that looks the
+    /// same, like a paint bucket.
+    Fill,
+}
+
+/// A painting tool. See the [module documentation](self).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaintTool {
+    /// What to paint with.
+    pub brush: Brush,
+    /// How to paint.
+    pub mode: PaintMode,
+    /// The mouse button that paints. Defaults to the left button.
+    pub button: MouseButton,
+    /// Added to the cell under the mouse to get the cell of the target
+    /// console, for targets that aren't drawn at the top-left of the screen.
+    pub offset: (i32, i32),
+    drag_start: Option<(i32, i32)>,
+    last_cell: (i32, i32),
+    /// The cells painted since the stroke started, as they were before.
+    before: HashMap<(i32, i32), CellContents>,
+}
+
+impl Default for PaintTool {
+    fn default() -> Self {
+        Self {
+            brush: Brush::default(),
+            mode: PaintMode::default(),
+            button: MouseButton::Left,
+            offset: (0, 0),
+            drag_start: None,
+            last_cell: (0, 0),
+            before: HashMap::new(),
+        }
+    }
+}
+
+impl PaintTool {
+    /// Creates a tool painting with the given brush, in the given mode.
+    pub fn new(brush: Brush, mode: PaintMode) -> Self {
+        Self {
+            brush,
+            mode,
+            ..Self::default()
+        }
+    }
+
+    /// Where the current drag started on the target console, if the button
+    /// is down.
+    pub fn drag_start(&self) -> Option<(i32, i32)> {
+        self.drag_start
+    }
+
+    /// Paints on `target` according to the mouse input since the last
+    /// update. Call this once per update. Returns the stroke, line, rectangle
+    /// or fill that was completed on this update, if any.
+    pub fn update(&mut self, input: &Input, target: &mut Console) -> Option<PaintStroke> {
+        let (mouse_x, mouse_y) = input.mouse_pos();
+        let cell = (
+            mouse_x.floor() as i32 + self.offset.0,
+            mouse_y.floor() as i32 + self.offset.1,
+        );
+
+        let mut completed = false;
+        if input.mouse_button_pressed(self.button) {
+            self.drag_start = Some(cell);
+            self.last_cell = cell;
+            match self.mode {
+                PaintMode::Freehand => self.paint(target, &self.line_cells(cell, cell)),
+                PaintMode::Fill => {
+                    self.paint(target, &fill_cells(target, cell));
+                    completed = true;
+                }
+                PaintMode::Line | PaintMode::Rect | PaintMode::FilledRect => (),
+            }
+        } else if self.drag_start.is_some() && self.mode == PaintMode::Freehand {
+            // Join up with the last cell so fast drags leave no gaps
+            self.paint(target, &self.line_cells(self.last_cell, cell));
+        }
+        self.last_cell = cell;
+
+        if input.mouse_button_released(self.button) || !input.mouse_button(self.button) {
+            if let Some(start) = self.drag_start.take() {
+                match self.mode {
+                    PaintMode::Line => self.paint(target, &self.line_cells(start, cell)),
+                    PaintMode::Rect | PaintMode::FilledRect => {
+                        self.paint(target, &self.rect_cells(start, cell));
+                    }
+                    PaintMode::Freehand | PaintMode::Fill => (),
+                }
+                completed = true;
+            }
+        }
+
+        if completed {
+            self.take_stroke(target)
+        } else {
+            None
+        }
+    }
+
+    /// Draws the line or rectangle being dragged out on `destination`,
+    /// without painting it on the target. `destination` is usually the root
+    /// console, drawn after the target.
+    pub fn draw_preview(&self, destination: &mut Console) {
+        let start = match self.drag_start {
+            Some(start) => start,
+            None => return,
+        };
+        let (offset_x, offset_y) = self.offset;
+        let to_screen = |(x, y): (i32, i32)| (x - offset_x, y - offset_y);
+        let cells = match self.mode {
+            PaintMode::Line => self.line_cells(to_screen(start), to_screen(self.last_cell)),
+            PaintMode::Rect | PaintMode::FilledRect => {
+                self.rect_cells(to_screen(start), to_screen(self.last_cell))
+            }
+            PaintMode::Freehand | PaintMode::Fill => return,
+        };
+        for cell in cells {
+            self.paint_cell(destination, cell);
+        }
+    }
+
+    fn paint_cell(&self, target: &mut Console, (x, y): (i32, i32)) {
+        let Brush {
+            glyph, fore, back, ..
+        } = self.brush;
+        target.cell(x, y, glyph, fore, back);
+    }
+
+    /// Paints `cells`, remembering what they looked like before.
+    fn paint(&mut self, target: &mut Console, cells: &[(i32, i32)]) {
+        for &cell in cells {
+            if let Some(contents) = cell_contents(target, cell) {
+                self.before.entry(cell).or_insert(contents);
+                self.paint_cell(target, cell);
+            }
+        }
+    }
+
+    fn take_stroke(&mut self, target: &Console) -> Option<PaintStroke> {
+        let mut cells: Vec<_> = self
+            .before
+            .drain()
+            .filter_map(|(cell, before)| {
+                let after = cell_contents(target, cell)?;
+                Some((cell, before, after)).filter(|_| before != after)
+            })
+            .collect();
+        if cells.is_empty() {
+            return None;
+        }
+        cells.sort_by_key(|&((x, y), _, _)| (y, x));
+        Some(PaintStroke {
+            mode: self.mode,
+            cells,
+        })
+    }
+
+    fn line_cells(&self, from: (i32, i32), to: (i32, i32)) -> Vec<(i32, i32)> {
+        let mut cells = Vec::new();
+        for center in line(from, to) {
+            if self.brush.size == 0 {
+                cells.push(center);
+            } else {
+                cells.extend(disc(center, self.brush.size));
+            }
+        }
+        cells
+    }
+
+    fn rect_cells(&self, (x0, y0): (i32, i32), (x1, y1): (i32, i32)) -> Vec<(i32, i32)> {
+        let (left, right) = (x0.min(x1), x0.max(x1));
+        let (top, bottom) = (y0.min(y1), y0.max(y1));
+        let mut cells = Vec::new();
+        for y in top..=bottom {
+            for x in left..=right {
+                let edge = x == left || x == right || y == top || y == bottom;
+                if edge || self.mode == PaintMode::FilledRect {
+                    cells.push((x, y));
+                }
+            }
+        }
+        cells
+    }
+}
+
+fn cell_contents(console: &Console, (x, y): (i32, i32)) -> Option<CellContents> {
+    Some(CellContents {
+        glyph: console.get_ascii(x, y)?,
+        fore: console.get_fore(x, y)?,
+        back: console.get_back(x, y)?,
+    })
+}
+
+/// The cell at `start` and every cell connected to it that looks the same.
+fn fill_cells(target: &Console, start: (i32, i32)) -> Vec<(i32, i32)> {
+    let original = match cell_contents(target, start) {
+        Some(original) => original,
+        None => return Vec::new(),
+    };
+
+    let mut seen = HashSet::new();
+    let mut pending = vec![start];
+    let mut cells = Vec::new();
+    while let Some((x, y)) = pending.pop() {
+        if !seen.insert((x, y)) || cell_contents(target, (x, y)) != Some(original) {
+            continue;
+        }
+        cells.push((x, y));
+        pending.extend_from_slice(&[(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]);
+    }
+    cells
+}
+
+/// The cells a completed stroke of a [`PaintTool`] changed, as they were
+/// before and after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaintStroke {
+    mode: PaintMode,
+    cells: Vec<((i32, i32), CellContents, CellContents)>,
+}
+
+impl PaintStroke {
+    /// The mode the stroke was painted in.
+    pub fn mode(&self) -> PaintMode {
+        self.mode
+    }
+
+    /// The cells the stroke changed, as they were before and after it, from
+    /// top to bottom and left to right.
+    pub fn cells(&self) -> &[((i32, i32), CellContents, CellContents)] {
+        &self.cells
+    }
+
+    /// Paints the stroke on `console` again.
+    pub fn redo(&self, console: &mut Console) {
+        for &(cell, _, after) in &self.cells {
+            set_cell(console, cell, after);
+        }
+    }
+
+    /// Puts back the cells the stroke painted over on `console`.
+    pub fn undo(&self, console: &mut Console) {
+        for &(cell, before, _) in &self.cells {
+            set_cell(console, cell, before);
+        }
+    }
+
+    /// The stroke as a [`ReversibleCommand`] on the console in the resource
+    /// `R`.
+    pub fn on<R: Resource + DerefMut<Target = Console>>(self) -> PaintCommand<R> {
+        PaintCommand {
+            stroke: self,
+            target: PhantomData,
+        }
+    }
+}
+
+fn set_cell(console: &mut Console, (x, y): (i32, i32), contents: CellContents) {
+    let CellContents { glyph, fore, back } = contents;
+    console.cell(x, y, Some(glyph), Some(fore), Some(back));
+}
+
+/// A [`PaintStroke`] on the console in the resource `R`, made with
+/// [`PaintStroke::on`].
+pub struct PaintCommand<R> {
+    stroke: PaintStroke,
+    target: PhantomData<fn() -> R>,
+}
+
+impl<R> std::fmt::Debug for PaintCommand<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PaintCommand")
+            .field("stroke", &self.stroke)
+            .finish()
+    }
+}
+
+impl<R: Resource + DerefMut<Target = Console>> ReversibleCommand for PaintCommand<R> {
+    fn apply(&mut self, world: &mut World) {
+        self.stroke.redo(&mut world.resource_mut::<R>());
+    }
+
+    fn undo(&mut self, world: &mut World) {
+        self.stroke.undo(&mut world.resource_mut::<R>());
+    }
+
+    fn describe(&self) -> Cow<'static, str> {
+        Cow::Borrowed(match self.stroke.mode {
+            PaintMode::Freehand => "Paint",
+            PaintMode::Line => "Line",
+            PaintMode::Rect | PaintMode::FilledRect => "Rectangle",
+            PaintMode::Fill => "Fill",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::WorldConsole;
+    use crate::headless::HeadlessInput;
+    use crate::undo::{UndoCommandsExt, UndoStack};
+    use bevy_ecs::system::{CommandQueue, Commands};
+
+    fn click(tool: &mut PaintTool, canvas: &mut Console, cell: (f32, f32)) -> Option<PaintStroke> {
+        let mut input = Input::default();
+        let mut press = HeadlessInput::default();
+        press.set_mouse_pos(cell);
+        press.press_mouse_button(MouseButton::Left);
+        input.handle_input(&[MouseButton::Left], &mut press);
+        assert!(tool.update(&input, canvas).is_none());
+
+        let mut release = HeadlessInput::default();
+        release.set_mouse_pos(cell);
+        release.release_mouse_button(MouseButton::Left);
+        input.handle_input(&[MouseButton::Left], &mut release);
+        tool.update(&input, canvas)
+    }
+
+    fn row(world: &World) -> String {
+        crate::headless::console_row(world.resource::<WorldConsole>(), 0)
+    }
+
+    #[test]
+    fn strokes_are_undone_through_the_undo_stack() {
+        let mut world = World::new();
+        world.init_resource::<UndoStack>();
+        world.insert_resource(WorldConsole(Console::new(3, 1)));
+
+        let mut tool = PaintTool::default();
+        let stroke = click(
+            &mut tool,
+            &mut world.resource_mut::<WorldConsole>(),
+            (1.5, 0.5),
+        );
+        let stroke = stroke.expect("the click painted a cell");
+        assert_eq!(stroke.cells().len(), 1);
+        assert_eq!(row(&world), " # ");
+
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        commands.execute_reversible(stroke.on::<WorldConsole>());
+        commands.undo();
+        queue.apply(&mut world);
+        assert_eq!(row(&world), "   ");
+        assert_eq!(
+            world.resource::<UndoStack>().redo_description().as_deref(),
+            Some("Paint")
+        );
+    }
+}
@@ -0,0 +1,364 @@
+//! Colors looked up by name or number, so a game can be re-themed by
+//! swapping its palette.
+//!
+//! A [`Palette`] holds named colors, such as `ui.border`, and a list of
+//! indexed colors, such as the 16 of a retro palette. The named colors can
+//! be set from code or parsed from a simple text format, which palette
+//! files can be loaded in:
+//!
+//! ```text
+//! # Lines starting with # that aren't colors are comments
+//! ui.border = #c0c0c0
+//! ui.text = white
+//! 0 = #000000
+//! 1 = #1d2b53
+//! ```
+//!
+//! A name made of digits sets an indexed color. Lines holding nothing but a
+//! color, like those of `.hex` palette files, are appended to the indexed
+//! colors, so those files can be parsed as they are.
+//!
+//! Drawing goes through [`WithPalette::with_palette`], whose methods take
+//! [`PaletteColor`]s, which can be palette names, indices or plain colors:
+//!
+//! ```
+//! # use bevy_doryen::doryen::TextAlign;
+//! # use bevy_doryen::palette::{Palette, PaletteColor, WithPalette};
+//! # use bevy_doryen::RootConsole;
+//! # use bevy_ecs::system::{Res, ResMut};
+//! fn draw_status(palette: Res<Palette>, mut root_console: ResMut<RootConsole>) {
+//!     let mut console = root_console.with_palette(&palette);
+//!     console.print(1, 1, "Status", TextAlign::Left, "ui.text", PaletteColor::Unchanged);
+//!     console.cell(0, 1, Some(16), 3_usize, (0, 0, 0, 255));
+//! }
+//! ```
+//!
+//! Names missing from the palette are drawn in [`Palette::MISSING`], a
+//! bright magenta, so they're easy to spot. The palette is a resource, but
+//! isn't added by any plugin; insert one to use it.
+
+use crate::doryen::{Color, Console, TextAlign};
+use bevy_ecs::system::Resource;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+/// Resource holding named and indexed colors.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Resource)]
+pub struct Palette {
+    named: HashMap<String, Color>,
+    indexed: Vec<Color>,
+}
+
+impl Palette {
+    /// The color drawn for names and indices missing from the palette.
+    pub const MISSING: Color = (255, 0, 255, 255);
+
+    /// An empty palette.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A palette of the given indexed colors.
+    pub fn from_indexed(colors: impl IntoIterator<Item = Color>) -> Self {
+        Self {
+            named: HashMap::new(),
+            indexed: colors.into_iter().collect(),
+        }
+    }
+
+    /// Parses a palette from text in the format described in the [module
+    /// documentation](self).
+    pub fn parse(source: &str) -> Result<Self, ParsePaletteError> {
+        let mut palette = Self::new();
+        palette.parse_into(source)?;
+        Ok(palette)
+    }
+
+    /// Adds the colors in `source`, in the format described in the [module
+    /// documentation](self), replacing those of the same names. Nothing is
+    /// added if there's a line that can't be parsed.
+    pub fn parse_into(&mut self, source: &str) -> Result<(), ParsePaletteError> {
+        let mut parsed = self.clone();
+        for (number, line) in source.lines().enumerate() {
+            let line = line.trim();
+            let error = || ParsePaletteError {
+                line: number + 1,
+                text: line.to_owned(),
+            };
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(color) = parse_bare_color(line) {
+                parsed.indexed.push(color);
+                continue;
+            }
+            if line.starts_with('#') {
+                continue;
+            }
+            let (name, value) = line.split_at(line.find('=').ok_or_else(error)?);
+            let (name, value) = (name.trim(), value[1..].trim());
+            let color = crate::rich_text::parse_color(value).ok_or_else(error)?;
+            if name.is_empty() {
+                return Err(error());
+            }
+            match name.parse::<usize>() {
+                Ok(index) => parsed.set_index(index, color),
+                Err(_) => parsed.set(name, color),
+            }
+        }
+        *self = parsed;
+        Ok(())
+    }
+
+    /// The color called `name`.
+    pub fn get(&self, name: &str) -> Option<Color> {
+        self.named.get(name).copied()
+    }
+
+    /// Sets the color called `name`.
+    pub fn set(&mut self, name: impl Into<String>, color: Color) {
+        self.named.insert(name.into(), color);
+    }
+
+    /// Forgets the color called `name`, returning it.
+    pub fn remove(&mut self, name: &str) -> Option<Color> {
+        self.named.remove(name)
+    }
+
+    /// The names of the named colors, in arbitrary order.
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.named.keys().map(String::as_str)
+    }
+
+    /// The indexed color at `index`.
+    pub fn index(&self, index: usize) -> Option<Color> {
+        self.indexed.get(index).copied()
+    }
+
+    /// Sets the indexed color at `index`. Indices skipped over to get there
+    /// are filled with [`MISSING`](Self::MISSING).
+    pub fn set_index(&mut self, index: usize, color: Color) {
+        if index >= self.indexed.len() {
+            self.indexed.resize(index + 1, Self::MISSING);
+        }
+        self.indexed[index] = color;
+    }
+
+    /// The indexed colors.
+    pub fn indexed(&self) -> &[Color] {
+        &self.indexed
+    }
+
+    /// The color `color` stands for, or `None` if it's a name or index
+    /// missing from the palette, or [`PaletteColor::Unchanged`].
+    pub fn resolve(&self, color: &PaletteColor) -> Option<Color> {
+        match color {
+            PaletteColor::Unchanged => None,
+            PaletteColor::Rgba(color) => Some(*color),
+            PaletteColor::Named(name) => self.get(name),
+            PaletteColor::Index(index) => self.index(*index),
+        }
+    }
+
+    /// The color `color` stands for, [`MISSING`](Self::MISSING) if it's a
+    /// name or index missing from the palette, or `None` for
+    /// [`PaletteColor::Unchanged`].
+    pub fn color(&self, color: &PaletteColor) -> Option<Color> {
+        match color {
+            PaletteColor::Unchanged => None,
+            _ => Some(self.resolve(color).unwrap_or(Self::MISSING)),
+        }
+    }
+}
+
+/// A color given as `rrggbb`, `rrggbbaa`, `#rrggbb` or `#rrggbbaa`, with
+/// nothing else on the line.
+fn parse_bare_color(line: &str) -> Option<Color> {
+    let hex = line.strip_prefix('#').unwrap_or(line);
+    crate::rich_text::parse_color(&format!("#{}", hex)).filter(|_| !hex.is_empty())
+}
+
+/// The error returned when parsing palette text with a line that's neither
+/// a comment, a color nor a `name = color` entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsePaletteError {
+    /// The number of the line, counting from 1.
+    pub line: usize,
+    /// The text of the line.
+    pub text: String,
+}
+
+impl fmt::Display for ParsePaletteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {} isn't a palette entry: {:?}",
+            self.line, self.text
+        )
+    }
+}
+
+impl Error for ParsePaletteError {}
+
+/// A color for drawing through a [`Palette`]: a name or index to look up in
+/// it, a plain color, or none at all.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub enum PaletteColor {
+    /// Leaves the color of the cells as it is.
+    #[default]
+    Unchanged,
+    /// A plain color.
+    Rgba(Color),
+    /// The palette's color of the given name.
+    Named(String),
+    /// The palette's indexed color at the given position.
+    Index(usize),
+}
+
+impl From<Color> for PaletteColor {
+    fn from(color: Color) -> Self {
+        Self::Rgba(color)
+    }
+}
+
+impl From<Option<Color>> for PaletteColor {
+    fn from(color: Option<Color>) -> Self {
+        color.map_or(Self::Unchanged, Self::Rgba)
+    }
+}
+
+impl From<&str> for PaletteColor {
+    fn from(name: &str) -> Self {
+        Self::Named(name.to_owned())
+    }
+}
+
+impl From<String> for PaletteColor {
+    fn from(name: String) -> Self {
+        Self::Named(name)
+    }
+}
+
+impl From<usize> for PaletteColor {
+    fn from(index: usize) -> Self {
+        Self::Index(index)
+    }
+}
+
+/// A console borrowed for drawing with the colors of a [`Palette`]. Made by
+/// [`WithPalette::with_palette`].
+pub struct PaletteConsole<'a> {
+    console: &'a mut Console,
+    palette: &'a Palette,
+}
+
+impl fmt::Debug for PaletteConsole<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PaletteConsole")
+            .field("width", &self.console.get_width())
+            .field("height", &self.console.get_height())
+            .field("palette", &self.palette)
+            .finish()
+    }
+}
+
+impl PaletteConsole<'_> {
+    /// The console being drawn on, for drawing with plain colors.
+    pub fn console(&mut self) -> &mut Console {
+        self.console
+    }
+
+    /// The palette the colors are looked up in.
+    pub fn palette(&self) -> &Palette {
+        self.palette
+    }
+
+    fn color(&self, color: impl Into<PaletteColor>) -> Option<Color> {
+        self.palette.color(&color.into())
+    }
+
+    /// [`Console::print`], with palette colors.
+    pub fn print(
+        &mut self,
+        x: i32,
+        y: i32,
+        text: &str,
+        align: TextAlign,
+        fore: impl Into<PaletteColor>,
+        back: impl Into<PaletteColor>,
+    ) {
+        let (fore, back) = (self.color(fore), self.color(back));
+        self.console.print(x, y, text, align, fore, back);
+    }
+
+    /// [`Console::cell`], with palette colors.
+    pub fn cell(
+        &mut self,
+        x: i32,
+        y: i32,
+        ascii: Option<u16>,
+        fore: impl Into<PaletteColor>,
+        back: impl Into<PaletteColor>,
+    ) {
+        let (fore, back) = (self.color(fore), self.color(back));
+        self.console.cell(x, y, ascii, fore, back);
+    }
+
+    /// [`Console::area`], with palette colors.
+    pub fn area(
+        &mut self,
+        (x, y): (i32, i32),
+        (width, height): (u32, u32),
+        fore: impl Into<PaletteColor>,
+        back: impl Into<PaletteColor>,
+        fill_char: Option<u16>,
+    ) {
+        let (fore, back) = (self.color(fore), self.color(back));
+        self.console
+            .area(x, y, width, height, fore, back, fill_char);
+    }
+
+    /// [`Console::clear`], with palette colors.
+    pub fn clear(
+        &mut self,
+        fore: impl Into<PaletteColor>,
+        back: impl Into<PaletteColor>,
+        fill_char: Option<u16>,
+    ) {
+        let (fore, back) = (self.color(fore), self.color(back));
+        self.console.clear(fore, back, fill_char);
+    }
+
+    /// Sets the foreground color of cell `(x, y)`, unless `color` is
+    /// [`PaletteColor::Unchanged`].
+    pub fn fore(&mut self, x: i32, y: i32, color: impl Into<PaletteColor>) {
+        if let Some(color) = self.color(color) {
+            self.console.fore(x, y, color);
+        }
+    }
+
+    /// Sets the background color of cell `(x, y)`, unless `color` is
+    /// [`PaletteColor::Unchanged`].
+    pub fn back(&mut self, x: i32, y: i32, color: impl Into<PaletteColor>) {
+        if let Some(color) = self.color(color) {
+            self.console.back(x, y, color);
+        }
+    }
+}
+
+/// Drawing on a console with palette colors. See the [module
+/// documentation](self).
+pub trait WithPalette {
+    /// Borrows the console for drawing with the colors of `palette`.
+    fn with_palette<'a>(&'a mut self, palette: &'a Palette) -> PaletteConsole<'a>;
+}
+
+impl WithPalette for Console {
+    fn with_palette<'a>(&'a mut self, palette: &'a Palette) -> PaletteConsole<'a> {
+        PaletteConsole {
+            console: self,
+            palette,
+        }
+    }
+}
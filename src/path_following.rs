@@ -0,0 +1,219 @@
+//! Smooth movement of entities along grid paths.
+//!
+//! An entity's place on the map is its [`GridPosition`], which is always a
+//! whole cell. Giving it a [`FollowPath`] component makes it walk a path at
+//! a set speed: its [`RenderPosition`] glides between the cells for drawing,
+//! while the grid position jumps from cell to cell as each one is reached.
+//! Once the last cell is reached, the [`FollowPath`] component is removed and
+//! a [`PathCompleted`] event is sent.
+//!
+//! ```
+//! # use bevy_doryen::path_following::{FollowPath, GridPosition};
+//! # use bevy_ecs::system::Commands;
+//! fn spawn_walker(mut commands: Commands) {
+//!     commands.spawn((
+//!         GridPosition(2, 2),
+//!         FollowPath::new([(2, 2), (3, 2), (4, 3), (5, 3)], 4.0),
+//!     ));
+//! }
+//! ```
+//!
+//! Entities with a grid position but nothing to follow have their render
+//! position kept on their grid position, as do all entities while
+//! [`ReducedMotion`] is enabled.
+
+use crate::pathfinding::Path;
+use crate::{ReducedMotion, UPDATES_PER_SECOND};
+use bevy_app::{App, Plugin, Update};
+use bevy_ecs::component::Component;
+use bevy_ecs::entity::Entity;
+use bevy_ecs::event::{Event, EventWriter};
+use bevy_ecs::query::{Changed, Without};
+use bevy_ecs::schedule::IntoSystemConfigs;
+use bevy_ecs::system::{Commands, Query, Res};
+
+/// The path following plugin. Adds the [`PathCompleted`] event.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct PathFollowingPlugin;
+
+impl Plugin for PathFollowingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PathCompleted>()
+            .add_systems(Update, (follow_paths, snap_render_positions).chain());
+    }
+}
+
+/// The cell an entity is in.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub struct GridPosition(pub i32, pub i32);
+
+/// Where to draw an entity, in console cell coordinates. Fractions put the
+/// entity part of the way between two cells. Entities with a
+/// [`GridPosition`] get one if they don't have one already.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Component)]
+pub struct RenderPosition {
+    /// The horizontal position.
+    pub x: f32,
+    /// The vertical position.
+    pub y: f32,
+}
+
+impl RenderPosition {
+    /// The cell closest to the render position.
+    pub fn cell(&self) -> (i32, i32) {
+        (self.x.round() as i32, self.y.round() as i32)
+    }
+}
+
+impl From<GridPosition> for RenderPosition {
+    fn from(GridPosition(x, y): GridPosition) -> Self {
+        Self {
+            x: x as f32,
+            y: y as f32,
+        }
+    }
+}
+
+/// Component making an entity walk along a path of cells. See the [module
+/// documentation](self).
+#[derive(Debug, Clone, PartialEq, Component)]
+pub struct FollowPath {
+    cells: Vec<(i32, i32)>,
+    /// How many cells per second the entity moves.
+    pub cells_per_second: f32,
+    progress: f32,
+}
+
+impl FollowPath {
+    /// Creates a path through the given cells, in order, walked at
+    /// `cells_per_second`. The first cell is where the walk starts, which is
+    /// usually where the entity already is.
+    pub fn new(cells: impl IntoIterator<Item = (i32, i32)>, cells_per_second: f32) -> Self {
+        Self {
+            cells: cells.into_iter().collect(),
+            cells_per_second,
+            progress: 0.0,
+        }
+    }
+
+    /// Creates a path following a path found by
+    /// [`find_path`](crate::pathfinding::find_path).
+    pub fn from_path(path: &Path, cells_per_second: f32) -> Self {
+        Self::new(path.cells.iter().copied(), cells_per_second)
+    }
+
+    /// The cells of the path.
+    pub fn cells(&self) -> &[(i32, i32)] {
+        &self.cells
+    }
+
+    /// How far along the path the entity is, in cells. `1.5` is halfway
+    /// between the second and third cell.
+    pub fn progress(&self) -> f32 {
+        self.progress
+    }
+
+    /// The cells that haven't been reached yet.
+    pub fn remaining(&self) -> &[(i32, i32)] {
+        let reached = (self.progress as usize + 1).min(self.cells.len());
+        &self.cells[reached..]
+    }
+
+    fn render_position(&self) -> RenderPosition {
+        let index = self.progress as usize;
+        let (x0, y0) = self.cells[index];
+        let (x1, y1) = self.cells.get(index + 1).copied().unwrap_or((x0, y0));
+        let t = self.progress.fract();
+        RenderPosition {
+            x: x0 as f32 + (x1 - x0) as f32 * t,
+            y: y0 as f32 + (y1 - y0) as f32 * t,
+        }
+    }
+}
+
+/// Sent when an entity has reached the end of its [`FollowPath`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Event)]
+pub struct PathCompleted {
+    /// The entity that was following the path.
+    pub entity: Entity,
+    /// The last cell of the path.
+    pub destination: (i32, i32),
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn follow_paths(
+    mut commands: Commands<'_, '_>,
+    mut followers: Query<
+        '_,
+        '_,
+        (
+            Entity,
+            &mut FollowPath,
+            &mut GridPosition,
+            Option<&mut RenderPosition>,
+        ),
+    >,
+    mut path_completed: EventWriter<'_, PathCompleted>,
+    reduced_motion: Option<Res<'_, ReducedMotion>>,
+) {
+    let reduced_motion = ReducedMotion::is_enabled(reduced_motion.as_deref());
+    for (entity, mut follow_path, mut grid_position, render_position) in &mut followers {
+        let last = if let Some(last) = follow_path.cells.len().checked_sub(1) {
+            last as f32
+        } else {
+            commands.entity(entity).remove::<FollowPath>();
+            continue;
+        };
+
+        let step = follow_path.cells_per_second.max(0.0) / UPDATES_PER_SECOND;
+        follow_path.progress = (follow_path.progress + step).min(last);
+
+        let (x, y) = follow_path.cells[follow_path.progress as usize];
+        if *grid_position != GridPosition(x, y) {
+            *grid_position = GridPosition(x, y);
+        }
+        let new_render_position = if reduced_motion {
+            RenderPosition::from(*grid_position)
+        } else {
+            follow_path.render_position()
+        };
+        match render_position {
+            Some(mut render_position) => *render_position = new_render_position,
+            None => {
+                commands.entity(entity).insert(new_render_position);
+            }
+        }
+
+        if follow_path.progress >= last {
+            commands.entity(entity).remove::<FollowPath>();
+            path_completed.send(PathCompleted {
+                entity,
+                destination: (x, y),
+            });
+        }
+    }
+}
+
+/// Entities moved to another cell other than by following a path.
+type MovedDirectly = (Changed<GridPosition>, Without<FollowPath>);
+
+fn snap_render_positions(
+    mut commands: Commands<'_, '_>,
+    mut positions: Query<
+        '_,
+        '_,
+        (Entity, &GridPosition, Option<&mut RenderPosition>),
+        MovedDirectly,
+    >,
+) {
+    for (entity, &grid_position, render_position) in &mut positions {
+        match render_position {
+            Some(mut render_position) => *render_position = grid_position.into(),
+            None => {
+                commands
+                    .entity(entity)
+                    .insert(RenderPosition::from(grid_position));
+            }
+        }
+    }
+}
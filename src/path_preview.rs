@@ -0,0 +1,140 @@
+//! Drawing computed paths on top of the console, for auto-travel previews and
+//! AI debugging.
+
+use crate::doryen::Color;
+use crate::{
+    PostRenderSet, ReducedMotion, RenderStage, RenderSystemExtensions, RootConsole,
+    UPDATES_PER_SECOND,
+};
+use bevy_app::{App, Plugin, Update};
+use bevy_ecs::schedule::IntoSystemConfigs;
+use bevy_ecs::system::{Res, ResMut, Resource};
+
+/// The plugin that adds the path preview overlay. Set the path to show with
+/// [`PathPreview::set_path`].
+#[derive(Default, Clone, Copy, Debug)]
+pub struct PathPreviewPlugin;
+
+impl Plugin for PathPreviewPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PathPreview>()
+            .add_systems(Update, animate_path_preview)
+            .add_doryen_render_systems_to_stage(
+                RenderStage::PostRender,
+                render_path_preview.in_set(PostRenderSet::Overlay),
+            );
+    }
+}
+
+/// How a previewed path is drawn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathPreviewStyle {
+    /// The character drawn on each cell of the path. `None` leaves the
+    /// characters alone.
+    pub glyph: Option<u16>,
+    /// The character drawn on the last cell of the path. `None` uses `glyph`.
+    pub end_glyph: Option<u16>,
+    /// The foreground color of the path. `None` leaves the colors alone.
+    pub fore: Option<Color>,
+    /// The background color of the path. `None` leaves the colors alone.
+    pub back: Option<Color>,
+    /// The background color of the animated cell travelling along the path.
+    pub head_color: Color,
+    /// How many cells per second the animated cell travels. Set to `0.0` to
+    /// disable the animation. It's also disabled while [`ReducedMotion`] is
+    /// enabled.
+    pub cells_per_second: f32,
+}
+
+impl Default for PathPreviewStyle {
+    fn default() -> Self {
+        Self {
+            glyph: Some(250),
+            end_glyph: Some('X' as u16),
+            fore: Some((255, 255, 128, 255)),
+            back: None,
+            head_color: (128, 128, 64, 255),
+            cells_per_second: 15.0,
+        }
+    }
+}
+
+/// Resource holding the path to preview.
+#[derive(Default, Debug, Clone, Resource)]
+pub struct PathPreview {
+    path: Vec<(i32, i32)>,
+    progress: f32,
+    /// How the path is drawn.
+    pub style: PathPreviewStyle,
+}
+
+impl PathPreview {
+    /// Sets the path to preview, in order from start to end, and restarts the
+    /// traversal animation.
+    pub fn set_path<I: IntoIterator<Item = (i32, i32)>>(&mut self, path: I) {
+        self.path.clear();
+        self.path.extend(path);
+        self.progress = 0.0;
+    }
+
+    /// Stops previewing any path.
+    pub fn clear(&mut self) {
+        self.path.clear();
+        self.progress = 0.0;
+    }
+
+    /// The path currently being previewed.
+    pub fn path(&self) -> &[(i32, i32)] {
+        &self.path
+    }
+
+    /// The index into [`path`](Self::path) of the cell the traversal animation
+    /// is currently on, if there is a path and the animation is enabled.
+    pub fn head(&self) -> Option<usize> {
+        if self.path.is_empty() || self.style.cells_per_second <= 0.0 {
+            None
+        } else {
+            Some(self.progress as usize % self.path.len())
+        }
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn animate_path_preview(
+    reduced_motion: Option<Res<'_, ReducedMotion>>,
+    mut path_preview: ResMut<'_, PathPreview>,
+) {
+    if path_preview.path.is_empty() || ReducedMotion::is_enabled(reduced_motion.as_deref()) {
+        return;
+    }
+
+    let len = path_preview.path.len() as f32;
+    let step = path_preview.style.cells_per_second / UPDATES_PER_SECOND;
+    path_preview.progress = (path_preview.progress + step) % len;
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn render_path_preview(
+    path_preview: Res<'_, PathPreview>,
+    reduced_motion: Option<Res<'_, ReducedMotion>>,
+    mut root_console: ResMut<'_, RootConsole>,
+) {
+    let style = &path_preview.style;
+    let last = path_preview.path.len().saturating_sub(1);
+    for (i, &(x, y)) in path_preview.path.iter().enumerate() {
+        let glyph = if i == last {
+            style.end_glyph.or(style.glyph)
+        } else {
+            style.glyph
+        };
+        root_console.cell(x, y, glyph, style.fore, style.back);
+    }
+
+    if ReducedMotion::is_enabled(reduced_motion.as_deref()) {
+        return;
+    }
+    if let Some(head) = path_preview.head() {
+        let (x, y) = path_preview.path[head];
+        root_console.back(x, y, style.head_color);
+    }
+}
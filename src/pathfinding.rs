@@ -0,0 +1,350 @@
+//! A* pathfinding over weighted terrain.
+//!
+//! The map is a [`TerrainGrid`] resource holding a [`TerrainId`] per cell.
+//! What those ids mean for movement is up to each actor: a [`TraversalRules`]
+//! gives the cost of entering each kind of terrain, or blocks it outright, so
+//! a flying monster can be given rules that let it cross water while walkers
+//! go around:
+//!
+//! ```
+//! # use bevy_doryen::pathfinding::{find_path, TerrainGrid, TraversalRules};
+//! const FLOOR: u16 = 0;
+//! const WATER: u16 = 1;
+//! let mut grid = TerrainGrid::new(5, 3, FLOOR);
+//! for y in 0..2 {
+//!     grid.set((2, y), WATER);
+//! }
+//!
+//! let walker = TraversalRules::new(2).blocked(WATER);
+//! let flyer = TraversalRules::new(2);
+//! let walked = find_path(&grid, &walker, (0, 0), (4, 0)).unwrap();
+//! let flown = find_path(&grid, &flyer, (0, 0), (4, 0)).unwrap();
+//! assert!(walked.cost > flown.cost);
+//! ```
+//!
+//! Paths that get asked for again and again, like a monster chasing the
+//! player, can be kept in the [`PathCache`]. With [`PathfindingPlugin`]
+//! added, cached paths are thrown away when the terrain they cross changes.
+
+use bevy_app::{App, Plugin, PostUpdate};
+use bevy_ecs::system::{ResMut, Resource};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+/// The pathfinding plugin. Adds the [`PathCache`] resource, and keeps it in
+/// line with the [`TerrainGrid`] resource, if there is one.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct PathfindingPlugin;
+
+impl Plugin for PathfindingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PathCache>()
+            .add_systems(PostUpdate, invalidate_changed_paths);
+    }
+}
+
+/// Identifies a kind of terrain, such as floor, water or lava.
+pub type TerrainId = u16;
+
+/// Resource holding the terrain of every cell of the map.
+#[derive(Debug, Clone, PartialEq, Eq, Resource)]
+pub struct TerrainGrid {
+    width: u32,
+    height: u32,
+    cells: Vec<TerrainId>,
+    changed: HashSet<(i32, i32)>,
+}
+
+impl TerrainGrid {
+    /// Creates a grid of the given size filled with `terrain`.
+    pub fn new(width: u32, height: u32, terrain: TerrainId) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![terrain; (width * height) as usize],
+            changed: HashSet::new(),
+        }
+    }
+
+    /// The width of the grid, in cells.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of the grid, in cells.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Whether the cell is inside the grid.
+    pub fn contains(&self, (x, y): (i32, i32)) -> bool {
+        x >= 0 && y >= 0 && (x as u32) < self.width && (y as u32) < self.height
+    }
+
+    /// The terrain of a cell, or `None` if it's outside the grid.
+    pub fn get(&self, cell: (i32, i32)) -> Option<TerrainId> {
+        self.index(cell).map(|index| self.cells[index])
+    }
+
+    /// Changes the terrain of a cell. Cells outside the grid are ignored.
+    pub fn set(&mut self, cell: (i32, i32), terrain: TerrainId) {
+        if let Some(index) = self.index(cell) {
+            if self.cells[index] != terrain {
+                self.cells[index] = terrain;
+                self.changed.insert(cell);
+            }
+        }
+    }
+
+    fn index(&self, cell: (i32, i32)) -> Option<usize> {
+        if self.contains(cell) {
+            Some((cell.1 as u32 * self.width + cell.0 as u32) as usize)
+        } else {
+            None
+        }
+    }
+}
+
+/// How an actor moves over the terrain: what each kind of terrain costs to
+/// enter, and which kinds it can't enter at all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraversalRules {
+    /// The cost of entering terrain without a cost of its own.
+    pub default_cost: Option<u32>,
+    /// Whether the actor can move diagonally. Defaults to `true`.
+    pub diagonal: bool,
+    costs: HashMap<TerrainId, Option<u32>>,
+}
+
+impl TraversalRules {
+    /// Rules where entering any terrain costs `default_cost`.
+    pub fn new(default_cost: u32) -> Self {
+        Self {
+            default_cost: Some(default_cost),
+            diagonal: true,
+            costs: HashMap::new(),
+        }
+    }
+
+    /// Rules where no terrain can be entered unless given a cost with
+    /// [`with_cost`](Self::with_cost).
+    pub fn blocked_by_default() -> Self {
+        Self {
+            default_cost: None,
+            ..Self::new(0)
+        }
+    }
+
+    /// Makes entering `terrain` cost `cost`.
+    pub fn with_cost(mut self, terrain: TerrainId, cost: u32) -> Self {
+        self.costs.insert(terrain, Some(cost));
+        self
+    }
+
+    /// Makes `terrain` impossible to enter.
+    pub fn blocked(mut self, terrain: TerrainId) -> Self {
+        self.costs.insert(terrain, None);
+        self
+    }
+
+    /// Sets whether the actor can move diagonally.
+    pub fn with_diagonal(mut self, diagonal: bool) -> Self {
+        self.diagonal = diagonal;
+        self
+    }
+
+    /// The cost of entering `terrain`, or `None` if it can't be entered.
+    pub fn cost(&self, terrain: TerrainId) -> Option<u32> {
+        self.costs
+            .get(&terrain)
+            .copied()
+            .unwrap_or(self.default_cost)
+    }
+
+    fn min_cost(&self) -> u32 {
+        self.costs
+            .values()
+            .chain(std::iter::once(&self.default_cost))
+            .filter_map(|&cost| cost)
+            .min()
+            .unwrap_or(0)
+    }
+}
+
+/// A path found by [`find_path`].
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Path {
+    /// The cells of the path, from the start to the goal, both included.
+    pub cells: Vec<(i32, i32)>,
+    /// The total cost of moving along the path.
+    pub cost: u32,
+}
+
+/// Finds the cheapest path from `from` to `to` over the grid, moving as
+/// `rules` allow, or `None` if there is none.
+///
+/// Moving onto a cell costs what its terrain costs under the rules; moving
+/// diagonally costs half as much again. Cells outside the grid can't be
+/// entered. The starting cell doesn't need to be enterable.
+pub fn find_path(
+    grid: &TerrainGrid,
+    rules: &TraversalRules,
+    from: (i32, i32),
+    to: (i32, i32),
+) -> Option<Path> {
+    find_path_with(from, to, rules.diagonal, rules.min_cost(), |cell| {
+        grid.get(cell).and_then(|terrain| rules.cost(terrain))
+    })
+}
+
+/// Finds the cheapest path from `from` to `to`, using `cost` to tell what
+/// entering a cell costs, or `None` if the cell can't be entered.
+///
+/// This is [`find_path`] for maps that aren't kept in a [`TerrainGrid`].
+/// `min_cost` must not be more than the cheapest cell costs, or the path
+/// found may not be the cheapest.
+pub fn find_path_with(
+    from: (i32, i32),
+    to: (i32, i32),
+    diagonal: bool,
+    min_cost: u32,
+    mut cost: impl FnMut((i32, i32)) -> Option<u32>,
+) -> Option<Path> {
+    const ORTHOGONAL: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+    const DIAGONAL: [(i32, i32); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+    let heuristic = |(x, y): (i32, i32)| {
+        let (dx, dy) = ((to.0 - x).unsigned_abs(), (to.1 - y).unsigned_abs());
+        if diagonal {
+            let (short, long) = (dx.min(dy), dx.max(dy));
+            min_cost * (long - short) + diagonal_cost(min_cost) * short
+        } else {
+            min_cost * (dx + dy)
+        }
+    };
+
+    let mut open = BinaryHeap::new();
+    let mut best: HashMap<(i32, i32), u32> = HashMap::new();
+    let mut came_from: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
+    best.insert(from, 0);
+    open.push(Reverse((heuristic(from), 0, from)));
+
+    while let Some(Reverse((_, so_far, cell))) = open.pop() {
+        if cell == to {
+            let mut cells = vec![cell];
+            let mut current = cell;
+            while let Some(&previous) = came_from.get(&current) {
+                cells.push(previous);
+                current = previous;
+            }
+            cells.reverse();
+            return Some(Path {
+                cells,
+                cost: so_far,
+            });
+        }
+        if best.get(&cell).is_some_and(|&known| known < so_far) {
+            continue;
+        }
+
+        let steps = ORTHOGONAL
+            .iter()
+            .map(|&step| (step, false))
+            .chain(DIAGONAL.iter().map(|&step| (step, true)))
+            .filter(|&(_, is_diagonal)| diagonal || !is_diagonal);
+        for ((dx, dy), is_diagonal) in steps {
+            let next = (cell.0 + dx, cell.1 + dy);
+            let step_cost = match cost(next) {
+                Some(step_cost) if is_diagonal => diagonal_cost(step_cost),
+                Some(step_cost) => step_cost,
+                None => continue,
+            };
+            let next_cost = so_far.saturating_add(step_cost);
+            if best.get(&next).is_none_or(|&known| next_cost < known) {
+                best.insert(next, next_cost);
+                came_from.insert(next, cell);
+                open.push(Reverse((
+                    next_cost.saturating_add(heuristic(next)),
+                    next_cost,
+                    next,
+                )));
+            }
+        }
+    }
+    None
+}
+
+#[inline]
+fn diagonal_cost(cost: u32) -> u32 {
+    cost + cost / 2
+}
+
+/// Resource caching paths between calls to [`find_path`].
+///
+/// Paths are cached per key, which identifies whose path it is, such as
+/// [`Entity::to_bits`](bevy_ecs::entity::Entity::to_bits), so actors with
+/// different [`TraversalRules`] don't share paths. Each key holds one path at
+/// a time.
+///
+/// With [`PathfindingPlugin`] added, cached paths crossing a cell whose
+/// terrain changed are thrown away at the end of the update. Other cached
+/// paths are kept even if the change opened up a cheaper route; call
+/// [`clear`](Self::clear) when that matters.
+#[derive(Default, Debug, Clone, Resource)]
+pub struct PathCache {
+    paths: HashMap<u64, CachedPath>,
+}
+
+/// The start and goal of a cached search, and the path it found, if any.
+type CachedPath = ((i32, i32), (i32, i32), Option<Path>);
+
+impl PathCache {
+    /// Returns the cached path for `key` if it goes from `from` to `to`, and
+    /// finds and caches a new one otherwise.
+    pub fn find_path(
+        &mut self,
+        key: u64,
+        grid: &TerrainGrid,
+        rules: &TraversalRules,
+        from: (i32, i32),
+        to: (i32, i32),
+    ) -> Option<&Path> {
+        let cached = self.paths.get(&key);
+        if !matches!(cached, Some(&(start, goal, _)) if start == from && goal == to) {
+            self.paths
+                .insert(key, (from, to, find_path(grid, rules, from, to)));
+        }
+        self.paths[&key].2.as_ref()
+    }
+
+    /// Forgets the cached path for `key`.
+    pub fn invalidate(&mut self, key: u64) {
+        self.paths.remove(&key);
+    }
+
+    /// Forgets every cached path that crosses one of the given cells.
+    /// Failed searches are forgotten too, since a route may have opened up.
+    pub fn invalidate_cells(&mut self, cells: &HashSet<(i32, i32)>) {
+        self.paths.retain(|_, (_, _, path)| match path {
+            Some(path) => !path.cells.iter().any(|cell| cells.contains(cell)),
+            None => false,
+        });
+    }
+
+    /// Forgets every cached path.
+    pub fn clear(&mut self) {
+        self.paths.clear();
+    }
+}
+
+fn invalidate_changed_paths(
+    grid: Option<ResMut<'_, TerrainGrid>>,
+    mut path_cache: ResMut<'_, PathCache>,
+) {
+    let mut grid = match grid {
+        Some(grid) if !grid.changed.is_empty() => grid,
+        _ => return,
+    };
+    path_cache.invalidate_cells(&grid.changed);
+    grid.changed.clear();
+}
@@ -0,0 +1,423 @@
+//! An in-console profiler showing where each frame's time goes.
+//!
+//! [`ProfilerPlugin`] times every schedule of the Bevy update, like `First`,
+//! `Update` and `Last`, and every [`RenderStage`] of the render schedule.
+//! Pressing the [toggle key](Profiler::toggle_key) shows the timings on top
+//! of the root console as a flamegraph, with the update and render phases on
+//! the first row and their stages in proportion below, followed by a bar
+//! chart of all the stages. The arrow keys select a stage and Tab switches
+//! between average and worst timings. Since it needs nothing but the console,
+//! it works everywhere bevy_doryen does, including on the web.
+//!
+//! Only whole schedules and stages are timed, not the systems within them.
+//! Schedules added to the main schedule order after the plugin was added
+//! aren't timed separately, but count towards the schedule before them.
+
+use crate::doryen::{Color, Console, TextAlign};
+use crate::window::Window;
+use crate::{DoryenRender, Input, Key, RenderStage, RenderSystemExtensions, RootConsole};
+use bevy_app::{App as BevyApp, MainScheduleOrder, Plugin, PreUpdate};
+use bevy_ecs::schedule::{IntoSystemConfigs, ScheduleLabel};
+use bevy_ecs::system::{Local, Res, ResMut, Resource};
+use bevy_utils::{Duration, Instant};
+use std::collections::VecDeque;
+
+/// How many samples the averages are taken over.
+const SAMPLES: usize = 60;
+
+const RENDER_STAGES: [(RenderStage, &str); 5] = [
+    (RenderStage::First, "render First"),
+    (RenderStage::PreRender, "PreRender"),
+    (RenderStage::Render, "Render"),
+    (RenderStage::PostRender, "PostRender"),
+    (RenderStage::Last, "render Last"),
+];
+
+const PALETTE: [Color; 4] = [
+    (70, 110, 170, 255),
+    (60, 140, 110, 255),
+    (150, 110, 60, 255),
+    (130, 80, 140, 255),
+];
+
+/// The profiler plugin. See the [module documentation](self).
+#[derive(Default, Clone, Copy, Debug)]
+pub struct ProfilerPlugin;
+
+impl Plugin for ProfilerPlugin {
+    fn build(&self, app: &mut BevyApp) {
+        // Put a marking schedule before the first schedule of the update and
+        // after every schedule, so the time between two marks is the time one
+        // schedule took
+        let mut update_stages = Vec::new();
+        if let Some(mut order) = app.world.get_resource_mut::<MainScheduleOrder>() {
+            let labels = std::mem::take(&mut order.labels);
+            order.labels.push(Box::new(UpdateMark(0)));
+            for (index, label) in labels.into_iter().enumerate() {
+                update_stages.push(format!("{:?}", label));
+                order.labels.push(label);
+                order.labels.push(Box::new(UpdateMark(index + 1)));
+            }
+        }
+        for index in 0..=update_stages.len() {
+            app.add_systems(
+                UpdateMark(index),
+                move |mut profiler: ResMut<'_, Profiler>| profiler.mark(Phase::Update, index),
+            );
+        }
+
+        // The render stages run in order, so marks go between them
+        let render_mark = |index: usize| {
+            move |mut profiler: ResMut<'_, Profiler>| profiler.mark(Phase::Render, index)
+        };
+        app.add_systems(DoryenRender, render_mark(0).before(RenderStage::First));
+        for (index, pair) in RENDER_STAGES.windows(2).enumerate() {
+            app.add_systems(
+                DoryenRender,
+                render_mark(index + 1).after(pair[0].0).before(pair[1].0),
+            );
+        }
+        app.add_systems(
+            DoryenRender,
+            render_mark(RENDER_STAGES.len()).after(RenderStage::Last),
+        );
+
+        app.insert_resource(Profiler::new(update_stages))
+            .add_systems(PreUpdate, navigate_profiler)
+            .add_doryen_render_systems_to_stage(
+                RenderStage::Last,
+                render_profiler.before(crate::render_error::render_error_overlay),
+            );
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, ScheduleLabel)]
+struct UpdateMark(usize);
+
+/// Which part of a frame a stage belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    /// The Bevy update.
+    Update,
+    /// The [`DoryenRender`] schedule.
+    Render,
+}
+
+/// The timings of one schedule or render stage.
+#[derive(Debug, Clone)]
+pub struct StageTiming {
+    name: String,
+    phase: Phase,
+    samples: VecDeque<Duration>,
+}
+
+impl StageTiming {
+    fn new(name: impl Into<String>, phase: Phase) -> Self {
+        Self {
+            name: name.into(),
+            phase,
+            samples: VecDeque::with_capacity(SAMPLES),
+        }
+    }
+
+    /// The name of the schedule or render stage.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Which part of the frame the stage belongs to.
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// How long the stage took the last time it ran.
+    pub fn last(&self) -> Option<Duration> {
+        self.samples.back().copied()
+    }
+
+    /// The average time the stage took over the last 60 runs.
+    pub fn average(&self) -> Option<Duration> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        Some(self.samples.iter().sum::<Duration>() / self.samples.len() as u32)
+    }
+
+    /// The longest time the stage took in the last 60 runs.
+    pub fn max(&self) -> Option<Duration> {
+        self.samples.iter().max().copied()
+    }
+}
+
+/// Resource holding the stage timings and the state of the profiler view.
+/// See the [module documentation](self).
+#[derive(Debug, Clone, Resource)]
+pub struct Profiler {
+    /// Whether the profiler view is drawn. Timings are taken either way.
+    /// Defaults to `false`.
+    pub visible: bool,
+    /// The key that shows and hides the profiler view. Defaults to `"F3"`.
+    pub toggle_key: String,
+    /// Whether the view shows the worst timings rather than the averages.
+    /// Defaults to `false`.
+    pub show_max: bool,
+    selected: usize,
+    stages: Vec<StageTiming>,
+    update_mark: Option<Instant>,
+    render_mark: Option<Instant>,
+}
+
+impl Profiler {
+    fn new(update_stages: Vec<String>) -> Self {
+        let stages = update_stages
+            .into_iter()
+            .map(|name| StageTiming::new(name, Phase::Update))
+            .chain(
+                RENDER_STAGES
+                    .iter()
+                    .map(|&(_, name)| StageTiming::new(name, Phase::Render)),
+            )
+            .collect();
+        Self {
+            visible: false,
+            toggle_key: String::from("F3"),
+            show_max: false,
+            selected: 0,
+            stages,
+            update_mark: None,
+            render_mark: None,
+        }
+    }
+
+    /// The timings of every stage, update stages first.
+    pub fn stages(&self) -> &[StageTiming] {
+        &self.stages
+    }
+
+    /// The stage selected in the profiler view.
+    pub fn selected(&self) -> &StageTiming {
+        &self.stages[self.selected]
+    }
+
+    /// The average time taken by all the stages of a phase.
+    pub fn phase_average(&self, phase: Phase) -> Duration {
+        self.phase_stages(phase)
+            .filter_map(StageTiming::average)
+            .sum()
+    }
+
+    fn phase_stages(&self, phase: Phase) -> impl Iterator<Item = &StageTiming> + '_ {
+        self.stages.iter().filter(move |stage| stage.phase == phase)
+    }
+
+    fn mark(&mut self, phase: Phase, index: usize) {
+        let now = Instant::now();
+        let first = match phase {
+            Phase::Update => 0,
+            Phase::Render => self.phase_stages(Phase::Update).count(),
+        };
+        let mark = match phase {
+            Phase::Update => &mut self.update_mark,
+            Phase::Render => &mut self.render_mark,
+        };
+        let previous = mark.replace(now);
+        if let (Some(previous), Some(stage)) = (
+            previous.filter(|_| index > 0),
+            index
+                .checked_sub(1)
+                .and_then(|i| self.stages.get_mut(first + i)),
+        ) {
+            if stage.samples.len() == SAMPLES {
+                stage.samples.pop_front();
+            }
+            stage.samples.push_back(now.duration_since(previous));
+        }
+    }
+
+    fn measure(&self, stage: &StageTiming) -> Duration {
+        if self.show_max {
+            stage.max()
+        } else {
+            stage.average()
+        }
+        .unwrap_or_default()
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn navigate_profiler(input: Res<'_, Input>, mut profiler: ResMut<'_, Profiler>) {
+    if input.key_pressed(&profiler.toggle_key) {
+        profiler.visible = !profiler.visible;
+    }
+    if !profiler.visible {
+        return;
+    }
+
+    let count = profiler.stages.len();
+    if input.key_pressed(Key::ArrowDown) {
+        profiler.selected = (profiler.selected + 1) % count;
+    }
+    if input.key_pressed(Key::ArrowUp) {
+        profiler.selected = (profiler.selected + count - 1) % count;
+    }
+    if input.key_pressed(Key::Tab) {
+        profiler.show_max = !profiler.show_max;
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn render_profiler(
+    profiler: Res<'_, Profiler>,
+    mut root_console: ResMut<'_, RootConsole>,
+    mut window: Local<'_, Option<Window>>,
+) {
+    if !profiler.visible {
+        return;
+    }
+
+    let width = root_console.get_width().min(72);
+    let height = (profiler.stages.len() as u32 + 6).min(root_console.get_height());
+    let resized = window
+        .as_ref()
+        .is_some_and(|window| window.width() != width || window.height() != height);
+    if resized {
+        *window = None;
+    }
+    let window =
+        window.get_or_insert_with(|| Window::new(0, 0, width, height).with_title("Profiler"));
+    window.clear();
+    draw_timings(&profiler, window.interior());
+    window.draw(&mut root_console);
+}
+
+fn draw_timings(profiler: &Profiler, console: &mut Console) {
+    let width = console.get_width() as i32;
+    let (fore, dim) = ((255, 255, 255, 255), (150, 150, 150, 255));
+    let millis = |duration: Duration| duration.as_secs_f64() * 1000.0;
+
+    let phase_measure = |phase| {
+        profiler
+            .phase_stages(phase)
+            .map(|stage| profiler.measure(stage))
+            .sum::<Duration>()
+    };
+    let phases = [
+        (Phase::Update, phase_measure(Phase::Update)),
+        (Phase::Render, phase_measure(Phase::Render)),
+    ];
+    let header = format!(
+        "update {:.2} ms  render {:.2} ms  ({}, Tab to switch)",
+        millis(phases[0].1),
+        millis(phases[1].1),
+        if profiler.show_max {
+            "worst"
+        } else {
+            "average"
+        },
+    );
+    console.print(0, 0, &header, TextAlign::Left, Some(dim), None);
+
+    // The flamegraph: phases on the first row, their stages below
+    let phase_edges = split(0, width, phases.iter().map(|&(_, measure)| measure));
+    let mut stage_index = 0;
+    for (p, &(phase, _)) in phases.iter().enumerate() {
+        let (start, end) = (phase_edges[p], phase_edges[p + 1]);
+        let name = match phase {
+            Phase::Update => "update",
+            Phase::Render => "render",
+        };
+        bar(console, start, 1, end - start, name, fore, PALETTE[p]);
+
+        let stages: Vec<_> = profiler.phase_stages(phase).collect();
+        let edges = split(
+            start,
+            end,
+            stages.iter().map(|stage| profiler.measure(stage)),
+        );
+        for (s, stage) in stages.iter().enumerate() {
+            let back = if stage_index == profiler.selected {
+                fore
+            } else {
+                PALETTE[(p + s + 1) % PALETTE.len()]
+            };
+            let text = if stage_index == profiler.selected {
+                (0, 0, 0, 255)
+            } else {
+                fore
+            };
+            bar(
+                console,
+                edges[s],
+                2,
+                edges[s + 1] - edges[s],
+                &stage.name,
+                text,
+                back,
+            );
+            stage_index += 1;
+        }
+    }
+
+    // The bar chart, one row per stage
+    let longest = profiler
+        .stages
+        .iter()
+        .map(|stage| profiler.measure(stage))
+        .max()
+        .unwrap_or_default();
+    let bar_start = 32;
+    for (index, stage) in profiler.stages.iter().enumerate() {
+        let y = index as i32 + 4;
+        let selected = index == profiler.selected;
+        let measure = profiler.measure(stage);
+        let line = format!(
+            "{} {:<18} {:>8.2} ms",
+            if selected { '>' } else { ' ' },
+            stage.name,
+            millis(measure),
+        );
+        let color = if selected { fore } else { dim };
+        console.print(0, y, &line, TextAlign::Left, Some(color), None);
+        let edges = split(bar_start, width, [measure, longest.saturating_sub(measure)]);
+        let back = PALETTE[(index + 1) % PALETTE.len()];
+        bar(console, bar_start, y, edges[1] - bar_start, "", fore, back);
+    }
+}
+
+/// Splits `start..end` into consecutive ranges in proportion to `measures`,
+/// returning the edges of the ranges. Without any time measured, the ranges
+/// are all the same size.
+fn split(start: i32, end: i32, measures: impl IntoIterator<Item = Duration>) -> Vec<i32> {
+    let measures: Vec<f64> = measures.into_iter().map(|m| m.as_secs_f64()).collect();
+    let total: f64 = measures.iter().sum();
+    let width = f64::from(end - start);
+    let mut edges = vec![start];
+    let mut sum = 0.0;
+    for (i, measure) in measures.iter().enumerate() {
+        sum += measure;
+        let fraction = if total > 0.0 {
+            sum / total
+        } else {
+            (i + 1) as f64 / measures.len() as f64
+        };
+        edges.push(start + (fraction * width).round() as i32);
+    }
+    edges
+}
+
+fn bar(console: &mut Console, x: i32, y: i32, width: i32, text: &str, fore: Color, back: Color) {
+    if width <= 0 {
+        return;
+    }
+    console.area(
+        x,
+        y,
+        width as u32,
+        1,
+        Some(fore),
+        Some(back),
+        Some(' ' as u16),
+    );
+    let text: String = text.chars().take(width as usize).collect();
+    console.print(x, y, &text, TextAlign::Left, Some(fore), Some(back));
+}
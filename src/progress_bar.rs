@@ -0,0 +1,326 @@
+//! Bars showing how full something is, like hit points, mana or loading.
+//!
+//! [`DrawBar::draw_bar`] draws a bar on any console, filled to a fraction
+//! between `0.0` and `1.0` in the way a [`BarStyle`] says:
+//!
+//! ```
+//! # use bevy_doryen::doryen::Console;
+//! # use bevy_doryen::progress_bar::{BarStyle, ColorRamp, DrawBar};
+//! # let mut root_console = Console::new(80, 45);
+//! let style = BarStyle::default().with_colors(ColorRamp::health());
+//! root_console.draw_bar(((1, 1), (20, 1)), 12.0 / 30.0, &style, Some("HP 12/30"));
+//! ```
+//!
+//! [`ProgressBar`] components are drawn on the root console every frame by
+//! [`ProgressBarPlugin`], for bars that stay on screen:
+//!
+//! ```
+//! # use bevy_doryen::progress_bar::{BarOrientation, BarStyle, ProgressBar};
+//! # use bevy_ecs::system::Commands;
+//! fn spawn_mana_gauge(mut commands: Commands) {
+//!     let style = BarStyle::default().with_orientation(BarOrientation::Vertical);
+//!     commands.spawn(ProgressBar::new(((78, 2), (1, 10)), 50.0).with_style(style));
+//! }
+//! ```
+//!
+//! Bars are filled either a cell at a time, or with the CP437 half blocks
+//! (`▌` and `▄`), which doubles how finely they show the fraction.
+
+use crate::doryen::{color_blend, Color, Console, TextAlign};
+use crate::{RenderSystemExtensions, RootConsole};
+use bevy_app::{App, Plugin};
+use bevy_ecs::component::Component;
+use bevy_ecs::system::{Query, ResMut};
+
+/// The CP437 left half block, `▌`.
+const LEFT_HALF_BLOCK: u16 = 221;
+/// The CP437 lower half block, `▄`.
+const LOWER_HALF_BLOCK: u16 = 220;
+
+/// The plugin that draws [`ProgressBar`] components on the root console.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct ProgressBarPlugin;
+
+impl Plugin for ProgressBarPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_doryen_render_systems(render_progress_bars);
+    }
+}
+
+/// Which way a bar fills.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BarOrientation {
+    /// From the left edge to the right edge.
+    Horizontal,
+    /// From the bottom edge to the top edge.
+    Vertical,
+}
+
+/// How finely a bar shows the fraction it's filled to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BarFill {
+    /// A whole cell at a time.
+    Cells,
+    /// Half a cell at a time, using half-block glyphs.
+    Smooth,
+}
+
+/// Colors picked by how full a bar is. Each stop gives the color from its
+/// fraction up to the next stop's; below the first stop, the first color is
+/// used. [Blended](Self::blended) ramps fade from one stop's color to the
+/// next instead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorRamp {
+    stops: Vec<(f32, Color)>,
+    blend: bool,
+}
+
+impl ColorRamp {
+    /// A ramp that's always `color`.
+    pub fn solid(color: Color) -> Self {
+        Self {
+            stops: vec![(0.0, color)],
+            blend: false,
+        }
+    }
+
+    /// Red below a quarter, yellow below a half, and green above, for hit
+    /// points.
+    pub fn health() -> Self {
+        Self::solid((192, 32, 32, 255))
+            .with_stop(0.25, (224, 192, 32, 255))
+            .with_stop(0.5, (32, 192, 32, 255))
+    }
+
+    /// Adds a stop at `fraction`, replacing any already there.
+    pub fn with_stop(mut self, fraction: f32, color: Color) -> Self {
+        self.stops.retain(|&(at, _)| at != fraction);
+        let index = self.stops.partition_point(|&(at, _)| at < fraction);
+        self.stops.insert(index, (fraction, color));
+        self
+    }
+
+    /// Fades from one stop's color to the next, rather than switching at
+    /// each stop.
+    pub fn blended(mut self) -> Self {
+        self.blend = true;
+        self
+    }
+
+    /// The color of a bar filled to `fraction`.
+    pub fn color_at(&self, fraction: f32) -> Color {
+        let next = self.stops.partition_point(|&(at, _)| at <= fraction);
+        match (
+            next.checked_sub(1).map(|i| self.stops[i]),
+            self.stops.get(next),
+        ) {
+            (Some((from_at, from)), Some(&(to_at, to))) if self.blend => {
+                color_blend(from, to, (fraction - from_at) / (to_at - from_at))
+            }
+            (Some((_, color)), _) | (None, Some(&(_, color))) => color,
+            (None, None) => (255, 255, 255, 255),
+        }
+    }
+}
+
+impl Default for ColorRamp {
+    fn default() -> Self {
+        Self::solid((192, 32, 32, 255))
+    }
+}
+
+/// How a bar looks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BarStyle {
+    /// Which way the bar fills.
+    pub orientation: BarOrientation,
+    /// How finely the bar shows the fraction it's filled to.
+    pub fill: BarFill,
+    /// The color of the filled part.
+    pub colors: ColorRamp,
+    /// The color of the empty part.
+    pub empty: Color,
+    /// The color of the label.
+    pub label_fore: Color,
+}
+
+impl Default for BarStyle {
+    fn default() -> Self {
+        Self {
+            orientation: BarOrientation::Horizontal,
+            fill: BarFill::Smooth,
+            colors: ColorRamp::default(),
+            empty: (48, 48, 48, 255),
+            label_fore: (255, 255, 255, 255),
+        }
+    }
+}
+
+impl BarStyle {
+    /// Sets which way the bar fills.
+    pub fn with_orientation(mut self, orientation: BarOrientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Sets how finely the bar shows the fraction it's filled to.
+    pub fn with_fill(mut self, fill: BarFill) -> Self {
+        self.fill = fill;
+        self
+    }
+
+    /// Sets the color of the filled part.
+    pub fn with_colors(mut self, colors: ColorRamp) -> Self {
+        self.colors = colors;
+        self
+    }
+
+    /// Sets the color of the empty part.
+    pub fn with_empty(mut self, empty: Color) -> Self {
+        self.empty = empty;
+        self
+    }
+}
+
+/// Drawing bars. See the [module documentation](self).
+pub trait DrawBar {
+    /// Draws a bar filling the rectangle of `size` cells with its top-left
+    /// cell at `position`, filled to `fraction`, clamped between `0.0` and
+    /// `1.0`. The `label`, if any, is printed in the middle of the bar.
+    fn draw_bar(
+        &mut self,
+        rect: ((i32, i32), (u32, u32)),
+        fraction: f32,
+        style: &BarStyle,
+        label: Option<&str>,
+    );
+}
+
+impl DrawBar for Console {
+    fn draw_bar(
+        &mut self,
+        ((x, y), (width, height)): ((i32, i32), (u32, u32)),
+        fraction: f32,
+        style: &BarStyle,
+        label: Option<&str>,
+    ) {
+        let fraction = if fraction.is_nan() {
+            0.0
+        } else {
+            fraction.clamp(0.0, 1.0)
+        };
+        let fore = style.colors.color_at(fraction);
+        let length = match style.orientation {
+            BarOrientation::Horizontal => width,
+            BarOrientation::Vertical => height,
+        };
+        let steps = match style.fill {
+            BarFill::Cells => 1,
+            BarFill::Smooth => 2,
+        };
+        let filled = (fraction * (length * steps) as f32).round() as u32;
+
+        for i in 0..length {
+            let halves = filled.saturating_sub(i * steps).min(steps);
+            let (glyph, back) = match (halves, steps) {
+                (0, _) => (u16::from(b' '), style.empty),
+                (1, 2) => match style.orientation {
+                    BarOrientation::Horizontal => (LEFT_HALF_BLOCK, style.empty),
+                    BarOrientation::Vertical => (LOWER_HALF_BLOCK, style.empty),
+                },
+                _ => (u16::from(b' '), fore),
+            };
+            match style.orientation {
+                BarOrientation::Horizontal => {
+                    for cell_y in y..y + height as i32 {
+                        self.cell(x + i as i32, cell_y, Some(glyph), Some(fore), Some(back));
+                    }
+                }
+                BarOrientation::Vertical => {
+                    let cell_y = y + (height - 1 - i) as i32;
+                    for cell_x in x..x + width as i32 {
+                        self.cell(cell_x, cell_y, Some(glyph), Some(fore), Some(back));
+                    }
+                }
+            }
+        }
+
+        if let Some(label) = label {
+            self.print(
+                x + (width / 2) as i32,
+                y + (height / 2) as i32,
+                label,
+                TextAlign::Center,
+                Some(style.label_fore),
+                None,
+            );
+        }
+    }
+}
+
+/// A component for a bar drawn on the [`RootConsole`] every frame, filled to
+/// `value` out of `max`.
+#[derive(Debug, Clone, PartialEq, Component)]
+pub struct ProgressBar {
+    /// The position of the top-left cell and the size of the bar.
+    pub rect: ((i32, i32), (u32, u32)),
+    /// How full the bar is, out of `max`.
+    pub value: f32,
+    /// The value of a full bar.
+    pub max: f32,
+    /// How the bar looks.
+    pub style: BarStyle,
+    /// The text printed in the middle of the bar, if any.
+    pub label: Option<String>,
+}
+
+impl ProgressBar {
+    /// A full bar in the given rectangle, with `max` as the value of a full
+    /// bar.
+    pub fn new(rect: ((i32, i32), (u32, u32)), max: f32) -> Self {
+        Self {
+            rect,
+            value: max,
+            max,
+            style: BarStyle::default(),
+            label: None,
+        }
+    }
+
+    /// Sets how full the bar is.
+    pub fn with_value(mut self, value: f32) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Sets how the bar looks.
+    pub fn with_style(mut self, style: BarStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the text printed in the middle of the bar.
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// How full the bar is, from `0.0` to `1.0`.
+    pub fn fraction(&self) -> f32 {
+        if self.max > 0.0 {
+            (self.value / self.max).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn render_progress_bars(
+    bars: Query<'_, '_, &ProgressBar>,
+    mut root_console: ResMut<'_, RootConsole>,
+) {
+    for bar in bars.iter() {
+        root_console.draw_bar(bar.rect, bar.fraction(), &bar.style, bar.label.as_deref());
+    }
+}
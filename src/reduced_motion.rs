@@ -0,0 +1,35 @@
+use crate::web;
+use bevy_ecs::system::Resource;
+
+/// Resource asking the built-in effects to keep motion to a minimum, for
+/// players who find it distracting or get motion sick. When enabled:
+///
+/// * the [title screen](crate::title_screen) background stops cycling;
+/// * the [path preview](crate::path_preview) doesn't animate the path;
+/// * entities [following paths](crate::path_following) jump from cell to
+///   cell instead of gliding.
+///
+/// Games can check it for their own effects too. In a browser, it starts out
+/// following the player's `prefers-reduced-motion` setting; everywhere else,
+/// it starts out disabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Resource)]
+pub struct ReducedMotion {
+    /// Whether motion should be reduced.
+    pub enabled: bool,
+}
+
+impl Default for ReducedMotion {
+    fn default() -> Self {
+        Self {
+            enabled: web::prefers_reduced_motion(),
+        }
+    }
+}
+
+impl ReducedMotion {
+    /// Whether motion should be reduced, treating a missing resource as
+    /// disabled.
+    pub(crate) fn is_enabled(reduced_motion: Option<&Self>) -> bool {
+        reduced_motion.is_some_and(|reduced_motion| reduced_motion.enabled)
+    }
+}
@@ -0,0 +1,124 @@
+use crate::doryen::TextAlign;
+use crate::RootConsole;
+use bevy_app::AppExit;
+use bevy_ecs::event::EventWriter;
+use bevy_ecs::system::{In, Res, ResMut, Resource};
+use bevy_utils::tracing::error;
+use std::fmt::Display;
+
+/// What to do when a fallible render system returns an error. See
+/// [`RenderSystemExtensions::add_doryen_fallible_render_system`](crate::RenderSystemExtensions::add_doryen_fallible_render_system).
+#[derive(Clone, Copy)]
+pub enum RenderErrorPolicy {
+    /// Log the error and carry on rendering. This is the default.
+    LogAndContinue,
+    /// Log the error and print it on top of the root console for the rest of
+    /// the frame.
+    ShowOverlay,
+    /// Log the error and exit the application by sending an [`AppExit`]
+    /// event.
+    Abort,
+    /// Call the given function with the error message.
+    Callback(fn(&str)),
+}
+
+impl std::fmt::Debug for RenderErrorPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LogAndContinue => f.write_str("LogAndContinue"),
+            Self::ShowOverlay => f.write_str("ShowOverlay"),
+            Self::Abort => f.write_str("Abort"),
+            Self::Callback(_) => f.write_str("Callback"),
+        }
+    }
+}
+
+/// Resource deciding how errors returned by fallible render systems are
+/// handled.
+#[derive(Debug, Resource)]
+pub struct RenderErrorHandler {
+    /// What to do when an error occurs.
+    pub policy: RenderErrorPolicy,
+    /// The foreground color of the error overlay.
+    pub overlay_fore: crate::doryen::Color,
+    /// The background color of the error overlay.
+    pub overlay_back: crate::doryen::Color,
+    frame_errors: Vec<String>,
+}
+
+impl Default for RenderErrorHandler {
+    fn default() -> Self {
+        Self {
+            policy: RenderErrorPolicy::LogAndContinue,
+            overlay_fore: (255, 255, 255, 255),
+            overlay_back: (160, 0, 0, 255),
+            frame_errors: Vec::new(),
+        }
+    }
+}
+
+impl RenderErrorHandler {
+    /// The errors that have occurred so far during the current render.
+    pub fn frame_errors(&self) -> &[String] {
+        &self.frame_errors
+    }
+}
+
+pub(crate) fn handle_render_error<E: Display + 'static>(
+    In(result): In<Result<(), E>>,
+    mut handler: ResMut<'_, RenderErrorHandler>,
+    mut app_exit: EventWriter<'_, AppExit>,
+) {
+    let message = match result {
+        Ok(()) => return,
+        Err(e) => e.to_string(),
+    };
+
+    match handler.policy {
+        RenderErrorPolicy::LogAndContinue | RenderErrorPolicy::ShowOverlay => {
+            error!("render system failed: {}", message);
+        }
+        RenderErrorPolicy::Abort => {
+            error!("render system failed, exiting: {}", message);
+            app_exit.send(AppExit);
+        }
+        RenderErrorPolicy::Callback(callback) => callback(&message),
+    }
+    handler.frame_errors.push(message);
+}
+
+pub(crate) fn clear_render_errors(mut handler: ResMut<'_, RenderErrorHandler>) {
+    handler.frame_errors.clear();
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub(crate) fn render_error_overlay(
+    handler: Res<'_, RenderErrorHandler>,
+    mut root_console: ResMut<'_, RootConsole>,
+) {
+    if !matches!(handler.policy, RenderErrorPolicy::ShowOverlay) {
+        return;
+    }
+
+    let width = root_console.get_width();
+    for (y, message) in handler.frame_errors.iter().enumerate() {
+        let y = y as i32;
+        root_console.area(
+            0,
+            y,
+            width,
+            1,
+            Some(handler.overlay_fore),
+            Some(handler.overlay_back),
+            Some(' ' as u16),
+        );
+        root_console.print(
+            0,
+            y,
+            message,
+            TextAlign::Left,
+            Some(handler.overlay_fore),
+            Some(handler.overlay_back),
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! Hiding whole categories of composited consoles and cameras at once.
+//!
+//! Every [composited console](crate::compositor) and [camera](crate::camera)
+//! is on a [`RenderLayer`], and the [`RenderLayers`] resource says which
+//! layers are drawn. A camera can carry its own [`RenderLayers`], which it
+//! goes by instead of the resource.
+
+use bevy_ecs::component::Component;
+use bevy_ecs::system::Resource;
+
+/// Component putting a [composited console](crate::compositor) or a
+/// [camera](crate::camera) in a category that can be hidden as a whole,
+/// such as debug overlays, fog or the UI. Entities without one are on
+/// [`RenderLayer::DEFAULT`].
+///
+/// There are 32 layers, numbered from `0` to `31`; the number is taken
+/// modulo 32.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Component)]
+pub struct RenderLayer(pub u8);
+
+impl RenderLayer {
+    /// The layer of the entities without a [`RenderLayer`].
+    pub const DEFAULT: Self = Self(0);
+
+    fn bit(self) -> u32 {
+        1 << (self.0 % 32)
+    }
+}
+
+/// Resource saying which [`RenderLayer`]s are drawn. All of them are, to
+/// begin with:
+///
+/// ```
+/// # use bevy_doryen::{RenderLayer, RenderLayers};
+/// # use bevy_ecs::system::ResMut;
+/// const DEBUG: RenderLayer = RenderLayer(1);
+///
+/// fn toggle_debug_overlays(mut layers: ResMut<RenderLayers>) {
+///     layers.toggle(DEBUG);
+/// }
+/// ```
+///
+/// As a component of a [`ConsoleCamera`](crate::camera::ConsoleCamera)
+/// entity, it decides whether that camera is drawn instead of the resource,
+/// so a minimap camera can stay on screen while its layer is hidden
+/// everywhere else.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Resource, Component)]
+pub struct RenderLayers(u32);
+
+impl Default for RenderLayers {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl RenderLayers {
+    /// Every layer drawn.
+    pub const ALL: Self = Self(u32::MAX);
+    /// No layer drawn.
+    pub const NONE: Self = Self(0);
+
+    /// Whether the given layer is drawn.
+    pub fn is_enabled(&self, layer: RenderLayer) -> bool {
+        self.0 & layer.bit() != 0
+    }
+
+    /// Draws the given layer.
+    pub fn enable(&mut self, layer: RenderLayer) -> &mut Self {
+        self.0 |= layer.bit();
+        self
+    }
+
+    /// Stops drawing the given layer.
+    pub fn disable(&mut self, layer: RenderLayer) -> &mut Self {
+        self.0 &= !layer.bit();
+        self
+    }
+
+    /// Draws the given layer if it isn't drawn, and stops drawing it if it
+    /// is.
+    pub fn toggle(&mut self, layer: RenderLayer) -> &mut Self {
+        self.0 ^= layer.bit();
+        self
+    }
+
+    /// Draws the given layer or stops drawing it.
+    pub fn set(&mut self, layer: RenderLayer, enabled: bool) -> &mut Self {
+        if enabled {
+            self.enable(layer)
+        } else {
+            self.disable(layer)
+        }
+    }
+
+    /// Whether an entity on `layer`, or on [`RenderLayer::DEFAULT`] if it
+    /// has none, is drawn. A missing resource draws everything.
+    pub(crate) fn shows(layers: Option<&Self>, layer: Option<&RenderLayer>) -> bool {
+        layers.is_none_or(|layers| layers.is_enabled(layer.copied().unwrap_or_default()))
+    }
+}
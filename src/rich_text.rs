@@ -0,0 +1,968 @@
+//! Multi-line rich text documents, for help screens, lore books and the like.
+//!
+//! Colored text can also be written as markup, with tags switching colors
+//! inline, for dialogue and combat logs. [`PrintMarkup::print_markup`] prints
+//! it straight on a console, and [`Paragraph::markup`] adds it to a
+//! document:
+//!
+//! ```
+//! # use bevy_doryen::doryen::TextAlign;
+//! # use bevy_doryen::rich_text::PrintMarkup;
+//! # use bevy_doryen::RootConsole;
+//! # use bevy_ecs::system::ResMut;
+//! fn log_hit(mut root_console: ResMut<RootConsole>) {
+//!     root_console.print_markup(
+//!         1,
+//!         40,
+//!         "The {red}goblin{/} hits you for {#ff8800}{bg:black}3{/}{/} damage.",
+//!         TextAlign::Left,
+//!         Some((192, 192, 192, 255)),
+//!         None,
+//!     );
+//! }
+//! ```
+//!
+//! `{name}` or `{#rrggbb}` switches the foreground color, `{bg:name}` or
+//! `{bg:#rrggbb}` the background color, and `{/}` switches back to the colors
+//! before the latest tag. `{{` prints a `{`. Colors can also be given with an
+//! alpha, as `#rrggbbaa`; the names are those of [`named_color`]. Anything
+//! else between braces is printed as it is.
+//!
+//! [`PrintMarkup::print_wrapped`] word-wraps markup into a rectangle
+//! instead, with the lines aligned or justified as a [`WrapAlign`] says, and
+//! tells how many lines the text took:
+//!
+//! ```
+//! # use bevy_doryen::rich_text::{PrintMarkup, WrapAlign};
+//! # use bevy_doryen::RootConsole;
+//! # use bevy_ecs::system::ResMut;
+//! fn draw_dialogue(mut root_console: ResMut<RootConsole>) {
+//!     let lines = root_console.print_wrapped(
+//!         ((2, 30), (40, 6)),
+//!         "{yellow}Old man:{/} It's dangerous to go alone. Take this!",
+//!         WrapAlign::Justify,
+//!         None,
+//!         None,
+//!     );
+//!     assert!(lines <= 6);
+//! }
+//! ```
+
+use crate::doryen::{Color, Console, TextAlign};
+use crate::{Input, Key, MouseButton, RenderSystemExtensions, RootConsole};
+use bevy_app::{App, Plugin, Update};
+use bevy_ecs::component::Component;
+use bevy_ecs::entity::Entity;
+use bevy_ecs::event::{Event, EventWriter};
+use bevy_ecs::system::{Query, Res, ResMut};
+use std::ops::Range;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// The plugin that draws and handles input for [`RichTextView`] components.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct RichTextPlugin;
+
+impl Plugin for RichTextPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<LinkClicked>()
+            .add_systems(Update, rich_text_view_input)
+            .add_doryen_render_systems(render_rich_text_views);
+    }
+}
+
+/// A run of text sharing the same colors and link target.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Span {
+    /// The text of the span.
+    pub text: String,
+    /// The foreground color of the span. `None` uses the view's default.
+    pub fore: Option<Color>,
+    /// The background color of the span. `None` leaves the background alone.
+    pub back: Option<Color>,
+    /// Where the span links to, if it is a link.
+    pub link: Option<String>,
+}
+
+impl Span {
+    /// A span of text with the default colors.
+    pub fn plain(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Self::default()
+        }
+    }
+
+    /// A span of text with the given foreground color.
+    pub fn colored(text: impl Into<String>, fore: Color) -> Self {
+        Self {
+            text: text.into(),
+            fore: Some(fore),
+            ..Self::default()
+        }
+    }
+
+    /// A span of text linking to the given target. Clicking it emits a
+    /// [`LinkClicked`] event.
+    pub fn link(text: impl Into<String>, target: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            link: Some(target.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the background color of the span.
+    pub fn with_back(mut self, back: Color) -> Self {
+        self.back = Some(back);
+        self
+    }
+}
+
+/// A paragraph of spans. Paragraphs always start on a new line.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Paragraph {
+    /// The spans making up the paragraph.
+    pub spans: Vec<Span>,
+    /// A name that can be scrolled to with
+    /// [`RichTextView::scroll_to_anchor`].
+    pub anchor: Option<String>,
+}
+
+impl Paragraph {
+    /// Creates an empty paragraph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a span to the paragraph.
+    pub fn span(mut self, span: Span) -> Self {
+        self.spans.push(span);
+        self
+    }
+
+    /// Appends plain text to the paragraph.
+    pub fn text(self, text: impl Into<String>) -> Self {
+        self.span(Span::plain(text))
+    }
+
+    /// Appends text written as markup to the paragraph, one span for each
+    /// run of text with the same colors. See the [module
+    /// documentation](self) for the tags.
+    pub fn markup(mut self, markup: &str) -> Self {
+        self.spans.extend(parse_markup(markup));
+        self
+    }
+
+    /// Sets the anchor of the paragraph.
+    pub fn with_anchor(mut self, anchor: impl Into<String>) -> Self {
+        self.anchor = Some(anchor.into());
+        self
+    }
+}
+
+/// A rich text document made up of paragraphs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RichText {
+    /// The paragraphs of the document.
+    pub paragraphs: Vec<Paragraph>,
+    /// How many blank lines to put between paragraphs.
+    pub paragraph_spacing: u32,
+}
+
+impl Default for RichText {
+    fn default() -> Self {
+        Self {
+            paragraphs: Vec::new(),
+            paragraph_spacing: 1,
+        }
+    }
+}
+
+impl RichText {
+    /// Creates an empty document with one blank line between paragraphs.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a paragraph to the document.
+    pub fn paragraph(mut self, paragraph: Paragraph) -> Self {
+        self.paragraphs.push(paragraph);
+        self
+    }
+
+    /// Lays the document out into lines no wider than `width` cells.
+    pub(crate) fn layout(&self, width: u32) -> Layout {
+        let width = width.max(1) as usize;
+        let mut layout = Layout::default();
+
+        for (p, paragraph) in self.paragraphs.iter().enumerate() {
+            if p > 0 {
+                for _ in 0..self.paragraph_spacing {
+                    layout.lines.push(Vec::new());
+                }
+            }
+            if let Some(anchor) = &paragraph.anchor {
+                layout.anchors.push((anchor.clone(), layout.lines.len()));
+            }
+
+            // Words and runs of whitespace can go on across spans, so they're
+            // put together first, as the pieces of every span they're made of
+            let mut runs: Vec<(bool, Vec<Piece<'_>>)> = Vec::new();
+            for (s, span) in paragraph.spans.iter().enumerate() {
+                for (token_start, token) in tokenize(&span.text) {
+                    let is_space = token.starts_with(char::is_whitespace);
+                    match runs.last_mut() {
+                        Some((run_is_space, pieces)) if *run_is_space == is_space => {
+                            pieces.push((s, token_start, token));
+                        }
+                        _ => runs.push((is_space, vec![(s, token_start, token)])),
+                    }
+                }
+            }
+
+            let mut line = Vec::new();
+            let mut line_width = 0;
+            for (is_space, pieces) in runs {
+                let run_width: usize = pieces.iter().map(|(_, _, text)| text.chars().count()).sum();
+                if is_space {
+                    // Whitespace never starts a line, and is dropped where a
+                    // line is broken.
+                    if line_width > 0 && line_width + run_width <= width {
+                        for (s, start, text) in pieces {
+                            push_segment(&mut line, line_width, p, s, start, text);
+                            line_width += text.chars().count();
+                        }
+                    }
+                    continue;
+                }
+
+                if line_width > 0 && line_width + run_width > width {
+                    layout.lines.push(std::mem::take(&mut line));
+                    line_width = 0;
+                }
+
+                for (s, start, text) in pieces {
+                    // Break words wider than the whole line wherever they hit
+                    // the edge.
+                    let mut rest = text;
+                    let mut rest_start = start;
+                    while rest.chars().count() > width - line_width {
+                        let split = rest
+                            .char_indices()
+                            .nth(width - line_width)
+                            .map_or(rest.len(), |(i, _)| i);
+                        push_segment(&mut line, line_width, p, s, rest_start, &rest[..split]);
+                        layout.lines.push(std::mem::take(&mut line));
+                        line_width = 0;
+                        rest_start += split;
+                        rest = &rest[split..];
+                    }
+                    if !rest.is_empty() {
+                        push_segment(&mut line, line_width, p, s, rest_start, rest);
+                        line_width += rest.chars().count();
+                    }
+                }
+            }
+            layout.last_lines.push(layout.lines.len());
+            layout.lines.push(line);
+        }
+
+        layout
+    }
+}
+
+/// A piece of the text of a span: the index of the span within its
+/// paragraph, the byte offset of the piece within the span's text, and the
+/// text of the piece.
+type Piece<'a> = (usize, usize, &'a str);
+
+/// Splits text into alternating runs of whitespace and non-whitespace,
+/// together with their byte offsets.
+fn tokenize(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut start = 0;
+    std::iter::from_fn(move || {
+        let rest = &text[start..];
+        let first = rest.chars().next()?;
+        let is_space = first.is_whitespace();
+        let len = rest
+            .char_indices()
+            .find(|&(_, c)| c.is_whitespace() != is_space)
+            .map_or(rest.len(), |(i, _)| i);
+        let token = (start, &rest[..len]);
+        start += len;
+        Some(token)
+    })
+}
+
+fn push_segment(
+    line: &mut Vec<Segment>,
+    x: usize,
+    paragraph: usize,
+    span: usize,
+    start: usize,
+    text: &str,
+) {
+    line.push(Segment {
+        x: x as u32,
+        paragraph,
+        span,
+        start,
+        text: text.to_owned(),
+    });
+}
+
+/// The byte range of the run of whitespace or non-whitespace that contains
+/// the character at `offset`.
+fn word_around(text: &str, offset: usize) -> Range<usize> {
+    tokenize(text)
+        .map(|(start, token)| start..start + token.len())
+        .find(|range| range.contains(&offset))
+        .unwrap_or(offset..offset)
+}
+
+/// A piece of a span placed on a line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Segment {
+    /// The column the segment starts at.
+    pub(crate) x: u32,
+    /// The index of the paragraph the segment comes from.
+    pub(crate) paragraph: usize,
+    /// The index of the span within its paragraph.
+    pub(crate) span: usize,
+    /// The byte offset of the segment within its span's text.
+    pub(crate) start: usize,
+    /// The text of the segment.
+    pub(crate) text: String,
+}
+
+/// A document laid out into lines.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Layout {
+    pub(crate) lines: Vec<Vec<Segment>>,
+    pub(crate) anchors: Vec<(String, usize)>,
+    /// The index of the last line of every paragraph.
+    pub(crate) last_lines: Vec<usize>,
+}
+
+impl Layout {
+    /// Prints the lines of `document`, which this is the layout of, into
+    /// `rect`, leaving out the first `skip` lines, and aligning the rest as
+    /// `align` says. `fore` and `back` are the colors of the spans that
+    /// don't set their own.
+    pub(crate) fn print(
+        &self,
+        document: &RichText,
+        console: &mut Console,
+        ((x, y), (width, height)): ((i32, i32), (u32, u32)),
+        align: WrapAlign,
+        (fore, back): (Option<Color>, Option<Color>),
+        skip: usize,
+    ) {
+        let is_space = |segment: &Segment| segment.text.starts_with(char::is_whitespace);
+        let lines = self.lines.iter().enumerate().skip(skip);
+        for (line_y, (i, line)) in (y..).zip(lines.take(height as usize)) {
+            // Whitespace at the end of a line isn't lined up with the rest
+            let line_width = line
+                .iter()
+                .filter(|segment| !is_space(segment))
+                .map(|segment| segment.x + segment.text.chars().count() as u32)
+                .max()
+                .unwrap_or(0);
+            let starts_word = |k: usize| !is_space(&line[k]) && (k == 0 || is_space(&line[k - 1]));
+            let gaps = (0..line.len())
+                .filter(|&k| starts_word(k))
+                .count()
+                .saturating_sub(1);
+            let extra = width.saturating_sub(line_width) as usize;
+            let ends_paragraph = self.last_lines.binary_search(&i).is_ok();
+            // Justified lines spread the extra cells over the gaps, the
+            // first gaps taking one more until the remainder runs out
+            let (line_x, (spread, remainder)) = match align {
+                WrapAlign::Justify if !ends_paragraph && gaps > 0 => {
+                    (x, (extra / gaps, extra % gaps))
+                }
+                WrapAlign::Left | WrapAlign::Justify => (x, (0, 0)),
+                WrapAlign::Center => (x + (extra / 2) as i32, (0, 0)),
+                WrapAlign::Right => (x + extra as i32, (0, 0)),
+            };
+
+            let mut gap = 0;
+            for (k, segment) in line.iter().enumerate() {
+                if is_space(segment) && segment.x >= line_width {
+                    continue;
+                }
+                if k > 0 && starts_word(k) {
+                    gap += 1;
+                }
+                let span = &document.paragraphs[segment.paragraph].spans[segment.span];
+                console.print(
+                    line_x + (segment.x as usize + gap * spread + gap.min(remainder)) as i32,
+                    line_y,
+                    &segment.text,
+                    TextAlign::Left,
+                    span.fore.or(fore),
+                    span.back.or(back),
+                );
+            }
+        }
+    }
+
+    fn segment_at(&self, column: u32, line: usize) -> Option<&Segment> {
+        self.lines.get(line)?.iter().find(|segment| {
+            column >= segment.x && column < segment.x + segment.text.chars().count() as u32
+        })
+    }
+}
+
+/// A component that displays a [`RichText`] document in a rectangle of the
+/// [`RootConsole`], with scrolling.
+///
+/// The document is laid out again only when it or the width of the view
+/// changes, so long documents are cheap to keep on screen.
+#[derive(Debug, Clone, Component)]
+pub struct RichTextView {
+    /// The document to display.
+    pub document: RichText,
+    /// The x coordinate of the top-left corner of the view.
+    pub x: i32,
+    /// The y coordinate of the top-left corner of the view.
+    pub y: i32,
+    /// The width of the view, in cells.
+    pub width: u32,
+    /// The height of the view, in cells.
+    pub height: u32,
+    /// The foreground color of spans that don't set their own.
+    pub fore: Color,
+    /// The foreground color of links that don't set their own.
+    pub link_fore: Color,
+    /// Whether the view responds to the scrolling keys (arrow keys, page up,
+    /// page down, home and end). Defaults to `false`, so views don't all
+    /// scroll at once.
+    pub focused: bool,
+    scroll: usize,
+    layout: LayoutCache,
+}
+
+/// The layout of a [`RichTextView`], and the document and width it was made
+/// for.
+#[derive(Debug, Default)]
+struct LayoutCache(Mutex<Option<(RichText, u32, Arc<Layout>)>>);
+
+impl LayoutCache {
+    fn lock(&self) -> MutexGuard<'_, Option<(RichText, u32, Arc<Layout>)>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Clone for LayoutCache {
+    fn clone(&self) -> Self {
+        Self(Mutex::new(self.lock().clone()))
+    }
+}
+
+impl RichTextView {
+    /// Creates a view of the given document in the given rectangle.
+    pub fn new(document: RichText, x: i32, y: i32, width: u32, height: u32) -> Self {
+        Self {
+            document,
+            x,
+            y,
+            width,
+            height,
+            fore: (255, 255, 255, 255),
+            link_fore: (128, 192, 255, 255),
+            focused: false,
+            scroll: 0,
+            layout: LayoutCache::default(),
+        }
+    }
+
+    /// Sets whether the view responds to the scrolling keys.
+    pub fn with_focus(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+
+    /// The layout of the document at the width of the view.
+    fn layout(&self) -> Arc<Layout> {
+        let mut cache = self.layout.lock();
+        if let Some((document, width, layout)) = &*cache {
+            if *width == self.width && *document == self.document {
+                return Arc::clone(layout);
+            }
+        }
+        let layout = Arc::new(self.document.layout(self.width));
+        *cache = Some((self.document.clone(), self.width, Arc::clone(&layout)));
+        layout
+    }
+
+    /// The index of the first visible line.
+    pub fn scroll(&self) -> usize {
+        self.scroll
+    }
+
+    /// The total number of lines the document takes up in this view.
+    pub fn line_count(&self) -> usize {
+        self.layout().lines.len()
+    }
+
+    /// Scrolls the view so that the given line is the first visible one, as
+    /// far as the length of the document allows.
+    pub fn set_scroll(&mut self, line: usize) {
+        let max_scroll = self.line_count().saturating_sub(self.height as usize);
+        self.scroll = line.min(max_scroll);
+    }
+
+    /// Scrolls the view by the given number of lines.
+    pub fn scroll_by(&mut self, lines: isize) {
+        let line = if lines < 0 {
+            self.scroll.saturating_sub(lines.unsigned_abs())
+        } else {
+            self.scroll.saturating_add(lines as usize)
+        };
+        self.set_scroll(line);
+    }
+
+    /// Scrolls the view to the paragraph with the given anchor. Returns
+    /// whether the anchor was found.
+    pub fn scroll_to_anchor(&mut self, anchor: &str) -> bool {
+        let layout = self.layout();
+        match layout.anchors.iter().find(|(name, _)| name == anchor) {
+            Some(&(_, line)) => {
+                self.set_scroll(line);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the link target at the given console cell, if there is a link
+    /// there.
+    pub fn link_at(&self, cell: (i32, i32)) -> Option<&str> {
+        self.hit_at(cell)?.link
+    }
+
+    /// Returns which character of the document is shown at the given console
+    /// cell, if any. Takes the scroll position and line wrapping into
+    /// account, so it can be used to make single words clickable or to
+    /// select text with the mouse.
+    pub fn hit_at(&self, (x, y): (i32, i32)) -> Option<TextHit<'_>> {
+        if x < self.x || y < self.y {
+            return None;
+        }
+        let (column, row) = ((x - self.x) as u32, (y - self.y) as u32);
+        if column >= self.width || row >= self.height {
+            return None;
+        }
+
+        let layout = self.layout();
+        let segment = layout.segment_at(column, self.scroll + row as usize)?;
+        let span = &self.document.paragraphs[segment.paragraph].spans[segment.span];
+        let offset = segment.start
+            + segment
+                .text
+                .char_indices()
+                .nth((column - segment.x) as usize)
+                .map_or(0, |(i, _)| i);
+        Some(TextHit {
+            paragraph: segment.paragraph,
+            span: segment.span,
+            offset,
+            word: word_around(&span.text, offset),
+            link: span.link.as_deref(),
+        })
+    }
+
+    /// Draws the visible part of the document onto the given console.
+    pub fn draw(&self, console: &mut Console) {
+        let layout = self.layout();
+        for (row, line) in layout
+            .lines
+            .iter()
+            .skip(self.scroll)
+            .take(self.height as usize)
+            .enumerate()
+        {
+            for segment in line {
+                let span = &self.document.paragraphs[segment.paragraph].spans[segment.span];
+                let default_fore = if span.link.is_some() {
+                    self.link_fore
+                } else {
+                    self.fore
+                };
+                console.print(
+                    self.x + segment.x as i32,
+                    self.y + row as i32,
+                    &segment.text,
+                    TextAlign::Left,
+                    Some(span.fore.unwrap_or(default_fore)),
+                    span.back,
+                );
+            }
+        }
+    }
+}
+
+/// The character of a [`RichText`] document found by
+/// [`RichTextView::hit_at`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextHit<'a> {
+    /// The index of the paragraph the character is in.
+    pub paragraph: usize,
+    /// The index of the span the character is in, within its paragraph.
+    pub span: usize,
+    /// The byte offset of the character within the span's text.
+    pub offset: usize,
+    /// The byte range of the word the character is part of, within the
+    /// span's text. If the character is whitespace, this is the range of the
+    /// whitespace around it instead.
+    pub word: Range<usize>,
+    /// The link target of the span, if it's a link.
+    pub link: Option<&'a str>,
+}
+
+/// Emitted when a link in a [`RichTextView`] is clicked.
+#[derive(Debug, Clone, PartialEq, Eq, Event)]
+pub struct LinkClicked {
+    /// The entity with the [`RichTextView`] containing the link.
+    pub view: Entity,
+    /// The target of the link.
+    pub target: String,
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn rich_text_view_input(
+    input: Res<'_, Input>,
+    mut views: Query<'_, '_, (Entity, &mut RichTextView)>,
+    mut link_clicked: EventWriter<'_, LinkClicked>,
+) {
+    let (mouse_x, mouse_y) = input.mouse_pos();
+    let mouse_cell = (mouse_x.floor() as i32, mouse_y.floor() as i32);
+    let clicked = input.mouse_button_pressed(MouseButton::Left);
+
+    for (entity, mut view) in &mut views {
+        if view.focused {
+            let page = view.height.max(1) as isize;
+            if input.key_pressed(Key::ArrowUp) {
+                view.scroll_by(-1);
+            }
+            if input.key_pressed(Key::ArrowDown) {
+                view.scroll_by(1);
+            }
+            if input.key_pressed(Key::PageUp) {
+                view.scroll_by(-page);
+            }
+            if input.key_pressed(Key::PageDown) {
+                view.scroll_by(page);
+            }
+            if input.key_pressed(Key::Home) {
+                view.set_scroll(0);
+            }
+            if input.key_pressed(Key::End) {
+                view.set_scroll(usize::MAX);
+            }
+        }
+
+        if clicked {
+            if let Some(target) = view.link_at(mouse_cell) {
+                link_clicked.send(LinkClicked {
+                    view: entity,
+                    target: target.to_owned(),
+                });
+            }
+        }
+    }
+}
+
+/// The color going by `name` in markup, ignoring case: `black`, `white`,
+/// `gray` or `grey`, `dark_gray` or `dark_grey`, `red`, `green`, `blue`,
+/// `yellow`, `cyan`, `magenta`, `orange`, `purple`, `brown` or `pink`.
+pub fn named_color(name: &str) -> Option<Color> {
+    let color = match name.to_ascii_lowercase().as_str() {
+        "black" => (0, 0, 0, 255),
+        "white" => (255, 255, 255, 255),
+        "gray" | "grey" => (128, 128, 128, 255),
+        "dark_gray" | "dark_grey" => (64, 64, 64, 255),
+        "red" => (255, 0, 0, 255),
+        "green" => (0, 255, 0, 255),
+        "blue" => (0, 0, 255, 255),
+        "yellow" => (255, 255, 0, 255),
+        "cyan" => (0, 255, 255, 255),
+        "magenta" => (255, 0, 255, 255),
+        "orange" => (255, 136, 0, 255),
+        "purple" => (128, 0, 128, 255),
+        "brown" => (139, 69, 19, 255),
+        "pink" => (255, 192, 203, 255),
+        _ => return None,
+    };
+    Some(color)
+}
+
+/// A color given by name or as `#rrggbb` or `#rrggbbaa`.
+pub(crate) fn parse_color(text: &str) -> Option<Color> {
+    let hex = match text.strip_prefix('#') {
+        Some(hex) => hex,
+        None => return named_color(text),
+    };
+    if !(hex.len() == 6 || hex.len() == 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+    Some((channel(0)?, channel(2)?, channel(4)?, alpha))
+}
+
+/// Splits text written as markup into spans, one for each run of text with
+/// the same colors. See the [module documentation](self) for the tags.
+pub fn parse_markup(markup: &str) -> Vec<Span> {
+    let mut spans = Vec::new();
+    // The colors of every open tag, innermost last
+    let mut styles: Vec<(Option<Color>, Option<Color>)> = Vec::new();
+    let mut text = String::new();
+    let mut flush = |pending: &mut String, (fore, back): (Option<Color>, Option<Color>)| {
+        if !pending.is_empty() {
+            spans.push(Span {
+                text: std::mem::take(pending),
+                fore,
+                back,
+                link: None,
+            });
+        }
+    };
+
+    let mut rest = markup;
+    while let Some(open) = rest.find('{') {
+        text.push_str(&rest[..open]);
+        rest = &rest[open..];
+        if let Some(after) = rest.strip_prefix("{{") {
+            text.push('{');
+            rest = after;
+            continue;
+        }
+        let close = match rest.find('}') {
+            Some(close) => close,
+            None => break,
+        };
+        let tag = &rest[1..close];
+        let style = styles.last().copied().unwrap_or_default();
+        if tag == "/" {
+            flush(&mut text, style);
+            styles.pop();
+        } else {
+            let (fore, back) = style;
+            let new_style = match tag.strip_prefix("bg:") {
+                Some(color) => parse_color(color).map(|color| (fore, Some(color))),
+                None => parse_color(tag).map(|color| (Some(color), back)),
+            };
+            match new_style {
+                Some(new_style) => {
+                    flush(&mut text, style);
+                    styles.push(new_style);
+                }
+                None => text.push_str(&rest[..=close]),
+            }
+        }
+        rest = &rest[close + 1..];
+    }
+    text.push_str(rest);
+    flush(&mut text, styles.last().copied().unwrap_or_default());
+    spans
+}
+
+/// Text and the colors to print it in.
+type ColoredText<'a> = (&'a str, Option<Color>, Option<Color>);
+
+/// Printing text written as markup. See the [module documentation](self).
+pub trait PrintMarkup {
+    /// Prints `markup` at `(x, y)`, aligned like
+    /// [`Console::print`]. `fore` and `back` are the colors outside of any
+    /// tag; `None` leaves the cells' own. Every `\n` starts a new line.
+    fn print_markup(
+        &mut self,
+        x: i32,
+        y: i32,
+        markup: &str,
+        align: TextAlign,
+        fore: Option<Color>,
+        back: Option<Color>,
+    );
+
+    /// Prints `markup` word-wrapped into `rect`, given as the position of its
+    /// top-left cell and its size, aligning every line as `align` says.
+    /// Words wider than the rectangle are broken where they hit its edge,
+    /// and every `\n` starts a new line. Lines that don't fit in the
+    /// rectangle are left out.
+    ///
+    /// Returns the number of lines the text takes, including those left
+    /// out.
+    fn print_wrapped(
+        &mut self,
+        rect: ((i32, i32), (u32, u32)),
+        markup: &str,
+        align: WrapAlign,
+        fore: Option<Color>,
+        back: Option<Color>,
+    ) -> u32;
+}
+
+/// How [`PrintMarkup::print_wrapped`] lines up the lines of text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WrapAlign {
+    /// Along the left edge.
+    Left,
+    /// In the middle.
+    Center,
+    /// Along the right edge.
+    Right,
+    /// Along both edges, by widening the spaces between words. The last line
+    /// of every paragraph is lined up along the left edge.
+    Justify,
+}
+
+impl PrintMarkup for Console {
+    fn print_markup(
+        &mut self,
+        x: i32,
+        y: i32,
+        markup: &str,
+        align: TextAlign,
+        fore: Option<Color>,
+        back: Option<Color>,
+    ) {
+        let spans = parse_markup(markup);
+        let mut lines: Vec<Vec<ColoredText<'_>>> = vec![Vec::new()];
+        for span in &spans {
+            for (i, part) in span.text.split('\n').enumerate() {
+                if i > 0 {
+                    lines.push(Vec::new());
+                }
+                if let Some(line) = lines.last_mut().filter(|_| !part.is_empty()) {
+                    line.push((part, span.fore.or(fore), span.back.or(back)));
+                }
+            }
+        }
+
+        for (line_y, line) in (y..).zip(&lines) {
+            let width: i32 = line
+                .iter()
+                .map(|(text, _, _)| text.chars().count() as i32)
+                .sum();
+            let mut line_x = match align {
+                TextAlign::Left => x,
+                TextAlign::Right => x - width + 1,
+                TextAlign::Center => x - width / 2,
+            };
+            for &(text, text_fore, text_back) in line {
+                self.print(line_x, line_y, text, TextAlign::Left, text_fore, text_back);
+                line_x += text.chars().count() as i32;
+            }
+        }
+    }
+
+    fn print_wrapped(
+        &mut self,
+        ((x, y), (width, height)): ((i32, i32), (u32, u32)),
+        markup: &str,
+        align: WrapAlign,
+        fore: Option<Color>,
+        back: Option<Color>,
+    ) -> u32 {
+        let document = markup_document(markup);
+        let layout = document.layout(width);
+        layout.print(
+            &document,
+            self,
+            ((x, y), (width, height)),
+            align,
+            (fore, back),
+            0,
+        );
+        layout.lines.len() as u32
+    }
+}
+
+/// Text written as markup as a document, with a paragraph for every line,
+/// the way [`PrintMarkup::print_wrapped`] lays it out.
+pub(crate) fn markup_document(markup: &str) -> RichText {
+    let mut document = RichText {
+        paragraphs: vec![Paragraph::new()],
+        paragraph_spacing: 0,
+    };
+    for span in parse_markup(markup) {
+        for (i, line) in span.text.split('\n').enumerate() {
+            if i > 0 {
+                document.paragraphs.push(Paragraph::new());
+            }
+            if let Some(paragraph) = document.paragraphs.last_mut().filter(|_| !line.is_empty()) {
+                paragraph.spans.push(Span {
+                    text: line.to_owned(),
+                    ..span.clone()
+                });
+            }
+        }
+    }
+    document
+}
+
+/// The number of lines `markup` takes when word-wrapped `width` cells wide,
+/// as by [`PrintMarkup::print_wrapped`].
+pub(crate) fn wrapped_line_count(markup: &str, width: u32) -> usize {
+    markup_document(markup).layout(width).lines.len()
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn render_rich_text_views(
+    views: Query<'_, '_, &RichTextView>,
+    mut root_console: ResMut<'_, RootConsole>,
+) {
+    for view in views.iter() {
+        view.draw(&mut root_console);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::console_row;
+
+    fn print_wrapped(markup: &str, width: u32, align: WrapAlign) -> Vec<String> {
+        let mut console = Console::new(width, 3);
+        let lines = console.print_wrapped(((0, 0), (width, 3)), markup, align, None, None);
+        (0..lines.min(3))
+            .map(|y| console_row(&console, y as i32))
+            .collect()
+    }
+
+    #[test]
+    fn words_going_on_across_spans_wrap_as_one() {
+        assert_eq!(
+            print_wrapped("ab {red}cd{/}ef", 5, WrapAlign::Left),
+            ["ab   ", "cdef "]
+        );
+    }
+
+    #[test]
+    fn justified_lines_widen_the_gaps_but_not_the_last_line() {
+        assert_eq!(
+            print_wrapped("a bb ccc dd\nee f", 6, WrapAlign::Justify),
+            ["a   bb", "ccc dd", "ee f  "]
+        );
+        assert_eq!(print_wrapped("a bb", 6, WrapAlign::Right), ["  a bb"]);
+    }
+
+    #[test]
+    fn views_lay_out_again_only_when_the_document_or_width_changes() {
+        let document = RichText::new().paragraph(Paragraph::new().text("one two three"));
+        let mut view = RichTextView::new(document, 0, 0, 20, 5);
+        let layout = view.layout();
+        assert!(Arc::ptr_eq(&layout, &view.layout()));
+        assert_eq!(view.line_count(), 1);
+
+        view.width = 5;
+        assert!(!Arc::ptr_eq(&layout, &view.layout()));
+        assert_eq!(view.line_count(), 3);
+
+        view.document.paragraphs.clear();
+        assert_eq!(view.line_count(), 0);
+    }
+}
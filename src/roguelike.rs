@@ -0,0 +1,513 @@
+//! A walkable `@` on a map, for getting a new roguelike off the ground.
+//!
+//! [`RoguelikeTemplatePlugin`] puts together the pieces most roguelikes start
+//! out with: an [`InputMap`] of [`RoguelikeAction`]s bound to the arrow keys,
+//! the vi keys and the numeric keypad, a [`TerrainGrid`] map, a
+//! [`ConsoleCamera`] following the player, a [`FieldOfView`] revealing the
+//! map as it's explored, a [`MessageLogView`] along the bottom of the screen
+//! and a HUD line along the top. Drawing some walls and spawning a
+//! [`PlayerBundle`] is all that's left to do:
+//!
+//! ```no_run
+//! # use bevy_app::{App, Startup};
+//! # use bevy_doryen::pathfinding::TerrainGrid;
+//! # use bevy_doryen::roguelike::{PlayerBundle, RoguelikeTemplatePlugin, WALL};
+//! # use bevy_doryen::DoryenPlugin;
+//! # use bevy_ecs::system::{Commands, ResMut};
+//! fn setup(mut commands: Commands, mut map: ResMut<TerrainGrid>) {
+//!     for x in 0..80 {
+//!         map.set((x, 0), WALL);
+//!         map.set((x, 44), WALL);
+//!     }
+//!     for y in 0..45 {
+//!         map.set((0, y), WALL);
+//!         map.set((79, y), WALL);
+//!     }
+//!     commands.spawn(PlayerBundle::new(40, 22));
+//! }
+//!
+//! App::new()
+//!     .add_plugins((DoryenPlugin, RoguelikeTemplatePlugin::default()))
+//!     .add_systems(Startup, setup)
+//!     .run();
+//! ```
+//!
+//! Walls block both movement and sight; every other kind of terrain is floor.
+//! Every entity with a [`GridPosition`] and a [`Glyph`] is drawn on the
+//! [`WorldConsole`] while a [`FieldOfView`] sees it, and the map cells are
+//! drawn once any [`FieldOfView`] has seen them, dimmed while none does.
+//! Without a [`FieldOfView`], the whole map is shown.
+//!
+//! Everything the template adds is an ordinary resource, component or
+//! plugin, so a game grows out of it by changing the [`InputMap`], pushing
+//! onto the [`MessageLog`], filling in the [`RoguelikeHud`] or replacing the
+//! systems with its own.
+
+use crate::camera::{
+    CameraFollow, CameraFollowOptions, ConsoleCamera, ConsoleCameraPlugin, WorldConsole,
+};
+use crate::compositor::{CompositorPlugin, ConsoleBundle, OffscreenConsole};
+use crate::doryen::{Color, Console, TextAlign};
+use crate::geometry;
+use crate::input_map::{Binding, InputMap, InputMapPlugin};
+use crate::message_log::{MessageLog, MessageLogPlugin, MessageLogView};
+use crate::path_following::GridPosition;
+use crate::pathfinding::{TerrainGrid, TerrainId};
+use crate::rich_text::PrintMarkup;
+use crate::{Key, RenderStage, RenderSystemExtensions, RootConsole};
+use bevy_app::{App, Plugin, Startup, Update};
+use bevy_ecs::bundle::Bundle;
+use bevy_ecs::component::Component;
+use bevy_ecs::entity::Entity;
+use bevy_ecs::query::{Added, With};
+use bevy_ecs::schedule::IntoSystemConfigs;
+use bevy_ecs::system::{Commands, Query, Res, ResMut, Resource};
+use std::collections::HashSet;
+
+/// The terrain of the cells the player can walk on and see through.
+pub const FLOOR: TerrainId = 0;
+/// The terrain of the cells that block movement and sight.
+pub const WALL: TerrainId = 1;
+
+const BLACK: Color = (0, 0, 0, 255);
+const FLOOR_COLOR: Color = (110, 110, 130, 255);
+const WALL_COLOR: Color = (200, 180, 140, 255);
+const REMEMBERED_COLOR: Color = (50, 50, 70, 255);
+
+/// The template plugin. See the [module documentation](self).
+///
+/// Adds the [`InputMapPlugin`] for [`RoguelikeAction`]s, the
+/// [`MessageLogPlugin`], the [`ConsoleCameraPlugin`] and the
+/// [`CompositorPlugin`], unless they've already been added, and a map of
+/// `map_width` by `map_height` floor cells, unless a [`TerrainGrid`] has
+/// already been inserted. The same goes for the default [`InputMap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoguelikeTemplatePlugin {
+    /// The width of the map, in cells. Defaults to 80.
+    pub map_width: u32,
+    /// The height of the map, in cells. Defaults to 45.
+    pub map_height: u32,
+    /// The number of lines of the message log at the bottom of the screen.
+    /// Defaults to 5.
+    pub log_height: u32,
+}
+
+impl Default for RoguelikeTemplatePlugin {
+    fn default() -> Self {
+        Self {
+            map_width: 80,
+            map_height: 45,
+            log_height: 5,
+        }
+    }
+}
+
+impl Plugin for RoguelikeTemplatePlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world.contains_resource::<InputMap<RoguelikeAction>>() {
+            app.insert_resource(RoguelikeAction::input_map());
+        }
+        if !app.world.contains_resource::<TerrainGrid>() {
+            app.insert_resource(TerrainGrid::new(self.map_width, self.map_height, FLOOR));
+        }
+        if !app.is_plugin_added::<InputMapPlugin<RoguelikeAction>>() {
+            app.add_plugins(InputMapPlugin::<RoguelikeAction>::default());
+        }
+        if !app.is_plugin_added::<MessageLogPlugin>() {
+            app.add_plugins(MessageLogPlugin);
+        }
+        if !app.is_plugin_added::<ConsoleCameraPlugin>() {
+            app.add_plugins(ConsoleCameraPlugin);
+        }
+        if !app.is_plugin_added::<CompositorPlugin>() {
+            app.add_plugins(CompositorPlugin);
+        }
+
+        app.insert_resource(TemplateLayout {
+            log_height: self.log_height,
+        })
+        .init_resource::<RoguelikeHud>()
+        .init_resource::<TurnCount>()
+        .add_systems(Startup, spawn_views)
+        .add_systems(
+            Update,
+            (follow_players, move_players, update_fields_of_view).chain(),
+        )
+        .add_doryen_render_systems_to_stage(RenderStage::PreRender, lay_out_views)
+        .add_doryen_render_systems((draw_map, draw_hud));
+    }
+}
+
+/// The actions of the default [`InputMap`] of the template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RoguelikeAction {
+    /// Move one cell up.
+    North,
+    /// Move one cell down.
+    South,
+    /// Move one cell right.
+    East,
+    /// Move one cell left.
+    West,
+    /// Move one cell up and right.
+    NorthEast,
+    /// Move one cell up and left.
+    NorthWest,
+    /// Move one cell down and right.
+    SouthEast,
+    /// Move one cell down and left.
+    SouthWest,
+    /// Let a turn pass without moving.
+    Wait,
+}
+
+impl RoguelikeAction {
+    /// Every action, in the order they're checked in.
+    pub const ALL: [Self; 9] = [
+        Self::North,
+        Self::South,
+        Self::East,
+        Self::West,
+        Self::NorthEast,
+        Self::NorthWest,
+        Self::SouthEast,
+        Self::SouthWest,
+        Self::Wait,
+    ];
+
+    /// How far the action moves the player, across and down.
+    pub fn direction(self) -> (i32, i32) {
+        match self {
+            Self::North => (0, -1),
+            Self::South => (0, 1),
+            Self::East => (1, 0),
+            Self::West => (-1, 0),
+            Self::NorthEast => (1, -1),
+            Self::NorthWest => (-1, -1),
+            Self::SouthEast => (1, 1),
+            Self::SouthWest => (-1, 1),
+            Self::Wait => (0, 0),
+        }
+    }
+
+    /// The default bindings: the arrow keys, with home, end, page up and page
+    /// down for the diagonals, the vi keys (`hjklyubn`) and the numeric
+    /// keypad move, and `.`, space or keypad 5 waits.
+    pub fn input_map() -> InputMap<Self> {
+        let bindings = [
+            (Self::North, [Key::ArrowUp, Key::KeyK, Key::Numpad8]),
+            (Self::South, [Key::ArrowDown, Key::KeyJ, Key::Numpad2]),
+            (Self::East, [Key::ArrowRight, Key::KeyL, Key::Numpad6]),
+            (Self::West, [Key::ArrowLeft, Key::KeyH, Key::Numpad4]),
+            (Self::NorthEast, [Key::PageUp, Key::KeyU, Key::Numpad9]),
+            (Self::NorthWest, [Key::Home, Key::KeyY, Key::Numpad7]),
+            (Self::SouthEast, [Key::PageDown, Key::KeyN, Key::Numpad3]),
+            (Self::SouthWest, [Key::End, Key::KeyB, Key::Numpad1]),
+            (Self::Wait, [Key::Period, Key::Numpad5, Key::Space]),
+        ];
+        let mut input_map = InputMap::new();
+        for (action, keys) in bindings {
+            for key in keys {
+                input_map.bind(action, Binding::key(key));
+            }
+        }
+        input_map
+    }
+}
+
+/// Marks the entity moved by the [`RoguelikeAction`]s, which the camera
+/// follows.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub struct Player;
+
+/// How an entity is drawn on the map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub struct Glyph {
+    /// The character drawn.
+    pub glyph: u16,
+    /// The color it's drawn in.
+    pub fore: Color,
+}
+
+impl Glyph {
+    /// A glyph of the given character and color.
+    pub fn new(glyph: u16, fore: Color) -> Self {
+        Self { glyph, fore }
+    }
+}
+
+/// The cells an entity with a [`GridPosition`] can see, and those it has
+/// seen before. Recomputed in every update.
+#[derive(Debug, Clone, PartialEq, Eq, Component)]
+pub struct FieldOfView {
+    /// How far the entity can see, in cells.
+    pub radius: u32,
+    visible: HashSet<(i32, i32)>,
+    explored: HashSet<(i32, i32)>,
+}
+
+impl FieldOfView {
+    /// A field of view seeing `radius` cells far, with nothing seen yet.
+    pub fn new(radius: u32) -> Self {
+        Self {
+            radius,
+            visible: HashSet::new(),
+            explored: HashSet::new(),
+        }
+    }
+
+    /// Whether the cell is in sight.
+    pub fn is_visible(&self, cell: (i32, i32)) -> bool {
+        self.visible.contains(&cell)
+    }
+
+    /// Whether the cell has ever been in sight.
+    pub fn is_explored(&self, cell: (i32, i32)) -> bool {
+        self.explored.contains(&cell)
+    }
+
+    /// Works out what can be seen from `origin` on the map, where walls
+    /// block sight.
+    pub fn compute(&mut self, grid: &TerrainGrid, origin: (i32, i32)) {
+        let is_blocking = |cell| grid.get(cell) == Some(WALL);
+        self.visible = geometry::disc(origin, self.radius)
+            .into_iter()
+            .filter(|&cell| grid.contains(cell) && geometry::los(origin, cell, is_blocking))
+            .collect();
+        self.explored.extend(&self.visible);
+    }
+}
+
+/// The components of a player: an `@` that sees 8 cells far.
+#[derive(Debug, Clone, Bundle)]
+pub struct PlayerBundle {
+    /// Marks the player.
+    pub player: Player,
+    /// Where the player is.
+    pub position: GridPosition,
+    /// What the player looks like.
+    pub glyph: Glyph,
+    /// What the player can see.
+    pub field_of_view: FieldOfView,
+}
+
+impl PlayerBundle {
+    /// A player standing on the given cell.
+    pub fn new(x: i32, y: i32) -> Self {
+        Self {
+            player: Player,
+            position: GridPosition(x, y),
+            glyph: Glyph::new(u16::from(b'@'), (255, 255, 255, 255)),
+            field_of_view: FieldOfView::new(8),
+        }
+    }
+}
+
+/// What the HUD line along the top of the screen shows.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Resource)]
+pub struct RoguelikeHud {
+    /// [Markup](crate::rich_text) shown on the left, such as the player's
+    /// health. The [`TurnCount`] is shown on the right.
+    pub status: String,
+}
+
+/// How many turns the player has taken, counting both moves and waits.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Resource)]
+pub struct TurnCount(pub u32);
+
+#[derive(Debug, Resource)]
+struct TemplateLayout {
+    log_height: u32,
+}
+
+/// Marks the camera, log view and HUD console spawned by the template.
+#[derive(Debug, Component)]
+struct TemplateView;
+
+fn spawn_views(mut commands: Commands<'_, '_>) {
+    commands.spawn((ConsoleCamera::new(0, 1, 1, 1), TemplateView));
+    let mut log = MessageLogView::new(0, 0, 1, 1);
+    log.back = Some(BLACK);
+    commands.spawn((log, TemplateView));
+    commands.spawn((ConsoleBundle::new(Console::new(1, 1), 0, 0), TemplateView));
+}
+
+/// Fits the views to the root console: the HUD on the top line, the log on
+/// the bottom ones, and the camera in between. The camera's rectangle is
+/// cleared, since the camera leaves the cells past the edges of the map alone.
+#[allow(clippy::needless_pass_by_value)]
+fn lay_out_views(
+    layout: Res<'_, TemplateLayout>,
+    mut root_console: ResMut<'_, RootConsole>,
+    grid: Res<'_, TerrainGrid>,
+    mut commands: Commands<'_, '_>,
+    mut cameras: Query<
+        '_,
+        '_,
+        (Entity, &mut ConsoleCamera, Option<&CameraFollowOptions>),
+        With<TemplateView>,
+    >,
+    mut logs: Query<'_, '_, &mut MessageLogView, With<TemplateView>>,
+    mut huds: Query<'_, '_, &mut OffscreenConsole, With<TemplateView>>,
+) {
+    let (width, height) = root_console.get_size();
+    let log_height = layout.log_height.min(height.saturating_sub(1));
+    let map_height = height.saturating_sub(1 + log_height);
+
+    let options = CameraFollowOptions::default().within_map(grid.width(), grid.height());
+    for (entity, mut camera, camera_options) in &mut cameras {
+        if camera.viewport() != ((0, 1), (width, map_height)) {
+            camera.x = 0;
+            camera.y = 1;
+            camera.width = width;
+            camera.height = map_height;
+        }
+        if camera_options != Some(&options) {
+            commands.entity(entity).insert(options);
+        }
+    }
+    for mut log in &mut logs {
+        if log.rect() != geometry::Rect::new(0, (1 + map_height) as i32, width, log_height) {
+            log.x = 0;
+            log.y = (1 + map_height) as i32;
+            log.width = width;
+            log.height = log_height;
+        }
+    }
+    for mut hud in &mut huds {
+        if hud.get_size() != (width, 1) {
+            hud.resize(width, 1);
+        }
+    }
+    root_console.area(
+        0,
+        1,
+        width,
+        map_height,
+        None,
+        Some(BLACK),
+        Some(u16::from(b' ')),
+    );
+}
+
+/// Points the template's camera at players as they're spawned.
+#[allow(clippy::needless_pass_by_value)]
+fn follow_players(
+    mut commands: Commands<'_, '_>,
+    players: Query<'_, '_, Entity, Added<Player>>,
+    cameras: Query<'_, '_, Entity, (With<ConsoleCamera>, With<TemplateView>)>,
+) {
+    if let Some(player) = players.iter().last() {
+        for camera in &cameras {
+            commands.entity(camera).insert(CameraFollow(player));
+        }
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn move_players(
+    input_map: Res<'_, InputMap<RoguelikeAction>>,
+    grid: Res<'_, TerrainGrid>,
+    mut log: ResMut<'_, MessageLog>,
+    mut turns: ResMut<'_, TurnCount>,
+    mut players: Query<'_, '_, &mut GridPosition, With<Player>>,
+) {
+    let action = match RoguelikeAction::ALL
+        .iter()
+        .find(|action| input_map.repeated(action))
+    {
+        Some(&action) => action,
+        None => return,
+    };
+
+    let (dx, dy) = action.direction();
+    for mut position in &mut players {
+        let target = (position.0 + dx, position.1 + dy);
+        match grid.get(target) {
+            Some(WALL) => {
+                log.push("You bump into a wall.");
+                continue;
+            }
+            None => continue,
+            _ => {}
+        }
+        if target != (position.0, position.1) {
+            *position = GridPosition(target.0, target.1);
+        }
+        turns.0 += 1;
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn update_fields_of_view(
+    grid: Res<'_, TerrainGrid>,
+    mut viewers: Query<'_, '_, (&GridPosition, &mut FieldOfView)>,
+) {
+    for (&GridPosition(x, y), mut field_of_view) in &mut viewers {
+        field_of_view.compute(&grid, (x, y));
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn draw_map(
+    grid: Res<'_, TerrainGrid>,
+    viewers: Query<'_, '_, &FieldOfView>,
+    glyphs: Query<'_, '_, (&GridPosition, &Glyph)>,
+    mut world: ResMut<'_, WorldConsole>,
+) {
+    let size = (grid.width(), grid.height());
+    if world.get_size() != size {
+        world.resize(size.0, size.1);
+    }
+    world.clear(Some(BLACK), Some(BLACK), Some(u16::from(b' ')));
+
+    let all_seen = viewers.is_empty();
+    let visible = |cell| all_seen || viewers.iter().any(|viewer| viewer.is_visible(cell));
+    let explored = |cell| all_seen || viewers.iter().any(|viewer| viewer.is_explored(cell));
+    for y in 0..size.1 as i32 {
+        for x in 0..size.0 as i32 {
+            if !explored((x, y)) {
+                continue;
+            }
+            let (glyph, fore) = if grid.get((x, y)) == Some(WALL) {
+                (b'#', WALL_COLOR)
+            } else {
+                (b'.', FLOOR_COLOR)
+            };
+            let fore = if visible((x, y)) {
+                fore
+            } else {
+                REMEMBERED_COLOR
+            };
+            world.ascii(x, y, u16::from(glyph));
+            world.fore(x, y, fore);
+        }
+    }
+    for (&GridPosition(x, y), glyph) in &glyphs {
+        if visible((x, y)) {
+            world.ascii(x, y, glyph.glyph);
+            world.fore(x, y, glyph.fore);
+        }
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn draw_hud(
+    hud: Res<'_, RoguelikeHud>,
+    turns: Res<'_, TurnCount>,
+    mut consoles: Query<'_, '_, &mut OffscreenConsole, With<TemplateView>>,
+) {
+    for mut console in &mut consoles {
+        let right = console.get_width() as i32 - 1;
+        console.clear(None, Some(BLACK), Some(u16::from(b' ')));
+        console.print_markup(0, 0, &hud.status, TextAlign::Left, None, None);
+        console.print(
+            right,
+            0,
+            &format!("Turn {}", turns.0),
+            TextAlign::Right,
+            None,
+            None,
+        );
+    }
+}
@@ -0,0 +1,276 @@
+//! Snapshots of the simulation state, for rolling the world back whole
+//! turns.
+//!
+//! Where [`undo`](crate::undo) needs every change written as a reversible
+//! command, rollback works on copies: the components and resources
+//! registered with [`RollbackAppExt`] are cloned into the [`SnapshotBuffer`]
+//! whenever [`RollbackCommandsExt::snapshot_turn`] runs, typically at the
+//! start of every turn, and [`RollbackCommandsExt::roll_back`] puts an older
+//! copy back. That makes an "undo turn" feature a one-liner, and comparing
+//! the buffered snapshots of two clients is a way to find where they
+//! diverged.
+//!
+//! ```
+//! # use bevy_app::App;
+//! # use bevy_doryen::rollback::{RollbackAppExt, RollbackCommandsExt, RollbackPlugin};
+//! # use bevy_ecs::component::Component;
+//! # use bevy_ecs::system::{Commands, Resource};
+//! #[derive(Clone, Component)]
+//! struct Health(i32);
+//!
+//! #[derive(Clone, Default, Resource)]
+//! struct Turn(u32);
+//!
+//! fn start_turn(mut commands: Commands) {
+//!     commands.snapshot_turn();
+//! }
+//!
+//! fn undo_turn(mut commands: Commands) {
+//!     commands.roll_back(1);
+//! }
+//!
+//! App::new()
+//!     .add_plugins(RollbackPlugin)
+//!     .register_rollback_component::<Health>()
+//!     .register_rollback_resource::<Turn>();
+//! ```
+//!
+//! Entities aren't respawned by a rollback: components are only put back on
+//! entities that still exist. Despawn entities by removing their registered
+//! components instead when they need to come back.
+
+use bevy_app::{App as BevyApp, Plugin};
+use bevy_ecs::component::Component;
+use bevy_ecs::entity::Entity;
+use bevy_ecs::system::{Command, Commands, Resource};
+use bevy_ecs::world::{Mut, World};
+use std::any::Any;
+use std::collections::{HashSet, VecDeque};
+use std::marker::PhantomData;
+
+/// The rollback plugin. Adds the [`SnapshotBuffer`] resource, unless one has
+/// already been inserted.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct RollbackPlugin;
+
+impl Plugin for RollbackPlugin {
+    fn build(&self, app: &mut BevyApp) {
+        app.init_resource::<SnapshotBuffer>()
+            .init_resource::<Registry>();
+    }
+}
+
+type Captured = Box<dyn Any + Send + Sync>;
+
+trait Snapshotter: Send + Sync + 'static {
+    fn capture(&self, world: &mut World) -> Captured;
+    fn restore(&self, world: &mut World, captured: &Captured);
+}
+
+struct ResourceSnapshotter<R>(PhantomData<fn() -> R>);
+
+impl<R: Resource + Clone> Snapshotter for ResourceSnapshotter<R> {
+    fn capture(&self, world: &mut World) -> Captured {
+        Box::new(world.get_resource::<R>().cloned())
+    }
+
+    fn restore(&self, world: &mut World, captured: &Captured) {
+        match captured.downcast_ref::<Option<R>>() {
+            Some(Some(resource)) => world.insert_resource(resource.clone()),
+            Some(None) => {
+                world.remove_resource::<R>();
+            }
+            None => (),
+        }
+    }
+}
+
+struct ComponentSnapshotter<C>(PhantomData<fn() -> C>);
+
+impl<C: Component + Clone> Snapshotter for ComponentSnapshotter<C> {
+    fn capture(&self, world: &mut World) -> Captured {
+        let components: Vec<(Entity, C)> = world
+            .query::<(Entity, &C)>()
+            .iter(world)
+            .map(|(entity, component)| (entity, component.clone()))
+            .collect();
+        Box::new(components)
+    }
+
+    fn restore(&self, world: &mut World, captured: &Captured) {
+        let components = match captured.downcast_ref::<Vec<(Entity, C)>>() {
+            Some(components) => components,
+            None => return,
+        };
+        let kept: HashSet<Entity> = components.iter().map(|&(entity, _)| entity).collect();
+        let added: Vec<Entity> = world
+            .query::<(Entity, &C)>()
+            .iter(world)
+            .map(|(entity, _)| entity)
+            .filter(|entity| !kept.contains(entity))
+            .collect();
+        for entity in added {
+            world.entity_mut(entity).remove::<C>();
+        }
+        for (entity, component) in components {
+            if let Some(mut entity) = world.get_entity_mut(*entity) {
+                entity.insert(component.clone());
+            }
+        }
+    }
+}
+
+/// The components and resources to snapshot.
+#[derive(Default, Resource)]
+struct Registry {
+    snapshotters: Vec<Box<dyn Snapshotter>>,
+}
+
+/// A copy of every registered component and resource, taken by
+/// [`RollbackCommandsExt::snapshot_turn`].
+pub struct Snapshot {
+    captured: Vec<Captured>,
+}
+
+impl std::fmt::Debug for Snapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Snapshot")
+            .field("captured", &self.captured.len())
+            .finish()
+    }
+}
+
+/// Resource holding the most recent snapshots, oldest first. Once it's full,
+/// taking a snapshot drops the oldest one.
+#[derive(Debug, Resource)]
+pub struct SnapshotBuffer {
+    snapshots: VecDeque<Snapshot>,
+    capacity: usize,
+}
+
+impl Default for SnapshotBuffer {
+    fn default() -> Self {
+        Self::with_capacity(100)
+    }
+}
+
+impl SnapshotBuffer {
+    /// Creates a buffer holding up to `capacity` snapshots.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            snapshots: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// How many snapshots the buffer holds at most.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// How many snapshots the buffer holds, and so how many turns can be
+    /// rolled back.
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    /// Whether the buffer holds no snapshots.
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    /// Drops every snapshot.
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+}
+
+/// Registration of the components and resources to snapshot.
+pub trait RollbackAppExt {
+    /// Includes the resource `R` in snapshots. Rolling back to a snapshot
+    /// taken while it didn't exist removes it.
+    fn register_rollback_resource<R: Resource + Clone>(&mut self) -> &mut Self;
+    /// Includes every `C` component in snapshots.
+    fn register_rollback_component<C: Component + Clone>(&mut self) -> &mut Self;
+}
+
+impl RollbackAppExt for BevyApp {
+    fn register_rollback_resource<R: Resource + Clone>(&mut self) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(Registry::default)
+            .snapshotters
+            .push(Box::new(ResourceSnapshotter::<R>(PhantomData)));
+        self
+    }
+
+    fn register_rollback_component<C: Component + Clone>(&mut self) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(Registry::default)
+            .snapshotters
+            .push(Box::new(ComponentSnapshotter::<C>(PhantomData)));
+        self
+    }
+}
+
+/// Extension methods on [`Commands`] for taking snapshots and rolling back.
+pub trait RollbackCommandsExt {
+    /// Takes a snapshot of the registered components and resources as they
+    /// are once the command is applied.
+    fn snapshot_turn(&mut self);
+    /// Restores the state from `turns` snapshots ago, where rolling back one
+    /// turn restores the most recent snapshot. That snapshot and every later
+    /// one are dropped. Does nothing if the buffer holds fewer than `turns`
+    /// snapshots, or if `turns` is zero.
+    fn roll_back(&mut self, turns: usize);
+}
+
+impl RollbackCommandsExt for Commands<'_, '_> {
+    fn snapshot_turn(&mut self) {
+        self.add(TakeSnapshot);
+    }
+
+    fn roll_back(&mut self, turns: usize) {
+        self.add(RollBack(turns));
+    }
+}
+
+struct TakeSnapshot;
+
+impl Command for TakeSnapshot {
+    fn apply(self, world: &mut World) {
+        world.resource_scope(|world, registry: Mut<'_, Registry>| {
+            let captured = registry
+                .snapshotters
+                .iter()
+                .map(|snapshotter| snapshotter.capture(world))
+                .collect();
+            let mut buffer = world.resource_mut::<SnapshotBuffer>();
+            if buffer.snapshots.len() == buffer.capacity {
+                buffer.snapshots.pop_front();
+            }
+            buffer.snapshots.push_back(Snapshot { captured });
+        });
+    }
+}
+
+struct RollBack(usize);
+
+impl Command for RollBack {
+    fn apply(self, world: &mut World) {
+        let mut buffer = world.resource_mut::<SnapshotBuffer>();
+        if self.0 == 0 || self.0 > buffer.snapshots.len() {
+            return;
+        }
+        let keep = buffer.snapshots.len() - self.0;
+        let snapshot = buffer.snapshots.drain(keep..).next();
+
+        if let Some(snapshot) = snapshot {
+            world.resource_scope(|world, registry: Mut<'_, Registry>| {
+                for (snapshotter, captured) in registry.snapshotters.iter().zip(&snapshot.captured)
+                {
+                    snapshotter.restore(world, captured);
+                }
+            });
+        }
+    }
+}
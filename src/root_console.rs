@@ -1,10 +1,39 @@
 use crate::doryen::Console;
+use bevy_ecs::system::Resource;
 use std::ops::{Deref, DerefMut};
 
 /// Provides access to the root console of the Doryen engine.
-#[derive(Default)]
+///
+/// The resource only counts as changed when something is drawn on it, so a
+/// system can skip work on frames where the console stayed the same, with
+/// [`is_changed`](bevy_ecs::change_detection::DetectChanges::is_changed) or a run
+/// condition:
+///
+/// ```
+/// # use bevy_app::App;
+/// # use bevy_doryen::{RenderStage, RenderSystemExtensions, RootConsole};
+/// # use bevy_ecs::prelude::*;
+/// fn mirror_to_second_screen(root_console: Res<RootConsole>) {
+///     // Only runs when the console was drawn on since it last ran
+/// }
+///
+/// App::new().add_doryen_render_systems_to_stage(
+///     RenderStage::Last,
+///     mirror_to_second_screen.run_if(resource_changed::<RootConsole>()),
+/// );
+/// ```
+///
+/// Like any resource, it counts as changed whenever it's mutably
+/// dereferenced, whether or not any cell ends up different. The built-in
+/// render systems only do so when they draw something, and the console is
+/// only cleared by the [`ClearPolicy`](crate::ClearPolicy) when it's set to.
+#[derive(Default, Resource)]
 pub struct RootConsole(pub(crate) Option<Console>);
 
+/// The name [`RootConsole`] went by in earlier versions of bevy_doryen.
+#[deprecated(since = "0.3.0", note = "renamed to `RootConsole`")]
+pub type DoryenRootConsole = RootConsole;
+
 impl std::fmt::Debug for RootConsole {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RootConsole")
@@ -0,0 +1,244 @@
+//! Run statistics and a high-score table.
+
+use crate::doryen::{Color, Console, TextAlign};
+use bevy_ecs::system::Resource;
+use std::fmt::Write as _;
+use std::io;
+use std::path::Path;
+
+/// Resource holding the statistics of the current run. Update it from your
+/// game systems as things happen, and turn it into a [`HighScoreEntry`] with
+/// [`to_entry`](Self::to_entry) once the run is over.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Resource)]
+pub struct RunStatistics {
+    /// How many turns the run has lasted.
+    pub turns: u64,
+    /// How many monsters were killed.
+    pub kills: u64,
+    /// The deepest level reached.
+    pub depth: u32,
+    /// What ended the run, if it is over.
+    pub cause_of_death: Option<String>,
+}
+
+impl RunStatistics {
+    /// Marks the run as over.
+    pub fn end(&mut self, cause_of_death: impl Into<String>) {
+        self.cause_of_death = Some(cause_of_death.into());
+    }
+
+    /// Whether the run is over.
+    pub fn is_over(&self) -> bool {
+        self.cause_of_death.is_some()
+    }
+
+    /// Creates a high score entry for this run.
+    pub fn to_entry(&self, name: impl Into<String>, score: u64) -> HighScoreEntry {
+        HighScoreEntry {
+            name: name.into(),
+            score,
+            turns: self.turns,
+            kills: self.kills,
+            depth: self.depth,
+            cause_of_death: self.cause_of_death.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// A single entry in the [`HighScores`] table.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct HighScoreEntry {
+    /// The name of the player.
+    pub name: String,
+    /// The score of the run.
+    pub score: u64,
+    /// How many turns the run lasted.
+    pub turns: u64,
+    /// How many monsters were killed.
+    pub kills: u64,
+    /// The deepest level reached.
+    pub depth: u32,
+    /// What ended the run.
+    pub cause_of_death: String,
+}
+
+impl HighScoreEntry {
+    fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            escape(&self.name),
+            self.score,
+            self.turns,
+            self.kills,
+            self.depth,
+            escape(&self.cause_of_death)
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let mut fields = line.split('\t');
+        Some(Self {
+            name: unescape(fields.next()?),
+            score: fields.next()?.parse().ok()?,
+            turns: fields.next()?.parse().ok()?,
+            kills: fields.next()?.parse().ok()?,
+            depth: fields.next()?.parse().ok()?,
+            cause_of_death: unescape(fields.next()?),
+        })
+    }
+}
+
+pub(crate) fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+}
+
+pub(crate) fn unescape(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('t') => result.push('\t'),
+                Some('n') => result.push('\n'),
+                Some(other) => result.push(other),
+                None => result.push('\\'),
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Resource holding a table of the best runs, ordered from best to worst.
+#[derive(Debug, Clone, PartialEq, Eq, Resource)]
+pub struct HighScores {
+    entries: Vec<HighScoreEntry>,
+    /// How many entries the table keeps.
+    pub capacity: usize,
+}
+
+impl Default for HighScores {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            capacity: 10,
+        }
+    }
+}
+
+impl HighScores {
+    /// Loads a high score table keeping `capacity` entries from the given
+    /// file. A missing file results in an empty table; lines that can't be
+    /// parsed are skipped, and so are entries beyond the capacity.
+    pub fn load(path: impl AsRef<Path>, capacity: usize) -> io::Result<Self> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+
+        let mut high_scores = Self {
+            entries: Vec::new(),
+            capacity,
+        };
+        for entry in contents.lines().filter_map(HighScoreEntry::from_line) {
+            high_scores.record(entry);
+        }
+        Ok(high_scores)
+    }
+
+    /// Saves the high score table to the given file.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut contents = String::new();
+        for entry in &self.entries {
+            writeln!(contents, "{}", entry.to_line()).unwrap();
+        }
+        std::fs::write(path, contents)
+    }
+
+    /// Records an entry in the table. Returns the position it was placed at,
+    /// or `None` if the score wasn't good enough to make the table.
+    pub fn record(&mut self, entry: HighScoreEntry) -> Option<usize> {
+        let position = self
+            .entries
+            .iter()
+            .position(|e| e.score < entry.score)
+            .unwrap_or(self.entries.len());
+        if position >= self.capacity {
+            return None;
+        }
+
+        self.entries.insert(position, entry);
+        self.entries.truncate(self.capacity);
+        Some(position)
+    }
+
+    /// The entries of the table, from best to worst.
+    pub fn entries(&self) -> &[HighScoreEntry] {
+        &self.entries
+    }
+}
+
+/// Draws the high score table at the given position, one entry per line. If
+/// `highlight` is given, the entry at that position is drawn in that color
+/// instead. At most `height` entries are drawn.
+pub fn draw_high_scores(
+    console: &mut Console,
+    x: i32,
+    y: i32,
+    height: u32,
+    high_scores: &HighScores,
+    fore: Color,
+    highlight: Option<(usize, Color)>,
+) {
+    for (i, entry) in high_scores
+        .entries()
+        .iter()
+        .take(height as usize)
+        .enumerate()
+    {
+        let line = format!(
+            "{:>2}. {:<16} {:>8}  depth {:<3} {}",
+            i + 1,
+            entry.name,
+            entry.score,
+            entry.depth,
+            entry.cause_of_death
+        );
+        let color = match highlight {
+            Some((position, highlight_fore)) if position == i => highlight_fore,
+            _ => fore,
+        };
+        console.print(x, y + i as i32, &line, TextAlign::Left, Some(color), None);
+    }
+}
+
+/// Draws a morgue-file style summary of a run at the given position. Returns
+/// the number of lines drawn.
+pub fn draw_morgue(
+    console: &mut Console,
+    x: i32,
+    y: i32,
+    statistics: &RunStatistics,
+    fore: Color,
+) -> u32 {
+    let lines = [
+        format!(
+            "Cause of death: {}",
+            statistics
+                .cause_of_death
+                .as_deref()
+                .unwrap_or("still alive")
+        ),
+        format!("Deepest level:  {}", statistics.depth),
+        format!("Turns taken:    {}", statistics.turns),
+        format!("Monsters slain: {}", statistics.kills),
+    ];
+    for (i, line) in lines.iter().enumerate() {
+        console.print(x, y + i as i32, line, TextAlign::Left, Some(fore), None);
+    }
+    lines.len() as u32
+}
@@ -0,0 +1,86 @@
+use crate::doryen::AppOptions;
+use crate::web::{self, WebOptions};
+use bevy_ecs::event::Event;
+use bevy_ecs::system::Resource;
+use bevy_utils::tracing::warn;
+
+/// Resource for changing some of the [`AppOptions`] while the app is running.
+///
+/// [`DoryenPlugin`](crate::DoryenPlugin) inserts it when starting up, filled
+/// in from the [`DoryenPluginSettings`](crate::DoryenPluginSettings). After
+/// every update, changed fields are passed on to Doryen where it supports
+/// changing them. Doryen's native window can't be changed once it's open, so
+/// only the browser build honors most of these; a warning is logged for
+/// every change that couldn't be made.
+#[derive(Debug, Clone, PartialEq, Eq, Resource)]
+pub struct DoryenRuntimeSettings {
+    /// The title of the window, or of the page when running in a browser.
+    pub window_title: String,
+    /// Whether the window is fullscreen. Browsers only allow entering
+    /// fullscreen in response to the player pressing a key or clicking.
+    pub fullscreen: bool,
+    /// Whether the mouse cursor is visible over the window.
+    pub show_cursor: bool,
+    /// Whether to wait for vertical sync when presenting frames. Can't be
+    /// changed at runtime on any platform.
+    pub vsync: bool,
+}
+
+impl Default for DoryenRuntimeSettings {
+    fn default() -> Self {
+        Self::from_app_options(&AppOptions::default())
+    }
+}
+
+impl DoryenRuntimeSettings {
+    pub(crate) fn from_app_options(app_options: &AppOptions) -> Self {
+        Self {
+            window_title: app_options.window_title.clone(),
+            fullscreen: app_options.fullscreen,
+            show_cursor: app_options.show_cursor,
+            vsync: app_options.vsync,
+        }
+    }
+}
+
+/// Emitted after changes to the [`DoryenRuntimeSettings`] resource have been
+/// passed on to Doryen, at the end of the update they were made in. Settings
+/// that couldn't be changed on this platform are included as they are in the
+/// resource.
+#[derive(Debug, Clone, PartialEq, Eq, Event)]
+pub struct RuntimeSettingsApplied {
+    /// The settings before the change.
+    pub previous: DoryenRuntimeSettings,
+    /// The settings after the change.
+    pub current: DoryenRuntimeSettings,
+}
+
+/// Passes on the settings that differ between `previous` and `current`.
+pub(crate) fn apply(
+    previous: &DoryenRuntimeSettings,
+    current: &DoryenRuntimeSettings,
+    web_options: &WebOptions,
+) {
+    let unsupported = |setting: &str| {
+        warn!(
+            "changing {} at runtime isn't supported on this platform",
+            setting
+        )
+    };
+    if previous.window_title != current.window_title && !web::set_title(&current.window_title) {
+        unsupported("the window title");
+    }
+    if previous.fullscreen != current.fullscreen
+        && !web::set_fullscreen(web_options, current.fullscreen)
+    {
+        unsupported("fullscreen");
+    }
+    if previous.show_cursor != current.show_cursor
+        && !web::set_show_cursor(web_options, current.show_cursor)
+    {
+        unsupported("cursor visibility");
+    }
+    if previous.vsync != current.vsync {
+        unsupported("vsync");
+    }
+}
@@ -0,0 +1,235 @@
+use crate::{DoryenPluginSettings, MouseButton, ResizeMode, WebOptions};
+use std::error::Error;
+use std::fmt;
+
+impl DoryenPluginSettings {
+    /// Creates a builder for the settings, starting out with the defaults.
+    ///
+    /// Unlike filling in the struct directly, the builder checks the settings
+    /// for mistakes when it builds them, so they're reported up front instead
+    /// of making Doryen panic or misbehave once the app is running.
+    ///
+    /// ```
+    /// # use bevy_doryen::DoryenPluginSettings;
+    /// let settings = DoryenPluginSettings::builder()
+    ///     .console_size(80, 45)
+    ///     .screen_size(80 * 8, 45 * 8)
+    ///     .window_title("My roguelike")
+    ///     .build()
+    ///     .expect("valid settings");
+    /// # assert_eq!(settings.app_options.console_width, 80);
+    /// ```
+    pub fn builder() -> DoryenPluginSettingsBuilder {
+        DoryenPluginSettingsBuilder::default()
+    }
+}
+
+/// Builds [`DoryenPluginSettings`], validating them along the way. Created
+/// with [`DoryenPluginSettings::builder`].
+#[derive(Debug, Default)]
+pub struct DoryenPluginSettingsBuilder {
+    settings: DoryenPluginSettings,
+}
+
+impl DoryenPluginSettingsBuilder {
+    /// Sets the size of the root console, in cells.
+    pub fn console_size(mut self, width: u32, height: u32) -> Self {
+        self.settings.app_options.console_width = width;
+        self.settings.app_options.console_height = height;
+        self
+    }
+
+    /// Sets the size of the window, in pixels.
+    pub fn screen_size(mut self, width: u32, height: u32) -> Self {
+        self.settings.app_options.screen_width = width;
+        self.settings.app_options.screen_height = height;
+        self
+    }
+
+    /// Sets the title of the window.
+    pub fn window_title(mut self, window_title: impl Into<String>) -> Self {
+        self.settings.app_options.window_title = window_title.into();
+        self
+    }
+
+    /// Sets the path of the font to load at startup.
+    pub fn font_path(mut self, font_path: impl Into<String>) -> Self {
+        self.settings.app_options.font_path = font_path.into();
+        self
+    }
+
+    /// Sets whether the window starts out fullscreen.
+    pub fn fullscreen(mut self, fullscreen: bool) -> Self {
+        self.settings.app_options.fullscreen = fullscreen;
+        self
+    }
+
+    /// Sets whether to wait for vertical sync when presenting frames.
+    pub fn vsync(mut self, vsync: bool) -> Self {
+        self.settings.app_options.vsync = vsync;
+        self
+    }
+
+    /// Sets whether the mouse cursor is visible over the window.
+    pub fn show_cursor(mut self, show_cursor: bool) -> Self {
+        self.settings.app_options.show_cursor = show_cursor;
+        self
+    }
+
+    /// Sets whether the window can be resized.
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.settings.app_options.resizable = resizable;
+        self
+    }
+
+    /// Sets whether closing the window is left to the app. See
+    /// [`CloseRequested`](crate::CloseRequested).
+    pub fn intercept_close_request(mut self, intercept_close_request: bool) -> Self {
+        self.settings.app_options.intercept_close_request = intercept_close_request;
+        self
+    }
+
+    /// Replaces the mouse buttons to request input data for.
+    pub fn mouse_button_listeners(
+        mut self,
+        mouse_buttons: impl IntoIterator<Item = MouseButton>,
+    ) -> Self {
+        self.settings.mouse_button_listeners = mouse_buttons.into_iter().collect();
+        self
+    }
+
+    /// Adds a mouse button to request input data for.
+    pub fn mouse_button_listener(mut self, mouse_button: MouseButton) -> Self {
+        self.settings.mouse_button_listeners.push(mouse_button);
+        self
+    }
+
+    /// Sets what to do when the window is resized.
+    pub fn resize_mode(mut self, resize_mode: ResizeMode) -> Self {
+        self.settings.resize_mode = resize_mode;
+        self
+    }
+
+    /// Sets the settings that only apply when running in a browser.
+    pub fn web_options(mut self, web_options: WebOptions) -> Self {
+        self.settings.web_options = web_options;
+        self
+    }
+
+    /// Checks the settings and returns them, or the first problem found.
+    pub fn build(self) -> Result<DoryenPluginSettings, SettingsError> {
+        let options = &self.settings.app_options;
+        if options.console_width == 0 || options.console_height == 0 {
+            return Err(SettingsError::ZeroConsoleSize {
+                width: options.console_width,
+                height: options.console_height,
+            });
+        }
+        if options.screen_width == 0 || options.screen_height == 0 {
+            return Err(SettingsError::ZeroScreenSize {
+                width: options.screen_width,
+                height: options.screen_height,
+            });
+        }
+        if options.screen_width < options.console_width
+            || options.screen_height < options.console_height
+        {
+            return Err(SettingsError::ScreenSmallerThanConsole {
+                console_size: (options.console_width, options.console_height),
+                screen_size: (options.screen_width, options.screen_height),
+            });
+        }
+        if options.font_path.is_empty() {
+            return Err(SettingsError::EmptyFontPath);
+        }
+        if let ResizeMode::Automatic | ResizeMode::Letterbox | ResizeMode::Callback(_) =
+            self.settings.resize_mode
+        {
+            if !options.resizable {
+                return Err(SettingsError::ResizeModeWithoutResizableWindow);
+            }
+        }
+
+        let listeners = &self.settings.mouse_button_listeners;
+        for (i, mouse_button) in listeners.iter().enumerate() {
+            if listeners[..i]
+                .iter()
+                .any(|other| other.to_usize() == mouse_button.to_usize())
+            {
+                return Err(SettingsError::DuplicateMouseButtonListener(*mouse_button));
+            }
+        }
+
+        Ok(self.settings)
+    }
+}
+
+/// A problem with the settings found by
+/// [`DoryenPluginSettingsBuilder::build`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsError {
+    /// The root console has no cells.
+    ZeroConsoleSize {
+        /// The requested console width.
+        width: u32,
+        /// The requested console height.
+        height: u32,
+    },
+    /// The window has no pixels.
+    ZeroScreenSize {
+        /// The requested window width.
+        width: u32,
+        /// The requested window height.
+        height: u32,
+    },
+    /// The window is too small to give every console cell at least one pixel.
+    ScreenSmallerThanConsole {
+        /// The requested console size, in cells.
+        console_size: (u32, u32),
+        /// The requested window size, in pixels.
+        screen_size: (u32, u32),
+    },
+    /// No font was given.
+    EmptyFontPath,
+    /// A [`ResizeMode`] reacting to window resizes was chosen, but the window
+    /// can't be resized.
+    ResizeModeWithoutResizableWindow,
+    /// The same mouse button was listed more than once.
+    DuplicateMouseButtonListener(MouseButton),
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroConsoleSize { width, height } => write!(
+                f,
+                "the console must be at least 1x1 cells, but is {}x{}",
+                width, height
+            ),
+            Self::ZeroScreenSize { width, height } => write!(
+                f,
+                "the window must be at least 1x1 pixels, but is {}x{}",
+                width, height
+            ),
+            Self::ScreenSmallerThanConsole {
+                console_size,
+                screen_size,
+            } => write!(
+                f,
+                "a {}x{} pixel window is too small for a {}x{} cell console",
+                screen_size.0, screen_size.1, console_size.0, console_size.1
+            ),
+            Self::EmptyFontPath => f.write_str("no font path was given"),
+            Self::ResizeModeWithoutResizableWindow => f.write_str(
+                "the resize mode reacts to window resizes, but the window isn't resizable",
+            ),
+            Self::DuplicateMouseButtonListener(mouse_button) => write!(
+                f,
+                "mouse button {:?} is listened to more than once",
+                mouse_button
+            ),
+        }
+    }
+}
+
+impl Error for SettingsError {}
@@ -0,0 +1,403 @@
+//! Streaming the screen to spectators over TCP.
+//!
+//! A game hosts a stream by adding a [`SpectateHost`] to the
+//! [`FrameSinks`](crate::FrameSinks). Every frame, it sends the cells that
+//! changed to everyone connected, after a full frame when they connect:
+//!
+//! ```no_run
+//! # use bevy_doryen::spectate::SpectateHost;
+//! # use bevy_doryen::FrameSinks;
+//! # use bevy_ecs::system::ResMut;
+//! fn host(mut frame_sinks: ResMut<FrameSinks>) {
+//!     frame_sinks.add(SpectateHost::bind("0.0.0.0:7777").unwrap());
+//! }
+//! ```
+//!
+//! Spectators run an app with the [`SpectateViewerPlugin`] and a
+//! [`SpectateViewer`] connected to the game, which draws the stream on their
+//! root console. Neither side needs any game-specific code, and the viewer
+//! works with any game using bevy_doryen.
+//!
+//! ```no_run
+//! # use bevy_app::App;
+//! # use bevy_doryen::spectate::{SpectateViewer, SpectateViewerPlugin};
+//! # use bevy_doryen::DoryenPlugin;
+//! App::new()
+//!     .insert_resource(SpectateViewer::connect("192.168.1.10:7777").unwrap())
+//!     .add_plugins((DoryenPlugin, SpectateViewerPlugin))
+//!     .run();
+//! ```
+//!
+//! The stream is a sequence of messages, each starting with a one byte tag,
+//! with all numbers little-endian:
+//!
+//! * `0`, a full frame: the width and height as `u32`s, then every cell row
+//!   by row;
+//! * `1`, changed cells: their number as a `u32`, then for each the x and y
+//!   coordinates as `u16`s followed by the cell.
+//!
+//! A cell is its glyph as a `u16`, followed by the red, green, blue and alpha
+//! of its foreground and then its background color, one byte each.
+
+use crate::doryen::{Color, Console};
+use crate::{CellContents, ConsoleDiff, FrameSink, FrameSnapshot};
+use crate::{RenderStage, RenderSystemExtensions, RootConsole};
+use bevy_app::{App, Plugin};
+use bevy_ecs::system::{Res, ResMut, Resource};
+use bevy_utils::tracing::{info, warn};
+use std::io::{self, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// The tag of a full frame message.
+const FULL_FRAME: u8 = 0;
+/// The tag of a changed cells message.
+const CHANGED_CELLS: u8 = 1;
+/// How many messages can wait for a spectator before it's considered too
+/// slow to keep up, and disconnected.
+const SPECTATOR_BACKLOG: usize = 120;
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// The state shared between a [`SpectateHost`] and the thread accepting
+/// spectators.
+#[derive(Default, Debug)]
+struct HostState {
+    snapshot: FrameSnapshot,
+    spectators: Vec<SyncSender<Arc<Vec<u8>>>>,
+}
+
+/// A [`FrameSink`] streaming every frame to the spectators connected to it.
+/// See the [module documentation](self).
+#[derive(Debug)]
+pub struct SpectateHost {
+    state: Arc<Mutex<HostState>>,
+    local_addr: SocketAddr,
+    previous: FrameSnapshot,
+    current: FrameSnapshot,
+}
+
+impl SpectateHost {
+    /// Starts listening for spectators on the given address.
+    pub fn bind(address: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        let local_addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(HostState::default()));
+
+        let accept_state = Arc::clone(&state);
+        std::thread::Builder::new()
+            .name(String::from("spectate host"))
+            .spawn(move || accept_spectators(&listener, &accept_state))?;
+
+        Ok(Self {
+            state,
+            local_addr,
+            previous: FrameSnapshot::default(),
+            current: FrameSnapshot::default(),
+        })
+    }
+
+    /// The address spectators connect to.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// How many spectators are connected.
+    pub fn spectators(&self) -> usize {
+        lock(&self.state).spectators.len()
+    }
+}
+
+impl FrameSink for SpectateHost {
+    fn frame(&mut self, console: &Console) {
+        self.current.capture_into(console);
+        let mut state = lock(&self.state);
+        let message = if state.spectators.is_empty() {
+            None
+        } else {
+            let diff = ConsoleDiff::between(&self.previous, &self.current);
+            if diff.size_changed() {
+                Some(encode_full_frame(&self.current))
+            } else if diff.is_empty() {
+                None
+            } else {
+                Some(encode_changed_cells(&diff))
+            }
+        };
+        state.snapshot.clone_from(&self.current);
+        if let Some(message) = message {
+            let message = Arc::new(message);
+            state
+                .spectators
+                .retain(|spectator| match spectator.try_send(Arc::clone(&message)) {
+                    Ok(()) => true,
+                    Err(TrySendError::Full(_)) => {
+                        info!("disconnecting a spectator that can't keep up");
+                        false
+                    }
+                    Err(TrySendError::Disconnected(_)) => false,
+                });
+        }
+        drop(state);
+        std::mem::swap(&mut self.previous, &mut self.current);
+    }
+}
+
+fn accept_spectators(listener: &TcpListener, state: &Arc<Mutex<HostState>>) {
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(error) => {
+                warn!("could not accept a spectator: {}", error);
+                continue;
+            }
+        };
+        let _ = stream.set_nodelay(true);
+        let peer = stream.peer_addr().ok();
+
+        let (sender, receiver) = mpsc::sync_channel(SPECTATOR_BACKLOG);
+        {
+            // Holding the lock keeps frames from being sent before the full
+            // frame they apply to
+            let mut state = lock(state);
+            let _ = sender.try_send(Arc::new(encode_full_frame(&state.snapshot)));
+            state.spectators.push(sender);
+        }
+        info!("spectator {:?} connected", peer);
+
+        let spawned = std::thread::Builder::new()
+            .name(String::from("spectator"))
+            .spawn(move || {
+                for message in receiver {
+                    if stream.write_all(&message).is_err() {
+                        break;
+                    }
+                }
+                info!("spectator {:?} disconnected", peer);
+            });
+        if let Err(error) = spawned {
+            warn!("could not start streaming to a spectator: {}", error);
+        }
+    }
+}
+
+fn encode_cell(message: &mut Vec<u8>, cell: CellContents) {
+    message.extend_from_slice(&cell.glyph.to_le_bytes());
+    let (fr, fg, fb, fa) = cell.fore;
+    let (br, bg, bb, ba) = cell.back;
+    message.extend_from_slice(&[fr, fg, fb, fa, br, bg, bb, ba]);
+}
+
+fn encode_full_frame(snapshot: &FrameSnapshot) -> Vec<u8> {
+    let cell_count = snapshot.glyphs.len();
+    let mut message = Vec::with_capacity(9 + cell_count * 10);
+    message.push(FULL_FRAME);
+    message.extend_from_slice(&snapshot.width.to_le_bytes());
+    message.extend_from_slice(&snapshot.height.to_le_bytes());
+    let cells = snapshot
+        .glyphs
+        .iter()
+        .zip(&snapshot.fore)
+        .zip(&snapshot.back);
+    for ((&glyph, &fore), &back) in cells {
+        encode_cell(&mut message, CellContents { glyph, fore, back });
+    }
+    message
+}
+
+fn encode_changed_cells(diff: &ConsoleDiff) -> Vec<u8> {
+    let changes: Vec<_> = diff
+        .changes()
+        .iter()
+        .filter_map(|change| Some((change.x, change.y, change.after?)))
+        .collect();
+    let mut message = Vec::with_capacity(5 + changes.len() * 14);
+    message.push(CHANGED_CELLS);
+    message.extend_from_slice(&(changes.len() as u32).to_le_bytes());
+    for (x, y, cell) in changes {
+        message.extend_from_slice(&(x as u16).to_le_bytes());
+        message.extend_from_slice(&(y as u16).to_le_bytes());
+        encode_cell(&mut message, cell);
+    }
+    message
+}
+
+/// The spectate viewer plugin. Draws the stream of the [`SpectateViewer`]
+/// resource, if there is one, on the root console.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct SpectateViewerPlugin;
+
+impl Plugin for SpectateViewerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_doryen_render_systems_to_stage(RenderStage::Render, render_spectated_frame);
+    }
+}
+
+/// Resource receiving the stream of a [`SpectateHost`]. See the [module
+/// documentation](self).
+#[derive(Debug, Resource)]
+pub struct SpectateViewer {
+    frame: Arc<Mutex<FrameSnapshot>>,
+    connected: Arc<AtomicBool>,
+    new_frame: Arc<AtomicBool>,
+}
+
+impl SpectateViewer {
+    /// Connects to a game hosting a stream.
+    pub fn connect(address: impl ToSocketAddrs) -> io::Result<Self> {
+        let stream = TcpStream::connect(address)?;
+        let frame = Arc::new(Mutex::new(FrameSnapshot::default()));
+        let connected = Arc::new(AtomicBool::new(true));
+        let new_frame = Arc::new(AtomicBool::new(false));
+
+        let thread_frame = Arc::clone(&frame);
+        let thread_connected = Arc::clone(&connected);
+        let thread_new_frame = Arc::clone(&new_frame);
+        std::thread::Builder::new()
+            .name(String::from("spectate viewer"))
+            .spawn(move || {
+                let mut reader = BufReader::new(stream);
+                if let Err(error) = receive_frames(&mut reader, &thread_frame, &thread_new_frame) {
+                    if error.kind() != io::ErrorKind::UnexpectedEof {
+                        warn!("lost the connection to the spectated game: {}", error);
+                    }
+                }
+                thread_connected.store(false, Ordering::Relaxed);
+            })?;
+
+        Ok(Self {
+            frame,
+            connected,
+            new_frame,
+        })
+    }
+
+    /// Whether the connection to the game is still open.
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    /// The size of the game's root console, in cells, or `(0, 0)` until the
+    /// first frame has arrived.
+    pub fn size(&self) -> (u32, u32) {
+        let frame = lock(&self.frame);
+        (frame.width, frame.height)
+    }
+
+    /// Draws the latest frame on `console`, with its top-left cell at
+    /// `(x, y)`. Returns whether a new frame arrived since the last call.
+    pub fn draw(&self, console: &mut Console, x: i32, y: i32) -> bool {
+        let new_frame = self.new_frame.swap(false, Ordering::Relaxed);
+        let frame = lock(&self.frame);
+        for cy in 0..frame.height {
+            for cx in 0..frame.width {
+                if let Some(cell) = frame.cell(cx, cy) {
+                    console.cell(
+                        x + cx as i32,
+                        y + cy as i32,
+                        Some(cell.glyph),
+                        Some(cell.fore),
+                        Some(cell.back),
+                    );
+                }
+            }
+        }
+        new_frame
+    }
+}
+
+fn read_u16(reader: &mut impl Read) -> io::Result<u16> {
+    let mut bytes = [0; 2];
+    reader.read_exact(&mut bytes)?;
+    Ok(u16::from_le_bytes(bytes))
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_cell(reader: &mut impl Read) -> io::Result<CellContents> {
+    let glyph = read_u16(reader)?;
+    let mut colors = [0; 8];
+    reader.read_exact(&mut colors)?;
+    let color = |c: &[u8]| -> Color { (c[0], c[1], c[2], c[3]) };
+    Ok(CellContents {
+        glyph,
+        fore: color(&colors[..4]),
+        back: color(&colors[4..]),
+    })
+}
+
+fn receive_frames(
+    reader: &mut impl Read,
+    frame: &Mutex<FrameSnapshot>,
+    new_frame: &AtomicBool,
+) -> io::Result<()> {
+    loop {
+        let mut tag = [0];
+        reader.read_exact(&mut tag)?;
+        match tag[0] {
+            FULL_FRAME => {
+                let (width, height) = (read_u32(reader)?, read_u32(reader)?);
+                let cells = (width as usize)
+                    .checked_mul(height as usize)
+                    .filter(|&cells| cells <= 1 << 24)
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "frame too large"))?;
+                let mut snapshot = FrameSnapshot {
+                    width,
+                    height,
+                    glyphs: Vec::with_capacity(cells),
+                    fore: Vec::with_capacity(cells),
+                    back: Vec::with_capacity(cells),
+                };
+                for _ in 0..cells {
+                    let cell = read_cell(reader)?;
+                    snapshot.glyphs.push(cell.glyph);
+                    snapshot.fore.push(cell.fore);
+                    snapshot.back.push(cell.back);
+                }
+                *lock(frame) = snapshot;
+            }
+            CHANGED_CELLS => {
+                let count = read_u32(reader)?;
+                let mut changes = Vec::new();
+                for _ in 0..count {
+                    let (x, y) = (read_u16(reader)?, read_u16(reader)?);
+                    changes.push((u32::from(x), u32::from(y), read_cell(reader)?));
+                }
+                let mut frame = lock(frame);
+                for (x, y, cell) in changes {
+                    if let Some(index) = frame.index(x, y) {
+                        frame.glyphs[index] = cell.glyph;
+                        frame.fore[index] = cell.fore;
+                        frame.back[index] = cell.back;
+                    }
+                }
+            }
+            tag => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unknown message {}", tag),
+                ));
+            }
+        }
+        new_frame.store(true, Ordering::Relaxed);
+    }
+}
+
+fn render_spectated_frame(
+    viewer: Option<Res<'_, SpectateViewer>>,
+    mut root_console: ResMut<'_, RootConsole>,
+) {
+    if let Some(viewer) = viewer {
+        viewer.draw(&mut root_console, 0, 0);
+    }
+}
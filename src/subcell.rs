@@ -0,0 +1,166 @@
+//! Drawing at twice the vertical resolution of the console.
+//!
+//! A [`SubCellCanvas`] is a grid of pixels two for every console cell, one
+//! above the other. Drawing it on a console turns every pair into a
+//! half-block glyph, with the top pixel in the foreground color and the
+//! bottom one in the background color, which makes for smooth minimaps and
+//! title art with any font that has the CP437 half blocks:
+//!
+//! ```
+//! # use bevy_doryen::subcell::SubCellCanvas;
+//! # use bevy_doryen::RootConsole;
+//! # use bevy_ecs::system::ResMut;
+//! fn draw_minimap(mut root_console: ResMut<RootConsole>) {
+//!     let mut minimap = SubCellCanvas::new(20, 20);
+//!     minimap.fill_rect((0, 0), (20, 20), (0, 0, 64, 255));
+//!     minimap.line((2, 2), (17, 12), (255, 255, 0, 255));
+//!     minimap.set_pixel(9, 15, (255, 0, 0, 255));
+//!     minimap.draw(&mut root_console, 60, 0);
+//! }
+//! ```
+//!
+//! Pixels that were never set are transparent, and leave whatever is on the
+//! console showing. Unlike [`Image::blit_2x`], which splits cells in four
+//! and needs a font with the matching glyphs, only the half blocks are used.
+
+use crate::doryen::{Color, Console, Image};
+use crate::geometry;
+
+/// The CP437 upper half block, `▀`.
+const UPPER_HALF_BLOCK: u16 = 223;
+/// The CP437 lower half block, `▄`.
+const LOWER_HALF_BLOCK: u16 = 220;
+
+/// A surface of pixels half a console cell tall. See the [module
+/// documentation](self).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubCellCanvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<Option<Color>>,
+}
+
+impl SubCellCanvas {
+    /// A transparent canvas of `width` by `height` pixels, which takes up
+    /// `width` by `height / 2` cells, rounded up.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![None; (width * height) as usize],
+        }
+    }
+
+    /// A transparent canvas covering all of `console`.
+    pub fn for_console(console: &Console) -> Self {
+        Self::new(console.get_width(), console.get_height() * 2)
+    }
+
+    /// The width of the canvas, in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of the canvas, in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if x < 0 || y < 0 || x as u32 >= self.width || y as u32 >= self.height {
+            return None;
+        }
+        Some(y as usize * self.width as usize + x as usize)
+    }
+
+    /// The color of pixel `(x, y)`, or `None` if it's transparent or outside
+    /// the canvas.
+    pub fn pixel(&self, x: i32, y: i32) -> Option<Color> {
+        self.index(x, y).and_then(|index| self.pixels[index])
+    }
+
+    /// Sets the color of pixel `(x, y)`. Pixels outside the canvas are
+    /// ignored.
+    pub fn set_pixel(&mut self, x: i32, y: i32, color: Color) {
+        if let Some(index) = self.index(x, y) {
+            self.pixels[index] = Some(color);
+        }
+    }
+
+    /// Makes pixel `(x, y)` transparent again.
+    pub fn clear_pixel(&mut self, x: i32, y: i32) {
+        if let Some(index) = self.index(x, y) {
+            self.pixels[index] = None;
+        }
+    }
+
+    /// Makes every pixel transparent.
+    pub fn clear(&mut self) {
+        self.pixels.iter_mut().for_each(|pixel| *pixel = None);
+    }
+
+    /// Draws a line of pixels from `from` to `to`, both ends included.
+    pub fn line(&mut self, from: (i32, i32), to: (i32, i32), color: Color) {
+        for (x, y) in geometry::line(from, to) {
+            self.set_pixel(x, y, color);
+        }
+    }
+
+    /// Fills the rectangle of `size` pixels with its top-left pixel at
+    /// `position`.
+    pub fn fill_rect(&mut self, position: (i32, i32), size: (u32, u32), color: Color) {
+        for y in position.1..position.1 + size.1 as i32 {
+            for x in position.0..position.0 + size.0 as i32 {
+                self.set_pixel(x, y, color);
+            }
+        }
+    }
+
+    /// Copies `image` onto the canvas, one image pixel per canvas pixel, with
+    /// its top-left pixel at `(x, y)`. Pixels of the `transparent` color, if
+    /// given, and fully transparent ones are left out. Returns `false`,
+    /// copying nothing, while the image is still loading.
+    pub fn blit_image(
+        &mut self,
+        image: &mut Image,
+        x: i32,
+        y: i32,
+        transparent: Option<Color>,
+    ) -> bool {
+        let (width, height) = match image.try_get_size() {
+            Some(size) => size,
+            None => return false,
+        };
+        for image_y in 0..height {
+            for image_x in 0..width {
+                let color = match image.pixel(image_x, image_y) {
+                    Some(color) if color.3 > 0 && Some(color) != transparent => color,
+                    _ => continue,
+                };
+                self.set_pixel(x + image_x as i32, y + image_y as i32, color);
+            }
+        }
+        true
+    }
+
+    /// Draws the canvas on `console`, with its top-left pixel in the top half
+    /// of cell `(x, y)`.
+    pub fn draw(&self, console: &mut Console, x: i32, y: i32) {
+        for cell_y in 0..self.height.div_ceil(2) as i32 {
+            for cell_x in 0..self.width as i32 {
+                let top = self.pixel(cell_x, cell_y * 2);
+                let bottom = self.pixel(cell_x, cell_y * 2 + 1);
+                let (glyph, fore, back) = match (top, bottom) {
+                    (Some(top), Some(bottom)) if top == bottom => {
+                        (u16::from(b' '), None, Some(top))
+                    }
+                    (Some(top), Some(bottom)) => (UPPER_HALF_BLOCK, Some(top), Some(bottom)),
+                    (Some(top), None) => (UPPER_HALF_BLOCK, Some(top), None),
+                    (None, Some(bottom)) => (LOWER_HALF_BLOCK, Some(bottom), None),
+                    (None, None) => continue,
+                };
+                console.cell(x + cell_x, y + cell_y, Some(glyph), fore, back);
+            }
+        }
+    }
+}
@@ -0,0 +1,289 @@
+//! Area targeting, for aiming spells, throwing items and the like.
+
+use crate::doryen::{color_blend, Color};
+use crate::geometry;
+use crate::{Input, MouseButton, PostRenderSet, RenderStage, RenderSystemExtensions, RootConsole};
+use bevy_app::{App, Plugin, Update};
+use bevy_ecs::event::{Event, EventWriter};
+use bevy_ecs::schedule::IntoSystemConfigs;
+use bevy_ecs::system::{Res, ResMut, Resource};
+
+/// The plugin that adds the targeting subsystem. Start targeting by calling
+/// [`Targeting::start`], and listen for [`TargetConfirmed`] and
+/// [`TargetCancelled`] events to learn about the outcome.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct TargetingPlugin;
+
+impl Plugin for TargetingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Targeting>()
+            .init_resource::<TargetingSettings>()
+            .add_event::<TargetConfirmed>()
+            .add_event::<TargetCancelled>()
+            .add_systems(Update, targeting_input)
+            .add_doryen_render_systems_to_stage(
+                RenderStage::PostRender,
+                render_targeting.in_set(PostRenderSet::Overlay),
+            );
+    }
+}
+
+/// The shape of the area being targeted.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TargetShape {
+    /// Only the cell under the target cursor.
+    Cell,
+    /// Every cell on the line from the origin to the target cursor.
+    Line,
+    /// A cone from the origin towards the target cursor, reaching `radius`
+    /// cells out and spreading `half_angle_degrees` to either side.
+    Cone {
+        /// How far out the cone reaches.
+        radius: u32,
+        /// How far the cone spreads to either side, in degrees.
+        half_angle_degrees: f32,
+    },
+    /// Every cell within the given radius of the target cursor.
+    Radius(u32),
+}
+
+impl TargetShape {
+    /// Returns the cells covered by this shape when aiming from `origin` at
+    /// `target`.
+    pub fn cells(&self, origin: (i32, i32), target: (i32, i32)) -> Vec<(i32, i32)> {
+        match *self {
+            Self::Cell => vec![target],
+            Self::Line => geometry::line(origin, target),
+            Self::Cone {
+                radius,
+                half_angle_degrees,
+            } => geometry::cone(origin, target, radius, half_angle_degrees),
+            Self::Radius(radius) => geometry::disc(target, radius),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TargetingSession {
+    origin: (i32, i32),
+    cursor: (i32, i32),
+    shape: TargetShape,
+    range: Option<u32>,
+    last_mouse_cell: Option<(i32, i32)>,
+}
+
+/// Resource controlling the targeting subsystem.
+#[derive(Default, Debug, Clone, Resource)]
+pub struct Targeting {
+    session: Option<TargetingSession>,
+}
+
+impl Targeting {
+    /// Starts targeting from `origin` with the given shape. The target cursor
+    /// starts out on `origin`. If `range` is given, the target cursor can't be
+    /// moved further than that many cells away from `origin`.
+    ///
+    /// Starting while targeting is already in progress replaces the current
+    /// session without emitting any events.
+    pub fn start(&mut self, origin: (i32, i32), shape: TargetShape, range: Option<u32>) {
+        self.session = Some(TargetingSession {
+            origin,
+            cursor: origin,
+            shape,
+            range,
+            last_mouse_cell: None,
+        });
+    }
+
+    /// Stops targeting without emitting any events.
+    pub fn stop(&mut self) {
+        self.session = None;
+    }
+
+    /// Whether targeting is currently in progress.
+    pub fn is_active(&self) -> bool {
+        self.session.is_some()
+    }
+
+    /// The cell the target cursor is on, if targeting is in progress.
+    pub fn cursor(&self) -> Option<(i32, i32)> {
+        self.session.as_ref().map(|s| s.cursor)
+    }
+
+    /// Moves the target cursor to the given cell, as far as the range allows.
+    pub fn set_cursor(&mut self, cell: (i32, i32)) {
+        if let Some(session) = &mut self.session {
+            if let Some(range) = session.range {
+                let range = range as i32;
+                let origin = session.origin;
+                if geometry::distance_squared(origin, cell) > range * range {
+                    // Walk the line towards the requested cell and stop at the
+                    // last cell still within range.
+                    let in_range = geometry::line(origin, cell)
+                        .into_iter()
+                        .take_while(|&c| geometry::distance_squared(origin, c) <= range * range)
+                        .last();
+                    if let Some(in_range) = in_range {
+                        session.cursor = in_range;
+                    }
+                    return;
+                }
+            }
+            session.cursor = cell;
+        }
+    }
+
+    /// The cells currently covered by the targeted area. Empty if targeting
+    /// isn't in progress.
+    pub fn cells(&self) -> Vec<(i32, i32)> {
+        self.session
+            .as_ref()
+            .map_or_else(Vec::new, |s| s.shape.cells(s.origin, s.cursor))
+    }
+}
+
+/// Settings for the targeting subsystem's input handling and rendering.
+#[derive(Debug, Clone, Resource)]
+pub struct TargetingSettings {
+    /// The background color blended into cells covered by the targeted area.
+    pub highlight_color: Color,
+    /// The background color blended into the cell under the target cursor.
+    pub cursor_color: Color,
+    /// How strongly the highlight colors are blended in, from `0.0` to `1.0`.
+    pub highlight_alpha: f32,
+    /// Keys that confirm the current target.
+    pub confirm_keys: Vec<String>,
+    /// Keys that cancel targeting.
+    pub cancel_keys: Vec<String>,
+    /// Whether the target cursor follows the mouse, left click confirms and
+    /// right click cancels.
+    pub use_mouse: bool,
+}
+
+impl Default for TargetingSettings {
+    fn default() -> Self {
+        Self {
+            highlight_color: (255, 160, 64, 255),
+            cursor_color: (255, 255, 128, 255),
+            highlight_alpha: 0.5,
+            confirm_keys: vec![String::from("Enter"), String::from("NumpadEnter")],
+            cancel_keys: vec![String::from("Escape")],
+            use_mouse: true,
+        }
+    }
+}
+
+/// Emitted when the player confirms a target.
+#[derive(Debug, Clone, Event)]
+pub struct TargetConfirmed {
+    /// The cell targeting started from.
+    pub origin: (i32, i32),
+    /// The cell the target cursor was on.
+    pub target: (i32, i32),
+    /// The cells covered by the targeted area.
+    pub cells: Vec<(i32, i32)>,
+}
+
+/// Emitted when the player cancels targeting.
+#[derive(Debug, Clone, Copy, Event)]
+pub struct TargetCancelled {
+    /// The cell targeting started from.
+    pub origin: (i32, i32),
+}
+
+const MOVEMENT_KEYS: [(&str, (i32, i32)); 16] = [
+    ("ArrowLeft", (-1, 0)),
+    ("ArrowRight", (1, 0)),
+    ("ArrowUp", (0, -1)),
+    ("ArrowDown", (0, 1)),
+    ("Numpad4", (-1, 0)),
+    ("Numpad6", (1, 0)),
+    ("Numpad8", (0, -1)),
+    ("Numpad2", (0, 1)),
+    ("Numpad7", (-1, -1)),
+    ("Numpad9", (1, -1)),
+    ("Numpad1", (-1, 1)),
+    ("Numpad3", (1, 1)),
+    ("KeyH", (-1, 0)),
+    ("KeyL", (1, 0)),
+    ("KeyK", (0, -1)),
+    ("KeyJ", (0, 1)),
+];
+
+#[allow(clippy::needless_pass_by_value)]
+fn targeting_input(
+    input: Res<'_, Input>,
+    settings: Res<'_, TargetingSettings>,
+    mut targeting: ResMut<'_, Targeting>,
+    mut confirmed: EventWriter<'_, TargetConfirmed>,
+    mut cancelled: EventWriter<'_, TargetCancelled>,
+) {
+    let (origin, cursor) = match &targeting.session {
+        Some(session) => (session.origin, session.cursor),
+        None => return,
+    };
+
+    let cancel = settings.cancel_keys.iter().any(|k| input.key_pressed(k))
+        || (settings.use_mouse && input.mouse_button_pressed(MouseButton::Right));
+    if cancel {
+        targeting.stop();
+        cancelled.send(TargetCancelled { origin });
+        return;
+    }
+
+    let (dx, dy) = MOVEMENT_KEYS
+        .iter()
+        .filter(|(key, _)| input.key_pressed(key))
+        .fold((0, 0), |(x, y), (_, (dx, dy))| (x + dx, y + dy));
+    if dx != 0 || dy != 0 {
+        targeting.set_cursor((cursor.0 + dx.signum(), cursor.1 + dy.signum()));
+    }
+
+    if settings.use_mouse {
+        let (mouse_x, mouse_y) = input.mouse_pos();
+        let mouse_cell = (mouse_x.floor() as i32, mouse_y.floor() as i32);
+        let session = targeting.session.as_mut().unwrap();
+        let mouse_moved = session.last_mouse_cell != Some(mouse_cell);
+        session.last_mouse_cell = Some(mouse_cell);
+        if mouse_moved {
+            targeting.set_cursor(mouse_cell);
+        }
+    }
+
+    let confirm = settings.confirm_keys.iter().any(|k| input.key_pressed(k))
+        || (settings.use_mouse && input.mouse_button_pressed(MouseButton::Left));
+    if confirm {
+        let cells = targeting.cells();
+        let target = targeting.cursor().unwrap();
+        targeting.stop();
+        confirmed.send(TargetConfirmed {
+            origin,
+            target,
+            cells,
+        });
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn render_targeting(
+    targeting: Res<'_, Targeting>,
+    settings: Res<'_, TargetingSettings>,
+    mut root_console: ResMut<'_, RootConsole>,
+) {
+    let cursor = match targeting.cursor() {
+        Some(cursor) => cursor,
+        None => return,
+    };
+
+    let mut highlight = |(x, y): (i32, i32), color: Color| {
+        if let Some(back) = root_console.get_back(x, y) {
+            root_console.back(x, y, color_blend(back, color, settings.highlight_alpha));
+        }
+    };
+    for cell in targeting.cells() {
+        if cell != cursor {
+            highlight(cell, settings.highlight_color);
+        }
+    }
+    highlight(cursor, settings.cursor_color);
+}
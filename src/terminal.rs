@@ -0,0 +1,560 @@
+//! A runner that draws the root console to a terminal instead of a Doryen
+//! window.
+//!
+//! Add the [`DoryenPlugin`](crate::DoryenPlugin) as usual, then replace its
+//! runner with [`terminal_runner`]:
+//!
+//! ```no_run
+//! use bevy_app::App;
+//! use bevy_doryen::terminal::terminal_runner;
+//! use bevy_doryen::DoryenPlugin;
+//!
+//! App::new()
+//!     .add_plugins(DoryenPlugin)
+//!     .set_runner(terminal_runner)
+//!     .run();
+//! ```
+//!
+//! The same `Update` and [`DoryenRender`] schedules run, and the
+//! [`RootConsole`] and [`Input`] resources behave as they do in a window,
+//! with a few differences imposed by terminals:
+//!
+//! * Every console cell is one terminal character, so [`ScreenInfo`] reports
+//!   a character size of 1×1 and a screen size equal to the console size.
+//!   [`Resized`] events give the size of the terminal in characters, and
+//!   with [`ResizeMode::Automatic`] the console is resized to fill it.
+//! * Glyphs are translated from code page 437 to Unicode. Fonts, and so
+//!   [`SetFontPath`](crate::SetFontPath) events, have no effect.
+//! * Most terminals only report key presses, not releases. A key that isn't
+//!   reported as released is released again on the following update, so
+//!   [`Input::key`] only holds for a single update per press.
+//! * Pressing Ctrl+C sets [`Input::close_requested`], and pressing it again
+//!   while the application is still running exits it, after a last run of
+//!   the [`DoryenShutdown`](crate::DoryenShutdown) schedule.
+//! * With [`on_demand`](crate::DoryenFrameLimits::on_demand) frame limits,
+//!   the runner sleeps until there's input while there's nothing to do.
+//!
+//! [`Input`]: crate::Input
+//! [`ScreenInfo`]: crate::ScreenInfo
+//! [`Input::key`]: crate::Input::key
+//! [`Input::close_requested`]: crate::Input::close_requested
+
+use crate::doryen::{AppOptions, Color, Console};
+use crate::frame_loop::{self, FrameLoop};
+use crate::input::InputSource;
+use crate::touch::TouchInput;
+use crate::{
+    ConsoleDiff, DirtyRegions, DoryenPluginSettings, DoryenRender, FpsInfo, FrameSnapshot,
+    Modifiers, MouseButton, ResizeMode, Resized, RootConsole, UpdateRequests, UPDATES_PER_SECOND,
+};
+use bevy_app::App as BevyApp;
+use bevy_ecs::world::World;
+use crossterm::event::{
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+    KeyModifiers, MouseButton as TermMouseButton, MouseEvent, MouseEventKind,
+};
+use crossterm::style::{
+    Color as TermColor, Print, ResetColor, SetBackgroundColor, SetForegroundColor,
+};
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen, SetTitle};
+use crossterm::{cursor, execute, queue};
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+/// The longest the runner sleeps for while waiting for input with on-demand
+/// frame limits, so it still notices things like an [`AppExit`] sent from
+/// another thread.
+const MAX_IDLE_SLEEP: Duration = Duration::from_secs(1);
+
+/// Runs the application in the terminal. See the [module
+/// documentation](self) for details.
+///
+/// # Panics
+///
+/// Panics if the terminal can't be put into raw mode.
+pub fn terminal_runner(mut app: BevyApp) {
+    if let Err(e) = crate::error::validate_world(&app.world) {
+        bevy_utils::tracing::error!("can't start the terminal runner: {}", e);
+        return;
+    }
+
+    let mut resource_settings = app
+        .world
+        .get_resource_or_insert_with(DoryenPluginSettings::default);
+    let DoryenPluginSettings {
+        app_options,
+        mouse_button_listeners,
+        resize_mode,
+        ..
+    } = std::mem::take(&mut *resource_settings);
+
+    let AppOptions {
+        console_width,
+        console_height,
+        window_title,
+        ..
+    } = app_options;
+
+    app.world.resource_mut::<RootConsole>().0 = Some(Console::new(console_width, console_height));
+
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode().expect("failed to put the terminal into raw mode");
+    let _ = execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        cursor::Hide,
+        SetTitle(&window_title)
+    );
+
+    let result = run(&mut app, &mouse_button_listeners, resize_mode, &mut stdout);
+
+    let _ = execute!(
+        stdout,
+        ResetColor,
+        cursor::Show,
+        DisableMouseCapture,
+        LeaveAlternateScreen
+    );
+    let _ = terminal::disable_raw_mode();
+
+    if let Err(e) = result {
+        bevy_utils::tracing::error!("terminal runner failed: {}", e);
+    }
+}
+
+fn run(
+    app: &mut BevyApp,
+    mouse_button_listeners: &[MouseButton],
+    resize_mode: ResizeMode,
+    stdout: &mut io::Stdout,
+) -> io::Result<()> {
+    let tick = Duration::from_secs_f32(1.0 / UPDATES_PER_SECOND);
+    let mut frame_loop = FrameLoop::default();
+    let mut input = TerminalInput::default();
+    let mut screen = TerminalScreen::default();
+    let mut fps = FpsCounter::default();
+    let mut terminal_size = terminal::size().map_or_else(
+        |_| app.world.resource::<RootConsole>().get_size(),
+        |(width, height)| (u32::from(width), u32::from(height)),
+    );
+
+    loop {
+        let frame_start = Instant::now();
+
+        input.begin_update();
+        // Keys still to be released count as input
+        let on_demand = frame_loop::frame_limits(&app.world).on_demand;
+        if on_demand && input.keys_released.is_empty() {
+            let timeout = app
+                .world
+                .get_resource::<UpdateRequests>()
+                .and_then(|requests| requests.time_until_next(Instant::now()))
+                .map_or(MAX_IDLE_SLEEP, |timeout| timeout.min(MAX_IDLE_SLEEP));
+            event::poll(timeout)?;
+        }
+        while event::poll(Duration::ZERO)? {
+            let event = event::read()?;
+            if let Event::Resize(width, height) = event {
+                let new_size = (u32::from(width), u32::from(height));
+                resize(&mut app.world, resize_mode, terminal_size, new_size);
+                terminal_size = new_size;
+            }
+            input.handle_event(&event);
+            if input.exit {
+                frame_loop::shut_down(&mut app.world);
+                return draw(&app.world, &mut screen, stdout);
+            }
+        }
+
+        let fps_info = fps.tick(frame_start);
+        *app.world.resource_mut::<FpsInfo>() = fps_info;
+
+        // Every console cell is one character, so the screen is as large as
+        // the console
+        let console_size = app
+            .world
+            .resource::<RootConsole>()
+            .0
+            .as_ref()
+            .map_or((0, 0), Console::get_size);
+        if frame_loop.begin_frame(
+            &mut app.world,
+            console_size,
+            console_size,
+            mouse_button_listeners,
+            &mut input,
+        ) {
+            app.update();
+
+            if frame_loop.exit_requested(&app.world) {
+                frame_loop::shut_down(&mut app.world);
+                return draw(&app.world, &mut screen, stdout);
+            }
+        }
+
+        if frame_loop.should_render(&app.world, fps_info.fps) {
+            app.world.run_schedule(DoryenRender);
+            let unchanged = app
+                .world
+                .get_resource::<DirtyRegions>()
+                .is_some_and(DirtyRegions::is_clean);
+            if !unchanged {
+                draw(&app.world, &mut screen, stdout)?;
+            }
+        }
+
+        if let Some(remaining) = tick.checked_sub(frame_start.elapsed()) {
+            std::thread::sleep(remaining);
+        }
+    }
+}
+
+/// Sends the [`Resized`](crate::Resized) event for a resize of the terminal,
+/// in characters, and resizes the root console as `resize_mode` says.
+fn resize(
+    world: &mut World,
+    resize_mode: ResizeMode,
+    (previous_width, previous_height): (u32, u32),
+    (new_width, new_height): (u32, u32),
+) {
+    let resized = Resized {
+        previous_width,
+        previous_height,
+        new_width,
+        new_height,
+    };
+    // Every character is one cell, so an automatically resized console fills
+    // the terminal whatever size it had before
+    crate::resize_root_console(
+        world,
+        resize_mode,
+        resized,
+        (previous_width, previous_height),
+    );
+}
+
+fn draw(world: &World, screen: &mut TerminalScreen, stdout: &mut io::Stdout) -> io::Result<()> {
+    match world.resource::<RootConsole>().0.as_ref() {
+        Some(console) => screen.draw(console, stdout),
+        None => Ok(()),
+    }
+}
+
+#[derive(Debug, Default)]
+struct FpsCounter {
+    second_start: Option<Instant>,
+    frames: u32,
+    fps: u32,
+    history: Vec<u32>,
+}
+
+impl FpsCounter {
+    fn tick(&mut self, now: Instant) -> FpsInfo {
+        let second_start = *self.second_start.get_or_insert(now);
+        self.frames += 1;
+        if now.duration_since(second_start) >= Duration::from_secs(1) {
+            self.fps = self.frames;
+            self.frames = 0;
+            self.second_start = Some(now);
+            if self.history.len() == 60 {
+                self.history.remove(0);
+            }
+            self.history.push(self.fps);
+        }
+
+        let average_fps = if self.history.is_empty() {
+            self.fps
+        } else {
+            self.history.iter().sum::<u32>() / self.history.len() as u32
+        };
+        FpsInfo {
+            fps: self.fps,
+            average_fps,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct TerminalInput {
+    keys_pressed: Vec<String>,
+    keys_released: Vec<String>,
+    pending_releases: Vec<String>,
+    reports_releases: bool,
+    mouse_buttons_pressed: Vec<usize>,
+    mouse_buttons_released: Vec<usize>,
+    text: String,
+    mouse_position: (f32, f32),
+    mouse_wheel: (f32, f32),
+    modifiers: Modifiers,
+    close_requested: bool,
+    close_requests: u32,
+    exit: bool,
+}
+
+impl TerminalInput {
+    fn begin_update(&mut self) {
+        self.keys_pressed.clear();
+        self.keys_released = std::mem::take(&mut self.pending_releases);
+        if !self.reports_releases {
+            // Like the keys, the modifiers only last for one update
+            self.modifiers = Modifiers::NONE;
+        }
+        self.mouse_buttons_pressed.clear();
+        self.mouse_buttons_released.clear();
+        self.text.clear();
+        self.mouse_wheel = (0.0, 0.0);
+        self.close_requested = false;
+    }
+
+    fn handle_event(&mut self, event: &Event) {
+        match *event {
+            Event::Key(key_event) => self.handle_key(key_event),
+            Event::Mouse(mouse_event) => self.handle_mouse(mouse_event),
+            _ => (),
+        }
+    }
+
+    fn handle_key(&mut self, key_event: KeyEvent) {
+        let KeyEvent {
+            code,
+            modifiers,
+            kind,
+            ..
+        } = key_event;
+
+        // Terminals don't report the modifier keys themselves, only which
+        // ones were held along with another key
+        self.modifiers = Modifiers::NONE;
+        for (key_modifier, modifier) in [
+            (KeyModifiers::SHIFT, Modifiers::SHIFT),
+            (KeyModifiers::CONTROL, Modifiers::CTRL),
+            (KeyModifiers::ALT, Modifiers::ALT),
+            (KeyModifiers::SUPER, Modifiers::META),
+            (KeyModifiers::META, Modifiers::META),
+        ]
+        .iter()
+        .copied()
+        {
+            if modifiers.contains(key_modifier) {
+                self.modifiers |= modifier;
+            }
+        }
+
+        if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('c') {
+            self.exit = self.close_requests > 0;
+            self.close_requests += 1;
+            self.close_requested = true;
+            return;
+        }
+
+        let name = match key_name(code) {
+            Some(name) => name,
+            None => return,
+        };
+        match kind {
+            KeyEventKind::Press | KeyEventKind::Repeat => {
+                if let KeyCode::Char(c) = code {
+                    if !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) {
+                        self.text.push(c);
+                    }
+                }
+                if !self.reports_releases && !self.pending_releases.contains(&name) {
+                    self.pending_releases.push(name.clone());
+                }
+                self.keys_pressed.push(name);
+            }
+            KeyEventKind::Release => {
+                self.reports_releases = true;
+                self.pending_releases.clear();
+                self.keys_released.push(name);
+            }
+        }
+    }
+
+    fn handle_mouse(&mut self, mouse_event: MouseEvent) {
+        let MouseEvent {
+            kind, column, row, ..
+        } = mouse_event;
+        self.mouse_position = (f32::from(column) + 0.5, f32::from(row) + 0.5);
+
+        let button = |button| match button {
+            TermMouseButton::Left => MouseButton::Left.to_usize(),
+            TermMouseButton::Middle => MouseButton::Middle.to_usize(),
+            TermMouseButton::Right => MouseButton::Right.to_usize(),
+        };
+        match kind {
+            MouseEventKind::Down(b) => self.mouse_buttons_pressed.push(button(b)),
+            MouseEventKind::Up(b) => self.mouse_buttons_released.push(button(b)),
+            MouseEventKind::ScrollUp => self.mouse_wheel.1 += 1.0,
+            MouseEventKind::ScrollDown => self.mouse_wheel.1 -= 1.0,
+            MouseEventKind::ScrollLeft => self.mouse_wheel.0 -= 1.0,
+            MouseEventKind::ScrollRight => self.mouse_wheel.0 += 1.0,
+            _ => (),
+        }
+    }
+}
+
+impl InputSource for TerminalInput {
+    fn for_each_key_pressed(&self, f: &mut dyn FnMut(&str)) {
+        self.keys_pressed.iter().map(String::as_str).for_each(f);
+    }
+
+    fn for_each_key_released(&self, f: &mut dyn FnMut(&str)) {
+        self.keys_released.iter().map(String::as_str).for_each(f);
+    }
+
+    fn mouse_button_pressed(&mut self, num: usize) -> bool {
+        self.mouse_buttons_pressed.contains(&num)
+    }
+
+    fn mouse_button_released(&mut self, num: usize) -> bool {
+        self.mouse_buttons_released.contains(&num)
+    }
+
+    fn text(&self) -> String {
+        self.text.clone()
+    }
+
+    fn mouse_pos(&self) -> (f32, f32) {
+        self.mouse_position
+    }
+
+    fn mouse_wheel(&mut self) -> (f32, f32) {
+        self.mouse_wheel
+    }
+
+    fn touches(&mut self) -> Vec<TouchInput> {
+        Vec::new()
+    }
+
+    fn close_requested(&self) -> bool {
+        self.close_requested
+    }
+
+    fn modifiers(&self) -> Option<Modifiers> {
+        Some(self.modifiers)
+    }
+}
+
+/// Translates a crossterm key code into the key name Doryen would use.
+fn key_name(code: KeyCode) -> Option<String> {
+    let name = match code {
+        KeyCode::Char(' ') => "Space",
+        KeyCode::Char(c) if c.is_ascii_alphabetic() => {
+            return Some(format!("Key{}", c.to_ascii_uppercase()))
+        }
+        KeyCode::Char(c) if c.is_ascii_digit() => return Some(format!("Digit{}", c)),
+        KeyCode::Char('-') => "Minus",
+        KeyCode::Char('=') => "Equal",
+        KeyCode::Char('[') => "BracketLeft",
+        KeyCode::Char(']') => "BracketRight",
+        KeyCode::Char('\\') => "Backslash",
+        KeyCode::Char(';') => "Semicolon",
+        KeyCode::Char('\'') => "Quote",
+        KeyCode::Char(',') => "Comma",
+        KeyCode::Char('.') => "Period",
+        KeyCode::Char('/') => "Slash",
+        KeyCode::Char('`') => "Backquote",
+        KeyCode::F(n) => return Some(format!("F{}", n)),
+        KeyCode::Enter => "Enter",
+        KeyCode::Esc => "Escape",
+        KeyCode::Backspace => "Backspace",
+        KeyCode::Tab | KeyCode::BackTab => "Tab",
+        KeyCode::Up => "ArrowUp",
+        KeyCode::Down => "ArrowDown",
+        KeyCode::Left => "ArrowLeft",
+        KeyCode::Right => "ArrowRight",
+        KeyCode::Home => "Home",
+        KeyCode::End => "End",
+        KeyCode::PageUp => "PageUp",
+        KeyCode::PageDown => "PageDown",
+        KeyCode::Insert => "Insert",
+        KeyCode::Delete => "Delete",
+        _ => return None,
+    };
+    Some(String::from(name))
+}
+
+/// What is currently on the terminal, so only changed cells get redrawn.
+#[derive(Debug, Default)]
+struct TerminalScreen {
+    shown: Option<FrameSnapshot>,
+    next: FrameSnapshot,
+}
+
+impl TerminalScreen {
+    fn draw(&mut self, console: &Console, stdout: &mut io::Stdout) -> io::Result<()> {
+        self.next.capture_into(console);
+        let size = (self.next.width, self.next.height);
+        if !matches!(&self.shown, Some(shown) if (shown.width, shown.height) == size) {
+            // Start from a blank screen, which every cell differs from
+            queue!(
+                stdout,
+                ResetColor,
+                terminal::Clear(terminal::ClearType::All)
+            )?;
+            self.shown = Some(FrameSnapshot::default());
+        }
+        let previous = self.shown.get_or_insert_with(FrameSnapshot::default);
+        let diff = ConsoleDiff::between(previous, &self.next);
+        std::mem::swap(previous, &mut self.next);
+
+        let mut colors = None;
+        let mut cursor_at = None;
+        for change in diff.changes() {
+            let cell = match change.after {
+                Some(cell) => cell,
+                None => continue,
+            };
+            let (x, y) = (change.x, change.y);
+            if cursor_at != Some((x, y)) {
+                queue!(stdout, cursor::MoveTo(x as u16, y as u16))?;
+            }
+            if colors != Some((cell.fore, cell.back)) {
+                queue!(
+                    stdout,
+                    SetForegroundColor(term_color(cell.fore)),
+                    SetBackgroundColor(term_color(cell.back))
+                )?;
+                colors = Some((cell.fore, cell.back));
+            }
+            queue!(stdout, Print(cp437_to_char(cell.glyph)))?;
+            cursor_at = Some((x + 1, y));
+        }
+        stdout.flush()
+    }
+}
+
+#[inline]
+fn term_color((r, g, b, _): Color) -> TermColor {
+    TermColor::Rgb { r, g, b }
+}
+
+/// Code page 437 glyphs 0 through 31.
+const CP437_LOW: [char; 32] = [
+    ' ', '☺', '☻', '♥', '♦', '♣', '♠', '•', '◘', '○', '◙', '♂', '♀', '♪', '♫', '☼', '►', '◄', '↕',
+    '‼', '¶', '§', '▬', '↨', '↑', '↓', '→', '←', '∟', '↔', '▲', '▼',
+];
+
+/// Code page 437 glyphs 127 through 255.
+const CP437_HIGH: [char; 129] = [
+    '⌂', 'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å', 'É', 'æ',
+    'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ', 'á', 'í', 'ó', 'ú', 'ñ',
+    'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»', '░', '▒', '▓', '│', '┤', '╡', '╢', '╖',
+    '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐', '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩',
+    '╦', '╠', '═', '╬', '╧', '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌',
+    '▐', '▀', 'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩', '≡',
+    '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', ' ',
+];
+
+/// Translates a Doryen glyph, which is a code page 437 index, into the Unicode
+/// character that looks like it.
+fn cp437_to_char(ascii: u16) -> char {
+    match ascii {
+        0..=31 => CP437_LOW[usize::from(ascii)],
+        32..=126 => char::from(ascii as u8),
+        127..=255 => CP437_HIGH[usize::from(ascii - 127)],
+        _ => '?',
+    }
+}
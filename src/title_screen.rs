@@ -0,0 +1,281 @@
+//! A ready-made title screen with an animated background and a menu.
+//!
+//! [`TitleScreenPlugin`] draws the [`TitleScreen`] resource over the whole
+//! root console while it's [active](TitleScreen::active): a background
+//! cycling through a palette in diagonal bands, a logo and a menu underneath
+//! it. The menu is navigated with the arrow keys and picked from with Enter
+//! or Space, which sends a [`TitleMenuSelected`] event and hides the title
+//! screen. Hook the game up to that event:
+//!
+//! ```
+//! # use bevy_app::App;
+//! # use bevy_doryen::title_screen::{TitleMenuSelected, TitleScreen, TitleScreenPlugin};
+//! # use bevy_ecs::event::EventReader;
+//! const LOGO: &str = r"
+//!  ___ ___ _  _ ___ ___  ___
+//! |_ _|   \ || | __| __|/ __|
+//!  | || |) | __ | _|| _|\__ \
+//! |___|___/|_||_|___|___|___/
+//! ";
+//!
+//! fn start_game(mut selected: EventReader<TitleMenuSelected>) {
+//!     for selected in selected.iter() {
+//!         match selected.index {
+//!             0 => { /* start a new game */ }
+//!             _ => { /* quit */ }
+//!         }
+//!     }
+//! }
+//!
+//! App::new()
+//!     .insert_resource(TitleScreen::new(LOGO, ["New game", "Quit"]))
+//!     .add_plugins(TitleScreenPlugin);
+//! ```
+//!
+//! The title screen is drawn in [`RenderStage::PostRender`], at the end of
+//! [`PostRenderSet::Compose`], covering the world and the composited
+//! consoles. Overlays such as tooltips and dialogs still show over it.
+
+use crate::doryen::{color_blend, Color, TextAlign};
+use crate::{
+    DoryenFrameLimits, Input, Key, PostRenderSet, ReducedMotion, RenderStage,
+    RenderSystemExtensions, RootConsole, UPDATES_PER_SECOND,
+};
+use bevy_app::{App as BevyApp, Plugin, Update};
+use bevy_ecs::event::{Event, EventWriter};
+use bevy_ecs::schedule::IntoSystemConfigs;
+use bevy_ecs::system::{Res, ResMut, Resource};
+
+/// The title screen plugin. Adds the [`TitleScreen`] resource, unless one has
+/// already been inserted, and the [`TitleMenuSelected`] event.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct TitleScreenPlugin;
+
+impl Plugin for TitleScreenPlugin {
+    fn build(&self, app: &mut BevyApp) {
+        app.init_resource::<TitleScreen>()
+            .add_event::<TitleMenuSelected>()
+            .add_systems(Update, navigate_title_screen)
+            .add_doryen_render_systems_to_stage(
+                RenderStage::PostRender,
+                render_title_screen
+                    .in_set(PostRenderSet::Compose)
+                    .after(crate::compositor::composite_consoles),
+            );
+    }
+}
+
+/// Sent when an entry of the title screen menu is picked.
+#[derive(Debug, Clone, PartialEq, Eq, Event)]
+pub struct TitleMenuSelected {
+    /// The position of the entry in [`TitleScreen::menu`].
+    pub index: usize,
+    /// The text of the entry.
+    pub item: String,
+}
+
+/// Resource describing the title screen. See the [module
+/// documentation](self).
+#[derive(Debug, Clone, Resource)]
+pub struct TitleScreen {
+    /// Whether the title screen is shown and takes input. Picking a menu
+    /// entry sets this to `false`; use [`show`](Self::show) to bring it back.
+    pub active: bool,
+    /// The lines of the logo, drawn centered above the menu.
+    pub logo: Vec<String>,
+    /// The entries of the menu.
+    pub menu: Vec<String>,
+    /// The colors the background cycles through. Defaults to shades of blue.
+    pub palette: Vec<Color>,
+    /// How many palette colors the background moves through per second.
+    /// Defaults to `2.0`.
+    pub cycle_speed: f32,
+    /// The color of the logo.
+    pub logo_color: Color,
+    /// The color of the menu entries that aren't selected.
+    pub menu_color: Color,
+    /// The color of the selected menu entry.
+    pub selected_color: Color,
+    selected: usize,
+    elapsed_secs: f32,
+}
+
+impl Default for TitleScreen {
+    fn default() -> Self {
+        Self {
+            active: true,
+            logo: Vec::new(),
+            menu: vec![String::from("Play"), String::from("Quit")],
+            palette: vec![
+                (10, 20, 60, 255),
+                (20, 50, 110, 255),
+                (40, 90, 150, 255),
+                (20, 50, 110, 255),
+            ],
+            cycle_speed: 2.0,
+            logo_color: (255, 220, 120, 255),
+            menu_color: (190, 190, 190, 255),
+            selected_color: (255, 255, 255, 255),
+            selected: 0,
+            elapsed_secs: 0.0,
+        }
+    }
+}
+
+impl TitleScreen {
+    /// Creates an active title screen with the given logo, one line of text
+    /// per line of the logo, and menu entries. Leading and trailing empty
+    /// lines of the logo are left out.
+    pub fn new<S: Into<String>>(logo: &str, menu: impl IntoIterator<Item = S>) -> Self {
+        let lines: Vec<&str> = logo.lines().collect();
+        let first = lines.iter().position(|line| !line.trim().is_empty());
+        let last = lines.iter().rposition(|line| !line.trim().is_empty());
+        let logo_lines = match (first, last) {
+            (Some(first), Some(last)) => lines[first..=last]
+                .iter()
+                .map(|&line| String::from(line))
+                .collect(),
+            _ => Vec::new(),
+        };
+        Self {
+            logo: logo_lines,
+            menu: menu.into_iter().map(Into::into).collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the colors the background cycles through.
+    pub fn with_palette(mut self, palette: Vec<Color>) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// The position of the selected menu entry.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Shows the title screen again, with the first menu entry selected.
+    pub fn show(&mut self) {
+        self.active = true;
+        self.selected = 0;
+    }
+
+    /// The background color of a cell at the current point of the animation.
+    fn background(&self, x: i32, y: i32) -> Color {
+        let count = self.palette.len();
+        match count {
+            0 => (0, 0, 0, 255),
+            1 => self.palette[0],
+            _ => {
+                // Diagonal bands, four cells wide, moving across the screen
+                let position = (x + y) as f32 / 4.0 + self.elapsed_secs * self.cycle_speed;
+                let position = position.rem_euclid(count as f32);
+                let index = position as usize % count;
+                let next = (index + 1) % count;
+                color_blend(self.palette[index], self.palette[next], position.fract())
+            }
+        }
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn navigate_title_screen(
+    input: Res<'_, Input>,
+    frame_limits: Option<Res<'_, DoryenFrameLimits>>,
+    reduced_motion: Option<Res<'_, ReducedMotion>>,
+    mut title_screen: ResMut<'_, TitleScreen>,
+    mut menu_selected: EventWriter<'_, TitleMenuSelected>,
+) {
+    if !title_screen.active {
+        return;
+    }
+
+    let updates_per_second = frame_limits
+        .and_then(|limits| limits.updates_per_second)
+        .map_or(UPDATES_PER_SECOND, |rate| {
+            (rate as f32).min(UPDATES_PER_SECOND)
+        });
+    if !ReducedMotion::is_enabled(reduced_motion.as_deref()) {
+        title_screen.elapsed_secs += 1.0 / updates_per_second;
+    }
+
+    let count = title_screen.menu.len();
+    if count == 0 {
+        return;
+    }
+    if input.key_pressed(Key::ArrowDown) {
+        title_screen.selected = (title_screen.selected + 1) % count;
+    }
+    if input.key_pressed(Key::ArrowUp) {
+        title_screen.selected = (title_screen.selected + count - 1) % count;
+    }
+    if input.key_pressed(Key::Enter) || input.key_pressed(Key::Space) {
+        let index = title_screen.selected.min(count - 1);
+        title_screen.active = false;
+        menu_selected.send(TitleMenuSelected {
+            index,
+            item: title_screen.menu[index].clone(),
+        });
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn render_title_screen(
+    title_screen: Res<'_, TitleScreen>,
+    mut root_console: ResMut<'_, RootConsole>,
+) {
+    if !title_screen.active {
+        return;
+    }
+
+    let (width, height) = (
+        root_console.get_width() as i32,
+        root_console.get_height() as i32,
+    );
+    for y in 0..height {
+        for x in 0..width {
+            let back = title_screen.background(x, y);
+            root_console.cell(x, y, Some(' ' as u16), None, Some(back));
+        }
+    }
+
+    // The logo and the menu, with a blank line between them, centered as a
+    // block
+    let logo_width = title_screen
+        .logo
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0) as i32;
+    let block_height = title_screen.logo.len() as i32 + 1 + title_screen.menu.len() as i32;
+    let top = ((height - block_height) / 2).max(0);
+    let left = ((width - logo_width) / 2).max(0);
+    for (row, line) in title_screen.logo.iter().enumerate() {
+        root_console.print(
+            left,
+            top + row as i32,
+            line,
+            TextAlign::Left,
+            Some(title_screen.logo_color),
+            None,
+        );
+    }
+
+    let menu_top = top + title_screen.logo.len() as i32 + 1;
+    for (row, item) in title_screen.menu.iter().enumerate() {
+        let (text, color) = if row == title_screen.selected {
+            (format!("> {} <", item), title_screen.selected_color)
+        } else {
+            (item.clone(), title_screen.menu_color)
+        };
+        root_console.print(
+            width / 2,
+            menu_top + row as i32,
+            &text,
+            TextAlign::Center,
+            Some(color),
+            None,
+        );
+    }
+}
@@ -0,0 +1,224 @@
+//! Framed tooltips shown next to the mouse.
+//!
+//! Give an entity with a [`HitRegion`](crate::hit_test::HitRegion) a
+//! [`Tooltip`], and its text is shown in a small frame next to the mouse
+//! while the mouse is over the region. For things that aren't entities,
+//! such as the cells of a map, register regions on the [`Tooltips`]
+//! resource every update instead:
+//!
+//! ```
+//! # use bevy_doryen::hit_test::HitRegion;
+//! # use bevy_doryen::tooltip::{Tooltip, Tooltips};
+//! # use bevy_ecs::system::{Commands, ResMut};
+//! fn spawn_potion(mut commands: Commands) {
+//!     commands.spawn((
+//!         HitRegion::cell(10, 4, 0),
+//!         Tooltip::new("{red}Potion of healing{/}\nRestores 10 HP."),
+//!     ));
+//! }
+//!
+//! fn describe_stairs(mut tooltips: ResMut<Tooltips>) {
+//!     tooltips.add(((40, 20), (1, 1)), "Stairs down");
+//! }
+//! ```
+//!
+//! The text is [markup](crate::rich_text), word-wrapped to the
+//! [`TooltipStyle`]'s width at most. Tooltips go below and to the right of
+//! the mouse, and move to the other side of it where they would run off the
+//! edge of the console. Entity tooltips need the
+//! [`HitTestPlugin`](crate::hit_test::HitTestPlugin) to be found.
+
+use crate::doryen::Console;
+use crate::hit_test::HitTest;
+use crate::rich_text::{self, PrintMarkup, WrapAlign};
+use crate::window::{DrawFrame, FrameFill, FrameStyle};
+use crate::{Input, PostRenderSet, RenderStage, RenderSystemExtensions, RootConsole};
+use bevy_app::{App, First, Plugin};
+use bevy_ecs::component::Component;
+use bevy_ecs::schedule::IntoSystemConfigs;
+use bevy_ecs::system::{Query, Res, ResMut, Resource};
+
+/// The plugin that adds the [`Tooltips`] and [`TooltipStyle`] resources,
+/// unless they have already been inserted, and draws the tooltip under the
+/// mouse.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct TooltipPlugin;
+
+impl Plugin for TooltipPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Tooltips>()
+            .init_resource::<TooltipStyle>()
+            .add_systems(First, clear_tooltips)
+            .add_doryen_render_systems_to_stage(
+                RenderStage::PostRender,
+                render_tooltip.in_set(PostRenderSet::Overlay),
+            );
+    }
+}
+
+/// A component giving an entity with a
+/// [`HitRegion`](crate::hit_test::HitRegion) a tooltip.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Component)]
+pub struct Tooltip {
+    /// The text of the tooltip, as markup.
+    pub text: String,
+}
+
+impl Tooltip {
+    /// A tooltip with the given text.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self { text: text.into() }
+    }
+}
+
+/// A rectangle, as the position of its top-left cell and its size, and the
+/// tooltip shown over it.
+type TooltipRegion = (((i32, i32), (u32, u32)), String);
+
+/// Resource holding the tooltip regions registered during the current
+/// update. They're forgotten at the start of every update, so the systems
+/// registering them do it again every update, for as long as the tooltips
+/// apply.
+#[derive(Default, Debug, Clone, Resource)]
+pub struct Tooltips {
+    regions: Vec<TooltipRegion>,
+}
+
+impl Tooltips {
+    /// Shows `text` while the mouse is over the rectangle of `size` cells
+    /// with its top-left cell at `position`. Regions added later are on top
+    /// of earlier ones, and of entity tooltips.
+    pub fn add(&mut self, rect: ((i32, i32), (u32, u32)), text: impl Into<String>) {
+        self.regions.push((rect, text.into()));
+    }
+
+    /// The text of the topmost region covering the given cell.
+    pub fn at(&self, (x, y): (i32, i32)) -> Option<&str> {
+        self.regions
+            .iter()
+            .rev()
+            .find(|(((region_x, region_y), (width, height)), _)| {
+                x >= *region_x
+                    && y >= *region_y
+                    && ((x - region_x) as u32) < *width
+                    && ((y - region_y) as u32) < *height
+            })
+            .map(|(_, text)| text.as_str())
+    }
+
+    /// Forgets every region.
+    pub fn clear(&mut self) {
+        self.regions.clear();
+    }
+}
+
+/// Resource holding how tooltips look.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Resource)]
+pub struct TooltipStyle {
+    /// The frame around the tooltips, and their colors.
+    pub frame: FrameStyle,
+    /// The widest a tooltip gets, frame included, in cells. Longer text is
+    /// word-wrapped.
+    pub max_width: u32,
+}
+
+impl Default for TooltipStyle {
+    fn default() -> Self {
+        Self {
+            frame: FrameStyle::SINGLE.with_colors((255, 255, 192, 255), (32, 32, 48, 255)),
+            max_width: 32,
+        }
+    }
+}
+
+impl TooltipStyle {
+    /// The position of the top-left cell and the size of a tooltip showing
+    /// `text` for the mouse at `mouse_cell`, kept inside a console of the
+    /// given size where it fits.
+    pub fn place(
+        &self,
+        text: &str,
+        (mouse_x, mouse_y): (i32, i32),
+        (console_width, console_height): (u32, u32),
+    ) -> ((i32, i32), (u32, u32)) {
+        let plain: String = rich_text::parse_markup(text)
+            .into_iter()
+            .map(|span| span.text)
+            .collect();
+        let longest = plain
+            .lines()
+            .map(|line| line.chars().count() as u32)
+            .max()
+            .unwrap_or(0);
+        let inner_width = longest.clamp(1, self.max_width.max(3) - 2);
+        let lines = rich_text::wrapped_line_count(text, inner_width) as u32;
+        let (width, height) = (inner_width + 2, lines + 2);
+
+        let place = |mouse: i32, size: u32, console_size: u32| {
+            let after = mouse + 1;
+            if after + size as i32 <= console_size as i32 {
+                after
+            } else {
+                // On the other side of the mouse, or as far as it goes
+                (mouse - size as i32).max(0)
+            }
+        };
+        (
+            (
+                place(mouse_x, width, console_width),
+                place(mouse_y, height, console_height),
+            ),
+            (width, height),
+        )
+    }
+
+    /// Draws a tooltip showing `text` for the mouse at `mouse_cell`.
+    pub fn draw(&self, console: &mut Console, text: &str, mouse_cell: (i32, i32)) {
+        let rect = self.place(
+            text,
+            mouse_cell,
+            (console.get_width(), console.get_height()),
+        );
+        let ((x, y), (width, height)) = rect;
+        console.draw_frame_filled(rect, &self.frame, None, FrameFill::Clear);
+        console.print_wrapped(
+            ((x + 1, y + 1), (width - 2, height - 2)),
+            text,
+            WrapAlign::Left,
+            Some(self.frame.fore),
+            None,
+        );
+    }
+}
+
+fn clear_tooltips(mut tooltips: ResMut<'_, Tooltips>) {
+    if !tooltips.regions.is_empty() {
+        tooltips.clear();
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn render_tooltip(
+    input: Res<'_, Input>,
+    style: Res<'_, TooltipStyle>,
+    tooltips: Res<'_, Tooltips>,
+    hit_test: Option<Res<'_, HitTest>>,
+    entity_tooltips: Query<'_, '_, &Tooltip>,
+    mut root_console: ResMut<'_, RootConsole>,
+) {
+    if !input.cursor_inside() {
+        return;
+    }
+    let mouse_cell = input.mouse_cell();
+    let text = tooltips.at(mouse_cell).or_else(|| {
+        hit_test.as_ref().and_then(|hit_test| {
+            hit_test
+                .all_at(mouse_cell)
+                .find_map(|entity| entity_tooltips.get(entity).ok())
+                .map(|tooltip| tooltip.text.as_str())
+        })
+    });
+    if let Some(text) = text {
+        style.draw(&mut root_console, text, mouse_cell);
+    }
+}
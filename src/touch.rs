@@ -0,0 +1,198 @@
+use crate::{Input, UPDATES_PER_SECOND};
+use bevy_ecs::event::{Event, EventWriter};
+use bevy_ecs::system::Res;
+use bevy_utils::Instant;
+
+/// How long a touch can last and still count as a tap, in seconds.
+const TAP_MAX_SECS: f32 = 0.3;
+/// How long a touch has to be held to count as a long press, in seconds.
+const LONG_PRESS_SECS: f32 = 0.5;
+/// How far a touch can move, in cells, and still count as a tap or a long
+/// press.
+const SLOP_CELLS: f32 = 1.0;
+
+/// What happened to a touch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TouchPhase {
+    /// A finger touched the screen.
+    Started,
+    /// A finger moved on the screen.
+    Moved,
+    /// A finger was lifted off the screen.
+    Ended,
+    /// The system took over the touch, e.g. for a gesture of its own.
+    Cancelled,
+}
+
+/// A touch as reported by an [`InputSource`](crate::input::InputSource), in
+/// console cell coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct TouchInput {
+    pub(crate) id: u64,
+    pub(crate) phase: TouchPhase,
+    pub(crate) position: (f32, f32),
+}
+
+/// A finger currently on the screen. See [`Input::touches`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Touch {
+    id: u64,
+    start_position: (f32, f32),
+    position: (f32, f32),
+    held_ticks: u32,
+    long_pressed: bool,
+}
+
+impl Touch {
+    /// The identifier of the touch, the same in all its [`TouchEvent`]s.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Where the touch started, in console cell coordinates.
+    pub fn start_position(&self) -> (f32, f32) {
+        self.start_position
+    }
+
+    /// Where the touch is now, in console cell coordinates.
+    pub fn position(&self) -> (f32, f32) {
+        self.position
+    }
+
+    /// For how many seconds the finger has been on the screen, measured in
+    /// Doryen update ticks like [`Input::key_held_secs`].
+    pub fn held_secs(&self) -> f32 {
+        self.held_ticks as f32 / UPDATES_PER_SECOND
+    }
+
+    /// Whether the touch has stayed close enough to where it started to be
+    /// a tap or a long press.
+    fn stayed(&self) -> bool {
+        let (dx, dy) = (
+            self.position.0 - self.start_position.0,
+            self.position.1 - self.start_position.1,
+        );
+        dx.abs() <= SLOP_CELLS && dy.abs() <= SLOP_CELLS
+    }
+}
+
+/// The touch part of [`Input`].
+#[derive(Default, Debug)]
+pub(crate) struct TouchState {
+    pub(crate) active: Vec<Touch>,
+    pub(crate) events: Vec<(TouchInput, Instant)>,
+    pub(crate) taps: Vec<(f32, f32)>,
+    pub(crate) long_presses: Vec<(f32, f32)>,
+}
+
+impl TouchState {
+    pub(crate) fn clear(&mut self) {
+        self.events.clear();
+        self.taps.clear();
+        self.long_presses.clear();
+    }
+
+    /// Advances the touches on the screen by an update tick, recognizing the
+    /// ones that have become long presses.
+    pub(crate) fn tick(&mut self) {
+        for touch in &mut self.active {
+            touch.held_ticks += 1;
+            if !touch.long_pressed && touch.held_secs() >= LONG_PRESS_SECS && touch.stayed() {
+                touch.long_pressed = true;
+                self.long_presses.push(touch.position);
+            }
+        }
+    }
+
+    pub(crate) fn handle(&mut self, input: TouchInput, now: Instant) {
+        self.events.push((input, now));
+        let index = self.active.iter().position(|touch| touch.id == input.id);
+        match (input.phase, index) {
+            (TouchPhase::Started, _) => {
+                if let Some(index) = index {
+                    self.active.swap_remove(index);
+                }
+                self.active.push(Touch {
+                    id: input.id,
+                    start_position: input.position,
+                    position: input.position,
+                    held_ticks: 0,
+                    long_pressed: false,
+                });
+            }
+            (TouchPhase::Moved, Some(index)) => self.active[index].position = input.position,
+            (TouchPhase::Ended, Some(index)) => {
+                let mut touch = self.active.swap_remove(index);
+                touch.position = input.position;
+                if !touch.long_pressed && touch.held_secs() <= TAP_MAX_SECS && touch.stayed() {
+                    self.taps.push(touch.position);
+                }
+            }
+            (TouchPhase::Cancelled, Some(index)) => {
+                self.active.swap_remove(index);
+            }
+            (_, None) => (),
+        }
+    }
+}
+
+/// Emitted at the start of the update for every change to a touch since the
+/// last update, in the order they happened.
+///
+/// Touches are only reported in a browser; Doryen doesn't report them in a
+/// native window, and terminals have no touch input.
+#[derive(Debug, Clone, Copy, PartialEq, Event)]
+pub struct TouchEvent {
+    /// The identifier of the touch, the same for all of its events.
+    pub id: u64,
+    /// What happened to the touch.
+    pub phase: TouchPhase,
+    /// Where the touch is, in console cell coordinates.
+    pub position: (f32, f32),
+    /// When the runner received the change. See [`Input::received_at`].
+    pub received_at: Instant,
+}
+
+/// Emitted at the start of the update when a short touch that didn't move
+/// more than a cell ended since the last update.
+#[derive(Debug, Clone, Copy, PartialEq, Event)]
+pub struct TapEvent {
+    /// Where the tap was, in console cell coordinates.
+    pub position: (f32, f32),
+}
+
+/// Emitted at the start of the update when a touch has been held in place
+/// for half a second. Such a touch doesn't also count as a tap when it ends.
+#[derive(Debug, Clone, Copy, PartialEq, Event)]
+pub struct LongPressEvent {
+    /// Where the touch is, in console cell coordinates.
+    pub position: (f32, f32),
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub(crate) fn send_touch_events(
+    input: Res<'_, Input>,
+    mut touch_events: EventWriter<'_, TouchEvent>,
+    mut tap_events: EventWriter<'_, TapEvent>,
+    mut long_press_events: EventWriter<'_, LongPressEvent>,
+) {
+    let touch = input.touch_state();
+    touch_events.send_batch(
+        touch
+            .events
+            .iter()
+            .map(|&(touch_input, received_at)| TouchEvent {
+                id: touch_input.id,
+                phase: touch_input.phase,
+                position: touch_input.position,
+                received_at,
+            }),
+    );
+    tap_events.send_batch(touch.taps.iter().map(|&position| TapEvent { position }));
+    long_press_events.send_batch(
+        touch
+            .long_presses
+            .iter()
+            .map(|&position| LongPressEvent { position }),
+    );
+}
@@ -0,0 +1,688 @@
+//! Buttons, menus and text inputs as entities.
+//!
+//! Spawn an entity with a [`UiPosition`] and one of the [`Button`], [`Menu`]
+//! or [`TextInput`] widgets, and [`UiPlugin`] takes care of the rest: the
+//! widgets are sized to fit their content, drawn every frame, and react to
+//! the mouse and to the keyboard while they have the [`UiFocus`]. What the
+//! player does with them comes back as events:
+//!
+//! ```
+//! # use bevy_doryen::ui::{Button, ButtonClicked, Menu, TextInput, UiPosition};
+//! # use bevy_ecs::event::EventReader;
+//! # use bevy_ecs::system::Commands;
+//! fn spawn_character_sheet(mut commands: Commands) {
+//!     commands.spawn((UiPosition::new(2, 2), TextInput::new(20).with_placeholder("Name")));
+//!     commands.spawn((UiPosition::new(2, 4), Menu::new(["Fighter", "Wizard", "Thief"])));
+//!     commands.spawn((UiPosition::new(2, 8), Button::new("Done")));
+//! }
+//!
+//! fn on_click(mut clicked: EventReader<ButtonClicked>) {
+//!     for clicked in clicked.iter() {
+//!         // clicked.entity is the button
+//!     }
+//! }
+//! ```
+//!
+//! Tab and Shift+Tab move the focus between widgets in reading order, and
+//! clicking a widget focuses it. A focused button is pressed with Enter or
+//! Space, a focused menu is navigated with the arrow keys and picked from
+//! with Enter or Space, and a focused text input is typed into, with the
+//! usual editing keys, until Enter submits it.
+//!
+//! The [`UiRect`] of every widget is kept up to date, for drawing around the
+//! widgets, and so is a [`HitRegion`] on [`UI_LAYER`], which the mouse goes
+//! through: a widget covered by another region on a higher layer can't be
+//! clicked. The widgets are drawn in the
+//! [`RenderStage::Render`](crate::RenderStage::Render) stage, in the colors
+//! of the [`UiStyle`] resource.
+
+use crate::doryen::{Color, Console, TextAlign};
+use crate::geometry::Rect;
+use crate::hit_test::{HitRegion, HitTest, HitTestPlugin};
+use crate::{Input, Key, MouseButton, RenderSystemExtensions, RootConsole};
+use bevy_app::{App, Plugin, Update};
+use bevy_ecs::component::Component;
+use bevy_ecs::entity::Entity;
+use bevy_ecs::event::{Event, EventWriter};
+use bevy_ecs::query::{AnyOf, Or, With};
+use bevy_ecs::schedule::{apply_deferred, IntoSystemConfigs};
+use bevy_ecs::system::{Commands, Local, Query, Res, ResMut, Resource};
+
+/// The [`HitRegion::layer`] of widgets.
+pub const UI_LAYER: i32 = 100;
+
+/// The UI plugin. Adds the [`UiFocus`] and [`UiStyle`] resources, unless
+/// they have already been inserted, and the widget events. Adds the
+/// [`HitTestPlugin`] too, unless it has already been added.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct UiPlugin;
+
+impl Plugin for UiPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<HitTestPlugin>() {
+            app.add_plugins(HitTestPlugin);
+        }
+        app.init_resource::<UiFocus>()
+            .init_resource::<UiStyle>()
+            .add_event::<ButtonClicked>()
+            .add_event::<MenuSelected>()
+            .add_event::<TextSubmitted>()
+            .add_systems(
+                Update,
+                (
+                    layout_widgets,
+                    apply_deferred,
+                    focus_widgets,
+                    press_buttons,
+                    navigate_menus,
+                    edit_text_inputs,
+                )
+                    .chain(),
+            )
+            .add_doryen_render_systems(draw_widgets);
+    }
+}
+
+/// Where the top-left cell of a widget goes on the root console.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub struct UiPosition {
+    /// The x coordinate of the top-left cell.
+    pub x: i32,
+    /// The y coordinate of the top-left cell.
+    pub y: i32,
+}
+
+impl UiPosition {
+    /// A position with the top-left cell at `(x, y)`.
+    pub fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+}
+
+/// The cells a widget takes up, worked out from its [`UiPosition`] and its
+/// content. Added to every widget and kept up to date by the plugin.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub struct UiRect(pub Rect);
+
+impl std::ops::Deref for UiRect {
+    type Target = Rect;
+
+    fn deref(&self) -> &Rect {
+        &self.0
+    }
+}
+
+/// A button, drawn as `[ label ]`. Sends a [`ButtonClicked`] event when it's
+/// clicked, or pressed while focused.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Component)]
+pub struct Button {
+    /// The text on the button.
+    pub label: String,
+}
+
+impl Button {
+    /// A button with the given label.
+    pub fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+        }
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (self.label.chars().count() as u32 + 4, 1)
+    }
+}
+
+/// A list of entries, one per line, of which one is selected. Sends a
+/// [`MenuSelected`] event when an entry is picked.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Component)]
+pub struct Menu {
+    /// The entries of the menu.
+    pub items: Vec<String>,
+    /// The position of the selected entry.
+    pub selected: usize,
+}
+
+impl Menu {
+    /// A menu with the given entries, the first one selected.
+    pub fn new<S: Into<String>>(items: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            items: items.into_iter().map(Into::into).collect(),
+            selected: 0,
+        }
+    }
+
+    /// The selected entry.
+    pub fn selected_item(&self) -> Option<&str> {
+        self.items.get(self.selected).map(String::as_str)
+    }
+
+    fn size(&self) -> (u32, u32) {
+        let width = self
+            .items
+            .iter()
+            .map(|item| item.chars().count())
+            .max()
+            .unwrap_or(0);
+        (width as u32 + 2, self.items.len() as u32)
+    }
+}
+
+/// A single-line text field. Sends a [`TextSubmitted`] event when Enter is
+/// pressed while it's focused.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Component)]
+pub struct TextInput {
+    /// The text typed in.
+    pub text: String,
+    /// The text shown in the field while it's empty and not focused.
+    pub placeholder: String,
+    /// The width of the field, in cells. Longer text scrolls.
+    pub width: u32,
+    /// The most characters the text can have, if there's a limit.
+    pub max_chars: Option<usize>,
+    cursor: usize,
+}
+
+impl TextInput {
+    /// An empty field `width` cells wide.
+    pub fn new(width: u32) -> Self {
+        Self {
+            text: String::new(),
+            placeholder: String::new(),
+            width: width.max(1),
+            max_chars: None,
+            cursor: 0,
+        }
+    }
+
+    /// Sets the text, with the cursor at its end.
+    pub fn with_text(mut self, text: impl Into<String>) -> Self {
+        self.set_text(text);
+        self
+    }
+
+    /// Sets the text shown while the field is empty.
+    pub fn with_placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    /// Limits the text to `max_chars` characters.
+    pub fn with_max_chars(mut self, max_chars: usize) -> Self {
+        self.max_chars = Some(max_chars);
+        self
+    }
+
+    /// Replaces the text, putting the cursor at its end.
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.cursor = self.text.chars().count();
+    }
+
+    /// Where the cursor is, in characters from the start of the text.
+    pub fn cursor(&self) -> usize {
+        self.cursor.min(self.text.chars().count())
+    }
+
+    fn byte_index(&self, chars: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(chars)
+            .map_or(self.text.len(), |(i, _)| i)
+    }
+
+    fn insert(&mut self, c: char) {
+        let len = self.text.chars().count();
+        if self.max_chars.is_some_and(|max_chars| len >= max_chars) {
+            return;
+        }
+        let index = self.byte_index(self.cursor());
+        self.text.insert(index, c);
+        self.cursor = self.cursor() + 1;
+    }
+
+    fn remove(&mut self, chars: usize) {
+        if chars < self.text.chars().count() {
+            let index = self.byte_index(chars);
+            self.text.remove(index);
+        }
+    }
+
+    /// The first character shown, so the cursor stays inside the field.
+    fn scroll(&self) -> usize {
+        (self.cursor() + 1).saturating_sub(self.width as usize)
+    }
+}
+
+/// Resource holding the widget the keyboard goes to, if any.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Resource)]
+pub struct UiFocus(pub Option<Entity>);
+
+impl UiFocus {
+    /// The focused widget.
+    pub fn focused(&self) -> Option<Entity> {
+        self.0
+    }
+
+    /// Whether `entity` is focused.
+    pub fn is_focused(&self, entity: Entity) -> bool {
+        self.0 == Some(entity)
+    }
+
+    /// Focuses `entity`.
+    pub fn focus(&mut self, entity: Entity) {
+        self.0 = Some(entity);
+    }
+
+    /// Unfocuses every widget.
+    pub fn clear(&mut self) {
+        self.0 = None;
+    }
+}
+
+/// Resource holding the colors of the widgets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Resource)]
+pub struct UiStyle {
+    /// The foreground color of widgets.
+    pub fore: Color,
+    /// The background color of widgets.
+    pub back: Color,
+    /// The foreground color of the focused widget, and of the selected menu
+    /// entry.
+    pub focus_fore: Color,
+    /// The background color of the focused widget, and of the selected menu
+    /// entry.
+    pub focus_back: Color,
+    /// The background color of text fields.
+    pub field_back: Color,
+    /// The color of the placeholder text of empty text fields.
+    pub placeholder_fore: Color,
+}
+
+impl Default for UiStyle {
+    fn default() -> Self {
+        Self {
+            fore: (192, 192, 192, 255),
+            back: (0, 0, 0, 255),
+            focus_fore: (0, 0, 0, 255),
+            focus_back: (192, 192, 192, 255),
+            field_back: (32, 32, 48, 255),
+            placeholder_fore: (96, 96, 96, 255),
+        }
+    }
+}
+
+/// Sent when a [`Button`] is clicked, or pressed while focused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Event)]
+pub struct ButtonClicked {
+    /// The button.
+    pub entity: Entity,
+}
+
+/// Sent when an entry of a [`Menu`] is picked.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Event)]
+pub struct MenuSelected {
+    /// The menu.
+    pub entity: Entity,
+    /// The position of the entry in [`Menu::items`].
+    pub index: usize,
+    /// The text of the entry.
+    pub item: String,
+}
+
+/// Sent when Enter is pressed in a focused [`TextInput`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Event)]
+pub struct TextSubmitted {
+    /// The text input.
+    pub entity: Entity,
+    /// The text typed in.
+    pub text: String,
+}
+
+type Widget<'a> = AnyOf<(&'a Button, &'a Menu, &'a TextInput)>;
+type IsWidget = Or<(With<Button>, With<Menu>, With<TextInput>)>;
+
+type Layout<'a> = (
+    Entity,
+    &'a UiPosition,
+    Widget<'a>,
+    Option<&'a mut UiRect>,
+    Option<&'a mut HitRegion>,
+);
+
+fn layout_widgets(mut commands: Commands<'_, '_>, mut widgets: Query<'_, '_, Layout<'_>>) {
+    for (entity, position, widget, rect, hit_region) in &mut widgets {
+        let (width, height) = match widget {
+            (Some(button), _, _) => button.size(),
+            (None, Some(menu), _) => menu.size(),
+            (None, None, Some(text_input)) => (text_input.width, 1),
+            (None, None, None) => continue,
+        };
+        let laid_out = Rect::new(position.x, position.y, width, height);
+        match rect {
+            Some(mut rect) => {
+                if rect.0 != laid_out {
+                    rect.0 = laid_out;
+                }
+            }
+            None => {
+                commands.entity(entity).insert(UiRect(laid_out));
+            }
+        }
+        match hit_region {
+            Some(mut hit_region) => {
+                if hit_region.rect != laid_out {
+                    hit_region.rect = laid_out;
+                }
+            }
+            None => {
+                commands
+                    .entity(entity)
+                    .insert(HitRegion::new(laid_out, UI_LAYER));
+            }
+        }
+    }
+}
+
+fn is_activated(input: &Input) -> bool {
+    input.key_pressed(Key::Enter) || input.key_pressed(Key::NumpadEnter)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn focus_widgets(
+    input: Res<'_, Input>,
+    hit_test: Res<'_, HitTest>,
+    mut focus: ResMut<'_, UiFocus>,
+    widgets: Query<'_, '_, (Entity, &UiRect), IsWidget>,
+) {
+    if let Some(focused) = focus.0 {
+        if widgets.get(focused).is_err() {
+            focus.clear();
+        }
+    }
+
+    if input.mouse_button_pressed(MouseButton::Left) {
+        let clicked = hit_test
+            .under_mouse(&input)
+            .filter(|&entity| widgets.contains(entity));
+        if focus.0 != clicked {
+            focus.0 = clicked;
+        }
+    }
+
+    if input.key_pressed(Key::Tab) {
+        // Reading order: top to bottom, then left to right
+        let mut order: Vec<_> = widgets.iter().collect();
+        order.sort_by_key(|&(entity, rect)| (rect.y, rect.x, entity));
+        let count = order.len();
+        if count == 0 {
+            return;
+        }
+        let current = focus
+            .0
+            .and_then(|focused| order.iter().position(|&(entity, _)| entity == focused));
+        let next = match (current, input.shift()) {
+            (Some(current), false) => (current + 1) % count,
+            (Some(current), true) => (current + count - 1) % count,
+            (None, false) => 0,
+            (None, true) => count - 1,
+        };
+        focus.focus(order[next].0);
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn press_buttons(
+    input: Res<'_, Input>,
+    hit_test: Res<'_, HitTest>,
+    focus: Res<'_, UiFocus>,
+    buttons: Query<'_, '_, Entity, With<Button>>,
+    mut clicked: EventWriter<'_, ButtonClicked>,
+) {
+    let under_mouse = hit_test.under_mouse(&input);
+    for entity in buttons.iter() {
+        let pressed = (input.mouse_button_pressed(MouseButton::Left)
+            && under_mouse == Some(entity))
+            || (focus.is_focused(entity)
+                && (is_activated(&input) || input.key_pressed(Key::Space)));
+        if pressed {
+            clicked.send(ButtonClicked { entity });
+        }
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn navigate_menus(
+    input: Res<'_, Input>,
+    hit_test: Res<'_, HitTest>,
+    focus: Res<'_, UiFocus>,
+    mut menus: Query<'_, '_, (Entity, &mut Menu, &UiRect)>,
+    mut selected: EventWriter<'_, MenuSelected>,
+    mut previous_mouse_cell: Local<'_, (i32, i32)>,
+) {
+    let mouse_cell = input.mouse_cell();
+    let mouse_moved = mouse_cell != *previous_mouse_cell;
+    *previous_mouse_cell = mouse_cell;
+    let under_mouse = hit_test.under_mouse(&input);
+
+    for (entity, mut menu, rect) in &mut menus {
+        let count = menu.items.len();
+        if count == 0 {
+            continue;
+        }
+
+        let hovered = if under_mouse == Some(entity) {
+            Some((mouse_cell.1 - rect.y) as usize).filter(|&index| index < count)
+        } else {
+            None
+        };
+        let mut picked = false;
+        if let Some(hovered) = hovered {
+            // The mouse only takes over the selection when it moves, so it
+            // doesn't fight the keyboard
+            if (mouse_moved || input.mouse_button_pressed(MouseButton::Left))
+                && menu.selected != hovered
+            {
+                menu.selected = hovered;
+            }
+            picked = input.mouse_button_pressed(MouseButton::Left);
+        }
+
+        if focus.is_focused(entity) {
+            if input.key_repeated(Key::ArrowDown) {
+                menu.selected = (menu.selected + 1) % count;
+            }
+            if input.key_repeated(Key::ArrowUp) {
+                menu.selected = (menu.selected.min(count - 1) + count - 1) % count;
+            }
+            picked |= is_activated(&input) || input.key_pressed(Key::Space);
+        }
+
+        if picked {
+            let index = menu.selected.min(count - 1);
+            selected.send(MenuSelected {
+                entity,
+                index,
+                item: menu.items[index].clone(),
+            });
+        }
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn edit_text_inputs(
+    input: Res<'_, Input>,
+    focus: Res<'_, UiFocus>,
+    mut text_inputs: Query<'_, '_, &mut TextInput>,
+    mut submitted: EventWriter<'_, TextSubmitted>,
+) {
+    let entity = match focus.0 {
+        Some(entity) => entity,
+        None => return,
+    };
+    let mut text_input = match text_inputs.get_mut(entity) {
+        Ok(text_input) => text_input,
+        Err(_) => return,
+    };
+
+    for c in input.text().chars().filter(|c| !c.is_control()) {
+        text_input.insert(c);
+    }
+    let mut cursor = text_input.cursor();
+    if input.key_repeated(Key::Backspace) && cursor > 0 {
+        text_input.remove(cursor - 1);
+        text_input.cursor = cursor - 1;
+        cursor = text_input.cursor();
+    }
+    if input.key_repeated(Key::Delete) {
+        text_input.remove(cursor);
+    }
+    if input.key_repeated(Key::ArrowLeft) {
+        text_input.cursor = cursor.saturating_sub(1);
+    }
+    let len = text_input.text.chars().count();
+    if input.key_repeated(Key::ArrowRight) && cursor < len {
+        text_input.cursor = cursor + 1;
+    }
+    if input.key_pressed(Key::Home) {
+        text_input.cursor = 0;
+    }
+    if input.key_pressed(Key::End) {
+        text_input.cursor = text_input.text.chars().count();
+    }
+    if is_activated(&input) {
+        submitted.send(TextSubmitted {
+            entity,
+            text: text_input.text.clone(),
+        });
+    }
+}
+
+fn draw_button(
+    console: &mut Console,
+    button: &Button,
+    rect: &UiRect,
+    focused: bool,
+    style: &UiStyle,
+) {
+    let (fore, back) = if focused {
+        (style.focus_fore, style.focus_back)
+    } else {
+        (style.fore, style.back)
+    };
+    let label = format!("[ {} ]", button.label);
+    console.print(
+        rect.x,
+        rect.y,
+        &label,
+        TextAlign::Left,
+        Some(fore),
+        Some(back),
+    );
+}
+
+fn draw_menu(console: &mut Console, menu: &Menu, rect: &UiRect, focused: bool, style: &UiStyle) {
+    for (i, item) in menu.items.iter().enumerate() {
+        let is_selected = i == menu.selected;
+        let (fore, back) = if is_selected {
+            (style.focus_fore, style.focus_back)
+        } else {
+            (style.fore, style.back)
+        };
+        let marker = if is_selected && focused { '>' } else { ' ' };
+        let line = format!(
+            "{} {:width$}",
+            marker,
+            item,
+            width = rect.width.saturating_sub(2) as usize
+        );
+        console.print(
+            rect.x,
+            rect.y + i as i32,
+            &line,
+            TextAlign::Left,
+            Some(fore),
+            Some(back),
+        );
+    }
+}
+
+fn draw_text_input(
+    console: &mut Console,
+    text_input: &TextInput,
+    rect: &UiRect,
+    focused: bool,
+    style: &UiStyle,
+) {
+    console.area(
+        rect.x,
+        rect.y,
+        rect.width,
+        1,
+        Some(style.fore),
+        Some(style.field_back),
+        Some(u16::from(b' ')),
+    );
+    if text_input.text.is_empty() && !focused {
+        let placeholder: String = text_input
+            .placeholder
+            .chars()
+            .take(rect.width as usize)
+            .collect();
+        console.print(
+            rect.x,
+            rect.y,
+            &placeholder,
+            TextAlign::Left,
+            Some(style.placeholder_fore),
+            None,
+        );
+        return;
+    }
+
+    let scroll = text_input.scroll();
+    let shown: String = text_input
+        .text
+        .chars()
+        .skip(scroll)
+        .take(rect.width as usize)
+        .collect();
+    console.print(
+        rect.x,
+        rect.y,
+        &shown,
+        TextAlign::Left,
+        Some(style.fore),
+        None,
+    );
+    if focused {
+        let cursor_x = rect.x + (text_input.cursor() - scroll) as i32;
+        console.cell(
+            cursor_x,
+            rect.y,
+            None,
+            Some(style.focus_fore),
+            Some(style.focus_back),
+        );
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn draw_widgets(
+    style: Res<'_, UiStyle>,
+    focus: Res<'_, UiFocus>,
+    widgets: Query<'_, '_, (Entity, &UiRect, Widget<'_>)>,
+    mut root_console: ResMut<'_, RootConsole>,
+) {
+    for (entity, rect, widget) in widgets.iter() {
+        let focused = focus.is_focused(entity);
+        match widget {
+            (Some(button), _, _) => draw_button(&mut root_console, button, rect, focused, &style),
+            (None, Some(menu), _) => draw_menu(&mut root_console, menu, rect, focused, &style),
+            (None, None, Some(text_input)) => {
+                draw_text_input(&mut root_console, text_input, rect, focused, &style);
+            }
+            (None, None, None) => (),
+        }
+    }
+}
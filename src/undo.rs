@@ -0,0 +1,254 @@
+//! Undo and redo through reversible commands.
+//!
+//! A [`ReversibleCommand`] knows how to apply a change to the [`World`] and
+//! how to take it back again. Commands executed through
+//! [`UndoCommandsExt::execute_reversible`] are recorded on the [`UndoStack`]
+//! resource, from which [`UndoCommandsExt::undo`] and
+//! [`UndoCommandsExt::redo`] replay them. This is enough for editor tools as
+//! well as puzzle games that let the player rewind turns: record each turn
+//! as a [`CommandGroup`] and undo it as one step.
+
+use bevy_app::{App as BevyApp, Plugin};
+use bevy_ecs::system::{Command, Commands, Resource};
+use bevy_ecs::world::{Mut, World};
+use std::borrow::Cow;
+
+/// The undo plugin. Adds the [`UndoStack`] resource, unless one has already
+/// been inserted.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct UndoPlugin;
+
+impl Plugin for UndoPlugin {
+    fn build(&self, app: &mut BevyApp) {
+        app.init_resource::<UndoStack>();
+    }
+}
+
+/// A change to the [`World`] that can be undone.
+pub trait ReversibleCommand: Send + Sync + 'static {
+    /// Applies the change. This is called when the command is first executed,
+    /// and again every time it's redone.
+    fn apply(&mut self, world: &mut World);
+    /// Takes back everything [`apply`](Self::apply) did.
+    fn undo(&mut self, world: &mut World);
+    /// A short description of the change, e.g. for showing "Undo paint" in a
+    /// menu.
+    fn describe(&self) -> Cow<'static, str> {
+        Cow::Borrowed("")
+    }
+}
+
+/// Several commands that are applied and undone as a single step. They are
+/// applied in order and undone in reverse order.
+#[derive(Default)]
+pub struct CommandGroup {
+    description: Cow<'static, str>,
+    commands: Vec<Box<dyn ReversibleCommand>>,
+}
+
+impl std::fmt::Debug for CommandGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CommandGroup")
+            .field("description", &self.description)
+            .field("commands", &self.commands.len())
+            .finish()
+    }
+}
+
+impl CommandGroup {
+    /// Creates an empty group with the given description.
+    pub fn new(description: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            description: description.into(),
+            commands: Vec::new(),
+        }
+    }
+
+    /// Adds a command to the end of the group.
+    pub fn push(&mut self, command: impl ReversibleCommand) {
+        self.commands.push(Box::new(command));
+    }
+
+    /// Adds a command to the end of the group.
+    pub fn with(mut self, command: impl ReversibleCommand) -> Self {
+        self.push(command);
+        self
+    }
+
+    /// Whether the group holds no commands.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
+
+impl ReversibleCommand for CommandGroup {
+    fn apply(&mut self, world: &mut World) {
+        for command in &mut self.commands {
+            command.apply(world);
+        }
+    }
+
+    fn undo(&mut self, world: &mut World) {
+        for command in self.commands.iter_mut().rev() {
+            command.undo(world);
+        }
+    }
+
+    fn describe(&self) -> Cow<'static, str> {
+        self.description.clone()
+    }
+}
+
+/// Resource recording executed [`ReversibleCommand`]s so they can be undone
+/// and redone.
+#[derive(Resource)]
+pub struct UndoStack {
+    done: Vec<Box<dyn ReversibleCommand>>,
+    undone: Vec<Box<dyn ReversibleCommand>>,
+    limit: Option<usize>,
+}
+
+impl std::fmt::Debug for UndoStack {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UndoStack")
+            .field("done", &self.done.len())
+            .field("undone", &self.undone.len())
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
+
+impl Default for UndoStack {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UndoStack {
+    /// Creates an undo stack that remembers every command.
+    pub fn new() -> Self {
+        Self {
+            done: Vec::new(),
+            undone: Vec::new(),
+            limit: None,
+        }
+    }
+
+    /// Creates an undo stack that only remembers the last `limit` commands.
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            limit: Some(limit),
+            ..Self::new()
+        }
+    }
+
+    /// Whether there is a command to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.done.is_empty()
+    }
+
+    /// Whether there is a command to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// The description of the command that would be undone next.
+    pub fn undo_description(&self) -> Option<Cow<'static, str>> {
+        self.done.last().map(|command| command.describe())
+    }
+
+    /// The description of the command that would be redone next.
+    pub fn redo_description(&self) -> Option<Cow<'static, str>> {
+        self.undone.last().map(|command| command.describe())
+    }
+
+    /// How many commands can be undone.
+    pub fn undo_len(&self) -> usize {
+        self.done.len()
+    }
+
+    /// How many commands can be redone.
+    pub fn redo_len(&self) -> usize {
+        self.undone.len()
+    }
+
+    /// Forgets every recorded command.
+    pub fn clear(&mut self) {
+        self.done.clear();
+        self.undone.clear();
+    }
+
+    fn record(&mut self, command: Box<dyn ReversibleCommand>) {
+        self.undone.clear();
+        self.done.push(command);
+        if let Some(limit) = self.limit {
+            if self.done.len() > limit {
+                let excess = self.done.len() - limit;
+                self.done.drain(..excess);
+            }
+        }
+    }
+}
+
+/// Adds undo and redo methods to [`Commands`]. They take effect when the
+/// commands are applied, like any other command. The [`UndoStack`] resource
+/// must exist; add the [`UndoPlugin`] to make sure it does.
+pub trait UndoCommandsExt {
+    /// Applies `command` and records it on the [`UndoStack`]. This discards
+    /// everything that could be redone.
+    fn execute_reversible(&mut self, command: impl ReversibleCommand);
+    /// Undoes the most recent command, if there is one.
+    fn undo(&mut self);
+    /// Redoes the most recently undone command, if there is one.
+    fn redo(&mut self);
+}
+
+impl UndoCommandsExt for Commands<'_, '_> {
+    fn execute_reversible(&mut self, command: impl ReversibleCommand) {
+        self.add(Execute(Box::new(command)));
+    }
+
+    fn undo(&mut self) {
+        self.add(Undo);
+    }
+
+    fn redo(&mut self) {
+        self.add(Redo);
+    }
+}
+
+struct Execute(Box<dyn ReversibleCommand>);
+
+impl Command for Execute {
+    fn apply(self, world: &mut World) {
+        let mut command = self.0;
+        command.apply(world);
+        world.resource_mut::<UndoStack>().record(command);
+    }
+}
+
+struct Undo;
+
+impl Command for Undo {
+    fn apply(self, world: &mut World) {
+        world.resource_scope(|world, mut stack: Mut<'_, UndoStack>| {
+            if let Some(mut command) = stack.done.pop() {
+                command.undo(world);
+                stack.undone.push(command);
+            }
+        });
+    }
+}
+
+struct Redo;
+
+impl Command for Redo {
+    fn apply(self, world: &mut World) {
+        world.resource_scope(|world, mut stack: Mut<'_, UndoStack>| {
+            if let Some(mut command) = stack.undone.pop() {
+                command.apply(world);
+                stack.done.push(command);
+            }
+        });
+    }
+}
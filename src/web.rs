@@ -0,0 +1,620 @@
+/// How the size of the Doryen canvas is adjusted for the display's pixel
+/// density when running in a browser. See [`WebOptions`].
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum PixelRatio {
+    /// Leave the canvas alone, so one canvas pixel is one CSS pixel. On
+    /// high-density displays the console gets scaled up and looks blurry.
+    /// This is the default.
+    #[default]
+    Ignore,
+    /// Shrink the displayed canvas by the display's `devicePixelRatio`, so one
+    /// canvas pixel is one physical pixel and the font stays crisp.
+    Device,
+    /// Shrink the displayed canvas by the given ratio.
+    Fixed(f64),
+}
+
+/// Settings that only apply when running in a browser, i.e. when targeting
+/// `wasm32`. They are ignored everywhere else.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebOptions {
+    /// Where to put the canvas Doryen creates. If the element with this id is
+    /// a `<canvas>`, it is replaced by Doryen's canvas, which takes over the
+    /// id. Any other element gets Doryen's canvas appended as a child. When
+    /// `None`, or when no such element exists, the canvas stays at the end of
+    /// the page body.
+    pub canvas_id: Option<String>,
+    /// How to adjust for the display's pixel density.
+    pub pixel_ratio: PixelRatio,
+    /// Whether the canvas should be focusable and take focus on startup, so
+    /// keyboard input reaches the game without clicking on it first.
+    /// Defaults to `true`.
+    pub capture_focus: bool,
+    /// Whether to show a spinner and how much of the font has downloaded
+    /// over the canvas until it's ready, instead of a black canvas. See
+    /// [`LoadingProgress`](crate::LoadingProgress). Defaults to `true`.
+    pub loading_screen: bool,
+}
+
+impl Default for WebOptions {
+    fn default() -> Self {
+        Self {
+            canvas_id: None,
+            pixel_ratio: PixelRatio::default(),
+            capture_focus: true,
+            loading_screen: true,
+        }
+    }
+}
+
+/// Applies the web options to the canvas the Doryen app has just created,
+/// which is about to load the font at `font_path`.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn configure_canvas(options: &WebOptions, font_path: &str) {
+    use wasm_bindgen::JsCast;
+    use web_sys::HtmlCanvasElement;
+
+    let window = match web_sys::window() {
+        Some(window) => window,
+        None => return,
+    };
+    let document = match window.document() {
+        Some(document) => document,
+        None => return,
+    };
+    let canvases = document.get_elements_by_tag_name("canvas");
+    let canvas = match canvases
+        .item(canvases.length().saturating_sub(1))
+        .and_then(|element| element.dyn_into::<HtmlCanvasElement>().ok())
+    {
+        Some(canvas) => canvas,
+        None => {
+            bevy_utils::tracing::warn!("could not find the Doryen canvas");
+            return;
+        }
+    };
+
+    if let Some(canvas_id) = &options.canvas_id {
+        match document.get_element_by_id(canvas_id) {
+            Some(target) if target.tag_name().eq_ignore_ascii_case("canvas") => {
+                if target.replace_with_with_node_1(&canvas).is_ok() {
+                    canvas.set_id(canvas_id);
+                }
+            }
+            Some(target) => {
+                let _ = target.append_child(&canvas);
+            }
+            None => bevy_utils::tracing::warn!("no element with id {:?}", canvas_id),
+        }
+    }
+
+    let ratio = match options.pixel_ratio {
+        PixelRatio::Ignore => None,
+        PixelRatio::Device => Some(window.device_pixel_ratio()),
+        PixelRatio::Fixed(ratio) => Some(ratio),
+    };
+    if let Some(ratio) = ratio.filter(|&ratio| ratio > 0.0) {
+        let style = canvas.style();
+        let _ = style.set_property("width", &format!("{}px", f64::from(canvas.width()) / ratio));
+        let _ = style.set_property(
+            "height",
+            &format!("{}px", f64::from(canvas.height()) / ratio),
+        );
+    }
+
+    if options.capture_focus {
+        let _ = canvas.set_attribute("tabindex", "0");
+        let _ = canvas.focus();
+    }
+
+    listen_to_mouse_wheel(&canvas);
+    listen_to_cursor(&canvas);
+    listen_to_key_layout(&document);
+    listen_to_touches(&canvas);
+    listen_to_pastes(&document);
+    track_font_download(&canvas, font_path, options.loading_screen);
+}
+
+#[cfg(target_arch = "wasm32")]
+thread_local! {
+    // Nothing is loading until a download is started
+    static LOADING: std::cell::Cell<(Option<f32>, bool)> = std::cell::Cell::new((Some(1.0), true));
+}
+
+/// The styles of the loading screen. The spinner is a ring with one side
+/// missing.
+#[cfg(target_arch = "wasm32")]
+const LOADING_SCREEN_STYLE: &str = "\
+@keyframes bevy-doryen-spin { to { transform: rotate(360deg); } }
+.bevy-doryen-loading {
+    position: fixed; display: flex; flex-direction: column;
+    align-items: center; justify-content: center; gap: 1em;
+    background: #000; color: #ccc; font: 16px monospace; z-index: 1;
+}
+.bevy-doryen-loading > div {
+    width: 2em; height: 2em; border: 0.25em solid #ccc;
+    border-right-color: transparent; border-radius: 50%;
+    animation: bevy-doryen-spin 0.8s linear infinite;
+}";
+
+/// Downloads the font Doryen is loading, to tell how far along it is, for
+/// [`loading_progress`]. Doryen's own request is then served from the
+/// browser's cache. With `show` set, a loading screen covers the canvas
+/// until the download is done.
+#[cfg(target_arch = "wasm32")]
+fn track_font_download(canvas: &web_sys::HtmlCanvasElement, font_path: &str, show: bool) {
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::JsCast;
+    use web_sys::{ProgressEvent, XmlHttpRequest};
+
+    let request = match XmlHttpRequest::new() {
+        Ok(request) => request,
+        Err(_) => return,
+    };
+    if request.open_with_async("GET", font_path, true).is_err() {
+        return;
+    }
+
+    let screen = if show {
+        create_loading_screen(canvas)
+    } else {
+        None
+    };
+
+    let canvas = canvas.clone();
+    let progress_screen = screen.clone();
+    let on_progress = Closure::wrap(Box::new(move |event: ProgressEvent| {
+        let fraction = if event.length_computable() && event.total() > 0.0 {
+            Some((event.loaded() / event.total()).min(1.0) as f32)
+        } else {
+            None
+        };
+        LOADING.with(|loading| loading.set((fraction, false)));
+        if let Some((screen, label)) = &progress_screen {
+            place_over(screen, &canvas);
+            label.set_text_content(Some(&match fraction {
+                Some(fraction) => format!("Loading… {:.0}%", fraction * 100.0),
+                None => "Loading…".to_string(),
+            }));
+        }
+    }) as Box<dyn FnMut(ProgressEvent)>);
+    // Sent after the download succeeds or fails alike
+    let on_load_end = Closure::wrap(Box::new(move |_: ProgressEvent| {
+        LOADING.with(|loading| loading.set((Some(1.0), true)));
+        if let Some((screen, _)) = &screen {
+            screen.remove();
+        }
+    }) as Box<dyn FnMut(ProgressEvent)>);
+
+    request.set_onprogress(Some(on_progress.as_ref().unchecked_ref()));
+    request.set_onloadend(Some(on_load_end.as_ref().unchecked_ref()));
+    if request.send().is_ok() {
+        LOADING.with(|loading| loading.set((None, false)));
+        // The request holds on to the callbacks until it's done
+        on_progress.forget();
+        on_load_end.forget();
+    } else if let Some((screen, _)) = &screen {
+        screen.remove();
+    }
+}
+
+/// Puts a loading screen over the canvas, returning it and the element
+/// holding its text.
+#[cfg(target_arch = "wasm32")]
+fn create_loading_screen(
+    canvas: &web_sys::HtmlCanvasElement,
+) -> Option<(web_sys::HtmlElement, web_sys::Element)> {
+    use wasm_bindgen::JsCast;
+
+    let document = web_sys::window()?.document()?;
+    let style = document.create_element("style").ok()?;
+    style.set_text_content(Some(LOADING_SCREEN_STYLE));
+    let screen: web_sys::HtmlElement = document.create_element("div").ok()?.dyn_into().ok()?;
+    screen.set_class_name("bevy-doryen-loading");
+    let spinner = document.create_element("div").ok()?;
+    let label = document.create_element("span").ok()?;
+    label.set_text_content(Some("Loading…"));
+
+    screen.append_child(&style).ok()?;
+    screen.append_child(&spinner).ok()?;
+    screen.append_child(&label).ok()?;
+    document.body()?.append_child(&screen).ok()?;
+    place_over(&screen, canvas);
+    Some((screen, label))
+}
+
+/// Moves the loading screen over wherever the canvas is now.
+#[cfg(target_arch = "wasm32")]
+fn place_over(screen: &web_sys::HtmlElement, canvas: &web_sys::HtmlCanvasElement) {
+    let rect = canvas.get_bounding_client_rect();
+    let style = screen.style();
+    let _ = style.set_property("left", &format!("{}px", rect.left()));
+    let _ = style.set_property("top", &format!("{}px", rect.top()));
+    let _ = style.set_property("width", &format!("{}px", rect.width()));
+    let _ = style.set_property("height", &format!("{}px", rect.height()));
+}
+
+/// How far along the font download is, and whether it's done.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn loading_progress() -> (Option<f32>, bool) {
+    LOADING.with(std::cell::Cell::get)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[inline]
+pub(crate) fn loading_progress() -> (Option<f32>, bool) {
+    (Some(1.0), true)
+}
+
+#[cfg(target_arch = "wasm32")]
+thread_local! {
+    static MOUSE_WHEEL: std::cell::Cell<(f32, f32)> = std::cell::Cell::new((0.0, 0.0));
+}
+
+/// Collects the wheel movement over the canvas, which Doryen ignores, for
+/// [`take_mouse_wheel`].
+#[cfg(target_arch = "wasm32")]
+fn listen_to_mouse_wheel(canvas: &web_sys::HtmlCanvasElement) {
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::JsCast;
+    use web_sys::WheelEvent;
+
+    let listener = Closure::wrap(Box::new(|event: WheelEvent| {
+        event.prevent_default();
+        // Browsers report pixels, lines or pages; make that about one per
+        // wheel notch, with up and right being positive
+        let scale = match event.delta_mode() {
+            WheelEvent::DOM_DELTA_PIXEL => 1.0 / 100.0,
+            WheelEvent::DOM_DELTA_LINE => 1.0 / 3.0,
+            _ => 1.0,
+        };
+        let (x, y) = (event.delta_x() * scale, -event.delta_y() * scale);
+        MOUSE_WHEEL.with(|wheel| {
+            let (wheel_x, wheel_y) = wheel.get();
+            wheel.set((wheel_x + x as f32, wheel_y + y as f32));
+        });
+    }) as Box<dyn FnMut(WheelEvent)>);
+    if canvas
+        .add_event_listener_with_callback("wheel", listener.as_ref().unchecked_ref())
+        .is_ok()
+    {
+        // The listener lives as long as the canvas, which is the whole app
+        listener.forget();
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+thread_local! {
+    static LOGICAL_KEYS: std::cell::RefCell<std::collections::HashMap<String, String>> =
+        std::cell::RefCell::new(std::collections::HashMap::new());
+}
+
+/// Learns which key the layout puts at each key position from the keys
+/// pressed, for [`logical_key`].
+#[cfg(target_arch = "wasm32")]
+fn listen_to_key_layout(document: &web_sys::Document) {
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::JsCast;
+
+    let listener = Closure::wrap(Box::new(move |event: web_sys::KeyboardEvent| {
+        let key = event.key();
+        let mut chars = key.chars();
+        let logical = match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_alphabetic() => format!("Key{}", c.to_ascii_uppercase()),
+            (Some(c), None) if c.is_ascii_digit() => format!("Digit{}", c),
+            // Keys that don't type a letter or digit are where they say
+            _ => return,
+        };
+        LOGICAL_KEYS.with(|logical_keys| {
+            logical_keys.borrow_mut().insert(event.code(), logical);
+        });
+    }) as Box<dyn FnMut(web_sys::KeyboardEvent)>);
+    if document
+        .add_event_listener_with_callback("keydown", listener.as_ref().unchecked_ref())
+        .is_ok()
+    {
+        listener.forget();
+    }
+}
+
+/// The name of the key the layout puts at the position of `key`, once a key
+/// has been pressed there.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn logical_key(key: &str) -> Option<String> {
+    LOGICAL_KEYS.with(|logical_keys| logical_keys.borrow().get(key).cloned())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[inline]
+pub(crate) fn logical_key(_key: &str) -> Option<String> {
+    None
+}
+
+#[cfg(target_arch = "wasm32")]
+thread_local! {
+    static CURSOR_INSIDE: std::cell::Cell<Option<bool>> = std::cell::Cell::new(None);
+}
+
+/// Keeps track of whether the cursor is over the canvas, for
+/// [`cursor_inside`].
+#[cfg(target_arch = "wasm32")]
+fn listen_to_cursor(canvas: &web_sys::HtmlCanvasElement) {
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::JsCast;
+
+    for (event_type, inside) in [("mouseenter", true), ("mouseleave", false)]
+        .iter()
+        .copied()
+    {
+        let listener = Closure::wrap(Box::new(move |_: web_sys::MouseEvent| {
+            CURSOR_INSIDE.with(|cursor_inside| cursor_inside.set(Some(inside)));
+        }) as Box<dyn FnMut(web_sys::MouseEvent)>);
+        if canvas
+            .add_event_listener_with_callback(event_type, listener.as_ref().unchecked_ref())
+            .is_ok()
+        {
+            listener.forget();
+        }
+    }
+}
+
+/// Whether the cursor is over the canvas, once it has entered or left it.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn cursor_inside() -> Option<bool> {
+    CURSOR_INSIDE.with(std::cell::Cell::get)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[inline]
+pub(crate) fn cursor_inside() -> Option<bool> {
+    None
+}
+
+#[cfg(target_arch = "wasm32")]
+thread_local! {
+    static CONSOLE_SIZE: std::cell::Cell<(u32, u32)> = std::cell::Cell::new((1, 1));
+    static TOUCHES: std::cell::RefCell<Vec<crate::touch::TouchInput>> =
+        std::cell::RefCell::new(Vec::new());
+}
+
+/// Collects the touches on the canvas, which Doryen ignores, for
+/// [`take_touches`].
+#[cfg(target_arch = "wasm32")]
+fn listen_to_touches(canvas: &web_sys::HtmlCanvasElement) {
+    use crate::touch::{TouchInput, TouchPhase};
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::JsCast;
+
+    for (event_type, phase) in [
+        ("touchstart", TouchPhase::Started),
+        ("touchmove", TouchPhase::Moved),
+        ("touchend", TouchPhase::Ended),
+        ("touchcancel", TouchPhase::Cancelled),
+    ]
+    .iter()
+    .copied()
+    {
+        let target = canvas.clone();
+        let listener = Closure::wrap(Box::new(move |event: web_sys::TouchEvent| {
+            // Keep the browser from scrolling or zooming instead
+            event.prevent_default();
+            let rect = target.get_bounding_client_rect();
+            let (console_width, console_height) = CONSOLE_SIZE.with(std::cell::Cell::get);
+            let to_cells = |client: i32, start: f64, size: f64, cells: u32| {
+                let fraction = if size > 0.0 {
+                    (f64::from(client) - start) / size
+                } else {
+                    0.0
+                };
+                (fraction * f64::from(cells)) as f32
+            };
+
+            let touches = event.changed_touches();
+            TOUCHES.with(|pending| {
+                let mut pending = pending.borrow_mut();
+                for touch in (0..touches.length()).filter_map(|i| touches.get(i)) {
+                    pending.push(TouchInput {
+                        id: u64::from(touch.identifier() as u32),
+                        phase,
+                        position: (
+                            to_cells(touch.client_x(), rect.left(), rect.width(), console_width),
+                            to_cells(touch.client_y(), rect.top(), rect.height(), console_height),
+                        ),
+                    });
+                }
+            });
+        }) as Box<dyn FnMut(web_sys::TouchEvent)>);
+        if canvas
+            .add_event_listener_with_callback(event_type, listener.as_ref().unchecked_ref())
+            .is_ok()
+        {
+            listener.forget();
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+thread_local! {
+    static PASTED: std::cell::RefCell<Vec<String>> = std::cell::RefCell::new(Vec::new());
+}
+
+/// Collects the text pasted into the page for [`take_pasted`].
+#[cfg(target_arch = "wasm32")]
+fn listen_to_pastes(document: &web_sys::Document) {
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::JsCast;
+    use web_sys::ClipboardEvent;
+
+    let listener = Closure::wrap(Box::new(|event: ClipboardEvent| {
+        let text = event
+            .clipboard_data()
+            .and_then(|data| data.get_data("text").ok())
+            .filter(|text| !text.is_empty());
+        if let Some(text) = text {
+            event.prevent_default();
+            PASTED.with(|pasted| pasted.borrow_mut().push(text));
+        }
+    }) as Box<dyn FnMut(ClipboardEvent)>);
+    if document
+        .add_event_listener_with_callback("paste", listener.as_ref().unchecked_ref())
+        .is_ok()
+    {
+        listener.forget();
+    }
+}
+
+/// Takes the text pasted into the page since the last call.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn take_pasted() -> Vec<String> {
+    PASTED.with(|pasted| std::mem::take(&mut *pasted.borrow_mut()))
+}
+
+/// Puts text on the clipboard. The browser does this in the background, and
+/// may refuse if the page doesn't have focus.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn write_clipboard(text: &str) {
+    if let Some(window) = web_sys::window() {
+        // Nothing to do if the browser refuses, so the promise is dropped
+        let _ = window.navigator().clipboard().write_text(text);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[inline]
+pub(crate) fn take_pasted() -> Vec<String> {
+    Vec::new()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[inline]
+pub(crate) fn write_clipboard(_text: &str) {}
+
+/// Whether the player has asked the browser for reduced motion.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn prefers_reduced_motion() -> bool {
+    web_sys::window()
+        .and_then(|window| window.match_media("(prefers-reduced-motion: reduce)").ok())
+        .flatten()
+        .map_or(false, |query| query.matches())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[inline]
+pub(crate) fn prefers_reduced_motion() -> bool {
+    false
+}
+
+/// Tells the touch listener how many cells the canvas is divided into.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn set_console_size(size: (u32, u32)) {
+    CONSOLE_SIZE.with(|console_size| console_size.set(size));
+}
+
+/// Takes the touches on the canvas since the last call, in console cell
+/// coordinates.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn take_touches() -> Vec<crate::touch::TouchInput> {
+    TOUCHES.with(|pending| std::mem::take(&mut *pending.borrow_mut()))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[inline]
+pub(crate) fn set_console_size(_size: (u32, u32)) {}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[inline]
+pub(crate) fn take_touches() -> Vec<crate::touch::TouchInput> {
+    Vec::new()
+}
+
+/// Takes the wheel movement over the canvas since the last call.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn take_mouse_wheel() -> (f32, f32) {
+    MOUSE_WHEEL.with(|wheel| wheel.replace((0.0, 0.0)))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[inline]
+pub(crate) fn take_mouse_wheel() -> (f32, f32) {
+    (0.0, 0.0)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[inline]
+pub(crate) fn configure_canvas(_options: &WebOptions, _font_path: &str) {}
+
+/// Finds the Doryen canvas after [`configure_canvas`] has put it in place.
+#[cfg(target_arch = "wasm32")]
+fn find_canvas(options: &WebOptions) -> Option<web_sys::HtmlCanvasElement> {
+    use wasm_bindgen::JsCast;
+
+    let document = web_sys::window()?.document()?;
+    let canvases = match options
+        .canvas_id
+        .as_deref()
+        .and_then(|canvas_id| document.get_element_by_id(canvas_id))
+    {
+        Some(target) if target.tag_name().eq_ignore_ascii_case("canvas") => {
+            return target.dyn_into().ok();
+        }
+        Some(target) => target.get_elements_by_tag_name("canvas"),
+        None => document.get_elements_by_tag_name("canvas"),
+    };
+    canvases
+        .item(canvases.length().saturating_sub(1))
+        .and_then(|element| element.dyn_into().ok())
+}
+
+/// Sets the title of the page. Returns whether that's supported here.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn set_title(title: &str) -> bool {
+    if let Some(document) = web_sys::window().and_then(|window| window.document()) {
+        document.set_title(title);
+    }
+    true
+}
+
+/// Makes the canvas enter or leave fullscreen. Returns whether that's
+/// supported here.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn set_fullscreen(options: &WebOptions, fullscreen: bool) -> bool {
+    if fullscreen {
+        if let Some(canvas) = find_canvas(options) {
+            if canvas.request_fullscreen().is_err() {
+                bevy_utils::tracing::warn!("the browser refused to enter fullscreen");
+            }
+        }
+    } else if let Some(document) = web_sys::window().and_then(|window| window.document()) {
+        document.exit_fullscreen();
+    }
+    true
+}
+
+/// Shows or hides the mouse cursor over the canvas. Returns whether that's
+/// supported here.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn set_show_cursor(options: &WebOptions, show_cursor: bool) -> bool {
+    if let Some(canvas) = find_canvas(options) {
+        let cursor = if show_cursor { "auto" } else { "none" };
+        let _ = canvas.style().set_property("cursor", cursor);
+    }
+    true
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[inline]
+pub(crate) fn set_title(_title: &str) -> bool {
+    false
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[inline]
+pub(crate) fn set_fullscreen(_options: &WebOptions, _fullscreen: bool) -> bool {
+    false
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[inline]
+pub(crate) fn set_show_cursor(_options: &WebOptions, _show_cursor: bool) -> bool {
+    false
+}
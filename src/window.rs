@@ -0,0 +1,587 @@
+//! Bordered popup windows drawn on top of another console.
+//!
+//! A [`Window`] owns a scratch console for its interior. Everything drawn on
+//! it through [`Window::interior`] is clipped to the inside of the frame, and
+//! [`Window::draw`] puts the frame, title, close button and interior on the
+//! destination console in one go:
+//!
+//! ```
+//! # use bevy_doryen::doryen::{Console, TextAlign};
+//! # use bevy_doryen::window::{FrameStyle, Window};
+//! # let mut root_console = Console::new(80, 45);
+//! let mut window = Window::new(10, 5, 30, 8)
+//!     .with_title("Inventory")
+//!     .with_frame(FrameStyle::DOUBLE)
+//!     .closable(true);
+//! window
+//!     .interior()
+//!     .print(0, 0, "a - a rusty sword", TextAlign::Left, None, None);
+//! window.draw(&mut root_console);
+//! ```
+//!
+//! Frames can also be drawn on their own, with [`DrawFrame::draw_frame`].
+//! Those join up with the lines already on the console, so frames that
+//! touch or cross get the right junctions, like `├` or `╬`, which makes
+//! panels split into sections a matter of drawing a frame for each:
+//!
+//! ```
+//! # use bevy_doryen::doryen::Console;
+//! # use bevy_doryen::window::{DrawFrame, FrameFill, FrameStyle};
+//! # let mut root_console = Console::new(80, 45);
+//! root_console.draw_frame_filled(((0, 0), (80, 45)), &FrameStyle::DOUBLE, Some("Map"), FrameFill::Clear);
+//! root_console.draw_frame(((59, 0), (21, 45)), &FrameStyle::DOUBLE, Some("Status"));
+//! root_console.draw_frame(((59, 20), (21, 25)), &FrameStyle::SINGLE, None);
+//! ```
+
+use crate::doryen::{Color, Console, TextAlign};
+use crate::geometry::Rect;
+
+/// The glyphs and colors of a window's frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameStyle {
+    /// The glyph of the top and bottom edges.
+    pub horizontal: u16,
+    /// The glyph of the left and right edges.
+    pub vertical: u16,
+    /// The glyphs of the top left, top right, bottom left and bottom right
+    /// corners.
+    pub corners: [u16; 4],
+    /// The foreground color of the frame and title.
+    pub fore: Color,
+    /// The background color of the frame and the interior.
+    pub back: Color,
+}
+
+impl FrameStyle {
+    /// A frame drawn with single lines.
+    pub const SINGLE: Self = Self {
+        horizontal: 196,
+        vertical: 179,
+        corners: [218, 191, 192, 217],
+        fore: (255, 255, 255, 255),
+        back: (0, 0, 0, 255),
+    };
+
+    /// A frame drawn with double lines.
+    pub const DOUBLE: Self = Self {
+        horizontal: 205,
+        vertical: 186,
+        corners: [201, 187, 200, 188],
+        ..Self::SINGLE
+    };
+
+    /// A thick frame drawn with half and full blocks. Blocks don't join up
+    /// with other frames.
+    pub const BLOCK: Self = Self {
+        horizontal: 223,
+        vertical: 219,
+        corners: [219, 219, 219, 219],
+        ..Self::SINGLE
+    };
+
+    /// A frame drawn with heavy lines. The glyphs are Unicode code points, so
+    /// this needs a Unicode font, like `static/unicode_16x16.png`. It joins up
+    /// with other Unicode frames, but not with CP437 ones.
+    pub const HEAVY: Self = Self {
+        horizontal: '━' as u16,
+        vertical: '┃' as u16,
+        corners: ['┏' as u16, '┓' as u16, '┗' as u16, '┛' as u16],
+        ..Self::SINGLE
+    };
+
+    /// A frame drawn with single lines and rounded corners. Like
+    /// [`HEAVY`](Self::HEAVY), this needs a Unicode font.
+    pub const ROUNDED: Self = Self {
+        horizontal: '─' as u16,
+        vertical: '│' as u16,
+        corners: ['╭' as u16, '╮' as u16, '╰' as u16, '╯' as u16],
+        ..Self::SINGLE
+    };
+
+    /// Sets the colors of the frame.
+    pub fn with_colors(mut self, fore: Color, back: Color) -> Self {
+        self.fore = fore;
+        self.back = back;
+        self
+    }
+}
+
+impl Default for FrameStyle {
+    fn default() -> Self {
+        Self::SINGLE
+    }
+}
+
+/// What [`DrawFrame::draw_frame_filled`] does with the inside of the frame.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FrameFill {
+    /// The inside is left alone.
+    #[default]
+    None,
+    /// The inside is cleared to spaces in the colors of the frame.
+    Clear,
+    /// The inside is filled like [`Console::area`] does. `None` leaves that
+    /// part of the cells alone.
+    Area {
+        /// The glyph to fill with.
+        glyph: Option<u16>,
+        /// The foreground color to fill with.
+        fore: Option<Color>,
+        /// The background color to fill with.
+        back: Option<Color>,
+    },
+}
+
+/// How many lines go up, down, left and right from the middle of the
+/// box-drawing glyphs, as `0` for none, `1` for single, `2` for double and `3`
+/// for heavy. The CP437 glyphs come first, then the Unicode ones, which are
+/// told apart by being past the first 256 glyphs.
+const BOX_GLYPHS: &[(u16, [u8; 4])] = &[
+    (179, [1, 1, 0, 0]),
+    (180, [1, 1, 1, 0]),
+    (181, [1, 1, 2, 0]),
+    (182, [2, 2, 1, 0]),
+    (183, [0, 2, 1, 0]),
+    (184, [0, 1, 2, 0]),
+    (185, [2, 2, 2, 0]),
+    (186, [2, 2, 0, 0]),
+    (187, [0, 2, 2, 0]),
+    (188, [2, 0, 2, 0]),
+    (189, [2, 0, 1, 0]),
+    (190, [1, 0, 2, 0]),
+    (191, [0, 1, 1, 0]),
+    (192, [1, 0, 0, 1]),
+    (193, [1, 0, 1, 1]),
+    (194, [0, 1, 1, 1]),
+    (195, [1, 1, 0, 1]),
+    (196, [0, 0, 1, 1]),
+    (197, [1, 1, 1, 1]),
+    (198, [1, 1, 0, 2]),
+    (199, [2, 2, 0, 1]),
+    (200, [2, 0, 0, 2]),
+    (201, [0, 2, 0, 2]),
+    (202, [2, 0, 2, 2]),
+    (203, [0, 2, 2, 2]),
+    (204, [2, 2, 0, 2]),
+    (205, [0, 0, 2, 2]),
+    (206, [2, 2, 2, 2]),
+    (207, [1, 0, 2, 2]),
+    (208, [2, 0, 1, 1]),
+    (209, [0, 1, 2, 2]),
+    (210, [0, 2, 1, 1]),
+    (211, [2, 0, 0, 1]),
+    (212, [1, 0, 0, 2]),
+    (213, [0, 1, 0, 2]),
+    (214, [0, 2, 0, 1]),
+    (215, [2, 2, 1, 1]),
+    (216, [1, 1, 2, 2]),
+    (217, [1, 0, 1, 0]),
+    (218, [0, 1, 0, 1]),
+    ('─' as u16, [0, 0, 1, 1]),
+    ('│' as u16, [1, 1, 0, 0]),
+    ('┌' as u16, [0, 1, 0, 1]),
+    ('┐' as u16, [0, 1, 1, 0]),
+    ('└' as u16, [1, 0, 0, 1]),
+    ('┘' as u16, [1, 0, 1, 0]),
+    ('├' as u16, [1, 1, 0, 1]),
+    ('┤' as u16, [1, 1, 1, 0]),
+    ('┬' as u16, [0, 1, 1, 1]),
+    ('┴' as u16, [1, 0, 1, 1]),
+    ('┼' as u16, [1, 1, 1, 1]),
+    ('━' as u16, [0, 0, 3, 3]),
+    ('┃' as u16, [3, 3, 0, 0]),
+    ('┏' as u16, [0, 3, 0, 3]),
+    ('┓' as u16, [0, 3, 3, 0]),
+    ('┗' as u16, [3, 0, 0, 3]),
+    ('┛' as u16, [3, 0, 3, 0]),
+    ('┣' as u16, [3, 3, 0, 3]),
+    ('┫' as u16, [3, 3, 3, 0]),
+    ('┳' as u16, [0, 3, 3, 3]),
+    ('┻' as u16, [3, 0, 3, 3]),
+    ('╋' as u16, [3, 3, 3, 3]),
+    ('┍' as u16, [0, 1, 0, 3]),
+    ('┎' as u16, [0, 3, 0, 1]),
+    ('┑' as u16, [0, 1, 3, 0]),
+    ('┒' as u16, [0, 3, 1, 0]),
+    ('┕' as u16, [1, 0, 0, 3]),
+    ('┖' as u16, [3, 0, 0, 1]),
+    ('┙' as u16, [1, 0, 3, 0]),
+    ('┚' as u16, [3, 0, 1, 0]),
+    ('┝' as u16, [1, 1, 0, 3]),
+    ('┠' as u16, [3, 3, 0, 1]),
+    ('┥' as u16, [1, 1, 3, 0]),
+    ('┨' as u16, [3, 3, 1, 0]),
+    ('┯' as u16, [0, 1, 3, 3]),
+    ('┰' as u16, [0, 3, 1, 1]),
+    ('┷' as u16, [1, 0, 3, 3]),
+    ('┸' as u16, [3, 0, 1, 1]),
+    ('┿' as u16, [1, 1, 3, 3]),
+    ('╂' as u16, [3, 3, 1, 1]),
+    ('╭' as u16, [0, 1, 0, 1]),
+    ('╮' as u16, [0, 1, 1, 0]),
+    ('╯' as u16, [1, 0, 1, 0]),
+    ('╰' as u16, [1, 0, 0, 1]),
+];
+
+fn box_arms(glyph: u16) -> Option<[u8; 4]> {
+    BOX_GLYPHS
+        .iter()
+        .find(|&&(g, _)| g == glyph)
+        .map(|&(_, arms)| arms)
+}
+
+fn is_unicode(glyph: u16) -> bool {
+    glyph > 255
+}
+
+fn box_glyph(arms: [u8; 4], unicode: bool) -> Option<u16> {
+    BOX_GLYPHS
+        .iter()
+        .find(|&&(g, a)| a == arms && is_unicode(g) == unicode)
+        .map(|&(glyph, _)| glyph)
+}
+
+/// The glyph joining `glyph` with the box-drawing glyph already in a cell,
+/// or `glyph` itself if either isn't a box-drawing glyph, or they're from
+/// different fonts.
+fn join_box_glyphs(existing: u16, glyph: u16) -> u16 {
+    let (existing_arms, arms) = match (box_arms(existing), box_arms(glyph)) {
+        (Some(existing_arms), Some(arms)) if is_unicode(existing) == is_unicode(glyph) => {
+            (existing_arms, arms)
+        }
+        _ => return glyph,
+    };
+    let unicode = is_unicode(glyph);
+    let mut joined = [0; 4];
+    for (i, arm) in joined.iter_mut().enumerate() {
+        *arm = if arms[i] > 0 {
+            arms[i]
+        } else {
+            existing_arms[i]
+        };
+    }
+    if joined == arms {
+        // keeps rounded corners, which have the same arms as square ones
+        return glyph;
+    }
+    // neither font has glyphs mixing line styles along the same axis, so
+    // failing an exact match, both ends of an axis take the new style
+    box_glyph(joined, unicode).unwrap_or_else(|| {
+        let (vertical, horizontal) = (arms[0].max(arms[1]), arms[2].max(arms[3]));
+        for (i, arm) in joined.iter_mut().enumerate() {
+            let style = if i < 2 { vertical } else { horizontal };
+            if *arm > 0 && style > 0 {
+                *arm = style;
+            }
+        }
+        box_glyph(joined, unicode).unwrap_or(glyph)
+    })
+}
+
+/// Drawing frames on a console. See the [module documentation](self).
+pub trait DrawFrame {
+    /// Draws a frame of `size` cells with its top-left corner at `position`,
+    /// in the given style, with `title` printed along its top edge. Lines of
+    /// the frame join up with those already on the console, and the inside
+    /// is left alone.
+    fn draw_frame(
+        &mut self,
+        rect: ((i32, i32), (u32, u32)),
+        style: &FrameStyle,
+        title: Option<&str>,
+    ) {
+        self.draw_frame_filled(rect, style, title, FrameFill::None);
+    }
+
+    /// Draws a frame like [`draw_frame`](Self::draw_frame), and fills its
+    /// inside as `fill` says.
+    fn draw_frame_filled(
+        &mut self,
+        rect: ((i32, i32), (u32, u32)),
+        style: &FrameStyle,
+        title: Option<&str>,
+        fill: FrameFill,
+    );
+}
+
+impl DrawFrame for Console {
+    fn draw_frame_filled(
+        &mut self,
+        ((x, y), (width, height)): ((i32, i32), (u32, u32)),
+        style: &FrameStyle,
+        title: Option<&str>,
+        fill: FrameFill,
+    ) {
+        if width < 2 || height < 2 {
+            return;
+        }
+        let (right, bottom) = (x + width as i32 - 1, y + height as i32 - 1);
+        let (fore, back) = (style.fore, style.back);
+
+        match fill {
+            FrameFill::None => (),
+            FrameFill::Clear => self.area(
+                x + 1,
+                y + 1,
+                width - 2,
+                height - 2,
+                Some(fore),
+                Some(back),
+                Some(u16::from(b' ')),
+            ),
+            FrameFill::Area {
+                glyph,
+                fore: fill_fore,
+                back: fill_back,
+            } => {
+                self.area(
+                    x + 1,
+                    y + 1,
+                    width - 2,
+                    height - 2,
+                    fill_fore,
+                    fill_back,
+                    glyph,
+                );
+            }
+        }
+
+        let line = |console: &mut Self, cx: i32, cy: i32, glyph: u16| {
+            let glyph = match console.get_ascii(cx, cy) {
+                Some(existing) => join_box_glyphs(existing, glyph),
+                None => return,
+            };
+            console.cell(cx, cy, Some(glyph), Some(fore), Some(back));
+        };
+        for cx in x + 1..right {
+            line(self, cx, y, style.horizontal);
+            line(self, cx, bottom, style.horizontal);
+        }
+        for cy in y + 1..bottom {
+            line(self, x, cy, style.vertical);
+            line(self, right, cy, style.vertical);
+        }
+        line(self, x, y, style.corners[0]);
+        line(self, right, y, style.corners[1]);
+        line(self, x, bottom, style.corners[2]);
+        line(self, right, bottom, style.corners[3]);
+
+        if let Some(title) = title {
+            let room = (width as usize).saturating_sub(4);
+            if room > 0 {
+                let title: String = title.chars().take(room).collect();
+                self.print(
+                    x + 1,
+                    y,
+                    &format!(" {} ", title),
+                    TextAlign::Left,
+                    Some(fore),
+                    Some(back),
+                );
+            }
+        }
+    }
+}
+
+/// A bordered rectangle with its own interior console. See the [module
+/// documentation](self).
+pub struct Window {
+    /// The x coordinate of the top left corner of the frame.
+    pub x: i32,
+    /// The y coordinate of the top left corner of the frame.
+    pub y: i32,
+    /// The title printed along the top edge, if any.
+    pub title: Option<String>,
+    /// How the frame is drawn.
+    pub frame: FrameStyle,
+    /// Whether to draw a close button in the top right corner.
+    pub close_button: bool,
+    width: u32,
+    height: u32,
+    interior: Console,
+}
+
+impl std::fmt::Debug for Window {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Window")
+            .field("x", &self.x)
+            .field("y", &self.y)
+            .field("title", &self.title)
+            .field("frame", &self.frame)
+            .field("close_button", &self.close_button)
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .finish()
+    }
+}
+
+impl Window {
+    /// Creates a window whose frame has its top left corner at `(x, y)` and
+    /// is `width` by `height` cells, frame included.
+    pub fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+        let (width, height) = (width.max(2), height.max(2));
+        let mut window = Self {
+            x,
+            y,
+            title: None,
+            frame: FrameStyle::default(),
+            close_button: false,
+            width,
+            height,
+            interior: Console::new((width - 2).max(1), (height - 2).max(1)),
+        };
+        window.clear();
+        window
+    }
+
+    /// Sets the title of the window.
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Sets the frame style of the window, and clears the interior to its
+    /// background color.
+    pub fn with_frame(mut self, frame: FrameStyle) -> Self {
+        self.frame = frame;
+        self.clear();
+        self
+    }
+
+    /// Sets whether the window has a close button.
+    pub fn closable(mut self, close_button: bool) -> Self {
+        self.close_button = close_button;
+        self
+    }
+
+    /// The width of the window, frame included.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of the window, frame included.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Changes the size of the window, frame included. The interior is
+    /// cleared.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.width = width.max(2);
+        self.height = height.max(2);
+        self.interior
+            .resize((self.width - 2).max(1), (self.height - 2).max(1));
+        self.clear();
+    }
+
+    /// The console for drawing inside the frame. Its top left cell is the
+    /// first cell inside the frame, and anything drawn past its edges is
+    /// cut off.
+    pub fn interior(&mut self) -> &mut Console {
+        &mut self.interior
+    }
+
+    /// Clears the interior to the background color of the frame.
+    pub fn clear(&mut self) {
+        self.interior.clear(
+            Some(self.frame.fore),
+            Some(self.frame.back),
+            Some(' ' as u16),
+        );
+    }
+
+    /// The cells of the window, frame included.
+    pub fn rect(&self) -> Rect {
+        Rect::new(self.x, self.y, self.width, self.height)
+    }
+
+    /// Converts a cell of the console the window is drawn on to a cell of the
+    /// interior console, if it's inside the frame.
+    pub fn to_interior(&self, (x, y): (i32, i32)) -> Option<(i32, i32)> {
+        let cell = (x - self.x - 1, y - self.y - 1);
+        let inside = cell.0 >= 0
+            && cell.1 >= 0
+            && cell.0 < self.width as i32 - 2
+            && cell.1 < self.height as i32 - 2;
+        if inside {
+            Some(cell)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the given cell of the console the window is drawn on is its
+    /// close button.
+    pub fn is_close_button(&self, (x, y): (i32, i32)) -> bool {
+        self.close_button
+            && y == self.y
+            && x >= self.close_button_x()
+            && x < self.close_button_x() + 3
+    }
+
+    fn close_button_x(&self) -> i32 {
+        self.x + self.width as i32 - 4
+    }
+
+    /// Draws the window on `destination`. Its frame joins up with lines
+    /// already there, as with [`DrawFrame::draw_frame`].
+    pub fn draw(&self, destination: &mut Console) {
+        let (x, y) = (self.x, self.y);
+        let FrameStyle { fore, back, .. } = self.frame;
+
+        // Leave room for the close button, if there is one
+        let title = self.title.as_ref().map(|title| {
+            let reserved = if self.close_button { 8 } else { 4 };
+            let room = (self.width as usize).saturating_sub(reserved);
+            title.chars().take(room).collect::<String>()
+        });
+        destination.draw_frame(
+            ((x, y), (self.width, self.height)),
+            &self.frame,
+            title.as_deref().filter(|title| !title.is_empty()),
+        );
+        if self.close_button && self.width >= 5 {
+            destination.print(
+                self.close_button_x(),
+                y,
+                "[x]",
+                TextAlign::Left,
+                Some(fore),
+                Some(back),
+            );
+        }
+
+        if self.width > 2 && self.height > 2 {
+            self.interior
+                .blit(x + 1, y + 1, destination, 1.0, 1.0, None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unicode_frames_join_up() {
+        let mut console = Console::new(10, 5);
+        console.draw_frame(((0, 0), (10, 5)), &FrameStyle::ROUNDED, None);
+        console.draw_frame(((4, 0), (6, 5)), &FrameStyle::ROUNDED, None);
+        assert_eq!(console.get_ascii(0, 0), Some('╭' as u16));
+        assert_eq!(console.get_ascii(4, 0), Some('┬' as u16));
+        assert_eq!(console.get_ascii(4, 4), Some('┴' as u16));
+        assert_eq!(console.get_ascii(9, 0), Some('╮' as u16));
+
+        console.draw_frame(((0, 2), (10, 3)), &FrameStyle::HEAVY, None);
+        assert_eq!(console.get_ascii(4, 2), Some('┿' as u16));
+        assert_eq!(console.get_ascii(0, 2), Some('┣' as u16));
+    }
+
+    #[test]
+    fn unicode_and_cp437_frames_dont_join() {
+        let mut console = Console::new(10, 5);
+        console.draw_frame(((0, 0), (10, 5)), &FrameStyle::SINGLE, None);
+        console.draw_frame(((4, 0), (6, 5)), &FrameStyle::ROUNDED, None);
+        assert_eq!(console.get_ascii(4, 0), Some('╭' as u16));
+    }
+}
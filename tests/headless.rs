@@ -0,0 +1,358 @@
+use bevy_app::{App, AppExit, Startup, Update};
+use bevy_doryen::doryen::{AppOptions, TextAlign};
+use bevy_doryen::headless::{
+    console_row, HeadlessDoryenExtensions, HeadlessDoryenPlugin, HeadlessInput,
+};
+use bevy_doryen::localization::Localization;
+use bevy_doryen::modal::{Dialog, DialogResult, ModalPlugin, ModalStack};
+use bevy_doryen::path_following::GridPosition;
+use bevy_doryen::pathfinding::TerrainGrid;
+use bevy_doryen::roguelike::{Player, PlayerBundle, RoguelikeTemplatePlugin, WALL};
+use bevy_doryen::{
+    ConsoleResized, DoryenFrameLimits, DoryenPluginSettings, DoryenShutdown, Input, RenderStage,
+    RenderSystemExtensions, ResizeMode, RootConsole, ScreenInfo,
+};
+use bevy_ecs::event::{EventWriter, Events};
+use bevy_ecs::query::With;
+use bevy_ecs::schedule::common_conditions::resource_changed;
+use bevy_ecs::schedule::IntoSystemConfigs;
+use bevy_ecs::system::{Commands, Res, ResMut, Resource};
+
+#[derive(Default, Resource)]
+struct Greeting(&'static str);
+
+fn headless_app(resize_mode: ResizeMode) -> App {
+    let mut app = App::new();
+    app.insert_resource(DoryenPluginSettings {
+        app_options: AppOptions {
+            console_width: 20,
+            console_height: 10,
+            screen_width: 160,
+            screen_height: 80,
+            ..AppOptions::default()
+        },
+        resize_mode,
+        ..DoryenPluginSettings::default()
+    })
+    .add_plugins(HeadlessDoryenPlugin);
+    app
+}
+
+fn console_resized_events(app: &App) -> Vec<ConsoleResized> {
+    let events = app.world.resource::<Events<ConsoleResized>>();
+    events.get_reader().iter(events).copied().collect()
+}
+
+fn print_greeting(greeting: Res<'_, Greeting>, mut root_console: ResMut<'_, RootConsole>) {
+    root_console.print(0, 0, greeting.0, TextAlign::Left, None, None);
+}
+
+fn greet_on_enter(input: Res<'_, Input>, mut greeting: ResMut<'_, Greeting>) {
+    if input.key_pressed("Enter") {
+        greeting.0 = "Hello";
+    }
+}
+
+#[test]
+fn renders_into_the_root_console() {
+    let mut app = headless_app(ResizeMode::Nothing);
+    app.insert_resource(Greeting("Hi"))
+        .add_doryen_render_systems(print_greeting);
+
+    assert!(app.run_doryen_frame());
+    let root_console = app.world.resource::<RootConsole>();
+    assert_eq!(console_row(root_console, 0).trim_end(), "Hi");
+}
+
+#[test]
+fn root_console_only_changes_when_drawn_on() {
+    #[derive(Default, Resource)]
+    struct Redraws(u32);
+
+    fn count_redraws(mut redraws: ResMut<'_, Redraws>) {
+        redraws.0 += 1;
+    }
+
+    let mut app = headless_app(ResizeMode::Nothing);
+    app.insert_resource(Greeting("Hi"))
+        .init_resource::<Redraws>()
+        .add_doryen_render_systems(print_greeting.run_if(resource_changed::<Greeting>()))
+        .add_doryen_render_systems_to_stage(
+            RenderStage::Last,
+            count_redraws.run_if(resource_changed::<RootConsole>()),
+        );
+
+    app.run_doryen_frame();
+    assert_eq!(app.world.resource::<Redraws>().0, 1);
+
+    app.run_doryen_frame();
+    app.run_doryen_frame();
+    assert_eq!(app.world.resource::<Redraws>().0, 1);
+
+    app.world.resource_mut::<Greeting>().0 = "Hello";
+    app.run_doryen_frame();
+    assert_eq!(app.world.resource::<Redraws>().0, 2);
+}
+
+#[test]
+fn simulated_input_reaches_systems() {
+    let mut app = headless_app(ResizeMode::Nothing);
+    app.init_resource::<Greeting>()
+        .add_systems(Update, greet_on_enter);
+
+    app.run_doryen_frame();
+    assert_eq!(app.world.resource::<Greeting>().0, "");
+
+    app.world.resource_mut::<HeadlessInput>().press_key("Enter");
+    app.run_doryen_frame();
+    assert_eq!(app.world.resource::<Greeting>().0, "Hello");
+    assert!(app.world.resource::<Input>().key("Enter"));
+}
+
+#[test]
+fn open_dialogs_capture_input() {
+    let mut app = headless_app(ResizeMode::Nothing);
+    app.init_resource::<Greeting>()
+        .add_plugins(ModalPlugin)
+        .add_systems(Update, greet_on_enter);
+    app.world
+        .resource_mut::<ModalStack>()
+        .push(Dialog::new("hi", "Hi"));
+    app.run_doryen_frame();
+
+    app.world.resource_mut::<HeadlessInput>().press_key("Enter");
+    app.run_doryen_frame();
+    assert!(app.world.resource::<ModalStack>().is_empty());
+    assert_eq!(app.world.resource::<Events<DialogResult>>().len(), 1);
+    assert_eq!(app.world.resource::<Greeting>().0, "");
+
+    app.world.resource_mut::<HeadlessInput>().press_key("Enter");
+    app.run_doryen_frame();
+    assert_eq!(app.world.resource::<Greeting>().0, "Hello");
+}
+
+#[test]
+fn dialog_buttons_are_translated_and_picked_by_letter() {
+    let mut localization = Localization::new("nl");
+    localization.add_language("nl", [("dialog.yes", "Ja"), ("dialog.no", "Nee")]);
+    let mut app = headless_app(ResizeMode::Nothing);
+    app.insert_resource(localization).add_plugins(ModalPlugin);
+    app.world
+        .resource_mut::<ModalStack>()
+        .push(Dialog::confirm("quit", "Stoppen?"));
+    app.run_doryen_frame();
+    let root_console = app.world.resource::<RootConsole>();
+    assert!((0..10).any(|y| console_row(root_console, y).contains("[ Ja ]  [ Nee ]")));
+
+    app.world.resource_mut::<HeadlessInput>().press_key("KeyY");
+    app.run_doryen_frame();
+    assert!(!app.world.resource::<ModalStack>().is_empty());
+    app.world.resource_mut::<HeadlessInput>().press_key("KeyJ");
+    app.run_doryen_frame();
+    assert!(app.world.resource::<ModalStack>().is_empty());
+
+    app.world
+        .resource_mut::<ModalStack>()
+        .push(Dialog::new("players", "Players?").with_buttons(["1 player", "2 players"]));
+    app.run_doryen_frame();
+    app.world
+        .resource_mut::<HeadlessInput>()
+        .press_key("Digit1");
+    app.run_doryen_frame();
+    assert!(!app.world.resource::<ModalStack>().is_empty());
+}
+
+#[cfg(feature = "dev_console")]
+#[test]
+fn the_open_dev_console_captures_input() {
+    use bevy_doryen::dev_console::{DevConsole, DevConsolePlugin};
+
+    let mut app = headless_app(ResizeMode::Nothing);
+    app.init_resource::<Greeting>()
+        .add_plugins(DevConsolePlugin)
+        .add_systems(Update, greet_on_enter);
+    app.world
+        .resource_mut::<HeadlessInput>()
+        .press_key("Backquote");
+    app.run_doryen_frame();
+    assert!(app.world.resource::<DevConsole>().is_open());
+
+    app.world.resource_mut::<HeadlessInput>().press_key("Enter");
+    app.run_doryen_frame();
+    assert_eq!(app.world.resource::<Greeting>().0, "");
+
+    app.world
+        .resource_mut::<HeadlessInput>()
+        .press_key("Escape");
+    app.run_doryen_frame();
+    assert!(!app.world.resource::<DevConsole>().is_open());
+
+    app.world.resource_mut::<HeadlessInput>().press_key("Enter");
+    app.run_doryen_frame();
+    assert_eq!(app.world.resource::<Greeting>().0, "Hello");
+}
+
+#[test]
+fn first_frame_populates_screen_info() {
+    let mut app = headless_app(ResizeMode::Nothing);
+    assert_eq!(*app.world.resource::<ScreenInfo>(), ScreenInfo::default());
+
+    app.run_doryen_frame();
+    let screen_info = *app.world.resource::<ScreenInfo>();
+    assert_eq!(
+        (screen_info.console_width, screen_info.console_height),
+        (20, 10)
+    );
+    assert_eq!(screen_info.char_size(), (8, 8));
+    assert!(console_resized_events(&app).is_empty());
+}
+
+#[test]
+fn resizing_the_window_resizes_the_console() {
+    let mut app = headless_app(ResizeMode::Automatic);
+    app.run_doryen_frame();
+
+    app.resize_window(320, 160);
+    app.run_doryen_frame();
+    let screen_info = *app.world.resource::<ScreenInfo>();
+    assert_eq!(
+        (screen_info.screen_width, screen_info.screen_height),
+        (320, 160)
+    );
+    assert_eq!(
+        (screen_info.console_width, screen_info.console_height),
+        (40, 20)
+    );
+    assert_eq!(
+        console_resized_events(&app),
+        [ConsoleResized {
+            width: 40,
+            height: 20
+        }]
+    );
+}
+
+#[test]
+fn resizing_the_window_stretches_a_fixed_console() {
+    let mut app = headless_app(ResizeMode::Nothing);
+    app.run_doryen_frame();
+
+    app.resize_window(320, 160);
+    app.run_doryen_frame();
+    let screen_info = *app.world.resource::<ScreenInfo>();
+    assert_eq!(
+        (screen_info.console_width, screen_info.console_height),
+        (20, 10)
+    );
+    assert_eq!(screen_info.char_size(), (16, 16));
+    assert!(console_resized_events(&app).is_empty());
+}
+
+#[test]
+fn resizing_the_console_from_a_system_is_announced() {
+    let mut app = headless_app(ResizeMode::Nothing);
+    app.run_doryen_frame();
+
+    app.world.resource_mut::<RootConsole>().resize(10, 5);
+    app.run_doryen_frame();
+    let screen_info = *app.world.resource::<ScreenInfo>();
+    assert_eq!(
+        (screen_info.console_width, screen_info.console_height),
+        (10, 5)
+    );
+    assert_eq!(
+        console_resized_events(&app),
+        [ConsoleResized {
+            width: 10,
+            height: 5
+        }]
+    );
+}
+
+#[test]
+fn exiting_runs_the_shutdown_schedule() {
+    fn exit(mut app_exit: EventWriter<'_, AppExit>) {
+        app_exit.send(AppExit);
+    }
+
+    let mut app = headless_app(ResizeMode::Nothing);
+    app.insert_resource(Greeting("Bye"))
+        .add_systems(Update, exit)
+        .add_systems(DoryenShutdown, print_greeting);
+
+    assert!(!app.run_doryen_frame());
+    let root_console = app.world.resource::<RootConsole>();
+    assert_eq!(console_row(root_console, 0).trim_end(), "Bye");
+}
+
+#[test]
+fn on_demand_frames_only_update_for_input() {
+    #[derive(Default, Resource)]
+    struct Updates(u32);
+
+    fn count_updates(mut updates: ResMut<'_, Updates>) {
+        updates.0 += 1;
+    }
+
+    let mut app = headless_app(ResizeMode::Nothing);
+    app.insert_resource(DoryenFrameLimits::on_demand())
+        .init_resource::<Updates>()
+        .add_systems(Update, count_updates);
+
+    app.run_doryen_frame();
+    app.run_doryen_frame();
+    app.run_doryen_frame();
+    assert_eq!(app.world.resource::<Updates>().0, 1);
+
+    app.world.resource_mut::<HeadlessInput>().press_key("Enter");
+    app.run_doryen_frame();
+    assert_eq!(app.world.resource::<Updates>().0, 2);
+}
+
+#[test]
+fn the_roguelike_template_walks_the_player_around() {
+    fn spawn_player(mut commands: Commands<'_, '_>, mut map: ResMut<'_, TerrainGrid>) {
+        map.set((4, 2), WALL);
+        commands.spawn(PlayerBundle::new(2, 2));
+    }
+
+    fn screen(app: &App) -> Vec<String> {
+        let root_console = app.world.resource::<RootConsole>();
+        (0..10).map(|y| console_row(root_console, y)).collect()
+    }
+
+    fn player_position(app: &mut App) -> GridPosition {
+        *app.world
+            .query_filtered::<&GridPosition, With<Player>>()
+            .single(&app.world)
+    }
+
+    let mut app = headless_app(ResizeMode::Nothing);
+    app.add_plugins(RoguelikeTemplatePlugin {
+        map_width: 10,
+        map_height: 5,
+        log_height: 3,
+    })
+    .add_systems(Startup, spawn_player);
+
+    app.run_doryen_frame();
+    assert!(screen(&app)[1..7].iter().any(|row| row.contains(".@.#")));
+
+    app.world
+        .resource_mut::<HeadlessInput>()
+        .press_key("ArrowRight");
+    app.run_doryen_frame();
+    assert_eq!(player_position(&mut app), GridPosition(3, 2));
+    assert!(screen(&app)[0].ends_with("Turn 1"));
+
+    app.world
+        .resource_mut::<HeadlessInput>()
+        .release_key("ArrowRight");
+    app.run_doryen_frame();
+    app.world
+        .resource_mut::<HeadlessInput>()
+        .press_key("ArrowRight");
+    app.run_doryen_frame();
+    assert_eq!(player_position(&mut app), GridPosition(3, 2));
+    assert!(screen(&app)[7..].concat().contains("You bump into a"));
+}